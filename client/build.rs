@@ -0,0 +1,91 @@
+/*
+ * Copyright 2022 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::fmt::Write as _;
+use std::path::Path;
+
+/// generates the keycode table from `keycodes.txt`.
+fn main() {
+    println!("cargo:rerun-if-changed=keycodes.txt");
+
+    let source = std::fs::read_to_string("keycodes.txt").expect("failed to read keycodes.txt");
+
+    let mut keycodes = Vec::<(&str, i32)>::new();
+    for (index, line) in source.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let (name, value) = match line.split_once(' ') {
+            Some(data) => data,
+            None => panic!("keycodes.txt:{}: unexpected format: {}", index + 1, line),
+        };
+
+        if !name.starts_with("KEYCODE_")
+            || !name
+                .chars()
+                .all(|data| data.is_ascii_uppercase() || data.is_ascii_digit() || data == '_')
+        {
+            panic!("keycodes.txt:{}: invalid name: {}", index + 1, name);
+        }
+
+        let value = value
+            .trim()
+            .parse::<i32>()
+            .unwrap_or_else(|_| panic!("keycodes.txt:{}: invalid value: {}", index + 1, value));
+
+        if let Some((_, last)) = keycodes.last() {
+            if *last >= value {
+                panic!("keycodes.txt:{}: must be ordered by the value", index + 1);
+            }
+        }
+
+        if keycodes.iter().any(|(data, _)| *data == name) {
+            panic!("keycodes.txt:{}: duplicated name: {}", index + 1, name);
+        }
+
+        keycodes.push((name, value));
+    }
+
+    let mut table = String::new();
+    writeln!(
+        table,
+        "/// `KEYCODE_*` constants of `android.view.KeyEvent` ordered by the value."
+    )
+    .unwrap();
+    writeln!(table, "pub const KEYCODE_NAMES: [&str; {}] = [", keycodes.len()).unwrap();
+    for (name, _) in &keycodes {
+        writeln!(table, "    {:?},", name).unwrap();
+    }
+    writeln!(table, "];").unwrap();
+    writeln!(table).unwrap();
+    writeln!(table, "/// pairs of the name and the value ordered by the value.").unwrap();
+    writeln!(
+        table,
+        "pub const KEYCODE_TABLE: [(&str, i32); {}] = [",
+        keycodes.len()
+    )
+    .unwrap();
+    for (name, value) in &keycodes {
+        writeln!(table, "    ({:?}, {}),", name, value).unwrap();
+    }
+    writeln!(table, "];").unwrap();
+
+    let out_dir = std::env::var("OUT_DIR").expect("OUT_DIR");
+    std::fs::write(Path::new(&out_dir).join("keycode_table.rs"), table)
+        .expect("failed to write keycode_table.rs");
+}
@@ -0,0 +1,375 @@
+# the UI strings of English that the other bundles fall back to.
+
+tab-main = Main
+tab-monkey = Monkey
+tab-logcat = Logcat
+tab-files = Files
+tab-apps = Apps
+tab-intent = Intent
+tab-info = Info
+tab-layout = Layout
+tab-settings = Settings
+
+main-adb = ADB:
+main-connect = connect
+main-status-connected = status: connected
+main-status-connected-no-hold = status: connected without the server (no hold)
+main-status-connecting = status: connecting
+main-status-degraded = status: degraded
+main-status-disconnected = status: disconnected
+main-status-failed = status: failed
+main-status-reconnecting = status: reconnecting ({ $retry }/{ $max })
+main-status-reconnecting-in = status: reconnecting in { $secs }s ({ $retry }/{ $max })
+main-status-crashed = status: server crashed
+main-warning-server-outdated = warning: server asset is older than its source
+main-warning-adb-outdated = warning: adb { $version } is older than { $minimum }
+main-warning-adb-unknown = warning: failed to detect the adb version
+main-warning-api = warning: api server { $error }
+main-api-observer = 1 observer connected
+main-api-observers = { $count } observers connected
+main-warning-mqtt = warning: mqtt { $error }
+main-warning-blocked = warning: the device is blocked in the preferences
+main-confirm-device = connect to { $serial } for the first time?
+main-confirm-connect = Connect
+main-confirm-cancel = Cancel
+main-confirm-block = Block
+main-confirm-power = send the power key to the device?
+main-confirm-power-send = Send Power
+main-reconnect = Reconnect
+main-device-unusable = { $serial } is { $state }
+main-device-hint = hint: { $hint }
+main-error-server-crashed = server crashed
+main-error-connection-lost = connection lost
+main-error-server-unresponsive = server stopped responding
+main-step-build-server = build server
+main-step-push-server = push server
+main-step-start-server = start server
+main-step-handshake = handshake
+main-step-ready = ready
+main-crash = error: server crashed
+main-crash-no-output = (no error output)
+main-device-name = Name: 
+main-device-name-placeholder = nickname
+main-layout = Layout: 
+main-layout-share = Share
+main-layout-share-hide = Hide
+main-layout-copy-code = Copy code
+main-user = User: 
+main-haptic = vibrate on click
+main-usage-storage = Storage: { $usage }
+main-usage-memory = Memory: { $usage }
+main-usage-loading = loading
+main-usage-unknown = unknown
+main-usage-refresh = Refresh
+main-keyboard-mode = keyboard mode
+main-pointer-mode = pointer mode
+main-pointer-pad = drag to swipe, click to tap
+main-screen-off = screen is off
+main-wake = Wake
+main-macro-stop = Stop macro
+main-macro-run-clipboard = Run clipboard macro
+main-macro-record = Record macro
+main-macro-record-stop = Stop recording
+main-macro-keys = { $count } keys
+main-macro-name = macro name
+main-macro-save = Save
+main-macro-discard = Discard
+main-macro-delete = Delete
+main-macro-status = macro: { $status }
+main-macro-clipboard-empty = macro: clipboard is empty
+main-macro-discarded = macro: discarded
+main-macro-no-keys = macro: no keys recorded
+main-macro-recorded = macro: recorded { $steps } steps
+main-macro-recording = macro: recording
+main-macro-name-required = macro: enter the name to save
+main-macro-saved = macro: saved { $name }
+main-macro-step = macro: { $progress } { $step }
+main-macro-wait = wait { $millis }ms
+main-macro-done = macro: done ({ $steps } steps)
+main-macro-stopped = macro: stopped
+main-macro-capturing = macro: { $reason } (capturing screenshot)
+main-macro-failed-screenshot = macro: { $reason }, screenshot: { $path }
+main-macro-report = macro: report { $path }
+main-capture = Capture input
+main-capture-secs = secs
+main-capture-copy = Copy
+main-capture-save = Save
+main-capture-invalid-secs = capture: seconds must be 1-{ $max }
+main-capture-recording = capture: recording { $secs }s
+main-capture-failed = capture: failed
+main-capture-copied = capture: copied { $count } events
+main-capture-saved = capture: saved to { $path }
+main-capture-save-failed = capture: failed to save
+main-diagnostics = Input diagnostics
+main-diagnostics-run = Diagnose
+main-diagnostics-hide = Hide
+main-diagnostics-failed = diagnostics: failed
+main-history = History:
+main-history-export = Export
+main-history-mark-log = Mark log
+main-pairing-adb = Wireless debugging
+main-pairing-atv = TV remote
+main-pairing-pair = Pair
+main-pairing-cancel = Cancel
+main-pairing-send = Send
+main-pairing-address = host:port
+main-pairing-code = code
+main-pairing-pairing = pairing
+main-pairing-paired = paired
+main-pairing-failed = failed to pair: { $error }
+main-pairing-atv-enter-code = enter the code shown on the TV
+main-accessibility = Accessibility
+main-accessibility-restore = Restore
+main-locale = Locale: 
+main-font-scale = Font scale: 
+main-reboot = Reboot
+main-reboot-step-reboot = reboot
+main-reboot-step-wait-for-device = wait for device
+main-reboot-step-wait-for-boot = wait for boot completed
+main-reboot-running = { $step }...
+main-reboot-failed = failed to { $step }: { $error }
+main-recording-start = Record
+main-recording-stop = Stop recording
+main-recording-elapsed = recording { $elapsed }s / { $limit }s
+main-recording-stopping = stopping...
+main-recording-pulling = pulling...
+main-recording-failed = failed to record: { $error }
+main-recording-saved = saved to { $path }
+main-recording-pull-failed = failed to pull: { $error }
+main-self-test-run = Run self-test
+main-self-test-running = running...
+main-self-test-copy = Copy report
+
+monkey-device = device: { $device }
+monkey-device-none = device: not selected
+monkey-package = package
+monkey-events = events
+monkey-throttle = throttle
+monkey-throttle-unit = ms
+monkey-seed = seed
+monkey-seed-placeholder = random
+monkey-start = Start
+monkey-stop = Stop
+monkey-status-no-device = status: select a device on the main view
+monkey-status-running = status: running
+monkey-status-stopping = status: stopping
+monkey-status-finished = status: finished
+monkey-status-exit-code = status: aborted (exit code { $code })
+monkey-status-aborted = status: aborted
+monkey-status-error = status: { $error }
+
+logcat-device = device: { $device }
+logcat-device-none = device: not selected
+logcat-start = Start
+logcat-stop = Stop
+logcat-pause = Pause
+logcat-resume = Resume
+logcat-clear = Clear
+logcat-tag = tag
+logcat-regex = regex
+logcat-keys = Keys
+logcat-status-no-device = status: select a device on the main view
+logcat-status-running = status: running
+logcat-status-stopped = status: stopped
+logcat-status-device-changed = status: stopped since the device was changed
+logcat-status-exited = status: logcat exited
+logcat-status-paused = { $status } (paused, { $count } new lines)
+
+files-device = device: { $device }
+files-device-none = device: not selected
+files-up = Up
+files-reload = Reload
+files-drop-hint = drop the files here to push them into this directory
+files-pull = Pull
+files-pull-title = Save the device file
+files-status-loading = status: loading
+files-status-count = status: { $count } entries
+files-status-not-absolute = status: the path must be absolute
+files-status-pushing = status: pushing { $path }
+files-status-pushed = status: pushed to { $path }
+files-status-pulling = status: pulling { $path }
+files-status-pulled = status: pulled to { $path }
+files-status-error = status: { $error }
+
+apps-device = device: { $device }
+apps-device-none = device: not selected
+apps-filter = filter
+apps-reload = Reload
+apps-launch = Launch
+apps-stop = Stop
+apps-clear = Clear
+apps-uninstall = Uninstall
+apps-install = Install APK
+apps-install-title = Select the APK to install
+apps-action-launch = launch
+apps-action-force-stop = force stop
+apps-action-clear-data = clear data
+apps-action-uninstall = uninstall
+apps-confirm = { $action } { $package }?
+apps-confirm-ok = OK
+apps-confirm-cancel = Cancel
+apps-status-loading = status: loading
+apps-status-count = status: { $count } apps
+apps-status-running = status: { $action } { $package }
+apps-status-finished = status: { $action } { $package } finished
+apps-status-installing = status: installing { $path }
+apps-status-installed = status: installed { $path }
+apps-status-error = status: { $error }
+
+intent-device = device: { $device }
+intent-device-none = device: not selected
+intent-action = action
+intent-data = data
+intent-component = component
+intent-extras = extras
+intent-send = Send
+intent-history = history
+intent-status-no-device = status: select an adb device on the main view
+intent-status-starting = status: starting
+intent-status-started = status: started
+intent-status-error = status: { $error }
+
+info-device = device: { $device }
+info-device-none = device: not selected
+info-reload = Reload
+info-copy-all = Copy all
+info-copy = Copy
+info-bugreport = Bugreport
+info-bugreport-title = Save the bugreport
+info-properties = { $count } properties
+info-status-loading = status: loading
+info-status-bugreport-capturing = status: capturing the bugreport
+info-status-bugreport-saved = status: saved to { $path }
+info-status-error = status: { $error }
+
+layout-editor-profile = Layout:
+layout-editor-help = Drag the keys into the grid. Click a button to change the size.
+layout-editor-grid = grid
+layout-editor-grid-empty = the sections are shown until the grid has a button
+layout-editor-new-row = + new row
+layout-editor-remove = Remove
+layout-editor-size = size
+layout-editor-use-sections = Use sections
+
+settings-reload = Reload preferences
+settings-open-dir = Open preferences directory
+settings-keycode-references = Open KeyCode references
+settings-copy-keycode = Copy keycode:
+settings-theme = Theme:
+settings-theme-light = Light
+settings-theme-dark = Dark
+settings-language = Language:
+settings-language-font = the font is applied after restarting
+settings-adb = adb:
+settings-adb-placeholder = found automatically
+settings-adb-pick-title = Select adb
+settings-browse = Browse
+settings-adb-server = adb server:
+settings-adb-server-kill = Kill
+settings-adb-server-start = Start
+settings-adb-server-version-button = Version
+settings-adb-server-running = running...
+settings-adb-server-killed = the adb server was killed
+settings-adb-server-started = the adb server is running, reload the devices
+settings-adb-server-version = adb { $version }
+settings-recording = Recording:
+settings-recording-secs = secs
+settings-recording-bit-rate = default
+settings-recording-secs-unit = s 
+settings-recording-bit-rate-unit = Mbps
+settings-recording-pick-title = Select the directory to save the recordings
+settings-api-port = REST API port:
+settings-api-port-placeholder = disabled
+settings-api-token = token:
+settings-api-token-copy = Copy
+settings-api-observer-token = Read-only observer token:
+settings-save-to = Save to:
+settings-save-to-placeholder = temporary directory
+settings-notification = Notify when the window is unfocused
+settings-confirm-devices = Confirm before connecting to new devices
+settings-confirm-power = Confirm before sending the power key
+settings-hold-buttons = Hold buttons to send down and up
+settings-check-updates = Check for updates on startup
+settings-layout = Layout:
+settings-section-color-keys = Color keys
+settings-section-dpad = D-pad
+settings-section-navigation = Navigation
+settings-section-numpad = Numpad
+settings-section-media = Media
+settings-section-power = Power
+settings-section-tv = TV
+settings-section-custom-keys = Custom keys
+settings-button-size-small = Small
+settings-button-size-medium = Medium
+settings-button-size-large = Large
+settings-import-profile = Import profile from clipboard
+settings-import-profile-imported = imported { $layout } for { $serial }
+settings-import-profile-failed = failed to import: { $error }
+settings-up = Up
+settings-down = Down
+settings-shortcuts = Shortcuts:
+settings-unpin = Unpin
+settings-pin = Pin
+settings-shortcut-label = label
+settings-shortcut-target = package or deeplink
+settings-custom-keys = Custom keys:
+settings-custom-key-label = label
+settings-custom-key-package = package (optional)
+settings-custom-key-add = Add
+settings-custom-key-remove = Remove
+settings-test = Test
+settings-key-map = Key map:
+settings-invalid-keycode = { $location }: { $keycode } is not a known keycode
+settings-invalid-keycode-suggestion = { $location }: { $keycode } is not a known keycode, did you mean { $suggestion }?
+settings-key-map-profile-name = profile name
+settings-key-map-profile-save = Save
+settings-key-map-profile-delete = Delete
+settings-key-layout = Key layout of the device:
+settings-key-layout-list = List
+settings-key-layout-no-device = select a device with the shell
+settings-key-layout-loading = loading
+settings-key-layout-found = { $count } keys of { $name }
+settings-key-layout-failed = failed to load: { $error }
+settings-key-layout-save = Save as key map profile
+settings-update-available = version { $version } is available
+settings-update-open = Open release page
+
+toast-error = error: { $error }
+toast-dismiss = x
+
+notification-error = Error
+notification-warning = Warning
+notification-connected = Connected
+notification-connected-to = connected to { $device }
+notification-connected-body = connected
+notification-connect-failed = failed to connect
+notification-connection-degraded = connection degraded
+notification-server-crashed = server crashed
+notification-reconnect-failed = failed to reconnect
+notification-reboot = Reboot
+notification-reboot-booted = { $serial } booted
+notification-recording = Recording
+notification-self-test = Self-test
+notification-self-test-passed = { $serial }: passed
+notification-self-test-failed = { $serial }: failed
+notification-macro = Macro
+notification-macro-done = done ({ $steps } steps)
+notification-macro-failed = failed: { $reason }
+notification-monkey = Monkey
+notification-monkey-finished = { $package } finished
+notification-monkey-exit-code = { $package } aborted (exit code { $code })
+notification-monkey-aborted = { $package } aborted
+notification-apps = Apps
+notification-apps-finished = { $action } { $package } finished
+notification-apps-failed = failed to { $action } { $package }: { $error }
+notification-install = Install
+notification-install-finished = installed { $path }
+notification-install-failed = failed to install { $path }: { $error }
+notification-files = Files
+notification-files-pushed = pushed to { $path }
+notification-files-push-failed = failed to push: { $error }
+notification-files-pulled = pulled to { $path }
+notification-files-pull-failed = failed to pull: { $error }
+notification-bugreport = Bugreport
+notification-bugreport-saved = saved to { $path }
+notification-bugreport-failed = failed to capture: { $error }
@@ -0,0 +1,373 @@
+tab-main = メイン
+tab-monkey = Monkey
+tab-logcat = Logcat
+tab-files = ファイル
+tab-apps = アプリ
+tab-intent = Intent
+tab-info = 情報
+tab-layout = レイアウト
+tab-settings = 設定
+
+main-adb = ADB:
+main-connect = 接続
+main-status-connected = 状態: 接続済み
+main-status-connected-no-hold = 状態: サーバーなしで接続済み (長押し不可)
+main-status-connecting = 状態: 接続中
+main-status-degraded = 状態: 不安定
+main-status-disconnected = 状態: 未接続
+main-status-failed = 状態: 失敗
+main-status-reconnecting = 状態: 再接続中 ({ $retry }/{ $max })
+main-status-reconnecting-in = 状態: { $secs } 秒後に再接続 ({ $retry }/{ $max })
+main-status-crashed = 状態: サーバーがクラッシュしました
+main-warning-server-outdated = 警告: サーバーがソースより古いです
+main-warning-adb-outdated = 警告: adb { $version } は { $minimum } より古いです
+main-warning-adb-unknown = 警告: adb のバージョンを取得できません
+main-warning-api = 警告: API サーバー { $error }
+main-api-observer = オブザーバー 1 人が接続中
+main-api-observers = オブザーバー { $count } 人が接続中
+main-warning-mqtt = 警告: MQTT { $error }
+main-warning-blocked = 警告: このデバイスは設定でブロックされています
+main-confirm-device = { $serial } に初めて接続しますか?
+main-confirm-connect = 接続
+main-confirm-cancel = キャンセル
+main-confirm-block = ブロック
+main-confirm-power = デバイスに電源キーを送信しますか?
+main-confirm-power-send = 電源キーを送信
+main-reconnect = 再接続
+main-device-unusable = { $serial } は { $state } です
+main-device-hint = ヒント: { $hint }
+main-error-server-crashed = サーバーがクラッシュしました
+main-error-connection-lost = 接続が切れました
+main-error-server-unresponsive = サーバーが応答しません
+main-step-build-server = サーバーをビルド
+main-step-push-server = サーバーを転送
+main-step-start-server = サーバーを起動
+main-step-handshake = ハンドシェイク
+main-step-ready = 準備完了
+main-crash = エラー: サーバーがクラッシュしました
+main-crash-no-output = (エラー出力なし)
+main-device-name = 名前: 
+main-device-name-placeholder = ニックネーム
+main-layout = レイアウト: 
+main-layout-share = 共有
+main-layout-share-hide = 隠す
+main-layout-copy-code = コードをコピー
+main-user = ユーザー: 
+main-haptic = クリック時に振動
+main-usage-storage = ストレージ: { $usage }
+main-usage-memory = メモリ: { $usage }
+main-usage-loading = 読み込み中
+main-usage-unknown = 不明
+main-usage-refresh = 更新
+main-keyboard-mode = キーボードモード
+main-pointer-mode = ポインターモード
+main-pointer-pad = ドラッグでスワイプ、クリックでタップ
+main-screen-off = 画面がオフです
+main-wake = スリープ解除
+main-macro-stop = マクロを停止
+main-macro-run-clipboard = クリップボードのマクロを実行
+main-macro-record = マクロを記録
+main-macro-record-stop = 記録を停止
+main-macro-keys = { $count } キー
+main-macro-name = マクロ名
+main-macro-save = 保存
+main-macro-discard = 破棄
+main-macro-delete = 削除
+main-macro-status = マクロ: { $status }
+main-macro-clipboard-empty = マクロ: クリップボードが空です
+main-macro-discarded = マクロ: 破棄しました
+main-macro-no-keys = マクロ: キーが記録されていません
+main-macro-recorded = マクロ: { $steps } ステップを記録しました
+main-macro-recording = マクロ: 記録中
+main-macro-name-required = マクロ: 保存する名前を入力してください
+main-macro-saved = マクロ: { $name } を保存しました
+main-macro-step = マクロ: { $progress } { $step }
+main-macro-wait = { $millis } ミリ秒待機
+main-macro-done = マクロ: 完了 ({ $steps } ステップ)
+main-macro-stopped = マクロ: 停止しました
+main-macro-capturing = マクロ: { $reason } (スクリーンショットを撮影中)
+main-macro-failed-screenshot = マクロ: { $reason }、スクリーンショット: { $path }
+main-macro-report = マクロ: レポート { $path }
+main-capture = 入力をキャプチャ
+main-capture-secs = 秒
+main-capture-copy = コピー
+main-capture-save = 保存
+main-capture-invalid-secs = キャプチャ: 秒数は 1-{ $max } で指定してください
+main-capture-recording = キャプチャ: { $secs } 秒間記録中
+main-capture-failed = キャプチャ: 失敗しました
+main-capture-copied = キャプチャ: { $count } 件のイベントをコピーしました
+main-capture-saved = キャプチャ: { $path } に保存しました
+main-capture-save-failed = キャプチャ: 保存に失敗しました
+main-diagnostics = 入力の診断
+main-diagnostics-run = 診断
+main-diagnostics-hide = 隠す
+main-diagnostics-failed = 診断: 失敗しました
+main-history = 履歴:
+main-history-export = エクスポート
+main-history-mark-log = ログにマーク
+main-pairing-adb = ワイヤレスデバッグ
+main-pairing-atv = TV リモコン
+main-pairing-pair = ペア設定
+main-pairing-cancel = キャンセル
+main-pairing-send = 送信
+main-pairing-address = ホスト:ポート
+main-pairing-code = コード
+main-pairing-pairing = ペア設定中
+main-pairing-paired = ペア設定しました
+main-pairing-failed = ペア設定に失敗しました: { $error }
+main-pairing-atv-enter-code = TV に表示されたコードを入力してください
+main-accessibility = ユーザー補助
+main-accessibility-restore = 元に戻す
+main-locale = 言語: 
+main-font-scale = フォントサイズ: 
+main-reboot = 再起動
+main-reboot-step-reboot = 再起動
+main-reboot-step-wait-for-device = デバイスを待機
+main-reboot-step-wait-for-boot = 起動完了を待機
+main-reboot-running = { $step }...
+main-reboot-failed = { $step } に失敗しました: { $error }
+main-recording-start = 録画
+main-recording-stop = 録画を停止
+main-recording-elapsed = 録画中 { $elapsed } 秒 / { $limit } 秒
+main-recording-stopping = 停止中...
+main-recording-pulling = 取得中...
+main-recording-failed = 録画に失敗しました: { $error }
+main-recording-saved = { $path } に保存しました
+main-recording-pull-failed = 取得に失敗しました: { $error }
+main-self-test-run = セルフテストを実行
+main-self-test-running = 実行中...
+main-self-test-copy = レポートをコピー
+
+monkey-device = デバイス: { $device }
+monkey-device-none = デバイス: 未選択
+monkey-package = パッケージ
+monkey-events = イベント数
+monkey-throttle = 間隔
+monkey-throttle-unit = ミリ秒
+monkey-seed = シード
+monkey-seed-placeholder = ランダム
+monkey-start = 開始
+monkey-stop = 停止
+monkey-status-no-device = 状態: メインでデバイスを選択してください
+monkey-status-running = 状態: 実行中
+monkey-status-stopping = 状態: 停止中
+monkey-status-finished = 状態: 完了しました
+monkey-status-exit-code = 状態: 中断しました (終了コード { $code })
+monkey-status-aborted = 状態: 中断しました
+monkey-status-error = 状態: { $error }
+
+logcat-device = デバイス: { $device }
+logcat-device-none = デバイス: 未選択
+logcat-start = 開始
+logcat-stop = 停止
+logcat-pause = 一時停止
+logcat-resume = 再開
+logcat-clear = クリア
+logcat-tag = タグ
+logcat-regex = 正規表現
+logcat-keys = キー
+logcat-status-no-device = 状態: メインでデバイスを選択してください
+logcat-status-running = 状態: 実行中
+logcat-status-stopped = 状態: 停止しました
+logcat-status-device-changed = 状態: デバイスが変更されたため停止しました
+logcat-status-exited = 状態: logcat が終了しました
+logcat-status-paused = { $status } (一時停止中、新しい行 { $count } 件)
+
+files-device = デバイス: { $device }
+files-device-none = デバイス: 未選択
+files-up = 上へ
+files-reload = 再読み込み
+files-drop-hint = ファイルをここにドロップするとこのディレクトリに転送します
+files-pull = 取得
+files-pull-title = デバイスのファイルを保存
+files-status-loading = 状態: 読み込み中
+files-status-count = 状態: { $count } 件
+files-status-not-absolute = 状態: 絶対パスを指定してください
+files-status-pushing = 状態: { $path } を転送中
+files-status-pushed = 状態: { $path } に転送しました
+files-status-pulling = 状態: { $path } を取得中
+files-status-pulled = 状態: { $path } に保存しました
+files-status-error = 状態: { $error }
+
+apps-device = デバイス: { $device }
+apps-device-none = デバイス: 未選択
+apps-filter = フィルター
+apps-reload = 再読み込み
+apps-launch = 起動
+apps-stop = 停止
+apps-clear = 消去
+apps-uninstall = アンインストール
+apps-install = APK をインストール
+apps-install-title = インストールする APK を選択
+apps-action-launch = 起動
+apps-action-force-stop = 強制停止
+apps-action-clear-data = データ消去
+apps-action-uninstall = アンインストール
+apps-confirm = { $package } を{ $action }しますか?
+apps-confirm-ok = OK
+apps-confirm-cancel = キャンセル
+apps-status-loading = 状態: 読み込み中
+apps-status-count = 状態: { $count } 個のアプリ
+apps-status-running = 状態: { $package } を{ $action }中
+apps-status-finished = 状態: { $package } の{ $action }が完了しました
+apps-status-installing = 状態: { $path } をインストール中
+apps-status-installed = 状態: { $path } をインストールしました
+apps-status-error = 状態: { $error }
+
+intent-device = デバイス: { $device }
+intent-device-none = デバイス: 未選択
+intent-action = アクション
+intent-data = データ
+intent-component = コンポーネント
+intent-extras = エクストラ
+intent-send = 送信
+intent-history = 履歴
+intent-status-no-device = 状態: メインで adb のデバイスを選択してください
+intent-status-starting = 状態: 開始中
+intent-status-started = 状態: 開始しました
+intent-status-error = 状態: { $error }
+
+info-device = デバイス: { $device }
+info-device-none = デバイス: 未選択
+info-reload = 再読み込み
+info-copy-all = すべてコピー
+info-copy = コピー
+info-bugreport = バグレポート
+info-bugreport-title = バグレポートを保存
+info-properties = プロパティ { $count } 件
+info-status-loading = 状態: 読み込み中
+info-status-bugreport-capturing = 状態: バグレポートを取得中
+info-status-bugreport-saved = 状態: { $path } に保存しました
+info-status-error = 状態: { $error }
+
+layout-editor-profile = レイアウト:
+layout-editor-help = キーをグリッドにドラッグします。ボタンをクリックするとサイズを変更できます。
+layout-editor-grid = グリッド
+layout-editor-grid-empty = グリッドにボタンがない間はセクションを表示します
+layout-editor-new-row = + 新しい行
+layout-editor-remove = 削除
+layout-editor-size = サイズ
+layout-editor-use-sections = セクションを使う
+
+settings-reload = 設定を再読み込み
+settings-open-dir = 設定のディレクトリを開く
+settings-keycode-references = KeyCode のリファレンスを開く
+settings-copy-keycode = キーコードをコピー:
+settings-theme = テーマ:
+settings-theme-light = ライト
+settings-theme-dark = ダーク
+settings-language = 言語:
+settings-language-font = フォントは再起動後に反映されます
+settings-adb = adb:
+settings-adb-placeholder = 自動で検出
+settings-adb-pick-title = adb を選択
+settings-browse = 参照
+settings-adb-server = adb サーバー:
+settings-adb-server-kill = 停止
+settings-adb-server-start = 起動
+settings-adb-server-version-button = バージョン
+settings-adb-server-running = 実行中...
+settings-adb-server-killed = adb サーバーを停止しました
+settings-adb-server-started = adb サーバーが起動しています。デバイスを再読み込みしてください
+settings-adb-server-version = adb { $version }
+settings-recording = 録画:
+settings-recording-secs = 秒
+settings-recording-bit-rate = 既定
+settings-recording-secs-unit = 秒 
+settings-recording-bit-rate-unit = Mbps
+settings-recording-pick-title = 録画を保存するディレクトリを選択
+settings-api-port = REST API のポート:
+settings-api-port-placeholder = 無効
+settings-api-token = トークン:
+settings-api-token-copy = コピー
+settings-api-observer-token = 閲覧専用のオブザーバー用トークン:
+settings-save-to = 保存先:
+settings-save-to-placeholder = 一時ディレクトリ
+settings-notification = ウィンドウが非アクティブのときに通知する
+settings-confirm-devices = 新しいデバイスに接続する前に確認する
+settings-confirm-power = 電源キーを送信する前に確認する
+settings-hold-buttons = 押している間 down と up を送る
+settings-check-updates = 起動時に更新を確認する
+settings-layout = レイアウト:
+settings-section-color-keys = カラーキー
+settings-section-dpad = 十字キー
+settings-section-navigation = ナビゲーション
+settings-section-numpad = テンキー
+settings-section-media = メディア
+settings-section-power = 電源
+settings-section-tv = TV
+settings-section-custom-keys = カスタムキー
+settings-button-size-small = 小
+settings-button-size-medium = 中
+settings-button-size-large = 大
+settings-import-profile = クリップボードからプロファイルを読み込む
+settings-import-profile-imported = { $serial } の { $layout } をインポートしました
+settings-import-profile-failed = インポートに失敗しました: { $error }
+settings-up = 上へ
+settings-down = 下へ
+settings-shortcuts = ショートカット:
+settings-unpin = 外す
+settings-pin = 追加
+settings-shortcut-label = ラベル
+settings-shortcut-target = パッケージかディープリンク
+settings-custom-keys = カスタムキー:
+settings-custom-key-label = ラベル
+settings-custom-key-package = パッケージ (任意)
+settings-custom-key-add = 追加
+settings-custom-key-remove = 削除
+settings-test = テスト
+settings-key-map = キーマップ:
+settings-invalid-keycode = { $location }: { $keycode } は不明なキーコードです
+settings-invalid-keycode-suggestion = { $location }: { $keycode } は不明なキーコードです。{ $suggestion } ではありませんか?
+settings-key-map-profile-name = プロファイル名
+settings-key-map-profile-save = 保存
+settings-key-map-profile-delete = 削除
+settings-key-layout = 端末のキーレイアウト:
+settings-key-layout-list = 一覧
+settings-key-layout-no-device = シェルのある端末を選択してください
+settings-key-layout-loading = 読み込み中
+settings-key-layout-found = { $name } のキー { $count } 個
+settings-key-layout-failed = 読み込みに失敗しました: { $error }
+settings-key-layout-save = キーマッププロファイルとして保存
+settings-update-available = バージョン { $version } が公開されています
+settings-update-open = リリースページを開く
+
+toast-error = エラー: { $error }
+toast-dismiss = x
+
+notification-error = エラー
+notification-warning = 警告
+notification-connected = 接続
+notification-connected-to = { $device } に接続しました
+notification-connected-body = 接続しました
+notification-connect-failed = 接続に失敗しました
+notification-connection-degraded = 接続が不安定です
+notification-server-crashed = サーバーがクラッシュしました
+notification-reconnect-failed = 再接続に失敗しました
+notification-reboot = 再起動
+notification-reboot-booted = { $serial } が起動しました
+notification-recording = 録画
+notification-self-test = セルフテスト
+notification-self-test-passed = { $serial }: 成功
+notification-self-test-failed = { $serial }: 失敗
+notification-macro = マクロ
+notification-macro-done = 完了 ({ $steps } ステップ)
+notification-macro-failed = 失敗: { $reason }
+notification-monkey = Monkey
+notification-monkey-finished = { $package } が完了しました
+notification-monkey-exit-code = { $package } が中断しました (終了コード { $code })
+notification-monkey-aborted = { $package } が中断しました
+notification-apps = アプリ
+notification-apps-finished = { $package } の{ $action }が完了しました
+notification-apps-failed = { $package } の{ $action }に失敗しました: { $error }
+notification-install = インストール
+notification-install-finished = { $path } をインストールしました
+notification-install-failed = { $path } のインストールに失敗しました: { $error }
+notification-files = ファイル
+notification-files-pushed = { $path } に転送しました
+notification-files-push-failed = 転送に失敗しました: { $error }
+notification-files-pulled = { $path } に取得しました
+notification-files-pull-failed = 取得に失敗しました: { $error }
+notification-bugreport = バグレポート
+notification-bugreport-saved = { $path } に保存しました
+notification-bugreport-failed = 取得に失敗しました: { $error }
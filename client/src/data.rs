@@ -15,5 +15,10 @@
  */
 
 pub mod asset;
+pub mod command_target;
+pub mod i18n;
+pub mod icon;
 pub mod preferences_repository;
+pub mod preferences_store;
 pub mod resource;
+pub mod session_dir;
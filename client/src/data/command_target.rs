@@ -0,0 +1,65 @@
+/*
+ * Copyright 2022 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::function::atv_remote_functions::RemoteConnection;
+#[cfg(feature = "cec")]
+use crate::function::cec_functions::CecAdapter;
+use crate::function::roku_functions::RokuDevice;
+use crate::model::protocol::ServerCommand;
+use crate::model::target::{DeviceCapabilities, DeviceKind};
+use crate::prelude::*;
+use std::path::Path;
+
+/// a device that receives the [ServerCommand]s without the adb server.
+#[async_trait::async_trait]
+pub trait CommandTarget: Send {
+    /// resolved when connected.
+    fn capabilities(&self) -> DeviceCapabilities;
+
+    async fn send(&mut self, command: &ServerCommand) -> Fallible<()>;
+
+    /// returns the reason when the device closes the connection.
+    ///
+    /// cancel safe.
+    async fn closed(&mut self) -> String {
+        std::future::pending().await
+    }
+
+    async fn close(&mut self) {}
+}
+
+/// connects to the device that is driven without adb.
+pub async fn connect_target(
+    kind: &DeviceKind,
+    config_dir: &Path,
+) -> Fallible<Box<dyn CommandTarget>> {
+    match kind {
+        DeviceKind::Adb => anyhow::bail!("adb device needs the server"),
+        DeviceKind::AndroidTvRemote { host } => {
+            Ok(Box::new(RemoteConnection::connect(host, config_dir).await?))
+        }
+        #[cfg(feature = "cec")]
+        DeviceKind::Cec {
+            port,
+            logical_address,
+        } => Ok(Box::new(
+            CecAdapter::open(port.clone(), *logical_address).await?,
+        )),
+        #[cfg(not(feature = "cec"))]
+        DeviceKind::Cec { .. } => anyhow::bail!("built without the cec feature"),
+        DeviceKind::Roku { host } => Ok(Box::new(RokuDevice::connect(host.clone()).await?)),
+    }
+}
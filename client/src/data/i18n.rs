@@ -0,0 +1,183 @@
+/*
+ * Copyright 2022 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! the UI strings in `resources/i18n/{code}.ftl` of the Fluent syntax.
+
+use crate::data::resource::Resource;
+use crate::model::language::Language;
+use fluent_bundle::concurrent::FluentBundle;
+use fluent_bundle::{FluentArgs, FluentResource};
+use std::sync::{Mutex, MutexGuard};
+use tracing::{debug, info, warn};
+
+static LANGUAGE: Mutex<Language> = Mutex::new(Language::English);
+
+/// the bundles are loaded on the first use of each language.
+static BUNDLES: Mutex<Vec<(Language, FluentBundle<FluentResource>)>> = Mutex::new(Vec::new());
+
+/// the fonts that have the Japanese glyphs on each platform.
+const JAPANESE_FONTS: &[&str] = &[
+    "C:\\Windows\\Fonts\\YuGothM.ttc",
+    "C:\\Windows\\Fonts\\meiryo.ttc",
+    "/System/Library/Fonts/ヒラギノ角ゴシック W3.ttc",
+    "/System/Library/Fonts/Hiragino Sans GB.ttc",
+    "/usr/share/fonts/opentype/noto/NotoSansCJK-Regular.ttc",
+    "/usr/share/fonts/noto-cjk/NotoSansCJK-Regular.ttc",
+    "/usr/share/fonts/google-noto-cjk/NotoSansCJK-Regular.ttc",
+];
+
+pub fn set_language(language: Language) {
+    info!(?language, "set language");
+    *LANGUAGE.lock().unwrap_or_else(|data| data.into_inner()) = language;
+}
+
+/// returns the string of the current language, or English if it isn't translated.
+pub fn tr(id: &str) -> String {
+    tr_args(id, &[])
+}
+
+/// replaces `{ $name }` of the message with the `args`.
+pub fn tr_args(id: &str, args: &[(&str, String)]) -> String {
+    let language = *LANGUAGE.lock().unwrap_or_else(|data| data.into_inner());
+    let mut bundles = bundles();
+
+    for language in [language, Language::English] {
+        let bundle = match bundles.iter().position(|(data, _)| *data == language) {
+            Some(index) => &bundles[index].1,
+            None => {
+                bundles.push((language, load_bundle(language)));
+                &bundles[bundles.len() - 1].1
+            }
+        };
+        if let Some(data) = format_message(bundle, id, args) {
+            return data;
+        }
+    }
+
+    debug!(id, "message not found");
+    id.into()
+}
+
+/// the font that the UI needs for the language instead of the default font.
+///
+/// the font is leaked since it is used until the process exits.
+pub fn load_font(language: Language) -> Option<&'static [u8]> {
+    let candidates = match language {
+        Language::English => return None,
+        Language::Japanese => JAPANESE_FONTS,
+    };
+
+    for path in candidates {
+        if let Ok(data) = std::fs::read(path) {
+            info!(path, "use the font");
+            return Some(Box::leak(data.into_boxed_slice()));
+        }
+    }
+
+    warn!(?language, "font not found");
+    None
+}
+
+fn bundles() -> MutexGuard<'static, Vec<(Language, FluentBundle<FluentResource>)>> {
+    BUNDLES.lock().unwrap_or_else(|data| data.into_inner())
+}
+
+fn load_bundle(language: Language) -> FluentBundle<FluentResource> {
+    let mut bundle = FluentBundle::new_concurrent(vec![language
+        .code()
+        .parse()
+        .expect("invalid language code")]);
+    // the isolating marks are shown as the boxes.
+    bundle.set_use_isolating(false);
+
+    let source = match Resource::get(&format!("i18n/{}.ftl", language.code())) {
+        Some(data) => String::from_utf8_lossy(&data.data).into_owned(),
+        None => {
+            warn!(?language, "bundle not found");
+            return bundle;
+        }
+    };
+
+    let resource = match FluentResource::try_new(source) {
+        Ok(data) => data,
+        Err((data, e)) => {
+            warn!(?e, ?language, "failed to parse the bundle partially");
+            data
+        }
+    };
+    if let Err(e) = bundle.add_resource(resource) {
+        warn!(?e, ?language, "failed to add the bundle");
+    }
+    bundle
+}
+
+fn format_message(
+    bundle: &FluentBundle<FluentResource>,
+    id: &str,
+    args: &[(&str, String)],
+) -> Option<String> {
+    let pattern = bundle.get_message(id)?.value()?;
+
+    let mut fluent_args = FluentArgs::new();
+    for (name, value) in args {
+        fluent_args.set(*name, value.clone());
+    }
+
+    let mut errors = vec![];
+    let ret = bundle.format_pattern(pattern, Some(&fluent_args), &mut errors);
+    if !errors.is_empty() {
+        warn!(?errors, id, "failed to format the message");
+    }
+    Some(ret.into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message_ids(language: Language) -> Vec<String> {
+        let source = Resource::get(&format!("i18n/{}.ftl", language.code())).unwrap();
+        String::from_utf8_lossy(&source.data)
+            .lines()
+            .filter_map(|line| line.split_once(" = "))
+            .map(|(id, _)| id.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn bundles_have_same_messages() {
+        let expected = message_ids(Language::English);
+        assert!(!expected.is_empty());
+        for language in Language::ALL {
+            assert_eq!(expected, message_ids(language), "{}", language);
+        }
+    }
+
+    #[test]
+    fn format_message_args() {
+        let bundle = load_bundle(Language::Japanese);
+        let args = [
+            ("secs", "3".to_string()),
+            ("retry", "1".to_string()),
+            ("max", "5".to_string()),
+        ];
+        assert_eq!(
+            Some("状態: 3 秒後に再接続 (1/5)".into()),
+            format_message(&bundle, "main-status-reconnecting-in", &args)
+        );
+        assert_eq!(None, format_message(&bundle, "unknown", &[]));
+    }
+}
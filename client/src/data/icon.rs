@@ -0,0 +1,73 @@
+/*
+ * Copyright 2022 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::data::resource::Resource;
+use crate::model::AppTheme;
+use iced::widget::svg::Handle as SvgHandle;
+use tracing::warn;
+
+/// shown instead of the icon that could not be loaded.
+const FALLBACK_SVG: &str = r#"<svg width="24" height="24" viewBox="0 0 24 24" fill="none" xmlns="http://www.w3.org/2000/svg">
+<rect x="4.5" y="4.5" width="15" height="15" rx="2" stroke="currentColor" stroke-width="1.5"/>
+</svg>"#;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Icon {
+    ArrowPath,
+    ChevronDown,
+    ChevronRight,
+    Link,
+    Play,
+}
+
+impl Icon {
+    fn file_name(&self) -> &'static str {
+        match self {
+            Icon::ArrowPath => "arrow-path.svg",
+            Icon::ChevronDown => "chevron-down.svg",
+            Icon::ChevronRight => "chevron-right.svg",
+            Icon::Link => "link.svg",
+            Icon::Play => "play.svg",
+        }
+    }
+
+    /// creates the handle with replacing `currentColor` by the foreground color of the theme.
+    ///
+    /// the icons are read from the `resources` directory each time on debug build, so that
+    /// editing the icons is reflected on the next redraw.
+    pub fn handle(&self, theme: &AppTheme) -> SvgHandle {
+        let color = match theme {
+            AppTheme::Light => "#0F172A",
+            AppTheme::Dark => "#E2E8F0",
+        };
+
+        let svg = match Resource::get(self.file_name())
+            .map(|data| String::from_utf8(data.data.into_owned()))
+        {
+            Some(Ok(data)) => data,
+            Some(Err(e)) => {
+                warn!(?e, icon = ?self, "failed to decode icon");
+                FALLBACK_SVG.to_string()
+            }
+            None => {
+                warn!(icon = ?self, "icon not found");
+                FALLBACK_SVG.to_string()
+            }
+        };
+
+        SvgHandle::from_memory(svg.replace("currentColor", color).into_bytes())
+    }
+}
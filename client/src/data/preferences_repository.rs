@@ -14,12 +14,29 @@
  * limitations under the License.
  */
 
-use crate::model::{AppTheme, FileVersion, KeyMap, Preferences};
+use crate::model::gesture::{ButtonGesture, LongPressMode};
+use crate::model::intent::{IntentExtra, IntentExtraKind, IntentSpec};
+use crate::model::key_macro::{format_macro, parse_macro, SavedMacro};
+use crate::model::language::Language;
+use crate::model::layout::{
+    ButtonSize, GridButton, LaunchShortcut, LayoutProfile, SectionConfig, SectionId,
+};
+use crate::model::mqtt::MqttConfig;
+use crate::model::schedule::ScheduledMacro;
+use crate::model::screen_record::ScreenRecordConfig;
+use crate::model::send_event_key::SendEventKey;
+use crate::model::shortcut::{KeyShortcut, Shortcut};
+use crate::model::target::{CecTarget, NetworkTarget, NetworkTargetKind};
+use crate::model::webhook::{Webhook, WebhookEventKind};
+use crate::model::{AppTheme, CustomKey, FileVersion, KeyMap, KeyMapProfile, Preferences};
 use crate::prelude::*;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::PathBuf;
+use std::time::Duration;
 use tokio::fs::{create_dir_all, File};
 use tokio::io::{AsyncReadExt, AsyncWriteExt, BufReader, BufWriter};
+use tracing::warn;
 
 #[async_trait::async_trait]
 pub trait PreferencesRepository: Send + Sync {
@@ -77,9 +94,16 @@ impl PreferencesRepository for PreferencesRepositoryImpl {
             .await
             .context("failed to load preferences file")?;
 
-        Ok(toml::from_str::<PrefsDto>(&prefs_string)
-            .with_context(|| format!("failed to parse preferences: {}", prefs_string))?
-            .into())
+        let prefs = Preferences::from(
+            toml::from_str::<PrefsDto>(&prefs_string)
+                .with_context(|| format!("failed to parse preferences: {}", prefs_string))?,
+        );
+
+        for data in prefs.invalid_keycodes() {
+            warn!(location = %data.location, keycode = %data.keycode, suggestion = ?data.suggestion(), "invalid keycode");
+        }
+
+        Ok(prefs)
     }
 
     async fn save(&self, data: Preferences) -> Fallible<()> {
@@ -110,7 +134,54 @@ impl PreferencesRepository for MockPreferencesRepository {
 struct PrefsDto {
     version: FileVersion,
     theme: Option<ThemeDto>,
+    language: Option<LanguageDto>,
+    notification: Option<bool>,
+    check_updates: Option<bool>,
+    server_auto_build: Option<bool>,
+    server_source_dir: Option<PathBuf>,
+    adb_path: Option<PathBuf>,
+    api_port: Option<u16>,
+    haptic_devices: Option<Vec<String>>,
+    approved_serials: Option<Vec<String>>,
+    blocked_serials: Option<Vec<String>>,
+    confirm_power_key: Option<bool>,
+    confirm_unknown_devices: Option<bool>,
+    hold_buttons: Option<bool>,
+    collapsed_sections: Option<Vec<SectionIdDto>>,
+    key_repeat_interval_ms: Option<u64>,
+    keyboard_repeat_delay_ms: Option<u64>,
+    // 0 disables the repeat of the keyboard.
+    keyboard_repeat_interval_ms: Option<u64>,
+    key_map_profile: Option<String>,
+    accessibility_services: Option<Vec<String>>,
+    recording_bit_rate_mbps: Option<u32>,
+    recording_dir: Option<PathBuf>,
+    recording_time_limit_secs: Option<u64>,
+    // needs to be placed before the tables for serializing an empty array.
+    custom_keys: Option<Vec<CustomKeyDto>>,
+    layout_profiles: Option<Vec<LayoutProfileDto>>,
+    // omitted if empty for serializing after the array of tables.
+    gestures: Option<Vec<ButtonGestureDto>>,
+    // omitted if empty for serializing after the array of tables.
+    network_targets: Option<Vec<NetworkTargetDto>>,
+    // omitted if empty for serializing after the array of tables.
+    cec_targets: Option<Vec<CecTargetDto>>,
+    // omitted if empty for serializing after the array of tables.
+    scheduled_macros: Option<Vec<ScheduledMacroDto>>,
+    // omitted if empty for serializing after the array of tables.
+    webhooks: Option<Vec<WebhookDto>>,
+    // omitted if empty for serializing after the array of tables.
+    intent_history: Option<Vec<IntentSpecDto>>,
+    // omitted if empty for serializing after the array of tables.
+    saved_macros: Option<Vec<SavedMacroDto>>,
+    // omitted if empty for serializing after the array of tables.
+    key_map_profiles: Option<Vec<KeyMapProfileDto>>,
+    mqtt: Option<MqttDto>,
     key_map: KeyMapDto,
+    device_labels: Option<BTreeMap<String, String>>,
+    device_layouts: Option<BTreeMap<String, String>>,
+    /// the shortcut like `ctrl+r` to the id of [SendEventKey].
+    key_shortcuts: Option<BTreeMap<String, String>>,
 }
 
 impl From<Preferences> for PrefsDto {
@@ -118,7 +189,148 @@ impl From<Preferences> for PrefsDto {
         Self {
             version: env!("CARGO_PKG_VERSION").parse().unwrap(),
             theme: Some(ThemeDto::from(value.theme)),
+            language: Some(LanguageDto::from(value.language)),
+            notification: Some(value.notification),
+            check_updates: Some(value.check_updates),
+            server_auto_build: Some(value.server_auto_build),
+            server_source_dir: value.server_source_dir,
+            adb_path: value.adb_path,
+            api_port: value.api_port,
+            haptic_devices: Some(value.haptic_devices.into_iter().collect()),
+            approved_serials: Some(value.approved_serials.into_iter().collect()),
+            blocked_serials: Some(value.blocked_serials.into_iter().collect()),
+            confirm_power_key: Some(value.confirm_power_key),
+            confirm_unknown_devices: Some(value.confirm_unknown_devices),
+            hold_buttons: Some(value.hold_buttons),
+            collapsed_sections: Some(
+                value
+                    .collapsed_sections
+                    .into_iter()
+                    .map(SectionIdDto::from)
+                    .collect(),
+            ),
+            key_repeat_interval_ms: value
+                .key_repeat_interval
+                .map(|data| data.as_millis() as u64),
+            keyboard_repeat_delay_ms: Some(value.keyboard_repeat_delay.as_millis() as u64),
+            keyboard_repeat_interval_ms: Some(
+                value
+                    .keyboard_repeat_interval
+                    .map(|data| data.as_millis() as u64)
+                    .unwrap_or(0),
+            ),
+            key_map_profile: value.key_map_profile,
+            accessibility_services: Some(value.accessibility_services),
+            recording_bit_rate_mbps: value.recording_bit_rate_mbps,
+            recording_dir: value.recording_dir,
+            recording_time_limit_secs: Some(value.recording_time_limit.as_secs()),
+            custom_keys: Some(
+                value
+                    .custom_keys
+                    .into_iter()
+                    .map(CustomKeyDto::from)
+                    .collect(),
+            ),
+            layout_profiles: Some(
+                value
+                    .layout_profiles
+                    .into_iter()
+                    .map(LayoutProfileDto::from)
+                    .collect(),
+            ),
+            gestures: if value.gestures.is_empty() {
+                None
+            } else {
+                Some(
+                    value
+                        .gestures
+                        .into_iter()
+                        .map(ButtonGestureDto::from)
+                        .collect(),
+                )
+            },
+            network_targets: if value.network_targets.is_empty() {
+                None
+            } else {
+                Some(
+                    value
+                        .network_targets
+                        .into_iter()
+                        .map(NetworkTargetDto::from)
+                        .collect(),
+                )
+            },
+            cec_targets: if value.cec_targets.is_empty() {
+                None
+            } else {
+                Some(
+                    value
+                        .cec_targets
+                        .into_iter()
+                        .map(CecTargetDto::from)
+                        .collect(),
+                )
+            },
+            scheduled_macros: if value.scheduled_macros.is_empty() {
+                None
+            } else {
+                Some(
+                    value
+                        .scheduled_macros
+                        .into_iter()
+                        .map(ScheduledMacroDto::from)
+                        .collect(),
+                )
+            },
+            webhooks: if value.webhooks.is_empty() {
+                None
+            } else {
+                Some(value.webhooks.into_iter().map(WebhookDto::from).collect())
+            },
+            intent_history: if value.intent_history.is_empty() {
+                None
+            } else {
+                Some(
+                    value
+                        .intent_history
+                        .into_iter()
+                        .map(IntentSpecDto::from)
+                        .collect(),
+                )
+            },
+            saved_macros: if value.saved_macros.is_empty() {
+                None
+            } else {
+                Some(
+                    value
+                        .saved_macros
+                        .into_iter()
+                        .map(SavedMacroDto::from)
+                        .collect(),
+                )
+            },
+            key_map_profiles: if value.key_map_profiles.is_empty() {
+                None
+            } else {
+                Some(
+                    value
+                        .key_map_profiles
+                        .into_iter()
+                        .map(KeyMapProfileDto::from)
+                        .collect(),
+                )
+            },
+            mqtt: value.mqtt.map(MqttDto::from),
             key_map: KeyMapDto::from(value.key_map),
+            device_labels: Some(value.device_labels),
+            device_layouts: Some(value.device_layouts),
+            key_shortcuts: Some(
+                value
+                    .key_shortcuts
+                    .into_iter()
+                    .map(|data| (data.shortcut.to_string(), data.key.id().into()))
+                    .collect(),
+            ),
         }
     }
 }
@@ -126,8 +338,142 @@ impl From<Preferences> for PrefsDto {
 impl From<PrefsDto> for Preferences {
     fn from(value: PrefsDto) -> Self {
         Self {
+            accessibility_services: value.accessibility_services.unwrap_or_default(),
+            adb_path: value.adb_path,
+            api_port: value.api_port,
+            approved_serials: value
+                .approved_serials
+                .unwrap_or_default()
+                .into_iter()
+                .collect(),
+            blocked_serials: value
+                .blocked_serials
+                .unwrap_or_default()
+                .into_iter()
+                .collect(),
+            cec_targets: value
+                .cec_targets
+                .unwrap_or_default()
+                .into_iter()
+                .map(CecTarget::from)
+                .collect(),
+            check_updates: value.check_updates.unwrap_or(true),
+            collapsed_sections: value
+                .collapsed_sections
+                .unwrap_or_default()
+                .into_iter()
+                .map(SectionId::from)
+                .collect(),
+            confirm_power_key: value.confirm_power_key.unwrap_or(true),
+            confirm_unknown_devices: value.confirm_unknown_devices.unwrap_or(true),
+            custom_keys: value
+                .custom_keys
+                .unwrap_or_default()
+                .into_iter()
+                .map(CustomKey::from)
+                .collect(),
+            device_labels: value.device_labels.unwrap_or_default(),
+            device_layouts: value.device_layouts.unwrap_or_default(),
+            gestures: value
+                .gestures
+                .unwrap_or_default()
+                .into_iter()
+                .map(ButtonGesture::from)
+                .collect(),
+            haptic_devices: value
+                .haptic_devices
+                .unwrap_or_default()
+                .into_iter()
+                .collect(),
+            hold_buttons: value.hold_buttons.unwrap_or_default(),
+            intent_history: value
+                .intent_history
+                .unwrap_or_default()
+                .into_iter()
+                .map(IntentSpec::from)
+                .collect(),
             key_map: KeyMap::from(value.key_map),
+            key_map_profile: value.key_map_profile,
+            key_map_profiles: value
+                .key_map_profiles
+                .unwrap_or_default()
+                .into_iter()
+                .map(KeyMapProfile::from)
+                .collect(),
+            key_repeat_interval: value.key_repeat_interval_ms.map(Duration::from_millis),
+            key_shortcuts: value
+                .key_shortcuts
+                .unwrap_or_default()
+                .into_iter()
+                .filter_map(|(shortcut, key)| match shortcut.parse::<Shortcut>() {
+                    Ok(data) => Some(KeyShortcut {
+                        shortcut: data,
+                        key: SendEventKey::from_id(&key),
+                    }),
+                    Err(e) => {
+                        warn!(?e, %shortcut, "skip the invalid shortcut");
+                        None
+                    }
+                })
+                .collect(),
+            keyboard_repeat_delay: value
+                .keyboard_repeat_delay_ms
+                .map(Duration::from_millis)
+                .unwrap_or(Duration::from_millis(400)),
+            keyboard_repeat_interval: match value.keyboard_repeat_interval_ms {
+                Some(0) => None,
+                Some(data) => Some(Duration::from_millis(data)),
+                None => Some(Duration::from_millis(50)),
+            },
+            language: value.language.map(Language::from).unwrap_or_default(),
+            layout_profiles: match value.layout_profiles {
+                Some(data) => data.into_iter().map(LayoutProfile::from).collect(),
+                None => LayoutProfile::default_profiles(),
+            },
+            mqtt: value.mqtt.map(MqttConfig::from),
+            network_targets: value
+                .network_targets
+                .unwrap_or_default()
+                .into_iter()
+                .map(NetworkTarget::from)
+                .collect(),
+            notification: value.notification.unwrap_or(true),
+            recording_bit_rate_mbps: value.recording_bit_rate_mbps,
+            recording_dir: value.recording_dir,
+            recording_time_limit: value
+                .recording_time_limit_secs
+                .map(Duration::from_secs)
+                .unwrap_or(ScreenRecordConfig::MAX_TIME_LIMIT),
+            saved_macros: value
+                .saved_macros
+                .unwrap_or_default()
+                .into_iter()
+                .filter_map(|data| match parse_macro(&data.source) {
+                    Ok(steps) => Some(SavedMacro {
+                        name: data.name,
+                        steps,
+                    }),
+                    Err(e) => {
+                        warn!(?e, name = %data.name, "skip the invalid macro");
+                        None
+                    }
+                })
+                .collect(),
+            scheduled_macros: value
+                .scheduled_macros
+                .unwrap_or_default()
+                .into_iter()
+                .map(ScheduledMacro::from)
+                .collect(),
+            server_auto_build: value.server_auto_build.unwrap_or_default(),
+            server_source_dir: value.server_source_dir,
             theme: value.theme.map(AppTheme::from).unwrap_or_default(),
+            webhooks: value
+                .webhooks
+                .unwrap_or_default()
+                .into_iter()
+                .map(Webhook::from)
+                .collect(),
         }
     }
 }
@@ -155,6 +501,26 @@ struct KeyMapDto {
     num_9: String,
     back: String,
     home: String,
+    volume_up: String,
+    volume_down: String,
+    mute: String,
+    media_play_pause: String,
+    media_stop: String,
+    media_rewind: String,
+    media_fast_forward: String,
+    media_next: String,
+    media_previous: String,
+    power: String,
+    sleep: String,
+    wakeup: String,
+    tv_input: String,
+    guide: String,
+    info: String,
+    channel_up: String,
+    channel_down: String,
+    assist: String,
+    // omitted if empty, and placed last for serializing the table after the keycodes.
+    long_keycodes: Option<BTreeMap<String, String>>,
 }
 
 impl From<KeyMapDto> for KeyMap {
@@ -181,6 +547,25 @@ impl From<KeyMapDto> for KeyMap {
             num_8: value.num_8,
             num_9: value.num_9,
             home: value.home,
+            volume_up: value.volume_up,
+            volume_down: value.volume_down,
+            mute: value.mute,
+            media_play_pause: value.media_play_pause,
+            media_stop: value.media_stop,
+            media_rewind: value.media_rewind,
+            media_fast_forward: value.media_fast_forward,
+            media_next: value.media_next,
+            media_previous: value.media_previous,
+            power: value.power,
+            sleep: value.sleep,
+            wakeup: value.wakeup,
+            tv_input: value.tv_input,
+            guide: value.guide,
+            info: value.info,
+            channel_up: value.channel_up,
+            channel_down: value.channel_down,
+            assist: value.assist,
+            long_keycodes: value.long_keycodes.unwrap_or_default(),
         }
     }
 }
@@ -209,6 +594,695 @@ impl From<KeyMap> for KeyMapDto {
             num_8: value.num_8,
             num_9: value.num_9,
             home: value.home,
+            volume_up: value.volume_up,
+            volume_down: value.volume_down,
+            mute: value.mute,
+            media_play_pause: value.media_play_pause,
+            media_stop: value.media_stop,
+            media_rewind: value.media_rewind,
+            media_fast_forward: value.media_fast_forward,
+            media_next: value.media_next,
+            media_previous: value.media_previous,
+            power: value.power,
+            sleep: value.sleep,
+            wakeup: value.wakeup,
+            tv_input: value.tv_input,
+            guide: value.guide,
+            info: value.info,
+            channel_up: value.channel_up,
+            channel_down: value.channel_down,
+            assist: value.assist,
+            long_keycodes: if value.long_keycodes.is_empty() {
+                None
+            } else {
+                Some(value.long_keycodes)
+            },
+        }
+    }
+}
+
+#[derive(Deserialize, Eq, PartialEq, Serialize)]
+struct KeyMapProfileDto {
+    name: String,
+    key_map: KeyMapDto,
+}
+
+impl From<KeyMapProfileDto> for KeyMapProfile {
+    fn from(value: KeyMapProfileDto) -> Self {
+        Self {
+            name: value.name,
+            key_map: KeyMap::from(value.key_map),
+        }
+    }
+}
+
+impl From<KeyMapProfile> for KeyMapProfileDto {
+    fn from(value: KeyMapProfile) -> Self {
+        Self {
+            name: value.name,
+            key_map: KeyMapDto::from(value.key_map),
+        }
+    }
+}
+
+#[derive(Deserialize, Eq, PartialEq, Serialize)]
+struct CustomKeyDto {
+    label: String,
+    keycode: String,
+    package: Option<String>,
+    long_keycode: Option<String>,
+}
+
+impl From<CustomKeyDto> for CustomKey {
+    fn from(value: CustomKeyDto) -> Self {
+        Self {
+            label: value.label,
+            keycode: value.keycode,
+            package: value.package,
+            long_keycode: value.long_keycode,
+        }
+    }
+}
+
+impl From<CustomKey> for CustomKeyDto {
+    fn from(value: CustomKey) -> Self {
+        Self {
+            label: value.label,
+            keycode: value.keycode,
+            package: value.package,
+            long_keycode: value.long_keycode,
+        }
+    }
+}
+
+#[derive(Deserialize, Eq, PartialEq, Serialize)]
+struct ButtonGestureDto {
+    keycode: String,
+    double_click: Option<String>,
+    ctrl_click_long_press: Option<bool>,
+    long_press: Option<LongPressModeDto>,
+}
+
+impl From<ButtonGestureDto> for ButtonGesture {
+    fn from(value: ButtonGestureDto) -> Self {
+        Self {
+            keycode: value.keycode,
+            double_click: value.double_click,
+            ctrl_click_long_press: value.ctrl_click_long_press.unwrap_or_default(),
+            long_press: value
+                .long_press
+                .map(LongPressMode::from)
+                .unwrap_or_default(),
+        }
+    }
+}
+
+impl From<ButtonGesture> for ButtonGestureDto {
+    fn from(value: ButtonGesture) -> Self {
+        Self {
+            keycode: value.keycode,
+            double_click: value.double_click,
+            ctrl_click_long_press: Some(value.ctrl_click_long_press),
+            long_press: Some(LongPressModeDto::from(value.long_press)),
+        }
+    }
+}
+
+#[derive(Deserialize, Eq, PartialEq, Serialize)]
+enum LongPressModeDto {
+    Flag,
+    Repeat,
+}
+
+impl From<LongPressModeDto> for LongPressMode {
+    fn from(value: LongPressModeDto) -> Self {
+        match value {
+            LongPressModeDto::Flag => LongPressMode::Flag,
+            LongPressModeDto::Repeat => LongPressMode::Repeat,
+        }
+    }
+}
+
+impl From<LongPressMode> for LongPressModeDto {
+    fn from(value: LongPressMode) -> Self {
+        match value {
+            LongPressMode::Flag => LongPressModeDto::Flag,
+            LongPressMode::Repeat => LongPressModeDto::Repeat,
+        }
+    }
+}
+
+#[derive(Deserialize, Eq, PartialEq, Serialize)]
+struct NetworkTargetDto {
+    kind: NetworkTargetKindDto,
+    name: String,
+    host: String,
+}
+
+impl From<NetworkTargetDto> for NetworkTarget {
+    fn from(value: NetworkTargetDto) -> Self {
+        Self {
+            kind: value.kind.into(),
+            name: value.name,
+            host: value.host,
+        }
+    }
+}
+
+impl From<NetworkTarget> for NetworkTargetDto {
+    fn from(value: NetworkTarget) -> Self {
+        Self {
+            kind: value.kind.into(),
+            name: value.name,
+            host: value.host,
+        }
+    }
+}
+
+#[derive(Deserialize, Eq, PartialEq, Serialize)]
+enum NetworkTargetKindDto {
+    AndroidTvRemote,
+    Roku,
+}
+
+impl From<NetworkTargetKindDto> for NetworkTargetKind {
+    fn from(value: NetworkTargetKindDto) -> Self {
+        match value {
+            NetworkTargetKindDto::AndroidTvRemote => NetworkTargetKind::AndroidTvRemote,
+            NetworkTargetKindDto::Roku => NetworkTargetKind::Roku,
+        }
+    }
+}
+
+impl From<NetworkTargetKind> for NetworkTargetKindDto {
+    fn from(value: NetworkTargetKind) -> Self {
+        match value {
+            NetworkTargetKind::AndroidTvRemote => NetworkTargetKindDto::AndroidTvRemote,
+            NetworkTargetKind::Roku => NetworkTargetKindDto::Roku,
+        }
+    }
+}
+
+#[derive(Deserialize, Eq, PartialEq, Serialize)]
+struct CecTargetDto {
+    name: String,
+    port: String,
+    logical_address: Option<u8>,
+}
+
+impl From<CecTargetDto> for CecTarget {
+    fn from(value: CecTargetDto) -> Self {
+        Self {
+            name: value.name,
+            port: value.port,
+            logical_address: value.logical_address.unwrap_or_default(),
+        }
+    }
+}
+
+impl From<CecTarget> for CecTargetDto {
+    fn from(value: CecTarget) -> Self {
+        Self {
+            name: value.name,
+            port: value.port,
+            logical_address: Some(value.logical_address),
+        }
+    }
+}
+
+#[derive(Deserialize, Eq, PartialEq, Serialize)]
+struct ScheduledMacroDto {
+    name: String,
+    time: String,
+    macro_path: PathBuf,
+    report_dir: PathBuf,
+    webhook_url: Option<String>,
+    serials: Vec<String>,
+}
+
+impl From<ScheduledMacroDto> for ScheduledMacro {
+    fn from(value: ScheduledMacroDto) -> Self {
+        Self {
+            name: value.name,
+            time: value.time,
+            macro_path: value.macro_path,
+            serials: value.serials,
+            report_dir: value.report_dir,
+            webhook_url: value.webhook_url,
+        }
+    }
+}
+
+impl From<ScheduledMacro> for ScheduledMacroDto {
+    fn from(value: ScheduledMacro) -> Self {
+        Self {
+            name: value.name,
+            time: value.time,
+            macro_path: value.macro_path,
+            report_dir: value.report_dir,
+            webhook_url: value.webhook_url,
+            serials: value.serials,
+        }
+    }
+}
+
+#[derive(Deserialize, Eq, PartialEq, Serialize)]
+struct MqttDto {
+    host: String,
+    port: Option<u16>,
+    topic: String,
+    serial: String,
+    username: Option<String>,
+    password: Option<String>,
+}
+
+impl From<MqttDto> for MqttConfig {
+    fn from(value: MqttDto) -> Self {
+        Self {
+            host: value.host,
+            port: value.port.unwrap_or(MqttConfig::DEFAULT_PORT),
+            topic: value.topic,
+            serial: value.serial,
+            username: value.username,
+            password: value.password,
+        }
+    }
+}
+
+impl From<MqttConfig> for MqttDto {
+    fn from(value: MqttConfig) -> Self {
+        Self {
+            host: value.host,
+            port: Some(value.port),
+            topic: value.topic,
+            serial: value.serial,
+            username: value.username,
+            password: value.password,
+        }
+    }
+}
+
+#[derive(Deserialize, Eq, PartialEq, Serialize)]
+struct WebhookDto {
+    url: String,
+    secret: Option<String>,
+    events: Option<Vec<WebhookEventKindDto>>,
+}
+
+impl From<WebhookDto> for Webhook {
+    fn from(value: WebhookDto) -> Self {
+        Self {
+            url: value.url,
+            secret: value.secret,
+            events: value
+                .events
+                .unwrap_or_default()
+                .into_iter()
+                .map(WebhookEventKind::from)
+                .collect(),
+        }
+    }
+}
+
+impl From<Webhook> for WebhookDto {
+    fn from(value: Webhook) -> Self {
+        Self {
+            url: value.url,
+            secret: value.secret,
+            events: Some(
+                value
+                    .events
+                    .into_iter()
+                    .map(WebhookEventKindDto::from)
+                    .collect(),
+            ),
+        }
+    }
+}
+
+#[derive(Deserialize, Eq, PartialEq, Serialize)]
+enum WebhookEventKindDto {
+    Disconnected,
+    MacroFailed,
+    MacroFinished,
+}
+
+impl From<WebhookEventKindDto> for WebhookEventKind {
+    fn from(value: WebhookEventKindDto) -> Self {
+        match value {
+            WebhookEventKindDto::Disconnected => WebhookEventKind::Disconnected,
+            WebhookEventKindDto::MacroFailed => WebhookEventKind::MacroFailed,
+            WebhookEventKindDto::MacroFinished => WebhookEventKind::MacroFinished,
+        }
+    }
+}
+
+impl From<WebhookEventKind> for WebhookEventKindDto {
+    fn from(value: WebhookEventKind) -> Self {
+        match value {
+            WebhookEventKind::Disconnected => WebhookEventKindDto::Disconnected,
+            WebhookEventKind::MacroFailed => WebhookEventKindDto::MacroFailed,
+            WebhookEventKind::MacroFinished => WebhookEventKindDto::MacroFinished,
+        }
+    }
+}
+
+/// the steps are saved as the text of [parse_macro] to be editable by hand.
+#[derive(Deserialize, Eq, PartialEq, Serialize)]
+struct SavedMacroDto {
+    name: String,
+    source: String,
+}
+
+impl From<SavedMacro> for SavedMacroDto {
+    fn from(value: SavedMacro) -> Self {
+        Self {
+            name: value.name,
+            source: format_macro(&value.steps),
+        }
+    }
+}
+
+#[derive(Deserialize, Eq, PartialEq, Serialize)]
+struct IntentSpecDto {
+    action: Option<String>,
+    component: Option<String>,
+    data_uri: Option<String>,
+    // omitted if empty for serializing after the array of tables.
+    extras: Option<Vec<IntentExtraDto>>,
+}
+
+impl From<IntentSpecDto> for IntentSpec {
+    fn from(value: IntentSpecDto) -> Self {
+        Self {
+            action: value.action.unwrap_or_default(),
+            component: value.component.unwrap_or_default(),
+            data_uri: value.data_uri.unwrap_or_default(),
+            extras: value
+                .extras
+                .unwrap_or_default()
+                .into_iter()
+                .map(IntentExtra::from)
+                .collect(),
+        }
+    }
+}
+
+impl From<IntentSpec> for IntentSpecDto {
+    fn from(value: IntentSpec) -> Self {
+        fn non_empty(value: String) -> Option<String> {
+            if value.is_empty() {
+                None
+            } else {
+                Some(value)
+            }
+        }
+
+        Self {
+            action: non_empty(value.action),
+            component: non_empty(value.component),
+            data_uri: non_empty(value.data_uri),
+            extras: if value.extras.is_empty() {
+                None
+            } else {
+                Some(value.extras.into_iter().map(IntentExtraDto::from).collect())
+            },
+        }
+    }
+}
+
+#[derive(Deserialize, Eq, PartialEq, Serialize)]
+struct IntentExtraDto {
+    kind: IntentExtraKindDto,
+    key: String,
+    value: String,
+}
+
+impl From<IntentExtraDto> for IntentExtra {
+    fn from(value: IntentExtraDto) -> Self {
+        Self {
+            kind: value.kind.into(),
+            key: value.key,
+            value: value.value,
+        }
+    }
+}
+
+impl From<IntentExtra> for IntentExtraDto {
+    fn from(value: IntentExtra) -> Self {
+        Self {
+            kind: value.kind.into(),
+            key: value.key,
+            value: value.value,
+        }
+    }
+}
+
+#[derive(Deserialize, Eq, PartialEq, Serialize)]
+enum IntentExtraKindDto {
+    Bool,
+    Int,
+    String,
+}
+
+impl From<IntentExtraKindDto> for IntentExtraKind {
+    fn from(value: IntentExtraKindDto) -> Self {
+        match value {
+            IntentExtraKindDto::Bool => IntentExtraKind::Bool,
+            IntentExtraKindDto::Int => IntentExtraKind::Int,
+            IntentExtraKindDto::String => IntentExtraKind::String,
+        }
+    }
+}
+
+impl From<IntentExtraKind> for IntentExtraKindDto {
+    fn from(value: IntentExtraKind) -> Self {
+        match value {
+            IntentExtraKind::Bool => IntentExtraKindDto::Bool,
+            IntentExtraKind::Int => IntentExtraKindDto::Int,
+            IntentExtraKind::String => IntentExtraKindDto::String,
+        }
+    }
+}
+
+#[derive(Deserialize, Eq, PartialEq, Serialize)]
+struct LayoutProfileDto {
+    name: String,
+    sections: Vec<SectionConfigDto>,
+    // omitted if empty for serializing after the array of tables.
+    shortcuts: Option<Vec<LaunchShortcutDto>>,
+    // omitted if empty for serializing after the array of tables.
+    grid: Option<Vec<GridRowDto>>,
+}
+
+impl From<LayoutProfileDto> for LayoutProfile {
+    fn from(value: LayoutProfileDto) -> Self {
+        Self {
+            name: value.name,
+            sections: value
+                .sections
+                .into_iter()
+                .map(SectionConfig::from)
+                .collect(),
+            shortcuts: value
+                .shortcuts
+                .unwrap_or_default()
+                .into_iter()
+                .map(LaunchShortcut::from)
+                .collect(),
+            grid: value
+                .grid
+                .unwrap_or_default()
+                .into_iter()
+                .map(|data| data.buttons.into_iter().map(GridButton::from).collect())
+                .collect(),
+        }
+    }
+}
+
+impl From<LayoutProfile> for LayoutProfileDto {
+    fn from(value: LayoutProfile) -> Self {
+        Self {
+            name: value.name,
+            sections: value
+                .sections
+                .into_iter()
+                .map(SectionConfigDto::from)
+                .collect(),
+            shortcuts: if value.shortcuts.is_empty() {
+                None
+            } else {
+                Some(
+                    value
+                        .shortcuts
+                        .into_iter()
+                        .map(LaunchShortcutDto::from)
+                        .collect(),
+                )
+            },
+            grid: if value.grid.is_empty() {
+                None
+            } else {
+                Some(
+                    value
+                        .grid
+                        .into_iter()
+                        .map(|data| GridRowDto {
+                            buttons: data.into_iter().map(GridButtonDto::from).collect(),
+                        })
+                        .collect(),
+                )
+            },
+        }
+    }
+}
+
+#[derive(Deserialize, Eq, PartialEq, Serialize)]
+struct GridRowDto {
+    buttons: Vec<GridButtonDto>,
+}
+
+#[derive(Deserialize, Eq, PartialEq, Serialize)]
+struct GridButtonDto {
+    /// a field name of the key map or a keycode.
+    id: String,
+    size: Option<ButtonSizeDto>,
+}
+
+impl From<GridButtonDto> for GridButton {
+    fn from(value: GridButtonDto) -> Self {
+        Self {
+            key: SendEventKey::from_id(&value.id),
+            size: value.size.map(ButtonSize::from).unwrap_or_default(),
+        }
+    }
+}
+
+impl From<GridButton> for GridButtonDto {
+    fn from(value: GridButton) -> Self {
+        Self {
+            id: value.key.id().into(),
+            size: Some(value.size.into()),
+        }
+    }
+}
+
+#[derive(Deserialize, Eq, PartialEq, Serialize)]
+struct LaunchShortcutDto {
+    label: String,
+    target: String,
+}
+
+impl From<LaunchShortcutDto> for LaunchShortcut {
+    fn from(value: LaunchShortcutDto) -> Self {
+        Self {
+            label: value.label,
+            target: value.target,
+        }
+    }
+}
+
+impl From<LaunchShortcut> for LaunchShortcutDto {
+    fn from(value: LaunchShortcut) -> Self {
+        Self {
+            label: value.label,
+            target: value.target,
+        }
+    }
+}
+
+#[derive(Deserialize, Eq, PartialEq, Serialize)]
+struct SectionConfigDto {
+    id: SectionIdDto,
+    visible: bool,
+    button_size: ButtonSizeDto,
+}
+
+impl From<SectionConfigDto> for SectionConfig {
+    fn from(value: SectionConfigDto) -> Self {
+        Self {
+            id: value.id.into(),
+            visible: value.visible,
+            button_size: value.button_size.into(),
+        }
+    }
+}
+
+impl From<SectionConfig> for SectionConfigDto {
+    fn from(value: SectionConfig) -> Self {
+        Self {
+            id: value.id.into(),
+            visible: value.visible,
+            button_size: value.button_size.into(),
+        }
+    }
+}
+
+#[derive(Deserialize, Eq, PartialEq, Serialize)]
+enum SectionIdDto {
+    ColorKeys,
+    Dpad,
+    Navigation,
+    Numpad,
+    Media,
+    Power,
+    Tv,
+    CustomKeys,
+}
+
+impl From<SectionIdDto> for SectionId {
+    fn from(value: SectionIdDto) -> Self {
+        match value {
+            SectionIdDto::ColorKeys => SectionId::ColorKeys,
+            SectionIdDto::Dpad => SectionId::Dpad,
+            SectionIdDto::Navigation => SectionId::Navigation,
+            SectionIdDto::Numpad => SectionId::Numpad,
+            SectionIdDto::Media => SectionId::Media,
+            SectionIdDto::Power => SectionId::Power,
+            SectionIdDto::Tv => SectionId::Tv,
+            SectionIdDto::CustomKeys => SectionId::CustomKeys,
+        }
+    }
+}
+
+impl From<SectionId> for SectionIdDto {
+    fn from(value: SectionId) -> Self {
+        match value {
+            SectionId::ColorKeys => SectionIdDto::ColorKeys,
+            SectionId::Dpad => SectionIdDto::Dpad,
+            SectionId::Navigation => SectionIdDto::Navigation,
+            SectionId::Numpad => SectionIdDto::Numpad,
+            SectionId::Media => SectionIdDto::Media,
+            SectionId::Power => SectionIdDto::Power,
+            SectionId::Tv => SectionIdDto::Tv,
+            SectionId::CustomKeys => SectionIdDto::CustomKeys,
+        }
+    }
+}
+
+#[derive(Deserialize, Eq, PartialEq, Serialize)]
+enum ButtonSizeDto {
+    Small,
+    Medium,
+    Large,
+}
+
+impl From<ButtonSizeDto> for ButtonSize {
+    fn from(value: ButtonSizeDto) -> Self {
+        match value {
+            ButtonSizeDto::Small => ButtonSize::Small,
+            ButtonSizeDto::Medium => ButtonSize::Medium,
+            ButtonSizeDto::Large => ButtonSize::Large,
+        }
+    }
+}
+
+impl From<ButtonSize> for ButtonSizeDto {
+    fn from(value: ButtonSize) -> Self {
+        match value {
+            ButtonSize::Small => ButtonSizeDto::Small,
+            ButtonSize::Medium => ButtonSizeDto::Medium,
+            ButtonSize::Large => ButtonSizeDto::Large,
         }
     }
 }
@@ -236,3 +1310,82 @@ impl From<AppTheme> for ThemeDto {
         }
     }
 }
+
+#[derive(Deserialize, Eq, PartialEq, Serialize)]
+enum LanguageDto {
+    English,
+    Japanese,
+}
+
+impl From<LanguageDto> for Language {
+    fn from(value: LanguageDto) -> Self {
+        match value {
+            LanguageDto::English => Language::English,
+            LanguageDto::Japanese => Language::Japanese,
+        }
+    }
+}
+
+impl From<Language> for LanguageDto {
+    fn from(value: Language) -> Self {
+        match value {
+            Language::English => LanguageDto::English,
+            Language::Japanese => LanguageDto::Japanese,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keyboard_repeat_interval(prefs: &str) -> Option<Duration> {
+        Preferences::from(toml::from_str::<PrefsDto>(prefs).unwrap()).keyboard_repeat_interval
+    }
+
+    #[test]
+    fn keyboard_repeat_interval_round_trip() {
+        for interval in [
+            None,
+            Some(Duration::from_millis(50)),
+            Some(Duration::from_millis(120)),
+        ] {
+            let prefs = Preferences {
+                keyboard_repeat_interval: interval,
+                ..Default::default()
+            };
+            let prefs = toml::to_string(&PrefsDto::from(prefs)).unwrap();
+            assert_eq!(
+                interval,
+                keyboard_repeat_interval(&prefs),
+                "prefs: {}",
+                prefs
+            );
+        }
+
+        // the default prefs without the interval, e.g. the file saved by the older version.
+        let base = toml::to_string(&PrefsDto::from(Preferences::default()))
+            .unwrap()
+            .lines()
+            .filter(|data| !data.starts_with("keyboard_repeat_interval_ms"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        for (expected, prefs) in [
+            (None, "keyboard_repeat_interval_ms = 0"),
+            (Some(Duration::from_millis(50)), ""),
+            (
+                Some(Duration::from_millis(120)),
+                "keyboard_repeat_interval_ms = 120",
+            ),
+        ] {
+            // the top-level key is placed before the tables.
+            let prefs = format!("{}\n{}", prefs, base);
+            assert_eq!(
+                expected,
+                keyboard_repeat_interval(&prefs),
+                "prefs: {}",
+                prefs
+            );
+        }
+    }
+}
@@ -0,0 +1,118 @@
+/*
+ * Copyright 2022 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::data::preferences_repository::PreferencesRepository;
+use crate::model::Preferences;
+use crate::prelude::*;
+use iced::subscription::{unfold, Subscription};
+use std::sync::Arc;
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+use tokio::sync::Mutex;
+use tracing::{debug, warn};
+
+type UpdateFn = Box<dyn FnOnce(&mut Preferences) + Send>;
+
+enum Request {
+    Reload,
+    Update(UpdateFn),
+}
+
+/// serializes loading and saving preferences in a single task to avoid lost updates.
+///
+/// every request yields the latest preferences from [PreferencesStore::subscription].
+#[derive(Clone)]
+pub struct PreferencesStore {
+    rx: Arc<Mutex<UnboundedReceiver<Request>>>,
+    tx: UnboundedSender<Request>,
+}
+
+impl PreferencesStore {
+    pub fn new() -> Self {
+        let (tx, rx) = unbounded_channel();
+        Self {
+            rx: Arc::new(Mutex::new(rx)),
+            tx,
+        }
+    }
+
+    pub fn reload(&self) {
+        if self.tx.send(Request::Reload).is_err() {
+            warn!("preferences store closed");
+        }
+    }
+
+    pub fn update(&self, f: impl FnOnce(&mut Preferences) + Send + 'static) {
+        if self.tx.send(Request::Update(Box::new(f))).is_err() {
+            warn!("preferences store closed");
+        }
+    }
+
+    pub fn subscription<Repo: PreferencesRepository + 'static>(
+        &self,
+        repo: Arc<Repo>,
+    ) -> Subscription<Arc<Preferences>> {
+        unfold(
+            std::any::TypeId::of::<PreferencesStoreType>(),
+            (repo, self.rx.clone()),
+            execute,
+        )
+    }
+}
+
+impl Default for PreferencesStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+struct PreferencesStoreType;
+
+type StreamState<Repo> = (Arc<Repo>, Arc<Mutex<UnboundedReceiver<Request>>>);
+
+async fn execute<Repo: PreferencesRepository>(
+    (repo, rx): StreamState<Repo>,
+) -> (Option<Arc<Preferences>>, StreamState<Repo>) {
+    let request = rx.lock().await.recv().await;
+    let ret = match request {
+        Some(Request::Reload) => {
+            debug!("reload");
+            repo.load().await
+        }
+        Some(Request::Update(f)) => {
+            debug!("update");
+            update(repo.as_ref(), f).await
+        }
+        None => {
+            debug!("channel closed");
+            iced::futures::future::pending().await
+        }
+    };
+
+    match ret {
+        Ok(data) => (Some(Arc::new(data)), (repo, rx)),
+        Err(e) => {
+            warn!(?e, "failed to process the preferences request");
+            (None, (repo, rx))
+        }
+    }
+}
+
+async fn update<Repo: PreferencesRepository>(repo: &Repo, f: UpdateFn) -> Fallible<Preferences> {
+    let mut prefs = repo.load().await?;
+    f(&mut prefs);
+    repo.save(prefs.clone()).await?;
+    Ok(prefs)
+}
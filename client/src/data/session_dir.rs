@@ -0,0 +1,69 @@
+/*
+ * Copyright 2022 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::prelude::*;
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+use tracing::{debug, info};
+
+/// temporary directory that lives while the app is running.
+///
+/// the directory and its contents are deleted when dropped.
+#[derive(Debug)]
+pub struct SessionDir {
+    dir: TempDir,
+}
+
+impl SessionDir {
+    pub fn new() -> Fallible<Self> {
+        let dir = tempfile::Builder::new()
+            .prefix("android-commander-")
+            .tempdir()
+            .context("failed to create the session directory")?;
+        info!(path = ?dir.path(), "session directory");
+        Ok(Self { dir })
+    }
+
+    pub fn path(&self) -> &Path {
+        self.dir.path()
+    }
+
+    pub fn join(&self, name: impl AsRef<Path>) -> PathBuf {
+        self.dir.path().join(name)
+    }
+}
+
+impl Drop for SessionDir {
+    fn drop(&mut self) {
+        debug!(path = ?self.dir.path(), "delete session directory");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn delete_on_drop() {
+        let session_dir = SessionDir::new().unwrap();
+        let path = session_dir.join("file");
+        std::fs::write(&path, "data").unwrap();
+        assert!(path.exists());
+
+        drop(session_dir);
+        assert!(!path.exists());
+    }
+}
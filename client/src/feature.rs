@@ -14,6 +14,15 @@
  * limitations under the License.
  */
 
+pub mod apps;
+pub mod device_info;
+pub mod files;
+pub mod intent;
+pub mod layout_editor;
+pub mod logcat;
 pub mod main;
 pub mod migrate;
+pub mod monkey;
+pub mod run_macro;
 pub mod settings;
+pub mod toast;
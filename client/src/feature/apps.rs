@@ -0,0 +1,387 @@
+/*
+ * Copyright 2022 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::data::i18n::{tr, tr_args};
+use crate::function::adb_functions::{
+    clear_package_data, force_stop_package, install_package, launch_shortcut, retrieve_packages,
+    uninstall_package,
+};
+use crate::model::layout::LaunchShortcut;
+use crate::model::{AndroidDevice, XMessage};
+use crate::prelude::*;
+use iced::widget::{button, column, row, scrollable, text, text_input, Column};
+use iced::{Command, Element, Length};
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::path::PathBuf;
+use std::sync::Arc;
+use tracing::warn;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum AppAction {
+    ClearData,
+    ForceStop,
+    Launch,
+    Uninstall,
+}
+
+impl AppAction {
+    /// asks before the action since the data can't be restored.
+    fn needs_confirmation(&self) -> bool {
+        matches!(self, Self::ClearData | Self::Uninstall)
+    }
+}
+
+impl Display for AppAction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ClearData => write!(f, "{}", tr("apps-action-clear-data")),
+            Self::ForceStop => write!(f, "{}", tr("apps-action-force-stop")),
+            Self::Launch => write!(f, "{}", tr("apps-action-launch")),
+            Self::Uninstall => write!(f, "{}", tr("apps-action-uninstall")),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum AppsViewCommand {
+    ActionFinished(String, AppAction, Result<(), String>),
+    ApkSelected(Option<PathBuf>),
+    Installed(String, PathBuf, Result<(), String>),
+    OnActionClicked(String, AppAction),
+    OnConfirmCancelled,
+    OnConfirmed,
+    OnFilterChanged(String),
+    OnInstallClicked,
+    OnRefreshClicked,
+    OnXMessage(XMessage),
+    PackagesLoaded(String, Result<Vec<String>, String>),
+    /// shows the desktop notification, handled by App.
+    ShowNotification(String, String),
+}
+
+#[derive(Default)]
+pub struct AppsView {
+    /// the destructive action waiting for the confirmation.
+    confirming: Option<(String, AppAction)>,
+    device: Option<Arc<AndroidDevice>>,
+    filter: String,
+    installing: bool,
+    /// the labels of the launch shortcuts since `pm` doesn't print the app labels.
+    labels: BTreeMap<String, String>,
+    packages: Vec<String>,
+    status: String,
+}
+
+impl AppsView {
+    pub fn update(&mut self, command: AppsViewCommand) -> Command<AppsViewCommand> {
+        match command {
+            AppsViewCommand::ActionFinished(package, action, ret) => {
+                let args = [("action", action.to_string()), ("package", package.clone())];
+                let body = match ret {
+                    Ok(_) => {
+                        self.status = tr_args("apps-status-finished", &args);
+                        if action == AppAction::Uninstall {
+                            self.packages.retain(|data| data != &package);
+                        }
+                        tr_args("notification-apps-finished", &args)
+                    }
+                    Err(e) => {
+                        self.status = tr_args("apps-status-error", &[("error", e.clone())]);
+                        let [action_arg, package_arg] = args;
+                        tr_args(
+                            "notification-apps-failed",
+                            &[action_arg, package_arg, ("error", e)],
+                        )
+                    }
+                };
+                return notification_command(tr("notification-apps"), body);
+            }
+            AppsViewCommand::ApkSelected(apk_path) => {
+                let (serial, apk_path) = match (&self.device, apk_path) {
+                    (Some(device), Some(apk_path)) => (device.serial.clone(), apk_path),
+                    _ => return Command::none(),
+                };
+
+                self.installing = true;
+                self.status = tr_args(
+                    "apps-status-installing",
+                    &[("path", apk_path.display().to_string())],
+                );
+                return Command::perform(
+                    install_package(serial.clone(), apk_path.clone()),
+                    move |ret| {
+                        AppsViewCommand::Installed(
+                            serial.clone(),
+                            apk_path.clone(),
+                            ret.map_err(|e| format!("{:#}", e)),
+                        )
+                    },
+                );
+            }
+            AppsViewCommand::Installed(serial, apk_path, ret) => {
+                self.installing = false;
+                let path = ("path", apk_path.display().to_string());
+                let body = match ret {
+                    Ok(_) => {
+                        self.status = tr_args("apps-status-installed", &[path.clone()]);
+                        tr_args("notification-install-finished", &[path])
+                    }
+                    Err(e) => {
+                        self.status = tr_args("apps-status-error", &[("error", e.clone())]);
+                        tr_args("notification-install-failed", &[path, ("error", e)])
+                    }
+                };
+                let notification = notification_command(tr("notification-install"), body);
+
+                // the response of the previous device doesn't change the packages.
+                if self.device.as_ref().map(|data| &data.serial) != Some(&serial) {
+                    return notification;
+                }
+                return Command::batch([notification, self.load_command()]);
+            }
+            AppsViewCommand::OnActionClicked(package, action) => {
+                if action.needs_confirmation() {
+                    self.confirming = Some((package, action));
+                    return Command::none();
+                }
+                return self.action_command(package, action);
+            }
+            AppsViewCommand::OnConfirmCancelled => self.confirming = None,
+            AppsViewCommand::OnConfirmed => {
+                if let Some((package, action)) = self.confirming.take() {
+                    return self.action_command(package, action);
+                }
+            }
+            AppsViewCommand::OnFilterChanged(data) => self.filter = data,
+            AppsViewCommand::OnInstallClicked => {
+                return Command::perform(pick_apk_path(), AppsViewCommand::ApkSelected);
+            }
+            AppsViewCommand::OnRefreshClicked => return self.load_command(),
+            AppsViewCommand::OnXMessage(data) => match data {
+                XMessage::OnDeviceSelected(device) => {
+                    let changed = self
+                        .device
+                        .as_ref()
+                        .map_or(true, |data| data.serial != device.serial);
+                    self.device = Some(device);
+                    if changed {
+                        self.confirming = None;
+                        self.packages.clear();
+                        return self.load_command();
+                    }
+                }
+                XMessage::OnNewPreferences(prefs) => {
+                    self.labels = prefs
+                        .layout_profiles
+                        .iter()
+                        .flat_map(|data| &data.shortcuts)
+                        .filter(|data| !data.is_deeplink())
+                        .map(|data| (data.target.clone(), data.label.clone()))
+                        .collect();
+                }
+                XMessage::OnKeycodeTestRequested(_)
+                | XMessage::OnPrefsFileUpdated
+                | XMessage::OnWindowFocusChanged(_) => {
+                    // do nothing.
+                }
+            },
+            AppsViewCommand::PackagesLoaded(serial, ret) => {
+                // the response of the previous device.
+                if self.device.as_ref().map(|data| &data.serial) != Some(&serial) {
+                    return Command::none();
+                }
+
+                match ret {
+                    Ok(data) => {
+                        self.status =
+                            tr_args("apps-status-count", &[("count", data.len().to_string())]);
+                        self.packages = data;
+                    }
+                    Err(e) => self.status = tr_args("apps-status-error", &[("error", e)]),
+                }
+            }
+            AppsViewCommand::ShowNotification(..) => {
+                // handled by App.
+            }
+        }
+        Command::none()
+    }
+
+    pub fn view(&self) -> Element<AppsViewCommand> {
+        let filter = self.filter.to_lowercase();
+        let mut apps = Column::new().spacing(8);
+        for package in &self.packages {
+            let label = self.labels.get(package);
+            if !filter.is_empty()
+                && !package.to_lowercase().contains(&filter)
+                && !label.map_or(false, |data| data.to_lowercase().contains(&filter))
+            {
+                continue;
+            }
+
+            let mut app = Column::new().spacing(2);
+            if let Some(label) = label {
+                app = app.push(text(label));
+            }
+            app = app.push(text(package).size(14)).push(
+                row![
+                    action_button(tr("apps-launch"), package, AppAction::Launch),
+                    action_button(tr("apps-stop"), package, AppAction::ForceStop),
+                    action_button(tr("apps-clear"), package, AppAction::ClearData),
+                    action_button(tr("apps-uninstall"), package, AppAction::Uninstall),
+                ]
+                .spacing(4),
+            );
+            apps = apps.push(app);
+        }
+
+        let view = column![
+            text(match &self.device {
+                Some(data) => tr_args("apps-device", &[("device", data.to_string())]),
+                None => tr("apps-device-none"),
+            }),
+            row![
+                text_input(
+                    &tr("apps-filter"),
+                    &self.filter,
+                    AppsViewCommand::OnFilterChanged
+                ),
+                button(text(tr("apps-reload")))
+                    .style(iced::theme::Button::Secondary)
+                    .on_press(AppsViewCommand::OnRefreshClicked),
+                if self.installing || self.device.is_none() {
+                    button(text(tr("apps-install"))).style(iced::theme::Button::Secondary)
+                } else {
+                    button(text(tr("apps-install")))
+                        .style(iced::theme::Button::Secondary)
+                        .on_press(AppsViewCommand::OnInstallClicked)
+                },
+            ]
+            .spacing(4),
+            text(&self.status),
+        ]
+        .spacing(8);
+
+        let view = match &self.confirming {
+            Some((package, action)) => view.push(
+                column![
+                    text(tr_args(
+                        "apps-confirm",
+                        &[("action", action.to_string()), ("package", package.clone())],
+                    ))
+                    .size(14),
+                    row![
+                        button(text(tr("apps-confirm-ok")))
+                            .style(iced::theme::Button::Destructive)
+                            .on_press(AppsViewCommand::OnConfirmed),
+                        button(text(tr("apps-confirm-cancel")))
+                            .style(iced::theme::Button::Secondary)
+                            .on_press(AppsViewCommand::OnConfirmCancelled),
+                    ]
+                    .spacing(4),
+                ]
+                .spacing(4),
+            ),
+            None => view,
+        };
+
+        view.push(scrollable(apps).height(Length::Fill)).into()
+    }
+
+    pub fn view_size() -> (u32, u32) {
+        (300, 580)
+    }
+
+    fn load_command(&mut self) -> Command<AppsViewCommand> {
+        let serial = match &self.device {
+            Some(data) if data.kind.capabilities().supports_shell => data.serial.clone(),
+            _ => return Command::none(),
+        };
+
+        self.status = tr("apps-status-loading");
+        Command::perform(retrieve_packages(serial.clone()), move |ret| {
+            if let Err(e) = &ret {
+                warn!(?e, "failed to retrieve packages");
+            }
+            AppsViewCommand::PackagesLoaded(serial.clone(), ret.map_err(|e| format!("{:#}", e)))
+        })
+    }
+
+    fn action_command(&mut self, package: String, action: AppAction) -> Command<AppsViewCommand> {
+        let serial = match &self.device {
+            Some(data) => data.serial.clone(),
+            None => return Command::none(),
+        };
+
+        self.status = tr_args(
+            "apps-status-running",
+            &[("action", action.to_string()), ("package", package.clone())],
+        );
+        Command::perform(run_action(serial, package.clone(), action), move |ret| {
+            AppsViewCommand::ActionFinished(
+                package.clone(),
+                action,
+                ret.map_err(|e| format!("{:#}", e)),
+            )
+        })
+    }
+}
+
+async fn run_action(serial: String, package: String, action: AppAction) -> Fallible<()> {
+    match action {
+        AppAction::ClearData => clear_package_data(serial, package).await,
+        AppAction::ForceStop => force_stop_package(serial, package).await,
+        AppAction::Launch => {
+            let shortcut = LaunchShortcut {
+                label: package.clone(),
+                target: package,
+            };
+            launch_shortcut(serial, shortcut, None).await
+        }
+        AppAction::Uninstall => uninstall_package(serial, package).await,
+    }
+}
+
+async fn pick_apk_path() -> Option<PathBuf> {
+    rfd::AsyncFileDialog::new()
+        .set_title(&tr("apps-install-title"))
+        .add_filter("apk", &["apk"])
+        .pick_file()
+        .await
+        .map(|data| data.path().to_path_buf())
+}
+
+fn notification_command(summary: String, body: String) -> Command<AppsViewCommand> {
+    Command::perform(async {}, move |_| {
+        AppsViewCommand::ShowNotification(summary, body)
+    })
+}
+
+fn action_button<'a>(
+    label: String,
+    package: &str,
+    action: AppAction,
+) -> Element<'a, AppsViewCommand> {
+    button(text(label).size(14))
+        .padding(2)
+        .style(match action {
+            AppAction::ClearData | AppAction::Uninstall => iced::theme::Button::Destructive,
+            AppAction::ForceStop | AppAction::Launch => iced::theme::Button::Secondary,
+        })
+        .on_press(AppsViewCommand::OnActionClicked(package.into(), action))
+        .into()
+}
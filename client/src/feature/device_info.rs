@@ -0,0 +1,248 @@
+/*
+ * Copyright 2022 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::data::i18n::{tr, tr_args};
+use crate::function::adb_functions::{capture_bugreport, retrieve_device_info};
+use crate::model::device_info::{DeviceInfo, DeviceInfoSection};
+use crate::model::{AndroidDevice, XMessage};
+use iced::widget::{button, column, row, scrollable, text, Column};
+use iced::{Command, Element, Length};
+use std::path::PathBuf;
+use std::sync::Arc;
+use tracing::warn;
+
+#[derive(Clone, Debug)]
+pub enum DeviceInfoViewCommand {
+    BugreportCaptured(Result<PathBuf, String>),
+    BugreportPathSelected(Option<PathBuf>),
+    DeviceInfoLoaded(String, Result<DeviceInfo, String>),
+    OnBugreportClicked,
+    OnCopyAllClicked,
+    OnCopyClicked(String),
+    OnRefreshClicked,
+    OnXMessage(XMessage),
+    /// shows the desktop notification, handled by App.
+    ShowNotification(String, String),
+}
+
+#[derive(Default)]
+pub struct DeviceInfoView {
+    capturing_bugreport: bool,
+    device: Option<Arc<AndroidDevice>>,
+    info: Option<DeviceInfo>,
+    status: String,
+}
+
+impl DeviceInfoView {
+    pub fn update(&mut self, command: DeviceInfoViewCommand) -> Command<DeviceInfoViewCommand> {
+        match command {
+            DeviceInfoViewCommand::BugreportCaptured(ret) => {
+                self.capturing_bugreport = false;
+                let body = match ret {
+                    Ok(data) => {
+                        let path = ("path", data.display().to_string());
+                        self.status = tr_args("info-status-bugreport-saved", &[path.clone()]);
+                        tr_args("notification-bugreport-saved", &[path])
+                    }
+                    Err(e) => {
+                        self.status = tr_args("info-status-error", &[("error", e.clone())]);
+                        tr_args("notification-bugreport-failed", &[("error", e)])
+                    }
+                };
+                return notification_command(tr("notification-bugreport"), body);
+            }
+            DeviceInfoViewCommand::BugreportPathSelected(path) => {
+                let (serial, path) = match (&self.device, path) {
+                    (Some(device), Some(path)) => (device.serial.clone(), path),
+                    _ => return Command::none(),
+                };
+
+                self.capturing_bugreport = true;
+                self.status = tr("info-status-bugreport-capturing");
+                return Command::perform(capture_bugreport(serial, path), |ret| {
+                    DeviceInfoViewCommand::BugreportCaptured(ret.map_err(|e| {
+                        warn!(?e, "failed to capture the bugreport");
+                        format!("{:#}", e)
+                    }))
+                });
+            }
+            DeviceInfoViewCommand::DeviceInfoLoaded(serial, ret) => {
+                // the response of the previous device.
+                if self.device.as_ref().map(|data| &data.serial) != Some(&serial) {
+                    return Command::none();
+                }
+
+                match ret {
+                    Ok(data) => {
+                        self.info = Some(data);
+                        self.status = "".into();
+                    }
+                    Err(e) => self.status = tr_args("info-status-error", &[("error", e)]),
+                }
+            }
+            DeviceInfoViewCommand::OnBugreportClicked => {
+                let serial = match &self.device {
+                    Some(data) => data.serial.clone(),
+                    None => return Command::none(),
+                };
+                return Command::perform(
+                    pick_bugreport_path(serial),
+                    DeviceInfoViewCommand::BugreportPathSelected,
+                );
+            }
+            DeviceInfoViewCommand::OnCopyAllClicked => {
+                if let Some(data) = &self.info {
+                    return iced::clipboard::write(data.to_text());
+                }
+            }
+            DeviceInfoViewCommand::OnCopyClicked(data) => return iced::clipboard::write(data),
+            DeviceInfoViewCommand::OnRefreshClicked => return self.load_command(),
+            DeviceInfoViewCommand::OnXMessage(data) => match data {
+                XMessage::OnDeviceSelected(device) => {
+                    let changed = self
+                        .device
+                        .as_ref()
+                        .map_or(true, |data| data.serial != device.serial);
+                    self.device = Some(device);
+                    if changed {
+                        self.info = None;
+                        return self.load_command();
+                    }
+                }
+                XMessage::OnKeycodeTestRequested(_)
+                | XMessage::OnNewPreferences(_)
+                | XMessage::OnPrefsFileUpdated
+                | XMessage::OnWindowFocusChanged(_) => {
+                    // do nothing.
+                }
+            },
+            DeviceInfoViewCommand::ShowNotification(..) => {
+                // handled by App.
+            }
+        }
+        Command::none()
+    }
+
+    pub fn view(&self) -> Element<DeviceInfoViewCommand> {
+        let mut sections = Column::new().spacing(12);
+        if let Some(info) = &self.info {
+            for section in [&info.device, &info.display, &info.battery] {
+                sections = sections.push(section_view(section));
+            }
+            sections = sections.push(
+                row![
+                    text(tr_args(
+                        "info-properties",
+                        &[("count", info.properties.entries.len().to_string())],
+                    ))
+                    .width(Length::Fill),
+                    copy_button(&info.properties),
+                ]
+                .spacing(4),
+            );
+        }
+
+        column![
+            text(match &self.device {
+                Some(data) => tr_args("info-device", &[("device", data.to_string())]),
+                None => tr("info-device-none"),
+            }),
+            row![
+                button(text(tr("info-reload")))
+                    .style(iced::theme::Button::Secondary)
+                    .on_press(DeviceInfoViewCommand::OnRefreshClicked),
+                button(text(tr("info-copy-all")))
+                    .style(iced::theme::Button::Secondary)
+                    .on_press(DeviceInfoViewCommand::OnCopyAllClicked),
+                if self.capturing_bugreport || self.device.is_none() {
+                    button(text(tr("info-bugreport"))).style(iced::theme::Button::Secondary)
+                } else {
+                    button(text(tr("info-bugreport")))
+                        .style(iced::theme::Button::Secondary)
+                        .on_press(DeviceInfoViewCommand::OnBugreportClicked)
+                },
+            ]
+            .spacing(4),
+            text(&self.status),
+            scrollable(sections).height(Length::Fill),
+        ]
+        .spacing(8)
+        .into()
+    }
+
+    pub fn view_size() -> (u32, u32) {
+        (300, 580)
+    }
+
+    fn load_command(&mut self) -> Command<DeviceInfoViewCommand> {
+        let serial = match &self.device {
+            Some(data) if data.kind.capabilities().supports_shell => data.serial.clone(),
+            _ => return Command::none(),
+        };
+
+        self.status = tr("info-status-loading");
+        Command::perform(retrieve_device_info(serial.clone()), move |ret| {
+            if let Err(e) = &ret {
+                warn!(?e, "failed to retrieve the device info");
+            }
+            DeviceInfoViewCommand::DeviceInfoLoaded(
+                serial.clone(),
+                ret.map_err(|e| format!("{:#}", e)),
+            )
+        })
+    }
+}
+
+async fn pick_bugreport_path(serial: String) -> Option<PathBuf> {
+    rfd::AsyncFileDialog::new()
+        .set_title(&tr("info-bugreport-title"))
+        .set_file_name(&format!(
+            "bugreport-{}-{}.zip",
+            serial.replace(':', "_"),
+            chrono::Local::now().format("%Y%m%d-%H%M%S")
+        ))
+        .save_file()
+        .await
+        .map(|data| data.path().to_path_buf())
+}
+
+fn notification_command(summary: String, body: String) -> Command<DeviceInfoViewCommand> {
+    Command::perform(async {}, move |_| {
+        DeviceInfoViewCommand::ShowNotification(summary, body)
+    })
+}
+
+fn section_view(section: &DeviceInfoSection) -> Element<DeviceInfoViewCommand> {
+    let mut view = Column::new().spacing(2).push(
+        row![
+            text(&section.title).width(Length::Fill),
+            copy_button(section)
+        ]
+        .spacing(4),
+    );
+    for (key, value) in &section.entries {
+        view = view.push(text(format!("{}: {}", key, value)).size(14));
+    }
+    view.into()
+}
+
+fn copy_button<'a>(section: &DeviceInfoSection) -> Element<'a, DeviceInfoViewCommand> {
+    button(text(tr("info-copy")).size(14))
+        .padding(2)
+        .style(iced::theme::Button::Secondary)
+        .on_press(DeviceInfoViewCommand::OnCopyClicked(section.to_text()))
+        .into()
+}
@@ -0,0 +1,289 @@
+/*
+ * Copyright 2022 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::data::i18n::{tr, tr_args};
+use crate::function::adb_functions::{list_device_dir, pull_device_file, push_device_file};
+use crate::model::device_file::{join_device_path, parent_device_path, DeviceFile};
+use crate::model::{AndroidDevice, XMessage};
+use iced::widget::{button, column, row, scrollable, text, text_input, Column};
+use iced::{Command, Element, Length};
+use std::path::PathBuf;
+use std::sync::Arc;
+use tracing::warn;
+
+#[derive(Clone, Debug)]
+pub enum FilesViewCommand {
+    DirListed(String, Result<Vec<DeviceFile>, String>),
+    FilePulled(Result<PathBuf, String>),
+    FilePushed(Result<String, String>),
+    OnDirClicked(String),
+    /// the host file to push into the current directory.
+    OnFileDropped(PathBuf),
+    OnParentClicked,
+    OnPathChanged(String),
+    OnPathSubmitted,
+    OnPullClicked(String),
+    OnRefreshClicked,
+    OnXMessage(XMessage),
+    PullPathSelected(String, Option<PathBuf>),
+    /// shows the desktop notification, handled by App.
+    ShowNotification(String, String),
+}
+
+pub struct FilesView {
+    device: Option<Arc<AndroidDevice>>,
+    dir: String,
+    entries: Vec<DeviceFile>,
+    path: String,
+    status: String,
+}
+
+impl Default for FilesView {
+    fn default() -> Self {
+        Self {
+            device: None,
+            dir: Self::DEFAULT_DIR.into(),
+            entries: vec![],
+            path: Self::DEFAULT_DIR.into(),
+            status: "".into(),
+        }
+    }
+}
+
+impl FilesView {
+    const DEFAULT_DIR: &'static str = "/sdcard";
+
+    pub fn update(&mut self, command: FilesViewCommand) -> Command<FilesViewCommand> {
+        match command {
+            FilesViewCommand::DirListed(dir, ret) => {
+                // the response of the previous directory.
+                if dir != self.dir {
+                    return Command::none();
+                }
+
+                match ret {
+                    Ok(data) => {
+                        self.status =
+                            tr_args("files-status-count", &[("count", data.len().to_string())]);
+                        self.entries = data;
+                    }
+                    Err(e) => {
+                        self.status = tr_args("files-status-error", &[("error", e)]);
+                        self.entries.clear();
+                    }
+                }
+            }
+            FilesViewCommand::FilePulled(ret) => {
+                let body = match ret {
+                    Ok(data) => {
+                        let path = ("path", data.display().to_string());
+                        self.status = tr_args("files-status-pulled", &[path.clone()]);
+                        tr_args("notification-files-pulled", &[path])
+                    }
+                    Err(e) => {
+                        self.status = tr_args("files-status-error", &[("error", e.clone())]);
+                        tr_args("notification-files-pull-failed", &[("error", e)])
+                    }
+                };
+                return notification_command(tr("notification-files"), body);
+            }
+            FilesViewCommand::FilePushed(ret) => match ret {
+                Ok(data) => {
+                    let path = ("path", data);
+                    self.status = tr_args("files-status-pushed", &[path.clone()]);
+                    return Command::batch([
+                        notification_command(
+                            tr("notification-files"),
+                            tr_args("notification-files-pushed", &[path]),
+                        ),
+                        self.list_command(),
+                    ]);
+                }
+                Err(e) => {
+                    self.status = tr_args("files-status-error", &[("error", e.clone())]);
+                    return notification_command(
+                        tr("notification-files"),
+                        tr_args("notification-files-push-failed", &[("error", e)]),
+                    );
+                }
+            },
+            FilesViewCommand::OnDirClicked(name) => {
+                let dir = join_device_path(&self.dir, &name);
+                return self.navigate(dir);
+            }
+            FilesViewCommand::OnFileDropped(local_path) => {
+                let serial = match &self.device {
+                    Some(data) => data.serial.clone(),
+                    None => return Command::none(),
+                };
+
+                self.status = tr_args(
+                    "files-status-pushing",
+                    &[("path", local_path.display().to_string())],
+                );
+                return Command::perform(
+                    push_device_file(serial, local_path, self.dir.clone()),
+                    |ret| FilesViewCommand::FilePushed(ret.map_err(|e| format!("{:#}", e))),
+                );
+            }
+            FilesViewCommand::OnParentClicked => {
+                if let Some(dir) = parent_device_path(&self.dir) {
+                    return self.navigate(dir);
+                }
+            }
+            FilesViewCommand::OnPathChanged(data) => self.path = data,
+            FilesViewCommand::OnPathSubmitted => {
+                let dir = self.path.trim();
+                if dir.starts_with('/') {
+                    return self.navigate(dir.to_string());
+                }
+                self.status = tr("files-status-not-absolute");
+            }
+            FilesViewCommand::OnPullClicked(name) => {
+                let device_path = join_device_path(&self.dir, &name);
+                return Command::perform(pick_pull_path(name), move |data| {
+                    FilesViewCommand::PullPathSelected(device_path.clone(), data)
+                });
+            }
+            FilesViewCommand::OnRefreshClicked => return self.list_command(),
+            FilesViewCommand::OnXMessage(data) => match data {
+                XMessage::OnDeviceSelected(device) => {
+                    let changed = self
+                        .device
+                        .as_ref()
+                        .map_or(true, |data| data.serial != device.serial);
+                    self.device = Some(device);
+                    if changed {
+                        return self.navigate(Self::DEFAULT_DIR.into());
+                    }
+                }
+                XMessage::OnKeycodeTestRequested(_)
+                | XMessage::OnNewPreferences(_)
+                | XMessage::OnPrefsFileUpdated
+                | XMessage::OnWindowFocusChanged(_) => {
+                    // do nothing.
+                }
+            },
+            FilesViewCommand::PullPathSelected(device_path, local_path) => {
+                let (serial, local_path) = match (&self.device, local_path) {
+                    (Some(device), Some(local_path)) => (device.serial.clone(), local_path),
+                    _ => return Command::none(),
+                };
+
+                self.status = tr_args("files-status-pulling", &[("path", device_path.clone())]);
+                return Command::perform(
+                    pull_device_file(serial, device_path, local_path),
+                    |ret| FilesViewCommand::FilePulled(ret.map_err(|e| format!("{:#}", e))),
+                );
+            }
+            FilesViewCommand::ShowNotification(..) => {
+                // handled by App.
+            }
+        }
+        Command::none()
+    }
+
+    pub fn view(&self) -> Element<FilesViewCommand> {
+        let mut entries = Column::new().spacing(2);
+        for entry in &self.entries {
+            entries = entries.push(if entry.is_navigable() {
+                row![button(text(format!("{}/", entry.name)).size(14))
+                    .padding(0)
+                    .style(iced::theme::Button::Text)
+                    .on_press(FilesViewCommand::OnDirClicked(entry.name.clone()))]
+            } else {
+                row![
+                    text(&entry.name).size(14).width(Length::Fill),
+                    text(entry.size_text()).size(14),
+                    button(text(tr("files-pull")).size(14))
+                        .padding(2)
+                        .style(iced::theme::Button::Secondary)
+                        .on_press(FilesViewCommand::OnPullClicked(entry.name.clone())),
+                ]
+                .spacing(4)
+            });
+        }
+
+        column![
+            text(match &self.device {
+                Some(data) => tr_args("files-device", &[("device", data.to_string())]),
+                None => tr("files-device-none"),
+            }),
+            row![
+                text_input(
+                    Self::DEFAULT_DIR,
+                    &self.path,
+                    FilesViewCommand::OnPathChanged
+                )
+                .on_submit(FilesViewCommand::OnPathSubmitted),
+                button(text(tr("files-up")))
+                    .style(iced::theme::Button::Secondary)
+                    .on_press(FilesViewCommand::OnParentClicked),
+                button(text(tr("files-reload")))
+                    .style(iced::theme::Button::Secondary)
+                    .on_press(FilesViewCommand::OnRefreshClicked),
+            ]
+            .spacing(4),
+            text(tr("files-drop-hint")).size(14),
+            text(&self.status),
+            scrollable(entries).height(Length::Fill),
+        ]
+        .spacing(8)
+        .into()
+    }
+
+    pub fn view_size() -> (u32, u32) {
+        (300, 580)
+    }
+
+    fn navigate(&mut self, dir: String) -> Command<FilesViewCommand> {
+        self.path = dir.clone();
+        self.dir = dir;
+        self.entries.clear();
+        self.list_command()
+    }
+
+    fn list_command(&mut self) -> Command<FilesViewCommand> {
+        let serial = match &self.device {
+            Some(data) if data.kind.capabilities().supports_shell => data.serial.clone(),
+            _ => return Command::none(),
+        };
+
+        self.status = tr("files-status-loading");
+        let dir = self.dir.clone();
+        Command::perform(list_device_dir(serial, dir.clone()), move |ret| {
+            if let Err(e) = &ret {
+                warn!(?e, "failed to list the directory");
+            }
+            FilesViewCommand::DirListed(dir.clone(), ret.map_err(|e| format!("{:#}", e)))
+        })
+    }
+}
+
+async fn pick_pull_path(file_name: String) -> Option<PathBuf> {
+    rfd::AsyncFileDialog::new()
+        .set_title(&tr("files-pull-title"))
+        .set_file_name(&file_name)
+        .save_file()
+        .await
+        .map(|data| data.path().to_path_buf())
+}
+
+fn notification_command(summary: String, body: String) -> Command<FilesViewCommand> {
+    Command::perform(async {}, move |_| {
+        FilesViewCommand::ShowNotification(summary, body)
+    })
+}
@@ -0,0 +1,196 @@
+/*
+ * Copyright 2022 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::data::i18n::{tr, tr_args};
+use crate::data::preferences_store::PreferencesStore;
+use crate::function::adb_functions::start_intent;
+use crate::model::intent::{push_intent_history, IntentSpec};
+use crate::model::{AndroidDevice, XMessage};
+use iced::widget::{button, column, row, scrollable, text, text_input, Column};
+use iced::{Command, Element, Length};
+use std::sync::Arc;
+use tracing::warn;
+
+#[derive(Clone, Debug)]
+pub enum IntentViewCommand {
+    IntentStarted(Result<(), String>),
+    OnActionChanged(String),
+    OnComponentChanged(String),
+    OnDataUriChanged(String),
+    OnExtrasChanged(String),
+    OnHistoryClicked(usize),
+    OnSendClicked,
+    OnXMessage(XMessage),
+}
+
+pub struct IntentView {
+    action: String,
+    component: String,
+    data_uri: String,
+    device: Option<Arc<AndroidDevice>>,
+    extras: String,
+    history: Vec<IntentSpec>,
+    prefs_store: PreferencesStore,
+    status: String,
+}
+
+impl IntentView {
+    pub fn new(prefs_store: PreferencesStore) -> Self {
+        Self {
+            action: "android.intent.action.VIEW".into(),
+            component: "".into(),
+            data_uri: "".into(),
+            device: None,
+            extras: "".into(),
+            history: vec![],
+            prefs_store,
+            status: "".into(),
+        }
+    }
+
+    pub fn update(&mut self, command: IntentViewCommand) -> Command<IntentViewCommand> {
+        match command {
+            IntentViewCommand::IntentStarted(ret) => {
+                self.status = match ret {
+                    Ok(_) => tr("intent-status-started"),
+                    Err(e) => tr_args("intent-status-error", &[("error", e)]),
+                };
+            }
+            IntentViewCommand::OnActionChanged(data) => self.action = data,
+            IntentViewCommand::OnComponentChanged(data) => self.component = data,
+            IntentViewCommand::OnDataUriChanged(data) => self.data_uri = data,
+            IntentViewCommand::OnExtrasChanged(data) => self.extras = data,
+            IntentViewCommand::OnHistoryClicked(index) => {
+                if let Some(spec) = self.history.get(index) {
+                    self.action = spec.action.clone();
+                    self.component = spec.component.clone();
+                    self.data_uri = spec.data_uri.clone();
+                    self.extras = spec.extras_text();
+                }
+            }
+            IntentViewCommand::OnSendClicked => {
+                let serial = match &self.device {
+                    Some(data) if data.kind.capabilities().supports_shell => data.serial.clone(),
+                    _ => {
+                        self.status = tr("intent-status-no-device");
+                        return Command::none();
+                    }
+                };
+
+                let spec = match IntentSpec::new(
+                    &self.action,
+                    &self.data_uri,
+                    &self.component,
+                    &self.extras,
+                ) {
+                    Ok(data) => data,
+                    Err(e) => {
+                        self.status =
+                            tr_args("intent-status-error", &[("error", format!("{:#}", e))]);
+                        return Command::none();
+                    }
+                };
+
+                let history_spec = spec.clone();
+                self.prefs_store.update(move |prefs| {
+                    push_intent_history(&mut prefs.intent_history, history_spec)
+                });
+
+                self.status = tr("intent-status-starting");
+                return Command::perform(start_intent(serial, spec), |ret| {
+                    if let Err(e) = &ret {
+                        warn!(?e, "failed to start the intent");
+                    }
+                    IntentViewCommand::IntentStarted(ret.map_err(|e| format!("{:#}", e)))
+                });
+            }
+            IntentViewCommand::OnXMessage(data) => match data {
+                XMessage::OnDeviceSelected(device) => self.device = Some(device),
+                XMessage::OnNewPreferences(prefs) => self.history = prefs.intent_history.clone(),
+                XMessage::OnKeycodeTestRequested(_)
+                | XMessage::OnPrefsFileUpdated
+                | XMessage::OnWindowFocusChanged(_) => {
+                    // do nothing.
+                }
+            },
+        }
+        Command::none()
+    }
+
+    pub fn view(&self) -> Element<IntentViewCommand> {
+        let mut history = Column::new().spacing(2);
+        for (index, spec) in self.history.iter().enumerate() {
+            history = history.push(
+                button(text(spec.to_string()).size(14))
+                    .padding(2)
+                    .style(iced::theme::Button::Text)
+                    .on_press(IntentViewCommand::OnHistoryClicked(index)),
+            );
+        }
+
+        column![
+            text(match &self.device {
+                Some(data) => tr_args("intent-device", &[("device", data.to_string())]),
+                None => tr("intent-device-none"),
+            }),
+            row![
+                text(tr("intent-action")).width(Length::Units(70)),
+                text_input(
+                    "android.intent.action.VIEW",
+                    &self.action,
+                    IntentViewCommand::OnActionChanged
+                ),
+            ],
+            row![
+                text(tr("intent-data")).width(Length::Units(70)),
+                text_input(
+                    "myapp://path",
+                    &self.data_uri,
+                    IntentViewCommand::OnDataUriChanged
+                ),
+            ],
+            row![
+                text(tr("intent-component")).width(Length::Units(70)),
+                text_input(
+                    "com.example/.MainActivity",
+                    &self.component,
+                    IntentViewCommand::OnComponentChanged
+                ),
+            ],
+            row![
+                text(tr("intent-extras")).width(Length::Units(70)),
+                text_input(
+                    "key=value; n:int=1; b:bool=true",
+                    &self.extras,
+                    IntentViewCommand::OnExtrasChanged
+                )
+                .on_submit(IntentViewCommand::OnSendClicked),
+            ],
+            button(text(tr("intent-send")))
+                .style(iced::theme::Button::Secondary)
+                .on_press(IntentViewCommand::OnSendClicked),
+            text(&self.status),
+            text(tr("intent-history")),
+            scrollable(history).height(Length::Fill),
+        ]
+        .spacing(8)
+        .into()
+    }
+
+    pub fn view_size() -> (u32, u32) {
+        (300, 580)
+    }
+}
@@ -0,0 +1,355 @@
+/*
+ * Copyright 2022 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+mod drag_area;
+
+use crate::data::i18n::tr;
+use crate::data::preferences_store::PreferencesStore;
+use crate::model::layout::{
+    key_label, ButtonSize, ButtonSpec, GridButton, GridPosition, LayoutProfile,
+};
+use crate::model::send_event_key::SendEventKey;
+use crate::model::{AndroidDevice, Preferences, XMessage};
+use drag_area::DragArea;
+use iced::widget::{button, column, container, pick_list, row, scrollable, text, Column, Row};
+use iced::{Command, Element, Length};
+use std::sync::Arc;
+use tracing::warn;
+
+/// what is being dragged.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum DragSource {
+    /// a new button from the key list.
+    Palette(SendEventKey),
+    Grid(GridPosition),
+}
+
+#[derive(Clone, Debug)]
+pub enum LayoutEditorViewCommand {
+    OnDragCancelled,
+    OnDragStarted(DragSource),
+    /// dropped before the position.
+    OnDropped(GridPosition),
+    OnProfileSelected(String),
+    OnRemoveDropped,
+    OnResetClicked,
+    OnSizeSelected(ButtonSize),
+    OnXMessage(XMessage),
+    Sink,
+}
+
+pub struct LayoutEditorView {
+    device: Option<Arc<AndroidDevice>>,
+    dragging: Option<DragSource>,
+    prefs: Arc<Preferences>,
+    prefs_store: PreferencesStore,
+    profile_selected: Option<String>,
+    /// the button to change the size, selected by the click.
+    selected: Option<GridPosition>,
+}
+
+impl LayoutEditorView {
+    pub fn new(prefs_store: PreferencesStore) -> Self {
+        Self {
+            device: None,
+            dragging: None,
+            prefs: Default::default(),
+            prefs_store,
+            profile_selected: None,
+            selected: None,
+        }
+    }
+
+    pub fn update(&mut self, command: LayoutEditorViewCommand) -> Command<LayoutEditorViewCommand> {
+        match command {
+            LayoutEditorViewCommand::OnDragCancelled => self.dragging = None,
+            LayoutEditorViewCommand::OnDragStarted(source) => self.dragging = Some(source),
+            LayoutEditorViewCommand::OnDropped(to) => match self.dragging.take() {
+                Some(DragSource::Palette(key)) => {
+                    self.selected = None;
+                    self.update_profile(move |profile| {
+                        profile.insert_grid_button(
+                            GridButton {
+                                key,
+                                size: Default::default(),
+                            },
+                            to,
+                        )
+                    });
+                }
+                // released on the pressed button.
+                Some(DragSource::Grid(from)) if from == to => self.selected = Some(from),
+                Some(DragSource::Grid(from)) => {
+                    self.selected = None;
+                    self.update_profile(move |profile| profile.move_grid_button(from, to));
+                }
+                None => (),
+            },
+            LayoutEditorViewCommand::OnProfileSelected(name) => {
+                self.profile_selected = Some(name);
+                self.selected = None;
+            }
+            LayoutEditorViewCommand::OnRemoveDropped => {
+                if let Some(DragSource::Grid(at)) = self.dragging.take() {
+                    self.selected = None;
+                    self.update_profile(move |profile| {
+                        profile.remove_grid_button(at);
+                    });
+                }
+            }
+            LayoutEditorViewCommand::OnResetClicked => {
+                self.selected = None;
+                self.update_profile(|profile| profile.grid.clear());
+            }
+            LayoutEditorViewCommand::OnSizeSelected(size) => {
+                if let Some(at) = self.selected {
+                    self.update_profile(move |profile| {
+                        if let Some(data) = profile.grid_button_mut(at) {
+                            data.size = size;
+                        }
+                    });
+                }
+            }
+            LayoutEditorViewCommand::OnXMessage(data) => match data {
+                XMessage::OnDeviceSelected(device) => {
+                    self.profile_selected =
+                        Some(self.prefs.layout_profile(Some(&device.serial)).name);
+                    self.selected = None;
+                    self.device = Some(device);
+                }
+                XMessage::OnNewPreferences(prefs) => {
+                    let exists = self.profile_selected.as_ref().map_or(false, |name| {
+                        prefs.layout_profiles.iter().any(|data| &data.name == name)
+                    });
+                    if !exists {
+                        let serial = self.device.as_ref().map(|data| data.serial.as_str());
+                        self.profile_selected = Some(prefs.layout_profile(serial).name);
+                        self.selected = None;
+                    }
+                    self.prefs = prefs;
+                }
+                XMessage::OnKeycodeTestRequested(_)
+                | XMessage::OnPrefsFileUpdated
+                | XMessage::OnWindowFocusChanged(_) => {
+                    // do nothing.
+                }
+            },
+            LayoutEditorViewCommand::Sink => (),
+        }
+        Command::none()
+    }
+
+    pub fn view(&self) -> Element<LayoutEditorViewCommand> {
+        let profile = self.find_profile();
+
+        let mut palette = Column::new().spacing(4);
+        for chunk in self.palette_keys().chunks(4) {
+            let mut palette_row = Row::new().spacing(4);
+            for key in chunk {
+                palette_row = palette_row.push(
+                    DragArea::new(
+                        button(text(self.label(key)).size(14))
+                            .width(Length::Units(64))
+                            .style(match &self.dragging {
+                                Some(DragSource::Palette(data)) if data == key => {
+                                    iced::theme::Button::Primary
+                                }
+                                _ => iced::theme::Button::Secondary,
+                            })
+                            .on_press(LayoutEditorViewCommand::Sink),
+                    )
+                    .on_drag(LayoutEditorViewCommand::OnDragStarted(
+                        DragSource::Palette(key.clone()),
+                    )),
+                );
+            }
+            palette = palette.push(palette_row);
+        }
+
+        let grid = profile.map(|data| data.grid.as_slice()).unwrap_or_default();
+        let mut grid_view = Column::new().spacing(4);
+        for (row_index, grid_row) in grid.iter().enumerate() {
+            let mut row_view = Row::new().spacing(4);
+            for (column, grid_button) in grid_row.iter().enumerate() {
+                let at = GridPosition {
+                    row: row_index,
+                    column,
+                };
+                let highlighted =
+                    self.selected == Some(at) || self.dragging == Some(DragSource::Grid(at));
+                row_view = row_view.push(
+                    DragArea::new(
+                        button(text(self.label(&grid_button.key)))
+                            .width(grid_button.size.width(ButtonSpec::DEFAULT_WIDTH).into())
+                            .height(ButtonSpec::HEIGHT.into())
+                            .style(if highlighted {
+                                iced::theme::Button::Primary
+                            } else {
+                                iced::theme::Button::Secondary
+                            })
+                            .on_press(LayoutEditorViewCommand::Sink),
+                    )
+                    .on_drag(LayoutEditorViewCommand::OnDragStarted(DragSource::Grid(at)))
+                    .on_drop(LayoutEditorViewCommand::OnDropped(at)),
+                );
+            }
+            row_view = row_view.push(drop_slot(
+                "+",
+                Length::Units(24),
+                GridPosition {
+                    row: row_index,
+                    column: grid_row.len(),
+                },
+            ));
+            grid_view = grid_view.push(row_view);
+        }
+        grid_view = grid_view.push(drop_slot(
+            &tr("layout-editor-new-row"),
+            Length::Fill,
+            GridPosition {
+                row: grid.len(),
+                column: 0,
+            },
+        ));
+
+        let selected_size = self
+            .selected
+            .and_then(|at| grid.get(at.row)?.get(at.column))
+            .map(|data| data.size);
+
+        let view = column![
+            row![
+                text(tr("layout-editor-profile")),
+                pick_list(
+                    self.prefs
+                        .layout_profiles
+                        .iter()
+                        .map(|data| data.name.clone())
+                        .collect::<Vec<_>>(),
+                    self.profile_selected.clone(),
+                    LayoutEditorViewCommand::OnProfileSelected,
+                ),
+            ]
+            .spacing(4)
+            .align_items(iced::alignment::Alignment::Center),
+            text(tr("layout-editor-help")).size(14),
+            palette,
+            text(tr(if grid.is_empty() {
+                "layout-editor-grid-empty"
+            } else {
+                "layout-editor-grid"
+            }))
+            .size(14),
+            scrollable(grid_view).height(Length::Fill),
+            row![
+                DragArea::new(
+                    button(text(tr("layout-editor-remove")))
+                        .style(iced::theme::Button::Destructive)
+                        .on_press(LayoutEditorViewCommand::Sink),
+                )
+                .on_drop(LayoutEditorViewCommand::OnRemoveDropped),
+                pick_list(
+                    &ButtonSize::ALL[..],
+                    selected_size,
+                    LayoutEditorViewCommand::OnSizeSelected,
+                )
+                .placeholder(tr("layout-editor-size")),
+                button(text(tr("layout-editor-use-sections")))
+                    .style(iced::theme::Button::Secondary)
+                    .on_press(LayoutEditorViewCommand::OnResetClicked),
+            ]
+            .spacing(4)
+            .align_items(iced::alignment::Alignment::Center),
+        ]
+        .spacing(8);
+
+        // cancels the drag released outside the drop targets.
+        DragArea::new(view)
+            .on_drop(LayoutEditorViewCommand::OnDragCancelled)
+            .into()
+    }
+
+    pub fn view_size() -> (u32, u32) {
+        (300, 700)
+    }
+
+    fn find_profile(&self) -> Option<&LayoutProfile> {
+        let name = self.profile_selected.as_ref()?;
+        self.prefs
+            .layout_profiles
+            .iter()
+            .find(|data| &data.name == name)
+    }
+
+    /// the mapped keys and the custom keys that aren't duplicated.
+    fn palette_keys(&self) -> Vec<SendEventKey> {
+        let mut keys = SendEventKey::MAPPED.to_vec();
+        for custom_key in &self.prefs.custom_keys {
+            let key = SendEventKey::Custom(custom_key.keycode.clone());
+            if !keys.contains(&key) {
+                keys.push(key);
+            }
+        }
+        keys
+    }
+
+    fn label(&self, key: &SendEventKey) -> String {
+        match key {
+            SendEventKey::Custom(keycode) => self
+                .prefs
+                .custom_keys
+                .iter()
+                .find(|data| &data.keycode == keycode)
+                .map(|data| data.label.clone())
+                .unwrap_or_else(|| keycode.trim_start_matches("KEYCODE_").into()),
+            key => key_label(key),
+        }
+    }
+
+    fn update_profile(&self, f: impl FnOnce(&mut LayoutProfile) + Send + 'static) {
+        let name = match &self.profile_selected {
+            Some(data) => data.clone(),
+            None => return,
+        };
+
+        self.prefs_store.update(move |prefs| {
+            match prefs
+                .layout_profiles
+                .iter_mut()
+                .find(|data| data.name == name)
+            {
+                Some(data) => f(data),
+                None => warn!(%name, "layout profile not found"),
+            }
+        });
+    }
+}
+
+fn drop_slot<'a>(
+    label: &str,
+    width: Length,
+    to: GridPosition,
+) -> Element<'a, LayoutEditorViewCommand> {
+    DragArea::new(
+        container(text(label).size(14))
+            .width(width)
+            .height(ButtonSpec::HEIGHT.into())
+            .center_x()
+            .center_y(),
+    )
+    .on_drop(LayoutEditorViewCommand::OnDropped(to))
+    .into()
+}
@@ -0,0 +1,303 @@
+/*
+ * Copyright 2022 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+mod logcat_recipe;
+
+use crate::data::i18n::{tr, tr_args};
+use crate::feature::logcat::logcat_recipe::{logcat, LogcatRecipeEvent};
+use crate::model::history::History;
+use crate::model::logcat::{merge_timeline, LogPriority, LogcatFilter, LogcatLine, TimelineRow};
+use crate::model::{AndroidDevice, XMessage};
+use iced::widget::{
+    button, checkbox, column, container, pick_list, row, scrollable, text, text_input, Column,
+};
+use iced::{Command, Element, Length, Subscription};
+use std::collections::VecDeque;
+use std::sync::Arc;
+use tracing::info;
+
+#[derive(Clone, Debug)]
+pub enum LogcatViewCommand {
+    LogcatRecipeResult(LogcatRecipeEvent),
+    OnClearClicked,
+    OnPatternChanged(String),
+    OnPauseClicked,
+    OnPrioritySelected(LogPriority),
+    OnShowKeysToggled(bool),
+    OnStartClicked,
+    OnStopClicked,
+    OnTagChanged(String),
+    OnXMessage(XMessage),
+}
+
+pub struct LogcatView {
+    device: Option<Arc<AndroidDevice>>,
+    filter: LogcatFilter,
+    /// the invalid regex, and the previous filter is kept.
+    filter_error: Option<String>,
+    lines: VecDeque<LogcatLine>,
+    pattern: String,
+    /// the lines received while paused, which are shown on resume.
+    paused: Option<VecDeque<LogcatLine>>,
+    priority: LogPriority,
+    run_id: u64,
+    /// the serial of the streaming device.
+    running: Option<String>,
+    /// shows the history entries of the device between the lines.
+    show_keys: bool,
+    status: String,
+    tag: String,
+}
+
+impl Default for LogcatView {
+    fn default() -> Self {
+        Self {
+            device: None,
+            filter: Default::default(),
+            filter_error: None,
+            lines: VecDeque::with_capacity(Self::LINES_CAPACITY),
+            pattern: "".into(),
+            paused: None,
+            priority: LogPriority::Verbose,
+            run_id: 0,
+            running: None,
+            show_keys: true,
+            status: "".into(),
+            tag: "".into(),
+        }
+    }
+}
+
+impl LogcatView {
+    /// keeps the latest lines only.
+    const LINES_CAPACITY: usize = 5000;
+    /// the number of the matched lines to show.
+    const VISIBLE_LINES: usize = 300;
+
+    pub fn update(&mut self, command: LogcatViewCommand) -> Command<LogcatViewCommand> {
+        match command {
+            LogcatViewCommand::LogcatRecipeResult(data) => match data {
+                LogcatRecipeEvent::Output(data) => {
+                    let lines = self.paused.as_mut().unwrap_or(&mut self.lines);
+                    for line in data {
+                        push_line(lines, LogcatLine::parse(line));
+                    }
+                }
+                LogcatRecipeEvent::Finished => {
+                    info!("logcat finished");
+                    self.running = None;
+                    self.status = tr("logcat-status-exited");
+                }
+            },
+            LogcatViewCommand::OnClearClicked => {
+                self.lines.clear();
+                if let Some(data) = &mut self.paused {
+                    data.clear();
+                }
+            }
+            LogcatViewCommand::OnPatternChanged(data) => {
+                self.pattern = data;
+                self.update_filter();
+            }
+            LogcatViewCommand::OnPauseClicked => match self.paused.take() {
+                Some(data) => {
+                    for line in data {
+                        push_line(&mut self.lines, line);
+                    }
+                }
+                None => self.paused = Some(VecDeque::new()),
+            },
+            LogcatViewCommand::OnPrioritySelected(data) => {
+                self.priority = data;
+                self.update_filter();
+            }
+            LogcatViewCommand::OnShowKeysToggled(data) => {
+                self.show_keys = data;
+            }
+            LogcatViewCommand::OnStartClicked => match &self.device {
+                Some(data) => {
+                    self.run_id += 1;
+                    self.running = Some(data.serial.clone());
+                    self.status = tr("logcat-status-running");
+                }
+                None => self.status = tr("logcat-status-no-device"),
+            },
+            LogcatViewCommand::OnStopClicked => {
+                self.running = None;
+                self.status = tr("logcat-status-stopped");
+            }
+            LogcatViewCommand::OnTagChanged(data) => {
+                self.tag = data;
+                self.update_filter();
+            }
+            LogcatViewCommand::OnXMessage(data) => match data {
+                XMessage::OnDeviceSelected(device) => {
+                    if self.running.is_some() && self.running.as_ref() != Some(&device.serial) {
+                        self.running = None;
+                        self.status = tr("logcat-status-device-changed");
+                    }
+                    self.device = Some(device);
+                }
+                XMessage::OnKeycodeTestRequested(_)
+                | XMessage::OnNewPreferences(_)
+                | XMessage::OnPrefsFileUpdated
+                | XMessage::OnWindowFocusChanged(_) => {
+                    // do nothing.
+                }
+            },
+        }
+        Command::none()
+    }
+
+    pub fn subscription(&self) -> Subscription<LogcatViewCommand> {
+        match &self.running {
+            Some(serial) => {
+                logcat(serial.clone(), self.run_id).map(LogcatViewCommand::LogcatRecipeResult)
+            }
+            None => Subscription::none(),
+        }
+    }
+
+    /// the newest line is on the top since the scrollable doesn't follow the new lines.
+    ///
+    /// the entries of `history` are shown as the markers between the lines.
+    pub fn view(&self, history: &History) -> Element<LogcatViewCommand> {
+        let lines = self
+            .lines
+            .iter()
+            .rev()
+            .filter(|data| self.filter.matches(data))
+            .take(Self::VISIBLE_LINES);
+        let serial = match (&self.running, &self.device) {
+            (Some(data), _) => Some(data),
+            (None, Some(data)) => Some(&data.serial),
+            (None, None) => None,
+        };
+        let entries = history
+            .iter()
+            .filter(|data| self.show_keys && Some(&data.serial) == serial);
+
+        let mut output = Column::new();
+        for data in merge_timeline(lines, entries) {
+            output = match data {
+                TimelineRow::Key(entry) => output.push(
+                    container(
+                        text(format!(
+                            "{} >>> {}",
+                            entry.sent_at.format("%m-%d %H:%M:%S%.3f"),
+                            entry.kind
+                        ))
+                        .size(14),
+                    )
+                    .width(Length::Fill)
+                    .style(iced::theme::Container::Box),
+                ),
+                TimelineRow::Line(line) => output.push(text(&line.raw).size(14)),
+            };
+        }
+
+        let mut view = column![
+            text(match &self.device {
+                Some(data) => tr_args("logcat-device", &[("device", data.to_string())]),
+                None => tr("logcat-device-none"),
+            }),
+            row![
+                match self.running {
+                    Some(_) => button(text(tr("logcat-stop")))
+                        .style(iced::theme::Button::Secondary)
+                        .on_press(LogcatViewCommand::OnStopClicked),
+                    None => button(text(tr("logcat-start")))
+                        .style(iced::theme::Button::Secondary)
+                        .on_press(LogcatViewCommand::OnStartClicked),
+                },
+                button(text(match self.paused {
+                    Some(_) => tr("logcat-resume"),
+                    None => tr("logcat-pause"),
+                }))
+                .style(iced::theme::Button::Secondary)
+                .on_press(LogcatViewCommand::OnPauseClicked),
+                button(text(tr("logcat-clear")))
+                    .style(iced::theme::Button::Secondary)
+                    .on_press(LogcatViewCommand::OnClearClicked),
+            ]
+            .spacing(4),
+            row![
+                text_input(
+                    &tr("logcat-tag"),
+                    &self.tag,
+                    LogcatViewCommand::OnTagChanged
+                )
+                .width(Length::Units(160)),
+                pick_list(
+                    &LogPriority::ALL[..],
+                    Some(self.priority),
+                    LogcatViewCommand::OnPrioritySelected,
+                ),
+                text_input(
+                    &tr("logcat-regex"),
+                    &self.pattern,
+                    LogcatViewCommand::OnPatternChanged
+                ),
+                checkbox(
+                    tr("logcat-keys"),
+                    self.show_keys,
+                    LogcatViewCommand::OnShowKeysToggled
+                ),
+            ]
+            .spacing(4)
+            .align_items(iced::alignment::Alignment::Center),
+        ]
+        .spacing(8);
+
+        if let Some(data) = &self.filter_error {
+            view = view.push(text(data));
+        }
+
+        view.push(text(match &self.paused {
+            Some(data) => tr_args(
+                "logcat-status-paused",
+                &[
+                    ("status", self.status.clone()),
+                    ("count", data.len().to_string()),
+                ],
+            ),
+            None => self.status.clone(),
+        }))
+        .push(scrollable(output).height(Length::Fill))
+        .into()
+    }
+
+    pub fn view_size() -> (u32, u32) {
+        (640, 700)
+    }
+
+    fn update_filter(&mut self) {
+        match LogcatFilter::new(&self.tag, self.priority, &self.pattern) {
+            Ok(data) => {
+                self.filter = data;
+                self.filter_error = None;
+            }
+            Err(e) => self.filter_error = Some(format!("{:#}", e)),
+        }
+    }
+}
+
+fn push_line(lines: &mut VecDeque<LogcatLine>, line: LogcatLine) {
+    if lines.len() == LogcatView::LINES_CAPACITY {
+        lines.pop_front();
+    }
+    lines.push_back(line);
+}
@@ -0,0 +1,117 @@
+/*
+ * Copyright 2022 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::function::command_functions::{adb_command, register_child, ChildRegistration};
+use iced::subscription::{unfold, Subscription};
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, BufReader, Lines};
+use tokio::process::{Child, ChildStdout};
+use tracing::{debug, info, warn};
+
+/// the lines are sent together since the whole buffer is printed at first.
+const BATCH_INTERVAL: Duration = Duration::from_millis(100);
+const BATCH_CAPACITY: usize = 500;
+
+#[derive(Clone, Debug)]
+pub enum LogcatRecipeEvent {
+    Finished,
+    Output(Vec<String>),
+}
+
+enum StreamState {
+    Init(String),
+    Running(Child, ChildRegistration, Lines<BufReader<ChildStdout>>),
+    Finish,
+}
+
+struct LogcatRecipeType;
+
+/// streams `logcat -v threadtime` of the device.
+pub fn logcat(serial: String, run_id: u64) -> Subscription<LogcatRecipeEvent> {
+    unfold(
+        (std::any::TypeId::of::<LogcatRecipeType>(), run_id),
+        StreamState::Init(serial),
+        execute,
+    )
+}
+
+async fn execute(state: StreamState) -> (Option<LogcatRecipeEvent>, StreamState) {
+    match state {
+        StreamState::Init(serial) => {
+            info!(%serial, "start logcat");
+
+            let mut command = adb_command();
+            command
+                .args(["-s", &serial, "logcat", "-v", "threadtime"])
+                .stdout(Stdio::piped())
+                .stderr(Stdio::null());
+
+            match command.spawn() {
+                Ok(mut child) => match child.stdout.take() {
+                    Some(stdout) => {
+                        let registration = register_child(child.id());
+                        (
+                            None,
+                            StreamState::Running(
+                                child,
+                                registration,
+                                BufReader::new(stdout).lines(),
+                            ),
+                        )
+                    }
+                    None => {
+                        warn!("stdout not found");
+                        child.kill().await.ok();
+                        (Some(LogcatRecipeEvent::Finished), StreamState::Finish)
+                    }
+                },
+                Err(e) => {
+                    warn!(?e, "failed to start logcat");
+                    (Some(LogcatRecipeEvent::Finished), StreamState::Finish)
+                }
+            }
+        }
+        StreamState::Running(mut child, registration, mut lines) => {
+            let mut batch = match lines.next_line().await {
+                Ok(Some(line)) => vec![line],
+                Ok(None) | Err(_) => {
+                    let ret = child.wait().await;
+                    debug!(?ret, "logcat exited");
+                    return (Some(LogcatRecipeEvent::Finished), StreamState::Finish);
+                }
+            };
+
+            let deadline = tokio::time::Instant::now() + BATCH_INTERVAL;
+            while batch.len() < BATCH_CAPACITY {
+                match tokio::time::timeout_at(deadline, lines.next_line()).await {
+                    Ok(Ok(Some(line))) => batch.push(line),
+                    // the exit is handled by the next read.
+                    Ok(Ok(None)) | Ok(Err(_)) | Err(_) => break,
+                }
+            }
+
+            (
+                Some(LogcatRecipeEvent::Output(batch)),
+                StreamState::Running(child, registration, lines),
+            )
+        }
+        StreamState::Finish => {
+            debug!("finish");
+            iced::futures::future::pending().await
+        }
+    }
+}
@@ -14,92 +14,746 @@
  * limitations under the License.
  */
 
+mod accessibility_panel;
 mod adb_server_recipe;
+mod api_server_recipe;
+mod command_target_recipe;
+mod focused_app_recipe;
+mod gamepad_recipe;
+mod hold_area;
+mod locale_panel;
+mod mqtt_recipe;
+mod pairing_panel;
+mod pointer_pad;
+mod reboot_panel;
+mod recording_panel;
+mod screen_state_recipe;
+mod self_test;
+mod self_test_panel;
 
-use crate::data::resource::Resource;
-use crate::feature::main::adb_server_recipe::{adb_server, AdbServerRecipeEvent};
+use crate::data::i18n::{tr, tr_args};
+use crate::data::icon::Icon;
+use crate::data::preferences_store::PreferencesStore;
+use crate::data::session_dir::SessionDir;
+use crate::feature::main::accessibility_panel::{AccessibilityPanel, AccessibilityPanelCommand};
+use crate::feature::main::adb_server_recipe::{
+    adb_server, AdbServerRecipeEvent, CommandReceiver, ConnectStep, COMMAND_QUEUE_CAPACITY,
+    SHUTDOWN_TIMEOUT,
+};
+use crate::feature::main::api_server_recipe::{api_server, ApiServerRecipeEvent};
+use crate::feature::main::command_target_recipe::command_target;
+use crate::feature::main::focused_app_recipe::{focused_app, FocusedAppRecipeEvent};
+use crate::feature::main::gamepad_recipe::{gamepad, GamepadRecipeEvent};
+use crate::feature::main::hold_area::HoldArea;
+use crate::feature::main::locale_panel::{LocalePanel, LocalePanelCommand};
+use crate::feature::main::mqtt_recipe::{mqtt, MqttRecipeEvent};
+use crate::feature::main::pairing_panel::{PairingPanel, PairingPanelCommand};
+use crate::feature::main::pointer_pad::PointerPad;
+use crate::feature::main::reboot_panel::{RebootPanel, RebootPanelCommand};
+use crate::feature::main::recording_panel::{RecordingPanel, RecordingPanelCommand};
+use crate::feature::main::screen_state_recipe::{screen_state, ScreenStateRecipeEvent};
+use crate::feature::main::self_test_panel::{SelfTestPanel, SelfTestPanelCommand};
+use crate::function::adb_functions::{
+    capture_input_events, capture_screenshot, dump_input, has_vibrator, launch_shortcut,
+    put_accessibility_services, retrieve_adb_version, retrieve_device_health,
+    retrieve_device_usage, retrieve_devices, retrieve_screen_size, retrieve_users,
+    send_pointer_gesture, vibrate, write_log_marker,
+};
+use crate::function::command_functions::describe_adb_error;
+use crate::function::notification_functions::show_notification;
+use crate::function::qr_functions::render_qr_svg;
+use crate::function::report_functions::export_macro_report;
+use crate::function::server_functions::{is_server_asset_outdated, resolve_server_source_dir};
+use crate::function::webhook_functions::post_webhook;
+use crate::model::adb_version::{AdbCapability, AdbVersion};
+use crate::model::api::{ApiResponse, ApiRoute, ApiState, ApiTokens};
+use crate::model::device_health::DeviceHealth;
+use crate::model::device_profile::DeviceProfile;
+use crate::model::device_usage::DeviceUsage;
+use crate::model::gesture::{ButtonAction, GestureRecognizer, LongPressMode};
+use crate::model::history::{format_relative_time, History, HistoryEntry, HistoryEntryKind};
+use crate::model::input_diagnostics::InputDiagnostics;
+use crate::model::key_macro::{parse_macro, MacroRecorder, MacroStep, SavedMacro};
+use crate::model::keycode::Keycode;
+use crate::model::layout::{
+    create_grid, create_layout, disable_keys, mark_pending_keys, ButtonSpec, ButtonStyle,
+    LaunchShortcut, SectionId,
+};
+use crate::model::pointer::PointerGesture;
+use crate::model::protocol::{encode_commands, ServerCommand};
+use crate::model::schedule::{MacroReport, ScheduledMacro};
 use crate::model::send_event_key::SendEventKey;
-use crate::model::{AndroidDevice, ColorKeyButtonStyle, KeyMap, Preferences, XMessage};
+use crate::model::shortcut::KeyShortcut;
+use crate::model::target::{DeviceCapabilities, DeviceKind};
+use crate::model::webhook::WebhookEvent;
+use crate::model::{
+    AndroidDevice, AndroidUser, ColorKeyButtonStyle, KeyMap, Preferences, StatusIndicatorStyle,
+    XMessage,
+};
 use crate::prelude::*;
-use iced::keyboard::{Event as KeyboardEvent, KeyCode};
+use iced::keyboard::{Event as KeyboardEvent, KeyCode, Modifiers};
+use iced::mouse::{Button as MouseButton, Event as MouseEvent};
 use iced::subscription::events as native_events;
 use iced::widget::{
-    button, checkbox, column, container, pick_list, row, svg, svg::Handle as SvgHandle, Space,
+    button, checkbox, column, container, pick_list, row, scrollable, svg, text, text_input, Column,
+    Row, Space,
 };
 use iced::{Command, Element, Event as NativeEvent, Length, Subscription};
-use std::io::BufRead;
+use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tracing::{debug, info, warn};
 
+const MACRO_KEY_INTERVAL: Duration = Duration::from_millis(200);
+const INPUT_CAPTURE_MAX_SECS: u64 = 60;
+const LONG_PRESS_TIMEOUT: Duration = Duration::from_millis(500);
+const HAPTIC_DURATION: Duration = Duration::from_millis(30);
+/// polls the battery and the thermal status while connected.
+const HEALTH_INTERVAL: Duration = Duration::from_secs(30);
+const SCHEDULE_INTERVAL: Duration = Duration::from_secs(30);
+const RECONNECT_DELAY_INITIAL: Duration = Duration::from_secs(1);
+const RECONNECT_DELAY_MAX: Duration = Duration::from_secs(30);
+const RECONNECT_MAX_RETRIES: u32 = 6;
+/// the room of the command queue that only the ups can take so that no key is left down, which
+/// is more than the keys held at once.
+const RELEASE_QUEUE_RESERVE: usize = 16;
+
 #[derive(Clone, Debug)]
 pub enum MainViewCommand {
+    Accessibility(AccessibilityPanelCommand),
     AdbDevicesSelected(Arc<AndroidDevice>),
     AdbServerRecipeResult(AdbServerRecipeEvent),
+    AdbVersionRetrieved(Option<AdbVersion>),
+    ApiServerRecipeResult(ApiServerRecipeEvent),
+    ButtonHeld(SendEventKey),
+    /// the button is still held after [LONG_PRESS_TIMEOUT].
+    ButtonLongPressTimedOut(u64),
+    ButtonPressed(SendEventKey),
+    ButtonReleased,
+    ClipboardMacroRead(Option<String>),
+    DeviceHealthRetrieved(Option<DeviceHealth>),
+    DeviceHealthTick,
+    DeviceUsageRetrieved(Option<DeviceUsage>),
     Event(NativeEvent),
-    InvokeDevicesResult(Vec<Arc<AndroidDevice>>),
+    FocusedAppRecipeResult(FocusedAppRecipeEvent),
+    GamepadRecipeResult(GamepadRecipeEvent),
+    GestureTimedOut(u64),
+    InputDiagnosticsRetrieved(Option<InputDiagnostics>),
+    InputEventsCaptured(InputCaptureTarget, Option<String>),
+    InputEventsSaved(Option<PathBuf>),
+    InvokeDevicesResult(Result<Vec<Arc<AndroidDevice>>, String>),
+    KeyRepeatTimedOut(u64),
+    KeyboardRepeatTimedOut(u64),
+    Locale(LocalePanelCommand),
+    LogMarkerWritten(String),
+    /// sends the long keycode of [Preferences::long_keycode] if the key is still held.
+    LongKeyTimedOut(u64),
+    LongPressTimedOut(Keycode, LongPressMode),
+    MacroFailed(String, Option<PathBuf>),
+    MacroReportExported(Option<PathBuf>),
+    MacroStepFinished(u64),
+    MqttRecipeResult(MqttRecipeEvent),
     OnAdbConnectClicked,
     OnAdbDevicesReloadClicked,
+    OnClipboardMacroClicked,
+    /// quits the server before the window is closed.
+    OnCloseRequested,
+    OnDeviceApproved,
+    OnDeviceBlocked,
+    OnDeviceConfirmCancelled,
+    OnDeviceLabelChanged(String),
+    /// saves the label, or removes it if empty.
+    OnDeviceLabelSubmitted,
+    OnDeviceUsageRefreshClicked,
+    OnHapticToggled(bool),
+    /// sends the entry again to the connected device.
+    OnHistoryEntryClicked(HistoryEntryKind),
+    OnHistoryExportClicked,
+    OnInputCaptureClicked(InputCaptureTarget),
+    OnInputCaptureSecsChanged(String),
+    OnInputDiagnosticsClicked,
+    OnInputDiagnosticsHideClicked,
+    OnKeyMapProfileSelected(String),
+    OnKeyboardModeToggled(bool),
+    OnLayoutProfileSelected(String),
+    OnMacroDeleteClicked(String),
+    OnMacroDiscardClicked,
+    OnMacroNameChanged(String),
+    /// starts or stops recording the macro.
+    OnMacroRecordClicked,
+    OnMacroSaveClicked,
+    OnMacroStopClicked,
+    OnMarkLogClicked,
     OnNewPrefs(Option<Arc<Preferences>>),
+    /// the notification of the other views, shown only while the window is unfocused.
+    OnNotificationRequested(String, String),
+    OnPointerModeToggled(bool),
+    OnPowerConfirmCancelled,
+    /// sends [SendEventKey::Power] asked by [Preferences::confirm_power_key].
+    OnPowerConfirmed,
+    OnProfileCodeCopyClicked,
+    OnProfileShareClicked,
+    /// connects again after the server crashed without waiting for the retries.
+    OnServerCrashReconnectClicked,
+    OnSavedMacroClicked(String),
+    OnSectionCollapseToggled(SectionId),
+    OnShortcutClicked(LaunchShortcut),
+    OnUserSelected(AndroidUser),
+    OnWakeClicked,
     OnXMessage(XMessage),
+    Pairing(PairingPanelCommand),
+    PointerGestured(PointerGesture),
+    Reboot(RebootPanelCommand),
+    /// with the connect attempt that scheduled the reconnection.
+    ReconnectDelayElapsed(u32),
+    Recording(RecordingPanelCommand),
     RequestSendEvent(SendEventKey),
+    ScheduleTick,
+    ScheduledMacroRead(ScheduledMacro, Result<String, String>),
+    ScreenSizeRetrieved(Option<(u32, u32)>),
+    ScreenStateRecipeResult(ScreenStateRecipeEvent),
+    SelfTest(SelfTestPanelCommand),
+    SendXMessage(XMessage),
+    ServerAssetChecked(bool),
+    ShortcutLaunched(LaunchShortcut),
+    /// the server has exited, handled by App to close the window.
+    ShutdownFinished,
+    /// shows the error by the toast of App.
+    ShowError(String),
+    Sink,
+    UsersRetrieved(Vec<AndroidUser>),
+    VibratorDetected(bool),
 }
 
 pub struct MainView {
+    accessibility: AccessibilityPanel,
     adb_connectivity: AdbConnectivity,
     adb_devices: Vec<Arc<AndroidDevice>>,
     adb_devices_selected: Option<Arc<AndroidDevice>>,
-    adb_server_rx: tokio::sync::watch::Receiver<String>,
-    adb_server_tx: tokio::sync::watch::Sender<String>,
+    adb_server_rx: CommandReceiver,
+    adb_server_tx: tokio::sync::mpsc::Sender<Vec<ServerCommand>>,
+    /// `None` if not retrieved yet.
+    adb_version: Option<Option<AdbVersion>>,
+    /// the commands sent to the device, which are pushed to the WebSocket clients.
+    api_commands: tokio::sync::broadcast::Sender<String>,
+    /// the port that failed to listen on and the reason.
+    api_error: Option<(u16, String)>,
+    /// the WebSocket clients that only watch.
+    api_observers: usize,
+    /// the state that is pushed to the WebSocket clients and the MQTT broker.
+    api_state: tokio::sync::watch::Sender<ApiState>,
+    api_tokens: ApiTokens,
+    config_dir: PathBuf,
+    connect_attempt: u32,
+    /// the serial of the unknown device waiting for the approval to connect.
+    connect_confirming: Option<String>,
+    connect_failed: bool,
+    connect_step: Option<ConnectStep>,
+    /// `None` until the device is connected.
+    connected_capabilities: Option<DeviceCapabilities>,
+    /// `None` until retrieved, and kept until the other device is selected.
+    device_health: Option<DeviceHealth>,
+    device_health_loading: bool,
+    /// the label being edited, or `None` to show the one in the preferences.
+    device_label: Option<String>,
+    /// `None` until retrieved, and kept until the other device is selected.
+    device_usage: Option<DeviceUsage>,
+    device_usage_loading: bool,
+    focused_package: Option<String>,
+    gesture_recognizer: GestureRecognizer,
+    /// the key that is held by the button in the hold mode, or long-pressed in the click mode.
+    held_key: Option<HeldKey>,
+    held_key_id: u64,
+    history: History,
+    input_capture_secs: String,
+    input_capture_status: Option<String>,
+    input_capturing: bool,
+    input_diagnosing: bool,
+    input_diagnostics: Option<InputDiagnostics>,
+    /// sends the typed characters as text instead of the key bindings.
+    keyboard_mode: bool,
+    /// invalidates the repeat of the last key pressed on the keyboard when changed.
+    keyboard_repeat_id: u64,
+    locale: LocalePanel,
+    log_marker_count: u64,
+    /// the held key that has the long keycode, which isn't sent until released or timed out.
+    long_key: Option<PendingLongKey>,
+    long_key_id: u64,
+    macro_name: String,
+    /// the recorded steps waiting for the name to save.
+    macro_recorded: Option<Vec<MacroStep>>,
+    macro_recorder: Option<MacroRecorder>,
+    macro_run: Option<MacroRun>,
+    macro_run_id: u64,
+    /// the scheduled macro that the running macro reports to.
+    macro_schedule: Option<ScheduledRun>,
+    macro_status: Option<String>,
+    modifiers: Modifiers,
+    /// when the left mouse button was pressed to measure how long the button is held.
+    mouse_pressed_at: Option<Instant>,
+    /// the reason why the MQTT client is reconnecting.
+    mqtt_error: Option<String>,
+    pairing: PairingPanel,
+    /// the commands sent to the server that aren't acked yet, to highlight their buttons.
+    pending_acks: Vec<ServerCommand>,
+    /// drives the touchscreen by the pointer pad.
+    pointer_mode: bool,
+    /// the power button is clicked and waiting for the confirmation.
+    power_confirming: bool,
+    /// the keys held on the keyboard, released when the window loses the focus.
+    ///
+    /// keeps the key code to release the same key even if the modifiers are released first.
+    pressed_keys: Vec<(KeyCode, SendEventKey)>,
+    /// the button pressed in the click mode, which is clicked on release unless long-pressed.
+    pressed_button: Option<PressedButton>,
+    /// the devices driven without adb, listed after the adb devices.
+    target_devices: Vec<Arc<AndroidDevice>>,
     prefs: Arc<Preferences>,
+    prefs_store: PreferencesStore,
+    /// the code and its QR code of the selected device, shown while sharing.
+    profile_share: Option<(String, iced::widget::svg::Handle)>,
+    reboot: RebootPanel,
+    recording: RecordingPanel,
+    /// the number of the automatic reconnections since the connection was lost.
+    reconnect_retry: u32,
+    /// the delay before the next reconnection while waiting for it.
+    reconnect_waiting: Option<Duration>,
+    /// the scheduled macros are due if their time is after this.
+    schedule_checked_at: chrono::NaiveDateTime,
+    screen_awake: Option<bool>,
+    /// `None` until the pointer mode is enabled.
+    screen_size: Option<(u32, u32)>,
+    self_test: SelfTestPanel,
+    /// the stderr excerpt of the crashed server.
+    server_crash: Option<Vec<String>>,
+    server_build_dir: Option<PathBuf>,
+    session_dir: Arc<SessionDir>,
+    server_outdated: bool,
+    /// waiting for the server to exit to close the window.
+    shutting_down: bool,
+    /// the users of the connected device, shown only if the device has secondary users.
+    users: Vec<AndroidUser>,
+    /// the user to launch the shortcuts as, `None` for the current user.
+    user_selected: Option<AndroidUser>,
+    /// `None` if not detected yet.
+    vibrator_available: Option<bool>,
+    window_focused: bool,
+}
+
+struct MacroRun {
+    id: u64,
+    position: usize,
+    steps: Vec<MacroStep>,
+}
+
+struct HeldKey {
+    id: u64,
+    keycode: Keycode,
+    /// the long-press has been sent and the release sends only the up.
+    long_pressed: bool,
+}
+
+struct PendingLongKey {
+    id: u64,
+    key: SendEventKey,
+    /// `None` for the button held in the hold mode.
+    key_code: Option<KeyCode>,
+    /// the long keycode has been sent and the release sends nothing.
+    sent: bool,
+}
+
+struct PressedButton {
+    id: u64,
+    key: SendEventKey,
+    /// the long-press has been sent while held, so the click on release is ignored.
+    long_pressed: bool,
+}
+
+struct ScheduledRun {
+    schedule: ScheduledMacro,
+    serial: String,
+    started_at: chrono::DateTime<chrono::Local>,
+    steps: usize,
+}
+
+/// where to put the captured `getevent` output.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum InputCaptureTarget {
+    Clipboard,
+    File,
 }
 
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
 enum AdbConnectivity {
     Connected,
     Connecting,
+    /// connected but failed to send the last key.
+    Degraded,
     Disconnected,
+    /// reconnecting after the server exited unexpectedly.
+    Reconnecting,
+}
+
+impl AdbConnectivity {
+    /// whether the keys can be sent.
+    fn is_connected(&self) -> bool {
+        matches!(self, AdbConnectivity::Connected | AdbConnectivity::Degraded)
+    }
+
+    /// whether the server subscription is running.
+    fn is_active(&self) -> bool {
+        !matches!(self, AdbConnectivity::Disconnected)
+    }
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            AdbConnectivity::Connected => "connected",
+            AdbConnectivity::Connecting => "connecting",
+            AdbConnectivity::Degraded => "degraded",
+            AdbConnectivity::Disconnected => "disconnected",
+            AdbConnectivity::Reconnecting => "reconnecting",
+        }
+    }
 }
 
 impl MainView {
-    pub fn new(prefs: Arc<Preferences>) -> Self {
-        let (adb_server_tx, adb_server_rx) = tokio::sync::watch::channel("".into());
+    pub fn new(
+        prefs: Arc<Preferences>,
+        prefs_store: PreferencesStore,
+        config_dir: PathBuf,
+        session_dir: Arc<SessionDir>,
+        api_tokens: ApiTokens,
+    ) -> Self {
+        let (adb_server_tx, adb_server_rx) = tokio::sync::mpsc::channel(COMMAND_QUEUE_CAPACITY);
+        let target_devices = target_devices(&prefs);
         Self {
+            accessibility: Default::default(),
             adb_connectivity: AdbConnectivity::Disconnected,
             adb_devices: vec![],
             adb_devices_selected: None,
-            adb_server_rx,
+            adb_server_rx: Arc::new(tokio::sync::Mutex::new(adb_server_rx)),
             adb_server_tx,
+            adb_version: None,
+            api_commands: tokio::sync::broadcast::channel(COMMAND_QUEUE_CAPACITY).0,
+            api_error: None,
+            api_observers: 0,
+            api_state: tokio::sync::watch::channel(ApiState::default()).0,
+            api_tokens,
+            config_dir,
+            connect_attempt: 0,
+            connect_confirming: None,
+            connect_failed: false,
+            connect_step: None,
+            connected_capabilities: None,
+            device_health: None,
+            device_health_loading: false,
+            device_label: None,
+            device_usage: None,
+            device_usage_loading: false,
+            focused_package: None,
+            gesture_recognizer: Default::default(),
+            held_key: None,
+            held_key_id: 0,
+            history: Default::default(),
+            input_capture_secs: "5".into(),
+            input_capture_status: None,
+            input_capturing: false,
+            input_diagnosing: false,
+            input_diagnostics: None,
+            keyboard_mode: false,
+            keyboard_repeat_id: 0,
+            locale: Default::default(),
+            log_marker_count: 0,
+            long_key: None,
+            long_key_id: 0,
+            macro_name: "".into(),
+            macro_recorded: None,
+            macro_recorder: None,
+            macro_run: None,
+            macro_run_id: 0,
+            macro_schedule: None,
+            macro_status: None,
+            mqtt_error: None,
+            modifiers: Default::default(),
+            mouse_pressed_at: None,
+            pairing: Default::default(),
+            pending_acks: vec![],
+            pointer_mode: false,
+            power_confirming: false,
+            pressed_keys: vec![],
+            pressed_button: None,
+            target_devices,
             prefs,
+            prefs_store,
+            schedule_checked_at: chrono::Local::now().naive_local(),
+            profile_share: None,
+            reboot: Default::default(),
+            reconnect_retry: 0,
+            reconnect_waiting: None,
+            recording: Default::default(),
+            screen_awake: None,
+            screen_size: None,
+            self_test: Default::default(),
+            server_crash: None,
+            server_build_dir: None,
+            session_dir,
+            server_outdated: false,
+            shutting_down: false,
+            users: vec![],
+            user_selected: None,
+            vibrator_available: None,
+            window_focused: true,
         }
     }
 
     pub fn init_command() -> Command<MainViewCommand> {
-        retrieve_devices_command()
+        Command::batch([
+            retrieve_devices_command(),
+            Command::perform(retrieve_adb_version(), |ret| {
+                MainViewCommand::AdbVersionRetrieved(match ret {
+                    Ok(data) => Some(data),
+                    Err(e) => {
+                        warn!(?e, "failed to retrieve the adb version");
+                        None
+                    }
+                })
+            }),
+        ])
     }
 
     pub fn update(&mut self, command: MainViewCommand) -> Command<MainViewCommand> {
+        let ret = self.handle_command(command);
+        self.publish_api_state();
+        ret
+    }
+
+    fn handle_command(&mut self, command: MainViewCommand) -> Command<MainViewCommand> {
         match command {
             MainViewCommand::AdbDevicesSelected(data) => {
                 info!(%data, "device selected");
-                self.adb_devices_selected = Some(data);
+                // the pick list can't disable the entry.
+                if let Some(details) = data.details.as_ref().filter(|_| !data.is_usable()) {
+                    let mut message = tr_args(
+                        "main-device-unusable",
+                        &[
+                            ("serial", data.serial.clone()),
+                            ("state", details.state.to_string()),
+                        ],
+                    );
+                    if let Some(hint) = details.state.hint() {
+                        message.push('\n');
+                        message.push_str(&tr_args("main-device-hint", &[("hint", hint.into())]));
+                    }
+                    return show_error_command(message);
+                }
+
+                let stop_recording = self
+                    .recording
+                    .reset(
+                        self.adb_devices_selected
+                            .as_ref()
+                            .map(|data| data.serial.clone()),
+                    )
+                    .map(MainViewCommand::Recording);
+                self.accessibility = Default::default();
+                self.adb_devices_selected = Some(data.clone());
+                self.connect_confirming = None;
+                self.power_confirming = false;
+                self.connected_capabilities = None;
+                self.device_health = None;
+                self.device_label = None;
+                self.device_usage = None;
+                self.input_diagnostics = None;
+                self.locale = Default::default();
+                self.pointer_mode = false;
+                self.profile_share = None;
+                self.reboot = Default::default();
+                self.screen_size = None;
+                self.self_test.reset();
+                self.users = vec![];
+                self.user_selected = None;
+                self.vibrator_available = None;
+                return Command::batch([
+                    stop_recording,
+                    Command::perform(async {}, move |_| {
+                        MainViewCommand::SendXMessage(XMessage::OnDeviceSelected(data))
+                    }),
+                ]);
             }
+            MainViewCommand::AdbServerRecipeResult(data) if self.shutting_down => match data {
+                AdbServerRecipeEvent::Acked(_) | AdbServerRecipeEvent::Progress(_) => (),
+                AdbServerRecipeEvent::Connected(_)
+                | AdbServerRecipeEvent::Crashed(_)
+                | AdbServerRecipeEvent::Disconnected
+                | AdbServerRecipeEvent::Error(_)
+                | AdbServerRecipeEvent::Unresponsive => {
+                    info!(?data, "server stopped for closing");
+                    self.adb_connectivity = AdbConnectivity::Disconnected;
+                    return Command::perform(async {}, |_| MainViewCommand::ShutdownFinished);
+                }
+            },
             MainViewCommand::AdbServerRecipeResult(data) => match data {
-                AdbServerRecipeEvent::Connected => {
-                    info!("adb connected");
+                AdbServerRecipeEvent::Acked(command) => {
+                    debug!(%command, "acked");
+                    if let ServerCommand::KeyUp(keycode) = &command {
+                        self.history.mark_received(keycode, chrono::Local::now());
+                    }
+                    if let Some(index) = self.pending_acks.iter().position(|data| data == &command)
+                    {
+                        self.pending_acks.remove(index);
+                    }
+                }
+                AdbServerRecipeEvent::Connected(capabilities) => {
+                    info!(?capabilities, "adb connected");
                     self.adb_connectivity = AdbConnectivity::Connected;
+                    self.pending_acks.clear();
+                    self.connected_capabilities = Some(capabilities);
+                    self.connect_step = Some(ConnectStep::Ready);
+                    self.reconnect_retry = 0;
+                    let mut commands = vec![self.notification_command(
+                        tr("notification-connected"),
+                        match &self.adb_devices_selected {
+                            Some(data) => tr_args(
+                                "notification-connected-to",
+                                &[("device", data.to_string())],
+                            ),
+                            None => tr("notification-connected-body"),
+                        },
+                    )];
+                    if !self.is_adb_device_selected() {
+                        return Command::batch(commands);
+                    }
+
+                    let serial = self.selected_serial();
+                    commands.push(self.device_health_command());
+                    commands.push(self.device_usage_command());
+                    commands.push(
+                        LocalePanel::retrieve_command(serial.clone()).map(MainViewCommand::Locale),
+                    );
+                    commands.push(
+                        AccessibilityPanel::retrieve_command(serial.clone())
+                            .map(MainViewCommand::Accessibility),
+                    );
+                    if self.vibrator_available.is_none() {
+                        commands.push(Command::perform(has_vibrator(serial.clone()), |ret| {
+                            MainViewCommand::VibratorDetected(match ret {
+                                Ok(data) => data,
+                                Err(e) => {
+                                    warn!(?e, "failed to detect the vibrator");
+                                    false
+                                }
+                            })
+                        }));
+                    }
+                    commands.push(Command::perform(retrieve_users(serial), |ret| {
+                        MainViewCommand::UsersRetrieved(match ret {
+                            Ok(data) => data,
+                            Err(e) => {
+                                warn!(?e, "failed to retrieve users");
+                                vec![]
+                            }
+                        })
+                    }));
+                    return Command::batch(commands);
+                }
+                AdbServerRecipeEvent::Error(message) => {
+                    info!(%message, "some error occurred");
+                    let notification = match self.adb_connectivity {
+                        AdbConnectivity::Connecting => {
+                            self.adb_connectivity = AdbConnectivity::Disconnected;
+                            self.connect_failed = true;
+                            self.notification_command(
+                                tr("notification-error"),
+                                tr("notification-connect-failed"),
+                            )
+                        }
+                        AdbConnectivity::Reconnecting => return self.reconnect_command(),
+                        AdbConnectivity::Connected => {
+                            // still connected, so notifies only once until it recovers.
+                            self.adb_connectivity = AdbConnectivity::Degraded;
+                            self.notification_command(
+                                tr("notification-warning"),
+                                tr("notification-connection-degraded"),
+                            )
+                        }
+                        AdbConnectivity::Degraded | AdbConnectivity::Disconnected => {
+                            Command::none()
+                        }
+                    };
+                    return Command::batch([notification, show_error_command(message)]);
                 }
-                AdbServerRecipeEvent::Error => {
-                    info!("some error occurred");
+                AdbServerRecipeEvent::Crashed(excerpt) => {
+                    warn!(?excerpt, "server crashed");
+                    self.focused_package = None;
+                    self.pending_acks.clear();
+                    self.pressed_keys.clear();
+                    self.held_key = None;
+                    self.long_key = None;
+                    self.screen_awake = None;
+                    self.server_crash = Some(excerpt);
+                    return Command::batch([
+                        self.notification_command(
+                            tr("notification-error"),
+                            tr("notification-server-crashed"),
+                        ),
+                        show_error_command(tr("main-error-server-crashed")),
+                        self.webhook_command(WebhookEvent::Disconnected {
+                            serial: self.selected_serial(),
+                            reason: "server crashed".into(),
+                        }),
+                        self.reconnect_command(),
+                    ]);
                 }
                 AdbServerRecipeEvent::Disconnected => {
                     info!("adb disconnected");
-                    self.adb_connectivity = AdbConnectivity::Disconnected;
-                    self.adb_server_tx.send("".into()).ok();
+                    self.focused_package = None;
+                    self.pending_acks.clear();
+                    self.pressed_keys.clear();
+                    self.held_key = None;
+                    self.long_key = None;
+                    self.screen_awake = None;
+                    if self.adb_connectivity.is_connected() {
+                        return Command::batch([
+                            show_error_command(tr("main-error-connection-lost")),
+                            self.webhook_command(WebhookEvent::Disconnected {
+                                serial: self.selected_serial(),
+                                reason: "connection lost".into(),
+                            }),
+                            self.reconnect_command(),
+                        ]);
+                    } else {
+                        self.adb_connectivity = AdbConnectivity::Disconnected;
+                        self.connected_capabilities = None;
+                    }
+                }
+                AdbServerRecipeEvent::Progress(step) => {
+                    info!(%step, "adb connecting");
+                    self.connect_step = Some(step);
+                }
+                AdbServerRecipeEvent::Unresponsive => {
+                    warn!("server unresponsive");
+                    self.focused_package = None;
+                    self.pending_acks.clear();
+                    self.pressed_keys.clear();
+                    self.held_key = None;
+                    self.long_key = None;
+                    self.screen_awake = None;
+                    return Command::batch([
+                        show_error_command(tr("main-error-server-unresponsive")),
+                        self.webhook_command(WebhookEvent::Disconnected {
+                            serial: self.selected_serial(),
+                            reason: "server unresponsive".into(),
+                        }),
+                        self.reconnect_command(),
+                    ]);
                 }
             },
             MainViewCommand::Event(data) => {
                 match self.adb_connectivity {
-                    AdbConnectivity::Connected => (),
-                    AdbConnectivity::Connecting | AdbConnectivity::Disconnected => {
+                    AdbConnectivity::Connected | AdbConnectivity::Degraded => (),
+                    AdbConnectivity::Connecting
+                    | AdbConnectivity::Disconnected
+                    | AdbConnectivity::Reconnecting => {
                         debug!("skip broadcasting");
                         return Command::none();
                     }
@@ -107,62 +761,242 @@ impl MainView {
 
                 match data {
                     NativeEvent::Keyboard(data) => match data {
-                        KeyboardEvent::KeyPressed { key_code, .. } => {
+                        KeyboardEvent::KeyPressed {
+                            key_code,
+                            modifiers,
+                        } => {
                             debug!(?key_code, "update KeyPressed");
 
-                            let send_event_key = match create_send_event_key(key_code) {
+                            // the key repeat of the OS while waiting for the long keycode.
+                            if self.is_long_key(Some(key_code)) {
+                                return Command::none();
+                            }
+
+                            // the key repeat of the OS, which is replaced by
+                            // Preferences::keyboard_repeat_interval.
+                            if self.pressed_keys.iter().any(|(data, _)| data == &key_code) {
+                                return Command::none();
+                            }
+
+                            let send_event_key = match self.send_event_key(key_code, modifiers) {
                                 Some(data) => data,
                                 None => return Command::none(),
                             };
 
-                            let ret = self.adb_server_tx.send(create_pressed_key_command(
+                            if self.prefs.long_keycode(&send_event_key).is_some() {
+                                return self.start_long_key(send_event_key, Some(key_code));
+                            }
+
+                            let command = match create_pressed_key_command(
                                 &self.prefs.key_map,
                                 &send_event_key,
-                            ));
+                            ) {
+                                Ok(data) => data,
+                                Err(e) => {
+                                    warn!(?e, "failed to create the command");
+                                    return Command::none();
+                                }
+                            };
 
-                            if let Err(e) = ret {
+                            if let Err(e) = self.send_to_server(command) {
                                 warn!(?e, "failed to send the sendevent");
+                                return Command::none();
                             }
+
+                            self.pressed_keys.push((key_code, send_event_key));
+                            self.keyboard_repeat_id += 1;
+                            return self.keyboard_repeat_command(self.keyboard_repeat_id, false);
                         }
-                        KeyboardEvent::KeyReleased { key_code, .. } => {
+                        KeyboardEvent::KeyReleased {
+                            key_code,
+                            modifiers,
+                        } => {
                             debug!(?key_code, "update KeyReleased");
 
-                            let send_event_key = match create_send_event_key(key_code) {
-                                Some(data) => data,
-                                None => return Command::none(),
+                            if self.is_long_key(Some(key_code)) {
+                                self.finish_long_key();
+                                return Command::none();
+                            }
+
+                            // stops the repeat even if the other keys are still held like the OS.
+                            self.keyboard_repeat_id += 1;
+
+                            let pressed = self
+                                .pressed_keys
+                                .iter()
+                                .position(|(data, _)| data == &key_code);
+                            let send_event_key = match pressed {
+                                Some(index) => self.pressed_keys.remove(index).1,
+                                None => match self.send_event_key(key_code, modifiers) {
+                                    Some(data) => data,
+                                    None => return Command::none(),
+                                },
                             };
 
-                            let ret = self.adb_server_tx.send(create_release_key_command(
+                            let command = match create_release_key_command(
                                 &self.prefs.key_map,
                                 &send_event_key,
-                            ));
+                            ) {
+                                Ok(data) => data,
+                                Err(e) => {
+                                    warn!(?e, "failed to create the command");
+                                    return Command::none();
+                                }
+                            };
 
-                            if let Err(e) = ret {
+                            if let Err(e) = self.send_to_server(command) {
                                 warn!(?e, "failed to send the sendevent");
                             }
                         }
+                        KeyboardEvent::CharacterReceived(data) if self.is_keyboard_mode() => {
+                            // the control characters are sent by the key bindings.
+                            if data.is_control() {
+                                return Command::none();
+                            }
+
+                            let command = match ServerCommand::text(&data.to_string()) {
+                                Ok(data) => data,
+                                Err(e) => {
+                                    warn!(?e, "failed to create the command");
+                                    return Command::none();
+                                }
+                            };
+                            if let Err(e) = self.send_to_server(vec![command]) {
+                                warn!(?e, "failed to send the text");
+                            }
+                        }
+                        KeyboardEvent::ModifiersChanged(modifiers) => {
+                            self.modifiers = modifiers;
+                        }
                         _ => (),
                     },
+                    NativeEvent::Mouse(MouseEvent::ButtonPressed(MouseButton::Left)) => {
+                        self.mouse_pressed_at = Some(Instant::now());
+                    }
                     NativeEvent::Mouse(_) => {
-                        // TODO: support long-press for button.
+                        // do nothing.
                     }
                     NativeEvent::Window(_) | NativeEvent::Touch(_) => {
                         // do nothing.
                     }
                 }
             }
-            MainViewCommand::InvokeDevicesResult(devices) => {
+            MainViewCommand::AdbVersionRetrieved(version) => {
+                info!(?version, "adb version");
+                if let Some(data) = &version {
+                    if data.is_outdated() {
+                        warn!(%data, minimum = %AdbVersion::minimum(), "adb is outdated");
+                    }
+                }
+                self.adb_version = Some(version);
+            }
+            MainViewCommand::ApiServerRecipeResult(data) => match data {
+                ApiServerRecipeEvent::Failed(e) => {
+                    self.api_error = self.prefs.api_port.map(|port| (port, e));
+                }
+                ApiServerRecipeEvent::Observers(count) => {
+                    info!(count, "api observers");
+                    self.api_observers = count;
+                }
+                ApiServerRecipeEvent::Request(route, responder) => {
+                    info!(?route, "api request");
+                    responder.respond(self.api_response(route));
+                }
+            },
+            MainViewCommand::MqttRecipeResult(data) => match data {
+                MqttRecipeEvent::Connected => {
+                    self.mqtt_error = None;
+                }
+                MqttRecipeEvent::Failed(e) => {
+                    self.mqtt_error = Some(e);
+                }
+                MqttRecipeEvent::Key(keycode) => {
+                    let serial = match &self.prefs.mqtt {
+                        Some(data) => data.serial.clone(),
+                        None => return Command::none(),
+                    };
+                    info!(%keycode, "mqtt key");
+                    let response = self.api_response(ApiRoute::Key { serial, keycode });
+                    if response.status != 200 {
+                        warn!(?response, "failed to send the mqtt key");
+                    }
+                }
+            },
+            MainViewCommand::FocusedAppRecipeResult(data) => match data {
+                FocusedAppRecipeEvent::Changed(package) => {
+                    info!(?package, "focused app changed");
+                    self.focused_package = package;
+                }
+            },
+            MainViewCommand::GamepadRecipeResult(data) => {
+                debug!(?data, "update GamepadRecipeResult");
+                if !self.adb_connectivity.is_connected() {
+                    debug!("skip broadcasting");
+                    return Command::none();
+                }
+
+                let command = match &data {
+                    GamepadRecipeEvent::Pressed(key) => {
+                        create_pressed_key_command(&self.prefs.key_map, key)
+                    }
+                    GamepadRecipeEvent::Released(key) => {
+                        create_release_key_command(&self.prefs.key_map, key)
+                    }
+                };
+                let command = match command {
+                    Ok(data) => data,
+                    Err(e) => {
+                        warn!(?e, "failed to create the command");
+                        return Command::none();
+                    }
+                };
+                if let Err(e) = self.send_to_server(command) {
+                    warn!(?e, "failed to send the sendevent");
+                }
+            }
+            MainViewCommand::ScreenStateRecipeResult(data) => match data {
+                ScreenStateRecipeEvent::Changed(awake) => {
+                    info!(awake, "screen state changed");
+                    self.screen_awake = Some(awake);
+                }
+            },
+            MainViewCommand::InvokeDevicesResult(ret) => {
                 info!("update InvokeDevicesResult");
+                let devices = match ret {
+                    Ok(data) => data,
+                    Err(e) => {
+                        // clears the devices that may be gone.
+                        return Command::batch([
+                            self.update(MainViewCommand::InvokeDevicesResult(Ok(vec![]))),
+                            show_error_command(e),
+                        ]);
+                    }
+                };
                 self.adb_devices = devices;
+                let devices = self.devices();
                 match &self.adb_devices_selected {
                     Some(selected) => {
-                        if !self.adb_devices.iter().any(|data| data == selected) {
+                        // the label may have been changed since selected.
+                        if !devices.iter().any(|data| {
+                            data.serial == selected.serial
+                                && data.kind == selected.kind
+                                && data.is_usable()
+                        }) {
                             self.adb_devices_selected = None;
+                            self.pointer_mode = false;
+                            self.screen_size = None;
+                            self.users = vec![];
+                            self.user_selected = None;
+                            self.vibrator_available = None;
                         }
                     }
                     None => {
-                        if let Some(data) = self.adb_devices.first() {
-                            self.adb_devices_selected = Some(data.clone())
+                        if let Some(data) = devices.iter().find(|data| data.is_usable()) {
+                            self.adb_devices_selected = Some(data.clone());
+                            let data = data.clone();
+                            return Command::perform(async {}, move |_| {
+                                MainViewCommand::SendXMessage(XMessage::OnDeviceSelected(data))
+                            });
                         }
                     }
                 }
@@ -175,277 +1009,2922 @@ impl MainView {
 
                 match self.adb_connectivity {
                     AdbConnectivity::Disconnected => {
-                        self.adb_connectivity = AdbConnectivity::Connecting
+                        if self.is_adb_device_selected() {
+                            // pushes the server only to the devices that the user knows in the
+                            // shared labs.
+                            let serial = self.selected_serial();
+                            if self.prefs.blocked_serials.contains(&serial) {
+                                info!(%serial, "blocked device");
+                                return Command::none();
+                            }
+                            if self.prefs.confirm_unknown_devices
+                                && !self.prefs.approved_serials.contains(&serial)
+                            {
+                                self.connect_confirming = Some(serial);
+                                return Command::none();
+                            }
+                        }
+                        return self.connect_command();
                     }
                     AdbConnectivity::Connecting => {
                         warn!("TODO");
                     }
-                    AdbConnectivity::Connected => {
+                    AdbConnectivity::Connected
+                    | AdbConnectivity::Degraded
+                    | AdbConnectivity::Reconnecting => {
+                        // best effort since the server stops soon after.
+                        self.release_pressed_keys();
                         self.adb_connectivity = AdbConnectivity::Disconnected;
-                        self.adb_server_tx.send("".into()).ok();
+                        self.reconnect_retry = 0;
+                        self.reconnect_waiting = None;
+                        self.server_crash = None;
+                        self.focused_package = None;
+                        self.screen_awake = None;
+
+                        // leaves the device as it was before testing.
+                        if let Some(data) = self.accessibility.disconnect() {
+                            return Command::perform(
+                                put_accessibility_services(self.selected_serial(), data),
+                                |ret| {
+                                    if let Err(e) = ret {
+                                        warn!(?e, "failed to restore the accessibility services");
+                                    }
+                                    MainViewCommand::Sink
+                                },
+                            );
+                        }
                     }
                 }
             }
+            MainViewCommand::OnDeviceApproved => {
+                let serial = match self.connect_confirming.take() {
+                    Some(data) => data,
+                    None => return Command::none(),
+                };
+                self.prefs_store.update(move |prefs| {
+                    prefs.blocked_serials.remove(&serial);
+                    prefs.approved_serials.insert(serial);
+                });
+                if self.adb_connectivity.is_active() {
+                    return Command::none();
+                }
+                return self.connect_command();
+            }
+            MainViewCommand::OnDeviceBlocked => {
+                if let Some(serial) = self.connect_confirming.take() {
+                    self.prefs_store.update(move |prefs| {
+                        prefs.approved_serials.remove(&serial);
+                        prefs.blocked_serials.insert(serial);
+                    });
+                }
+            }
+            MainViewCommand::OnDeviceConfirmCancelled => {
+                self.connect_confirming = None;
+            }
+            MainViewCommand::OnDeviceLabelChanged(data) => self.device_label = Some(data),
+            MainViewCommand::OnDeviceLabelSubmitted => {
+                let (serial, label) = match (&self.adb_devices_selected, self.device_label.take()) {
+                    (Some(device), Some(label)) => {
+                        (device.serial.clone(), label.trim().to_string())
+                    }
+                    _ => return Command::none(),
+                };
+                info!(%serial, %label, "device label submitted");
+                self.prefs_store.update(move |prefs| {
+                    if label.is_empty() {
+                        prefs.device_labels.remove(&serial);
+                    } else {
+                        prefs.device_labels.insert(serial, label);
+                    }
+                });
+            }
+            MainViewCommand::Accessibility(command) => {
+                return self
+                    .accessibility
+                    .update(command, self.selected_serial())
+                    .map(MainViewCommand::Accessibility);
+            }
             MainViewCommand::OnAdbDevicesReloadClicked => {
                 return retrieve_devices_command();
             }
+            MainViewCommand::OnDeviceUsageRefreshClicked => return self.device_usage_command(),
+            MainViewCommand::DeviceHealthRetrieved(data) => {
+                self.device_health_loading = false;
+                let was_warning = matches!(&self.device_health, Some(data) if data.is_warning());
+                match &data {
+                    Some(data) if data.is_warning() && !was_warning => {
+                        warn!(health = %data.summary(), "device is throttling or hot");
+                    }
+                    _ => (),
+                }
+                self.device_health = data;
+            }
+            MainViewCommand::DeviceHealthTick => return self.device_health_command(),
+            MainViewCommand::DeviceUsageRetrieved(data) => {
+                self.device_usage_loading = false;
+                self.device_usage = data;
+            }
+            MainViewCommand::Reboot(RebootPanelCommand::OnRebootClicked) => {
+                if self.reboot.is_rebooting() {
+                    return Command::none();
+                }
+
+                let accessibility_original = self.accessibility.take_original();
+                let disconnect = if self.adb_connectivity.is_active() {
+                    self.update(MainViewCommand::OnAdbConnectClicked)
+                } else {
+                    Command::none()
+                };
+                return Command::batch([
+                    disconnect,
+                    self.reboot
+                        .start(self.selected_serial(), accessibility_original)
+                        .map(MainViewCommand::Reboot),
+                ]);
+            }
+            MainViewCommand::Reboot(RebootPanelCommand::Booted(serial)) => {
+                let notification = self.notification_command(
+                    tr("notification-reboot"),
+                    tr_args("notification-reboot-booted", &[("serial", serial)]),
+                );
+                if self.adb_connectivity.is_active() {
+                    return notification;
+                }
+                // re-establishes the server and retrieves the device states again.
+                return Command::batch([
+                    notification,
+                    self.update(MainViewCommand::OnAdbConnectClicked),
+                ]);
+            }
+            MainViewCommand::Reboot(RebootPanelCommand::Failed(status)) => {
+                return self.notification_command(tr("notification-reboot"), status);
+            }
+            MainViewCommand::Reboot(command) => {
+                return self
+                    .reboot
+                    .update(command, self.selected_serial())
+                    .map(MainViewCommand::Reboot);
+            }
+            MainViewCommand::Recording(RecordingPanelCommand::Completed(status)) => {
+                return self.notification_command(tr("notification-recording"), status);
+            }
+            MainViewCommand::Recording(command) => {
+                return self
+                    .recording
+                    .update(command, self.selected_serial(), &self.prefs)
+                    .map(MainViewCommand::Recording);
+            }
+            MainViewCommand::SelfTest(command) => {
+                let notification = match &command {
+                    SelfTestPanelCommand::Finished(data) => self.notification_command(
+                        tr("notification-self-test"),
+                        tr_args(
+                            if data.is_passed() {
+                                "notification-self-test-passed"
+                            } else {
+                                "notification-self-test-failed"
+                            },
+                            &[("serial", data.serial.clone())],
+                        ),
+                    ),
+                    SelfTestPanelCommand::OnCopyClicked | SelfTestPanelCommand::OnRunClicked => {
+                        Command::none()
+                    }
+                };
+                let serial = self
+                    .is_adb_device_selected()
+                    .then(|| self.selected_serial());
+                return Command::batch([
+                    notification,
+                    self.self_test
+                        .update(command, serial, &self.prefs, &self.session_dir)
+                        .map(MainViewCommand::SelfTest),
+                ]);
+            }
+            MainViewCommand::OnServerCrashReconnectClicked => {
+                if self.adb_connectivity.is_connected() {
+                    return Command::none();
+                }
+
+                info!("reconnect after the crash");
+                return self.connect_command();
+            }
+            MainViewCommand::ReconnectDelayElapsed(attempt) => {
+                // disconnected or connected again while waiting.
+                if attempt != self.connect_attempt || self.reconnect_waiting.is_none() {
+                    return Command::none();
+                }
+
+                info!(retry = self.reconnect_retry, "reconnect");
+                self.reconnect_waiting = None;
+                self.connect_attempt += 1;
+                self.connect_step = None;
+            }
+            MainViewCommand::Locale(command) => {
+                return self
+                    .locale
+                    .update(command, self.selected_serial())
+                    .map(MainViewCommand::Locale);
+            }
+            MainViewCommand::Pairing(command) => {
+                let paired = matches!(command, PairingPanelCommand::AdbFinished(Ok(_)));
+                let atv_host = match self.adb_devices_selected.as_deref() {
+                    Some(AndroidDevice {
+                        kind: DeviceKind::AndroidTvRemote { host },
+                        ..
+                    }) => Some(host.clone()),
+                    _ => None,
+                };
+                let command = self
+                    .pairing
+                    .update(command, atv_host, &self.config_dir)
+                    .map(MainViewCommand::Pairing);
+                if paired {
+                    return Command::batch([command, retrieve_devices_command()]);
+                }
+                return command;
+            }
             MainViewCommand::RequestSendEvent(data) => {
                 info!(?data, "update RequestSendEvent");
-                match self.adb_connectivity {
-                    AdbConnectivity::Connected => (),
-                    AdbConnectivity::Connecting | AdbConnectivity::Disconnected => {
-                        debug!("skip broadcasting");
+                if !self.adb_connectivity.is_connected() {
+                    debug!("skip broadcasting");
+                    return Command::none();
+                }
+
+                // the long-press has been sent while held, and ButtonReleased sends the up.
+                if let Some(PressedButton {
+                    long_pressed: true, ..
+                }) = self.pressed_button
+                {
+                    self.mouse_pressed_at = None;
+                    return Command::none();
+                }
+
+                if self.needs_power_confirmation(&data) {
+                    self.mouse_pressed_at = None;
+                    self.power_confirming = true;
+                    return Command::none();
+                }
+
+                // the button is clicked on release.
+                let held_for = self
+                    .mouse_pressed_at
+                    .take()
+                    .map(|data| data.elapsed())
+                    .unwrap_or_default();
+                if LONG_PRESS_TIMEOUT <= held_for {
+                    if let Some(long_keycode) = self.prefs.long_keycode(&data) {
+                        let key = SendEventKey::Custom(long_keycode.into());
+                        if let Err(e) = self.send_click_key(&key) {
+                            warn!(?e, "failed to send the long keycode");
+                        }
                         return Command::none();
                     }
                 }
 
-                let ret = self
-                    .adb_server_tx
-                    .send(create_click_key_command(&self.prefs.key_map, &data));
+                let keycode = self.prefs.key_map.keycode(&data).to_owned();
+                let actions = self.gesture_recognizer.click(
+                    &self.prefs.gestures,
+                    &keycode,
+                    self.modifiers.control(),
+                    LONG_PRESS_TIMEOUT <= held_for,
+                );
+                return self.run_button_actions(actions, held_for);
+            }
+            MainViewCommand::ButtonHeld(data) => {
+                info!(?data, "update ButtonHeld");
+                if !self.adb_connectivity.is_connected() {
+                    debug!("skip broadcasting");
+                    return Command::none();
+                }
 
-                if let Err(e) = ret {
-                    warn!(?e, "failed to send the sendevent");
+                // the up is ignored by ButtonReleased since nothing is held.
+                if self.needs_power_confirmation(&data) {
+                    self.power_confirming = true;
+                    return Command::none();
                 }
-            }
-            MainViewCommand::OnNewPrefs(prefs) => {
-                info!("OnNewPreferences");
 
-                if let Some(data) = prefs {
-                    self.prefs = data;
+                if self.prefs.long_keycode(&data).is_some() {
+                    return self.start_long_key(data, None);
+                }
+
+                let keycode = match self.prefs.key_map.keycode(&data).parse::<Keycode>() {
+                    Ok(data) => data,
+                    Err(e) => {
+                        warn!(?e, "failed to parse the keycode");
+                        return Command::none();
+                    }
+                };
+                if let Err(e) = self.send_to_server(vec![ServerCommand::KeyDown(keycode.clone())]) {
+                    warn!(?e, "failed to send the down");
+                    return Command::none();
                 }
+
+                self.expect_ack(ServerCommand::KeyDown(keycode.clone()));
+                self.held_key_id += 1;
+                self.held_key = Some(HeldKey {
+                    id: self.held_key_id,
+                    keycode,
+                    long_pressed: false,
+                });
+                return Command::batch([
+                    self.key_repeat_command(self.held_key_id),
+                    self.button_long_press_command(self.held_key_id),
+                ]);
             }
-            MainViewCommand::OnXMessage(_) => {
-                // do nothing.
+            MainViewCommand::ButtonPressed(data) => {
+                debug!(?data, "update ButtonPressed");
+                if !self.adb_connectivity.is_connected() {
+                    return Command::none();
+                }
+
+                self.held_key_id += 1;
+                self.pressed_button = Some(PressedButton {
+                    id: self.held_key_id,
+                    key: data,
+                    long_pressed: false,
+                });
+                return self.button_long_press_command(self.held_key_id);
             }
-        }
-        Command::none()
-    }
+            MainViewCommand::ButtonLongPressTimedOut(id) => {
+                if !self.adb_connectivity.is_connected() {
+                    return Command::none();
+                }
 
-    pub fn subscription(&self) -> Subscription<MainViewCommand> {
-        match self.adb_connectivity {
-            AdbConnectivity::Connecting | AdbConnectivity::Connected => {
-                let device = match &self.adb_devices_selected {
-                    Some(data) => data.clone(),
-                    None => {
-                        warn!("device not selected");
-                        return Subscription::none();
+                // the hold mode has sent the down on press.
+                let held_keycode = match &mut self.held_key {
+                    Some(data) if data.id == id && !data.long_pressed => {
+                        data.long_pressed = true;
+                        Some(data.keycode.clone())
+                    }
+                    _ => None,
+                };
+                if let Some(keycode) = held_keycode {
+                    let command = self.long_press_server_command(keycode);
+                    if let Err(e) = self.send_to_server(vec![command]) {
+                        warn!(?e, "failed to send the long-press");
+                    }
+                    return Command::none();
+                }
+
+                let key = match &self.pressed_button {
+                    Some(data) if data.id == id && !data.long_pressed => data.key.clone(),
+                    _ => return Command::none(),
+                };
+                // the power is confirmed on release.
+                if self.needs_power_confirmation(&key) {
+                    return Command::none();
+                }
+
+                if let Some(long_keycode) = self.prefs.long_keycode(&key) {
+                    let long_keycode = SendEventKey::Custom(long_keycode.into());
+                    if let Err(e) = self.send_click_key(&long_keycode) {
+                        warn!(?e, "failed to send the long keycode");
+                        return Command::none();
+                    }
+                    if let Some(data) = &mut self.pressed_button {
+                        data.long_pressed = true;
+                    }
+                    return self.haptic_command();
+                }
+
+                let keycode = match self.prefs.key_map.keycode(&key).parse::<Keycode>() {
+                    Ok(data) => data,
+                    Err(e) => {
+                        warn!(?e, "failed to parse the keycode");
+                        return Command::none();
                     }
                 };
+                let commands = vec![
+                    ServerCommand::KeyDown(keycode.clone()),
+                    self.long_press_server_command(keycode.clone()),
+                ];
+                if let Err(e) = self.send_to_server(commands) {
+                    warn!(?e, "failed to send the long-press");
+                    return Command::none();
+                }
 
-                Subscription::batch(vec![
-                    adb_server(device, self.adb_server_rx.clone())
-                        .map(MainViewCommand::AdbServerRecipeResult),
-                    native_events().map(MainViewCommand::Event),
-                ])
+                if let Some(data) = &mut self.pressed_button {
+                    data.long_pressed = true;
+                }
+                self.expect_ack(ServerCommand::KeyDown(keycode.clone()));
+                self.held_key = Some(HeldKey {
+                    id,
+                    keycode,
+                    long_pressed: true,
+                });
+                return self.key_repeat_command(id);
             }
-            AdbConnectivity::Disconnected => Subscription::none(),
-        }
-    }
+            MainViewCommand::KeyRepeatTimedOut(id) => {
+                let keycode = match &self.held_key {
+                    Some(data) if data.id == id => data.keycode.clone(),
+                    _ => return Command::none(),
+                };
+                if self.is_queue_busy() {
+                    debug!("skip the repeat");
+                    return self.key_repeat_command(id);
+                }
+                if let Err(e) = self.send_to_server(vec![ServerCommand::KeyDown(keycode)]) {
+                    warn!(?e, "failed to repeat the down");
+                    return Command::none();
+                }
+                return self.key_repeat_command(id);
+            }
+            MainViewCommand::KeyboardRepeatTimedOut(id) => {
+                if id != self.keyboard_repeat_id {
+                    return Command::none();
+                }
+                let send_event_key = match self.pressed_keys.last() {
+                    Some((_, data)) => data.clone(),
+                    None => return Command::none(),
+                };
+                // the repeat is skipped rather than taking the room that the up needs.
+                if self.is_queue_busy() {
+                    debug!("skip the repeat");
+                    return self.keyboard_repeat_command(id, true);
+                }
+                let command = match create_pressed_key_command(&self.prefs.key_map, &send_event_key)
+                {
+                    Ok(data) => data,
+                    Err(e) => {
+                        warn!(?e, "failed to create the command");
+                        return Command::none();
+                    }
+                };
+                if let Err(e) = self.send_to_server(command) {
+                    warn!(?e, "failed to repeat the down");
+                    return Command::none();
+                }
+                return self.keyboard_repeat_command(id, true);
+            }
+            MainViewCommand::ButtonReleased => {
+                self.pressed_button = None;
+                if self.is_long_key(None) {
+                    self.finish_long_key();
+                    return Command::none();
+                }
 
-    pub fn view(&self) -> Element<MainViewCommand> {
-        let button_width = Length::Units(90);
-        let button_height = Length::Units(30);
+                let held_key = match self.held_key.take() {
+                    Some(data) => data,
+                    None => return Command::none(),
+                };
+                if let Err(e) =
+                    self.send_to_server(vec![ServerCommand::KeyUp(held_key.keycode.clone())])
+                {
+                    warn!(?e, "failed to send the up");
+                    return Command::none();
+                }
 
-        column![
-            "ADB:",
-            row![
-                button(svg(SvgHandle::from_memory(
-                    Resource::get("arrow-path.svg")
-                        .context("arrow-path.svg")
-                        .unwrap()
-                        .data,
-                )))
-                .style(iced::theme::Button::Secondary)
-                .on_press(MainViewCommand::OnAdbDevicesReloadClicked),
-                pick_list(
-                    &self.adb_devices,
-                    self.adb_devices_selected.clone(),
-                    MainViewCommand::AdbDevicesSelected,
-                ),
-            ]
-            .height(button_height),
-            Space::with_height(4.into()),
-            checkbox(
-                "connect",
-                match self.adb_connectivity {
-                    AdbConnectivity::Connecting | AdbConnectivity::Disconnected => false,
-                    AdbConnectivity::Connected => true,
-                },
-                |_| MainViewCommand::OnAdbConnectClicked,
-            ),
-            match self.adb_connectivity {
-                AdbConnectivity::Connecting => "status: connecting",
-                AdbConnectivity::Connected => "status: connected",
-                AdbConnectivity::Disconnected => "status: disconnected",
-            },
-            Space::with_height(16.into()),
-            row![
-                button(Space::new(Length::Fill, Length::Fill))
-                    .width(70.into())
-                    .height(button_height)
-                    .style(iced::theme::Button::Custom(Box::new(
-                        ColorKeyButtonStyle::ColorKeyRed
-                    )))
-                    .on_press(MainViewCommand::RequestSendEvent(SendEventKey::ColorRed)),
-                button(Space::new(Length::Fill, Length::Fill))
-                    .width(70.into())
-                    .height(button_height)
-                    .style(iced::theme::Button::Custom(Box::new(
-                        ColorKeyButtonStyle::ColorKeyGreen
-                    )))
-                    .on_press(MainViewCommand::RequestSendEvent(SendEventKey::ColorGreen)),
-                button(Space::new(Length::Fill, Length::Fill))
-                    .width(70.into())
-                    .height(button_height)
-                    .style(iced::theme::Button::Custom(Box::new(
-                        ColorKeyButtonStyle::ColorKeyBlue
-                    )))
-                    .on_press(MainViewCommand::RequestSendEvent(SendEventKey::ColorBlue)),
-                button(Space::new(Length::Fill, Length::Fill))
-                    .width(70.into())
-                    .height(button_height)
-                    .style(iced::theme::Button::Custom(Box::new(
-                        ColorKeyButtonStyle::ColorKeyYellow
-                    )))
-                    .on_press(MainViewCommand::RequestSendEvent(SendEventKey::ColorYellow)),
-            ]
-            .spacing(4),
-            Space::with_height(8.into()),
-            row![
-                Space::with_width((90 + 8).into()),
-                button("Up (k)")
-                    .width(button_width)
-                    .height(button_height)
-                    .style(iced::theme::Button::Secondary)
-                    .on_press(MainViewCommand::RequestSendEvent(SendEventKey::DpadUp)),
-            ]
-            .spacing(4),
-            Space::with_height(4.into()),
-            row![
-                Space::with_width(4.into()),
-                button("Left (h)")
-                    .width(button_width)
-                    .height(button_height)
-                    .style(iced::theme::Button::Secondary)
-                    .on_press(MainViewCommand::RequestSendEvent(SendEventKey::DpadLeft)),
-                button("OK")
-                    .width(button_width)
-                    .height(button_height)
-                    .style(iced::theme::Button::Secondary)
-                    .on_press(MainViewCommand::RequestSendEvent(SendEventKey::DpadOk)),
-                button("Right (l)")
-                    .width(button_width)
-                    .height(button_height)
-                    .style(iced::theme::Button::Secondary)
-                    .on_press(MainViewCommand::RequestSendEvent(SendEventKey::DpadRight)),
-            ]
-            .spacing(4),
+                self.expect_ack(ServerCommand::KeyUp(held_key.keycode.clone()));
+                self.history.push(HistoryEntry::new(
+                    if held_key.long_pressed {
+                        HistoryEntryKind::LongPress(held_key.keycode)
+                    } else {
+                        HistoryEntryKind::Key(held_key.keycode)
+                    },
+                    self.selected_serial(),
+                ));
+                return self.haptic_command();
+            }
+            MainViewCommand::GestureTimedOut(id) => {
+                if let Some(keycode) = self.gesture_recognizer.timeout(id) {
+                    match self.send_click_key(&SendEventKey::Custom(keycode)) {
+                        Ok(_) => return self.haptic_command(),
+                        Err(e) => warn!(?e, "failed to send the key"),
+                    }
+                }
+            }
+            MainViewCommand::LongKeyTimedOut(id) => {
+                let key = match &mut self.long_key {
+                    Some(data) if data.id == id && !data.sent => {
+                        data.sent = true;
+                        data.key.clone()
+                    }
+                    _ => return Command::none(),
+                };
+                let long_keycode = match self.prefs.long_keycode(&key) {
+                    Some(data) => SendEventKey::Custom(data.into()),
+                    None => return Command::none(),
+                };
+                if let Err(e) = self.send_click_key(&long_keycode) {
+                    warn!(?e, "failed to send the long keycode");
+                }
+            }
+            MainViewCommand::LongPressTimedOut(keycode, mode) => {
+                let command = vec![
+                    match mode {
+                        LongPressMode::Flag => ServerCommand::KeyLongPress(keycode.clone()),
+                        LongPressMode::Repeat => ServerCommand::KeyDown(keycode.clone()),
+                    },
+                    ServerCommand::KeyUp(keycode.clone()),
+                ];
+                match self.send_to_server(command) {
+                    Ok(_) => {
+                        self.expect_ack(ServerCommand::KeyUp(keycode.clone()));
+                        self.history.push(HistoryEntry::new(
+                            HistoryEntryKind::LongPress(keycode),
+                            self.selected_serial(),
+                        ));
+                    }
+                    Err(e) => warn!(?e, "failed to release the long-press"),
+                }
+            }
+            MainViewCommand::OnCloseRequested => {
+                if !self.adb_connectivity.is_connected() || self.shutting_down {
+                    // the subscription is dropped with the window.
+                    return Command::perform(async {}, |_| MainViewCommand::ShutdownFinished);
+                }
+
+                info!("quit the server");
+                self.shutting_down = true;
+                self.adb_server_tx.try_send(vec![ServerCommand::Quit]).ok();
+                // closes the window even if the recipe doesn't finish.
+                return Command::perform(tokio::time::sleep(SHUTDOWN_TIMEOUT * 2), |_| {
+                    MainViewCommand::ShutdownFinished
+                });
+            }
+            MainViewCommand::OnClipboardMacroClicked => {
+                return iced::clipboard::read(MainViewCommand::ClipboardMacroRead);
+            }
+            MainViewCommand::ClipboardMacroRead(data) => {
+                let steps = match data.as_deref().map(parse_macro) {
+                    Some(Ok(data)) => data,
+                    Some(Err(e)) => {
+                        info!(?e, "failed to parse the clipboard");
+                        self.macro_status =
+                            Some(tr_args("main-macro-status", &[("status", e.to_string())]));
+                        return Command::none();
+                    }
+                    None => {
+                        self.macro_status = Some(tr("main-macro-clipboard-empty"));
+                        return Command::none();
+                    }
+                };
+
+                info!(len = steps.len(), "run clipboard macro");
+                return self.start_macro(steps);
+            }
+            MainViewCommand::OnMacroDeleteClicked(name) => {
+                self.prefs_store.update(move |prefs| {
+                    prefs.saved_macros.retain(|data| data.name != name);
+                });
+            }
+            MainViewCommand::OnMacroDiscardClicked => {
+                self.macro_recorded = None;
+                self.macro_status = Some(tr("main-macro-discarded"));
+            }
+            MainViewCommand::OnMacroNameChanged(data) => self.macro_name = data,
+            MainViewCommand::OnMacroRecordClicked => match self.macro_recorder.take() {
+                Some(recorder) => {
+                    let steps = recorder.finish();
+                    if steps.is_empty() {
+                        self.macro_status = Some(tr("main-macro-no-keys"));
+                    } else {
+                        self.macro_status = Some(tr_args(
+                            "main-macro-recorded",
+                            &[("steps", steps.len().to_string())],
+                        ));
+                        self.macro_recorded = Some(steps);
+                    }
+                }
+                None => {
+                    self.macro_recorded = None;
+                    self.macro_recorder = Some(MacroRecorder::new(MACRO_KEY_INTERVAL));
+                    self.macro_status = Some(tr("main-macro-recording"));
+                }
+            },
+            MainViewCommand::OnMacroSaveClicked => {
+                let name = self.macro_name.trim().to_string();
+                if name.is_empty() {
+                    self.macro_status = Some(tr("main-macro-name-required"));
+                    return Command::none();
+                }
+                let steps = match self.macro_recorded.take() {
+                    Some(data) => data,
+                    None => return Command::none(),
+                };
+
+                info!(%name, len = steps.len(), "save macro");
+                self.macro_status = Some(tr_args("main-macro-saved", &[("name", name.clone())]));
+                self.macro_name.clear();
+                self.prefs_store.update(move |prefs| {
+                    let saved = SavedMacro { name, steps };
+                    match prefs
+                        .saved_macros
+                        .iter_mut()
+                        .find(|data| data.name == saved.name)
+                    {
+                        Some(data) => *data = saved,
+                        None => prefs.saved_macros.push(saved),
+                    }
+                });
+            }
+            MainViewCommand::OnSavedMacroClicked(name) => {
+                if self.macro_run.is_some() {
+                    return Command::none();
+                }
+                let steps = match self
+                    .prefs
+                    .saved_macros
+                    .iter()
+                    .find(|data| data.name == name)
+                {
+                    Some(data) => data.steps.clone(),
+                    None => return Command::none(),
+                };
+
+                info!(%name, len = steps.len(), "run saved macro");
+                return self.start_macro(steps);
+            }
+            MainViewCommand::MacroStepFinished(id) => match &mut self.macro_run {
+                Some(data) if data.id == id => {
+                    data.position += 1;
+                    return self.run_macro_step();
+                }
+                _ => debug!(id, "skip the stopped macro"),
+            },
+            MainViewCommand::OnProfileShareClicked => {
+                // toggles the QR code.
+                if self.profile_share.take().is_some() {
+                    return Command::none();
+                }
+
+                let code = match self.device_profile() {
+                    Some(data) => data.encode(),
+                    None => return Command::none(),
+                };
+                match render_qr_svg(&code) {
+                    Ok(data) => {
+                        let handle = iced::widget::svg::Handle::from_memory(data.into_bytes());
+                        self.profile_share = Some((code, handle));
+                    }
+                    Err(e) => warn!(?e, "failed to render the profile"),
+                }
+            }
+            MainViewCommand::OnProfileCodeCopyClicked => {
+                if let Some((code, _)) = &self.profile_share {
+                    return iced::clipboard::write(code.clone());
+                }
+            }
+            MainViewCommand::OnMarkLogClicked => {
+                let serial = match &self.adb_devices_selected {
+                    Some(data) => data.serial.clone(),
+                    None => return Command::none(),
+                };
+
+                self.log_marker_count += 1;
+                let marker = format!("MARK {}", self.log_marker_count);
+                return Command::perform(write_log_marker(serial, marker.clone()), move |ret| {
+                    match ret {
+                        Ok(_) => MainViewCommand::LogMarkerWritten(marker),
+                        Err(e) => {
+                            warn!(?e, "failed to write the log marker");
+                            MainViewCommand::Sink
+                        }
+                    }
+                });
+            }
+            MainViewCommand::LogMarkerWritten(marker) => {
+                self.history.push(HistoryEntry::new(
+                    HistoryEntryKind::Marker(marker),
+                    self.selected_serial(),
+                ));
+            }
+            MainViewCommand::OnWakeClicked => {
+                if let Err(e) = self.send_click_key(&SendEventKey::Wakeup) {
+                    warn!(?e, "failed to wake the device");
+                }
+            }
+            MainViewCommand::OnPowerConfirmCancelled => self.power_confirming = false,
+            MainViewCommand::OnPowerConfirmed => {
+                self.power_confirming = false;
+                if !self.adb_connectivity.is_connected() {
+                    debug!("skip broadcasting");
+                    return Command::none();
+                }
+
+                if let Err(e) = self.send_click_key(&SendEventKey::Power) {
+                    warn!(?e, "failed to send the power");
+                }
+            }
+            MainViewCommand::OnHistoryEntryClicked(kind) => {
+                info!(%kind, "resend the history entry");
+                match kind {
+                    HistoryEntryKind::Key(keycode) => {
+                        match self.send_click_key(&SendEventKey::Custom(keycode.to_string())) {
+                            Ok(_) => return self.haptic_command(),
+                            Err(e) => warn!(?e, "failed to resend the key"),
+                        }
+                    }
+                    HistoryEntryKind::LongPress(keycode) => {
+                        match self.start_long_press(
+                            keycode.as_str(),
+                            LongPressMode::Flag,
+                            LONG_PRESS_TIMEOUT,
+                        ) {
+                            Ok(data) => return data,
+                            Err(e) => warn!(?e, "failed to resend the long-press"),
+                        }
+                    }
+                    HistoryEntryKind::Launch(target) => {
+                        let shortcut = LaunchShortcut {
+                            label: target.clone(),
+                            target,
+                        };
+                        return Command::perform(async {}, move |_| {
+                            MainViewCommand::OnShortcutClicked(shortcut)
+                        });
+                    }
+                    HistoryEntryKind::MacroFailed { .. } | HistoryEntryKind::Marker(_) => (),
+                }
+            }
+            MainViewCommand::OnHistoryExportClicked => {
+                return iced::clipboard::write(self.history.to_tsv());
+            }
+            MainViewCommand::OnInputCaptureSecsChanged(data) => self.input_capture_secs = data,
+            MainViewCommand::OnInputCaptureClicked(target) => {
+                let serial = match &self.adb_devices_selected {
+                    Some(data) => data.serial.clone(),
+                    None => return Command::none(),
+                };
+
+                let secs = match self.input_capture_secs.trim().parse::<u64>() {
+                    Ok(data) if (1..=INPUT_CAPTURE_MAX_SECS).contains(&data) => data,
+                    _ => {
+                        self.input_capture_status = Some(tr_args(
+                            "main-capture-invalid-secs",
+                            &[("max", INPUT_CAPTURE_MAX_SECS.to_string())],
+                        ));
+                        return Command::none();
+                    }
+                };
+
+                self.input_capturing = true;
+                self.input_capture_status = Some(tr_args(
+                    "main-capture-recording",
+                    &[("secs", secs.to_string())],
+                ));
+                return Command::perform(
+                    capture_input_events(serial, Duration::from_secs(secs)),
+                    move |ret| match ret {
+                        Ok(data) => MainViewCommand::InputEventsCaptured(target, Some(data)),
+                        Err(e) => {
+                            warn!(?e, "failed to capture the input events");
+                            MainViewCommand::InputEventsCaptured(target, None)
+                        }
+                    },
+                );
+            }
+            MainViewCommand::InputEventsCaptured(target, data) => {
+                self.input_capturing = false;
+                let events = match data {
+                    Some(data) => data,
+                    None => {
+                        self.input_capture_status = Some(tr("main-capture-failed"));
+                        return Command::none();
+                    }
+                };
+
+                match target {
+                    InputCaptureTarget::Clipboard => {
+                        self.input_capture_status = Some(tr_args(
+                            "main-capture-copied",
+                            &[("count", events.lines().count().to_string())],
+                        ));
+                        return iced::clipboard::write(events);
+                    }
+                    InputCaptureTarget::File => {
+                        let path = std::env::temp_dir().join(format!(
+                            "android-commander-getevent-{}.txt",
+                            chrono::Local::now().format("%Y%m%d-%H%M%S")
+                        ));
+                        return Command::perform(
+                            async move { tokio::fs::write(&path, events).await.map(|_| path) },
+                            |ret| match ret {
+                                Ok(data) => MainViewCommand::InputEventsSaved(Some(data)),
+                                Err(e) => {
+                                    warn!(?e, "failed to save the input events");
+                                    MainViewCommand::InputEventsSaved(None)
+                                }
+                            },
+                        );
+                    }
+                }
+            }
+            MainViewCommand::OnInputDiagnosticsClicked => {
+                let serial = match &self.adb_devices_selected {
+                    Some(data) => data.serial.clone(),
+                    None => return Command::none(),
+                };
+
+                self.input_diagnosing = true;
+                return Command::perform(dump_input(serial), |ret| {
+                    MainViewCommand::InputDiagnosticsRetrieved(match ret {
+                        Ok(data) => Some(InputDiagnostics::parse(&data)),
+                        Err(e) => {
+                            warn!(?e, "failed to retrieve the input diagnostics");
+                            None
+                        }
+                    })
+                });
+            }
+            MainViewCommand::InputDiagnosticsRetrieved(data) => {
+                self.input_diagnosing = false;
+                if data.is_none() {
+                    self.input_capture_status = Some(tr("main-diagnostics-failed"));
+                }
+                self.input_diagnostics = data;
+            }
+            MainViewCommand::OnInputDiagnosticsHideClicked => self.input_diagnostics = None,
+            MainViewCommand::InputEventsSaved(data) => {
+                self.input_capture_status = Some(match data {
+                    Some(data) => tr_args(
+                        "main-capture-saved",
+                        &[("path", data.display().to_string())],
+                    ),
+                    None => tr("main-capture-save-failed"),
+                });
+            }
+            MainViewCommand::MacroFailed(reason, screenshot) => {
+                self.macro_status = Some(match &screenshot {
+                    Some(data) => tr_args(
+                        "main-macro-failed-screenshot",
+                        &[
+                            ("reason", reason.clone()),
+                            ("path", data.display().to_string()),
+                        ],
+                    ),
+                    None => tr_args("main-macro-status", &[("status", reason.clone())]),
+                });
+                self.history.push(HistoryEntry::new(
+                    HistoryEntryKind::MacroFailed {
+                        reason: reason.clone(),
+                        screenshot: screenshot.clone(),
+                    },
+                    self.selected_serial(),
+                ));
+                return Command::batch([
+                    self.notification_command(
+                        tr("notification-macro"),
+                        tr_args("notification-macro-failed", &[("reason", reason.clone())]),
+                    ),
+                    self.webhook_command(WebhookEvent::MacroFailed {
+                        serial: self.selected_serial(),
+                        reason: reason.clone(),
+                    }),
+                    self.finish_scheduled_macro(Some(reason), screenshot),
+                ]);
+            }
+            MainViewCommand::MacroReportExported(path) => {
+                if let Some(data) = path {
+                    self.macro_status = Some(tr_args(
+                        "main-macro-report",
+                        &[("path", data.display().to_string())],
+                    ));
+                }
+            }
+            MainViewCommand::OnMacroStopClicked => {
+                self.macro_run = None;
+                self.macro_status = Some(tr("main-macro-stopped"));
+                return self.finish_scheduled_macro(Some("stopped".into()), None);
+            }
+            MainViewCommand::ScheduleTick => {
+                let now = chrono::Local::now().naive_local();
+                let since = std::mem::replace(&mut self.schedule_checked_at, now);
+                let due = self
+                    .prefs
+                    .scheduled_macros
+                    .iter()
+                    .filter(|data| data.is_due(since, now))
+                    .cloned()
+                    .collect::<Vec<_>>();
+                return Command::batch(
+                    due.into_iter()
+                        .map(|data| self.start_scheduled_macro(data))
+                        .collect::<Vec<_>>(),
+                );
+            }
+            MainViewCommand::ScheduledMacroRead(schedule, source) => {
+                let steps =
+                    match source.and_then(|data| parse_macro(&data).map_err(|e| e.to_string())) {
+                        Ok(data) => data,
+                        Err(e) => {
+                            warn!(%e, name = %schedule.name, "failed to read the scheduled macro");
+                            return self.skip_scheduled_macro(schedule, e);
+                        }
+                    };
+                if self.macro_run.is_some() {
+                    return self.skip_scheduled_macro(schedule, "another macro is running".into());
+                }
+
+                info!(name = %schedule.name, len = steps.len(), "run scheduled macro");
+                self.macro_run_id += 1;
+                self.macro_schedule = Some(ScheduledRun {
+                    schedule,
+                    serial: self.selected_serial(),
+                    started_at: chrono::Local::now(),
+                    steps: steps.len(),
+                });
+                self.macro_run = Some(MacroRun {
+                    id: self.macro_run_id,
+                    position: 0,
+                    steps,
+                });
+                return self.run_macro_step();
+            }
+            MainViewCommand::OnHapticToggled(enabled) => {
+                let serial = match &self.adb_devices_selected {
+                    Some(data) => data.serial.clone(),
+                    None => return Command::none(),
+                };
+                self.prefs_store.update(move |prefs| {
+                    if enabled {
+                        prefs.haptic_devices.insert(serial);
+                    } else {
+                        prefs.haptic_devices.remove(&serial);
+                    }
+                });
+            }
+            MainViewCommand::OnKeyboardModeToggled(enabled) => {
+                info!(enabled, "update OnKeyboardModeToggled");
+                self.keyboard_mode = enabled;
+            }
+            MainViewCommand::OnSectionCollapseToggled(id) => {
+                self.prefs_store.update(move |prefs| {
+                    if !prefs.collapsed_sections.remove(&id) {
+                        prefs.collapsed_sections.insert(id);
+                    }
+                });
+            }
+            MainViewCommand::OnKeyMapProfileSelected(name) => {
+                self.prefs_store.update(move |prefs| {
+                    prefs.select_key_map_profile(&name);
+                });
+            }
+            MainViewCommand::OnLayoutProfileSelected(name) => {
+                let serial = match &self.adb_devices_selected {
+                    Some(data) => data.serial.clone(),
+                    None => return Command::none(),
+                };
+                self.prefs_store.update(move |prefs| {
+                    prefs.device_layouts.insert(serial, name);
+                });
+            }
+            MainViewCommand::OnShortcutClicked(shortcut) => {
+                let serial = match &self.adb_devices_selected {
+                    Some(data) => data.serial.clone(),
+                    None => return Command::none(),
+                };
+
+                let user = self.user_selected.as_ref().map(|data| data.id);
+                return Command::perform(
+                    launch_shortcut(serial, shortcut.clone(), user),
+                    move |ret| match ret {
+                        Ok(_) => MainViewCommand::ShortcutLaunched(shortcut),
+                        Err(e) => {
+                            warn!(?e, "failed to launch the shortcut");
+                            MainViewCommand::Sink
+                        }
+                    },
+                );
+            }
+            MainViewCommand::OnUserSelected(data) => {
+                info!(%data, "user selected");
+                self.user_selected = Some(data);
+            }
+            MainViewCommand::ShortcutLaunched(shortcut) => {
+                self.history.push(HistoryEntry::new(
+                    HistoryEntryKind::Launch(shortcut.target),
+                    self.selected_serial(),
+                ));
+            }
+            MainViewCommand::OnNewPrefs(prefs) => {
+                info!("OnNewPreferences");
+
+                if let Some(data) = prefs {
+                    self.prefs = data;
+                    self.target_devices = target_devices(&self.prefs);
+                }
+            }
+            MainViewCommand::ServerAssetChecked(outdated) => {
+                if outdated {
+                    warn!("server asset is older than the server source");
+                }
+                self.server_outdated = outdated;
+            }
+            MainViewCommand::SendXMessage(_)
+            | MainViewCommand::ShowError(_)
+            | MainViewCommand::ShutdownFinished => {
+                // handled by App.
+            }
+            MainViewCommand::Sink => {
+                // do nothing.
+            }
+            MainViewCommand::UsersRetrieved(data) => {
+                info!(?data, "users retrieved");
+                if let Some(selected) = &self.user_selected {
+                    if !data.contains(selected) {
+                        self.user_selected = None;
+                    }
+                }
+                self.users = data;
+            }
+            MainViewCommand::OnNotificationRequested(summary, body) => {
+                return self.notification_command(summary, body);
+            }
+            MainViewCommand::OnPointerModeToggled(enabled) => {
+                info!(enabled, "update OnPointerModeToggled");
+                self.pointer_mode = enabled;
+                if !enabled || self.screen_size.is_some() {
+                    return Command::none();
+                }
+
+                return Command::perform(retrieve_screen_size(self.selected_serial()), |ret| {
+                    MainViewCommand::ScreenSizeRetrieved(match ret {
+                        Ok(data) => Some(data),
+                        Err(e) => {
+                            warn!(?e, "failed to retrieve the screen size");
+                            None
+                        }
+                    })
+                });
+            }
+            MainViewCommand::ScreenSizeRetrieved(data) => {
+                info!(?data, "screen size retrieved");
+                self.screen_size = data;
+                if data.is_none() {
+                    self.pointer_mode = false;
+                }
+            }
+            MainViewCommand::PointerGestured(gesture) => {
+                let size = match self.screen_size {
+                    Some(data) if self.adb_connectivity.is_connected() => data,
+                    _ => {
+                        debug!("skip broadcasting");
+                        return Command::none();
+                    }
+                };
+
+                return Command::perform(
+                    send_pointer_gesture(self.selected_serial(), gesture, size),
+                    |ret| {
+                        if let Err(e) = ret {
+                            warn!(?e, "failed to send the pointer gesture");
+                        }
+                        MainViewCommand::Sink
+                    },
+                );
+            }
+            MainViewCommand::VibratorDetected(data) => {
+                info!(data, "vibrator detected");
+                self.vibrator_available = Some(data);
+            }
+            MainViewCommand::OnXMessage(data) => match data {
+                XMessage::OnNewPreferences(prefs) => {
+                    self.prefs = prefs;
+                    self.target_devices = target_devices(&self.prefs);
+                }
+                XMessage::OnDeviceSelected(_) | XMessage::OnPrefsFileUpdated => {
+                    // do nothing.
+                }
+                XMessage::OnKeycodeTestRequested(keycode) => {
+                    info!(%keycode, "update OnKeycodeTestRequested");
+                    if !self.adb_connectivity.is_connected() {
+                        debug!("skip broadcasting");
+                        return Command::none();
+                    }
+
+                    match self.send_click_key(&SendEventKey::Custom(keycode)) {
+                        Ok(_) => return self.haptic_command(),
+                        Err(e) => warn!(?e, "failed to send the keycode"),
+                    }
+                }
+                XMessage::OnWindowFocusChanged(focused) => {
+                    self.window_focused = focused;
+                    // the window doesn't receive the releases while unfocused, e.g. Alt-Tab.
+                    if !focused {
+                        self.release_pressed_keys();
+                    }
+                }
+            },
+        }
+        Command::none()
+    }
+
+    pub fn subscription(&self) -> Subscription<MainViewCommand> {
+        let mut subscriptions = vec![];
+
+        if !self.history.is_empty() || self.recording.is_recording() {
+            // refreshes the relative times of the history and the recording.
+            subscriptions
+                .push(iced::time::every(Duration::from_secs(1)).map(|_| MainViewCommand::Sink));
+        }
+
+        if let Some(port) = self.prefs.api_port {
+            subscriptions.push(
+                api_server(
+                    port,
+                    self.api_tokens.clone(),
+                    self.api_state.subscribe(),
+                    self.api_commands.clone(),
+                )
+                .map(MainViewCommand::ApiServerRecipeResult),
+            );
+        }
+
+        if let Some(config) = &self.prefs.mqtt {
+            subscriptions.push(
+                mqtt(config.clone(), self.api_state.subscribe())
+                    .map(MainViewCommand::MqttRecipeResult),
+            );
+        }
+
+        if !self.prefs.scheduled_macros.is_empty() {
+            subscriptions
+                .push(iced::time::every(SCHEDULE_INTERVAL).map(|_| MainViewCommand::ScheduleTick));
+        }
+
+        if !self.adb_connectivity.is_active() {
+            return Subscription::batch(subscriptions);
+        }
+
+        let device = match &self.adb_devices_selected {
+            Some(data) => data.clone(),
+            None => {
+                warn!("device not selected");
+                return Subscription::batch(subscriptions);
+            }
+        };
+
+        subscriptions.push(
+            match &device.kind {
+                DeviceKind::Adb => adb_server(
+                    device.clone(),
+                    self.adb_server_rx.clone(),
+                    self.session_dir.clone(),
+                    self.server_build_dir.clone(),
+                    self.connect_attempt,
+                ),
+                kind => command_target(
+                    kind.clone(),
+                    self.adb_server_rx.clone(),
+                    self.config_dir.clone(),
+                    self.connect_attempt,
+                ),
+            }
+            .map(MainViewCommand::AdbServerRecipeResult),
+        );
+        subscriptions.push(native_events().map(MainViewCommand::Event));
+        if cfg!(feature = "gamepad") {
+            subscriptions.push(gamepad().map(MainViewCommand::GamepadRecipeResult));
+        }
+
+        if !self.capabilities().supports_shell {
+            return Subscription::batch(subscriptions);
+        }
+
+        if self.adb_connectivity.is_connected() {
+            subscriptions
+                .push(screen_state(device.clone()).map(MainViewCommand::ScreenStateRecipeResult));
+            subscriptions.push(
+                iced::time::every(HEALTH_INTERVAL).map(|_| MainViewCommand::DeviceHealthTick),
+            );
+        }
+
+        if self
+            .prefs
+            .custom_keys
+            .iter()
+            .any(|data| data.package.is_some())
+        {
+            subscriptions.push(focused_app(device).map(MainViewCommand::FocusedAppRecipeResult));
+        }
+
+        Subscription::batch(subscriptions)
+    }
+
+    pub fn view(&self) -> Element<MainViewCommand> {
+        let button_height = Length::Units(ButtonSpec::HEIGHT);
+
+        let devices_row = row![
+            button(svg(Icon::ArrowPath.handle(&self.prefs.theme)))
+                .style(iced::theme::Button::Secondary)
+                .on_press(MainViewCommand::OnAdbDevicesReloadClicked),
+            status_indicator(self.status_indicator_style()),
+            pick_list(
+                self.devices(),
+                self.adb_devices_selected
+                    .as_ref()
+                    .map(|data| self.labeled_device(data)),
+                MainViewCommand::AdbDevicesSelected,
+            ),
+        ];
+
+        // the quick switcher is shown once a key map is saved as the profile in the settings.
+        let devices_row = if self.prefs.key_map_profiles.is_empty() {
+            devices_row
+        } else {
+            devices_row.push(pick_list(
+                self.prefs
+                    .key_map_profiles
+                    .iter()
+                    .map(|data| data.name.clone())
+                    .collect::<Vec<_>>(),
+                self.prefs.key_map_profile.clone(),
+                MainViewCommand::OnKeyMapProfileSelected,
+            ))
+        };
+
+        let view = column![
+            text(tr("main-adb")),
+            devices_row
+                .spacing(4)
+                .align_items(iced::alignment::Alignment::Center)
+                .height(button_height),
             Space::with_height(4.into()),
+            checkbox(
+                tr("main-connect"),
+                match self.adb_connectivity {
+                    AdbConnectivity::Connecting | AdbConnectivity::Disconnected => false,
+                    AdbConnectivity::Connected
+                    | AdbConnectivity::Degraded
+                    | AdbConnectivity::Reconnecting => true,
+                },
+                |_| MainViewCommand::OnAdbConnectClicked,
+            ),
             row![
-                Space::with_width((90 + 8).into()),
-                button("Down (j)")
-                    .width(button_width)
-                    .height(button_height)
-                    .style(iced::theme::Button::Secondary)
-                    .on_press(MainViewCommand::RequestSendEvent(SendEventKey::DpadDown)),
-                Space::new(button_width, button_height),
-            ]
-            .spacing(4),
-            Space::with_height(8.into()),
-            row![
-                Space::with_width(4.into()),
-                button("Back")
-                    .width(button_width)
-                    .height(button_height)
-                    .style(iced::theme::Button::Secondary)
-                    .on_press(MainViewCommand::RequestSendEvent(SendEventKey::Back)),
-                button("Home")
-                    .width(button_width)
-                    .height(button_height)
-                    .style(iced::theme::Button::Secondary)
-                    .on_press(MainViewCommand::RequestSendEvent(SendEventKey::Home)),
-            ]
-            .spacing(4),
-            Space::with_height(8.into()),
-            row![
-                Space::with_width(4.into()),
-                button(container("1").width(Length::Fill).center_x())
-                    .width(button_width)
-                    .height(button_height)
-                    .style(iced::theme::Button::Secondary)
-                    .on_press(MainViewCommand::RequestSendEvent(SendEventKey::Num1)),
-                button(container("2").width(Length::Fill).center_x())
-                    .width(button_width)
-                    .height(button_height)
-                    .style(iced::theme::Button::Secondary)
-                    .on_press(MainViewCommand::RequestSendEvent(SendEventKey::Num2)),
-                button(container("3").width(Length::Fill).center_x())
-                    .width(button_width)
-                    .height(button_height)
-                    .style(iced::theme::Button::Secondary)
-                    .on_press(MainViewCommand::RequestSendEvent(SendEventKey::Num3)),
+                status_indicator(self.status_indicator_style()),
+                match self.adb_connectivity {
+                    AdbConnectivity::Connecting => self.connect_steps_view(),
+                    AdbConnectivity::Reconnecting => match self.reconnect_waiting {
+                        Some(delay) => text(tr_args(
+                            "main-status-reconnecting-in",
+                            &[
+                                ("secs", delay.as_secs().to_string()),
+                                ("retry", self.reconnect_retry.to_string()),
+                                ("max", RECONNECT_MAX_RETRIES.to_string()),
+                            ],
+                        ))
+                        .into(),
+                        None => self.connect_steps_view(),
+                    },
+                    AdbConnectivity::Connected if !self.capabilities().supports_hold => {
+                        text(tr("main-status-connected-no-hold")).into()
+                    }
+                    AdbConnectivity::Connected => text(tr("main-status-connected")).into(),
+                    AdbConnectivity::Degraded => text(tr("main-status-degraded")).into(),
+                    AdbConnectivity::Disconnected if self.server_crash.is_some() => {
+                        text(tr("main-status-crashed")).into()
+                    }
+                    AdbConnectivity::Disconnected if self.connect_failed => {
+                        self.connect_steps_view()
+                    }
+                    AdbConnectivity::Disconnected => text(tr("main-status-disconnected")).into(),
+                },
             ]
             .spacing(4),
-            Space::with_height(4.into()),
-            row![
-                Space::with_width(4.into()),
-                button(container("4").width(Length::Fill).center_x())
-                    .width(button_width)
-                    .height(button_height)
-                    .style(iced::theme::Button::Secondary)
-                    .on_press(MainViewCommand::RequestSendEvent(SendEventKey::Num4)),
-                button(container("5").width(Length::Fill).center_x())
-                    .width(button_width)
-                    .height(button_height)
-                    .style(iced::theme::Button::Secondary)
-                    .on_press(MainViewCommand::RequestSendEvent(SendEventKey::Num5)),
-                button(container("6").width(Length::Fill).center_x())
-                    .width(button_width)
-                    .height(button_height)
+            if self.server_outdated {
+                text(tr("main-warning-server-outdated"))
+            } else {
+                text("")
+            },
+            match &self.adb_version {
+                Some(Some(data)) if data.is_outdated() => text(tr_args(
+                    "main-warning-adb-outdated",
+                    &[
+                        ("version", data.to_string()),
+                        ("minimum", AdbVersion::minimum().to_string()),
+                    ],
+                )),
+                Some(None) => text(tr("main-warning-adb-unknown")),
+                Some(Some(_)) | None => text(""),
+            },
+        ];
+
+        let view = match &self.api_error {
+            Some((port, e)) if self.prefs.api_port == Some(*port) => {
+                view.push(text(tr_args("main-warning-api", &[("error", e.clone())])))
+            }
+            _ => view,
+        };
+
+        let view = match self.api_observers {
+            _ if self.prefs.api_port.is_none() => view,
+            0 => view,
+            1 => view.push(text(tr("main-api-observer")).size(14)),
+            count => view.push(
+                text(tr_args(
+                    "main-api-observers",
+                    &[("count", count.to_string())],
+                ))
+                .size(14),
+            ),
+        };
+
+        let view = match (&self.mqtt_error, &self.prefs.mqtt) {
+            (Some(e), Some(_)) => {
+                view.push(text(tr_args("main-warning-mqtt", &[("error", e.clone())])))
+            }
+            _ => view,
+        };
+
+        let view = match &self.device_health {
+            Some(data) if self.adb_connectivity.is_connected() => view.push(
+                row![
+                    status_indicator(if data.is_warning() {
+                        StatusIndicatorStyle::Yellow
+                    } else {
+                        StatusIndicatorStyle::Green
+                    }),
+                    text(data.summary()).size(14),
+                ]
+                .spacing(4)
+                .align_items(iced::alignment::Alignment::Center),
+            ),
+            _ => view,
+        };
+
+        let view = match &self.server_crash {
+            Some(excerpt) => view.push(server_crash_view(
+                excerpt,
+                !self.adb_connectivity.is_connected(),
+            )),
+            None => view,
+        };
+
+        let view = match &self.connect_confirming {
+            Some(serial) => view.push(
+                column![
+                    text(tr_args(
+                        "main-confirm-device",
+                        &[("serial", serial.clone())]
+                    ))
+                    .size(14),
+                    row![
+                        button(text(tr("main-confirm-connect")))
+                            .on_press(MainViewCommand::OnDeviceApproved),
+                        button(text(tr("main-confirm-cancel")))
+                            .style(iced::theme::Button::Secondary)
+                            .on_press(MainViewCommand::OnDeviceConfirmCancelled),
+                        button(text(tr("main-confirm-block")))
+                            .style(iced::theme::Button::Destructive)
+                            .on_press(MainViewCommand::OnDeviceBlocked),
+                    ]
+                    .spacing(4),
+                ]
+                .spacing(4),
+            ),
+            None => match &self.adb_devices_selected {
+                Some(device) if self.prefs.blocked_serials.contains(&device.serial) => {
+                    view.push(text(tr("main-warning-blocked")))
+                }
+                _ => view,
+            },
+        };
+
+        let view = if self.power_confirming && self.adb_connectivity.is_connected() {
+            view.push(
+                column![
+                    text(tr("main-confirm-power")).size(14),
+                    row![
+                        button(text(tr("main-confirm-power-send")))
+                            .style(iced::theme::Button::Destructive)
+                            .on_press(MainViewCommand::OnPowerConfirmed),
+                        button(text(tr("main-confirm-cancel")))
+                            .style(iced::theme::Button::Secondary)
+                            .on_press(MainViewCommand::OnPowerConfirmCancelled),
+                    ]
+                    .spacing(4),
+                ]
+                .spacing(4),
+            )
+        } else {
+            view
+        };
+
+        let view = if self.is_adb_device_selected() {
+            view.push(self.reboot.view().map(MainViewCommand::Reboot))
+                .push(
+                    self.recording
+                        .view(self.prefs.recording_time_limit)
+                        .map(MainViewCommand::Recording),
+                )
+                .push(self.self_test.view().map(MainViewCommand::SelfTest))
+        } else {
+            view
+        };
+
+        let view = if self.adb_supports(AdbCapability::Pair) {
+            view.push(self.pairing.adb_view().map(MainViewCommand::Pairing))
+        } else {
+            view
+        };
+
+        let view = match &self.adb_devices_selected {
+            Some(_) if self.capabilities().needs_pairing => {
+                view.push(self.pairing.atv_view().map(MainViewCommand::Pairing))
+            }
+            _ => view,
+        };
+
+        let view = match &self.adb_devices_selected {
+            Some(device) => {
+                let label = match &self.device_label {
+                    Some(data) => data.clone(),
+                    None => self
+                        .prefs
+                        .device_labels
+                        .get(&device.serial)
+                        .cloned()
+                        .unwrap_or_default(),
+                };
+                view.push(
+                    row![
+                        text(tr("main-device-name")),
+                        text_input(
+                            &tr("main-device-name-placeholder"),
+                            &label,
+                            MainViewCommand::OnDeviceLabelChanged
+                        )
+                        .on_submit(MainViewCommand::OnDeviceLabelSubmitted),
+                    ]
+                    .spacing(4)
+                    .align_items(iced::alignment::Alignment::Center),
+                )
+            }
+            None => view,
+        };
+
+        let view = match &self.adb_devices_selected {
+            Some(device) => view.push(
+                row![
+                    text(tr("main-layout")),
+                    pick_list(
+                        self.prefs
+                            .layout_profiles
+                            .iter()
+                            .map(|data| data.name.clone())
+                            .collect::<Vec<_>>(),
+                        Some(self.prefs.layout_profile(Some(&device.serial)).name),
+                        MainViewCommand::OnLayoutProfileSelected,
+                    ),
+                    button(text(if self.profile_share.is_some() {
+                        tr("main-layout-share-hide")
+                    } else {
+                        tr("main-layout-share")
+                    }))
                     .style(iced::theme::Button::Secondary)
-                    .on_press(MainViewCommand::RequestSendEvent(SendEventKey::Num6)),
-            ]
-            .spacing(4),
-            Space::with_height(4.into()),
+                    .on_press(MainViewCommand::OnProfileShareClicked),
+                ]
+                .spacing(4)
+                .align_items(iced::alignment::Alignment::Center),
+            ),
+            None => view,
+        };
+
+        let view = match &self.profile_share {
+            Some((_, handle)) => view.push(
+                column![
+                    svg(handle.clone())
+                        .width(Length::Units(200))
+                        .height(Length::Units(200)),
+                    button(text(tr("main-layout-copy-code")))
+                        .style(iced::theme::Button::Secondary)
+                        .on_press(MainViewCommand::OnProfileCodeCopyClicked),
+                ]
+                .spacing(4),
+            ),
+            None => view,
+        };
+
+        let view = if 1 < self.users.len() {
+            view.push(
+                row![
+                    text(tr("main-user")),
+                    pick_list(
+                        self.users.clone(),
+                        self.user_selected.clone(),
+                        MainViewCommand::OnUserSelected,
+                    ),
+                ]
+                .align_items(iced::alignment::Alignment::Center),
+            )
+        } else {
+            view
+        };
+
+        // hidden on the TV without the vibrator.
+        let view = match &self.adb_devices_selected {
+            Some(device) if self.vibrator_available == Some(true) => view.push(checkbox(
+                tr("main-haptic"),
+                self.prefs.haptic_devices.contains(&device.serial),
+                MainViewCommand::OnHapticToggled,
+            )),
+            _ => view,
+        };
+
+        let connected = self.adb_connectivity.is_connected();
+        let view = if connected && self.is_adb_device_selected() {
+            view.push(self.device_usage_view())
+        } else {
+            view
+        };
+
+        let view = if connected && self.is_adb_device_selected() {
+            view.push(self.locale.view().map(MainViewCommand::Locale))
+        } else {
+            view
+        };
+
+        let view = if connected && self.accessibility.is_retrieved() {
+            view.push(
+                self.accessibility
+                    .view(&self.prefs.accessibility_services)
+                    .map(MainViewCommand::Accessibility),
+            )
+        } else {
+            view
+        };
+
+        let view = if connected && self.capabilities().supports_text_injection {
+            view.push(checkbox(
+                tr("main-keyboard-mode"),
+                self.keyboard_mode,
+                MainViewCommand::OnKeyboardModeToggled,
+            ))
+        } else {
+            view
+        };
+
+        let view = if connected && self.is_adb_device_selected() {
+            view.push(checkbox(
+                tr("main-pointer-mode"),
+                self.pointer_mode,
+                MainViewCommand::OnPointerModeToggled,
+            ))
+        } else {
+            view
+        };
+
+        let view = match self.screen_size {
+            Some((width, height)) if connected && self.pointer_mode => {
+                view.push(pointer_pad_view(width, height))
+            }
+            _ => view,
+        };
+
+        let view = match self.screen_awake {
+            Some(false) if connected => view.push(
+                row![
+                    text(tr("main-screen-off")),
+                    Space::with_width(Length::Fill),
+                    button(text(tr("main-wake")))
+                        .style(iced::theme::Button::Secondary)
+                        .on_press(MainViewCommand::OnWakeClicked),
+                ]
+                .align_items(iced::alignment::Alignment::Center),
+            ),
+            _ => view,
+        };
+        let view = view.push(match self.macro_run {
+            Some(_) => button(text(tr("main-macro-stop")))
+                .style(iced::theme::Button::Secondary)
+                .on_press(MainViewCommand::OnMacroStopClicked),
+            None if connected => button(text(tr("main-macro-run-clipboard")))
+                .style(iced::theme::Button::Secondary)
+                .on_press(MainViewCommand::OnClipboardMacroClicked),
+            None => {
+                button(text(tr("main-macro-run-clipboard"))).style(iced::theme::Button::Secondary)
+            }
+        });
+        let view = match &self.macro_status {
+            Some(data) => view.push(text(data)),
+            None => view,
+        };
+        let view = view.push(self.macro_recorder_view(connected));
+
+        // hides the panels that need the shell of the device.
+        if self.capabilities().supports_shell {
+            view.push(self.input_capture_view())
+                .push(Space::with_height(8.into()))
+                .push(self.layout_view())
+                .push(Space::with_height(8.into()))
+                .push(self.shortcuts_view())
+                .push(Space::with_height(8.into()))
+                .push(self.history_view())
+                .into()
+        } else {
+            view.push(Space::with_height(8.into()))
+                .push(self.layout_view())
+                .push(Space::with_height(8.into()))
+                .push(self.history_view())
+                .into()
+        }
+    }
+
+    fn layout_view(&self) -> Element<MainViewCommand> {
+        let custom_keys = self
+            .prefs
+            .custom_keys
+            .iter()
+            .filter(|data| data.is_visible(self.focused_package.as_deref()))
+            .collect::<Vec<_>>();
+
+        let profile = self.prefs.layout_profile(
+            self.adb_devices_selected
+                .as_ref()
+                .map(|data| data.serial.as_str()),
+        );
+
+        let supports_color_keys = self.capabilities().supports_color_keys;
+        let press = self.capabilities().supports_hold;
+        let hold = self.prefs.hold_buttons && press;
+        let mut view = Column::new();
+
+        if !profile.grid.is_empty() {
+            let mut rows = create_grid(&profile.grid, &custom_keys);
+            if !supports_color_keys {
+                disable_keys(&mut rows, SendEventKey::is_color_key);
+            }
+            mark_pending_keys(&mut rows, |key| self.is_ack_pending(key));
+            return view
+                .push(Space::with_height(8.into()))
+                .push(rows_view(rows, hold, press))
+                .into();
+        }
+
+        for mut section in create_layout(&profile, &custom_keys) {
+            if !supports_color_keys {
+                section.disable_keys(SendEventKey::is_color_key);
+            }
+            section.mark_pending_keys(|key| self.is_ack_pending(key));
+            let mut section_view = Column::new().spacing(4);
+            let collapsed =
+                section.id.is_collapsible() && self.prefs.collapsed_sections.contains(&section.id);
+            if section.id.is_collapsible() {
+                section_view = section_view.push(
+                    button(
+                        row![
+                            svg(if collapsed {
+                                Icon::ChevronRight
+                            } else {
+                                Icon::ChevronDown
+                            }
+                            .handle(&self.prefs.theme))
+                            .width(Length::Units(16))
+                            .height(Length::Units(16)),
+                            text(section.id.to_string()).size(14),
+                        ]
+                        .spacing(4)
+                        .align_items(iced::alignment::Alignment::Center),
+                    )
+                    .style(iced::theme::Button::Text)
+                    .on_press(MainViewCommand::OnSectionCollapseToggled(section.id)),
+                );
+            }
+            // shows only the header while collapsed.
+            if !collapsed {
+                section_view = section_view.push(rows_view(section.rows, hold, press));
+            }
+            view = view.push(Space::with_height(8.into())).push(section_view);
+        }
+        view.into()
+    }
+
+    pub fn view_size() -> (u32, u32) {
+        (300, 900)
+    }
+
+    fn shortcuts_view(&self) -> Element<MainViewCommand> {
+        let serial = match &self.adb_devices_selected {
+            Some(data) => data.serial.as_str(),
+            None => return Space::with_height(0.into()).into(),
+        };
+
+        let mut view = Row::new().spacing(4);
+        for shortcut in self.prefs.layout_profile(Some(serial)).shortcuts {
+            let icon = if shortcut.is_deeplink() {
+                Icon::Link
+            } else {
+                Icon::Play
+            };
+            view = view.push(
+                button(
+                    row![
+                        svg(icon.handle(&self.prefs.theme))
+                            .width(Length::Units(16))
+                            .height(Length::Units(16)),
+                        text(shortcut.label.clone()),
+                    ]
+                    .spacing(4)
+                    .align_items(iced::alignment::Alignment::Center),
+                )
+                .style(iced::theme::Button::Secondary)
+                .on_press(MainViewCommand::OnShortcutClicked(shortcut)),
+            );
+        }
+        view.into()
+    }
+
+    fn device_usage_view(&self) -> Element<MainViewCommand> {
+        let refresh_button =
+            button(text(tr("main-usage-refresh"))).style(iced::theme::Button::Secondary);
+        let (storage, memory) = match &self.device_usage {
+            Some(data) => (
+                data.storage.map(|data| data.summary()),
+                data.memory.map(|data| data.summary()),
+            ),
+            None => (None, None),
+        };
+        let placeholder = if self.device_usage_loading {
+            tr("main-usage-loading")
+        } else {
+            tr("main-usage-unknown")
+        };
+
+        row![
+            column![
+                text(tr_args(
+                    "main-usage-storage",
+                    &[("usage", storage.unwrap_or_else(|| placeholder.clone()))],
+                ))
+                .size(14),
+                text(tr_args(
+                    "main-usage-memory",
+                    &[("usage", memory.unwrap_or(placeholder))],
+                ))
+                .size(14),
+            ],
+            Space::with_width(Length::Fill),
+            if self.device_usage_loading {
+                refresh_button
+            } else {
+                refresh_button.on_press(MainViewCommand::OnDeviceUsageRefreshClicked)
+            },
+        ]
+        .spacing(4)
+        .align_items(iced::alignment::Alignment::Center)
+        .into()
+    }
+
+    fn macro_recorder_view(&self, connected: bool) -> Element<MainViewCommand> {
+        let record_button = match &self.macro_recorder {
+            Some(_) => button(text(tr("main-macro-record-stop")))
+                .on_press(MainViewCommand::OnMacroRecordClicked),
+            None if connected && self.macro_run.is_none() => button(text(tr("main-macro-record")))
+                .style(iced::theme::Button::Secondary)
+                .on_press(MainViewCommand::OnMacroRecordClicked),
+            None => button(text(tr("main-macro-record"))).style(iced::theme::Button::Secondary),
+        };
+        let mut view = column![row![
+            record_button,
+            text(match &self.macro_recorder {
+                Some(data) => tr_args(
+                    "main-macro-keys",
+                    &[("count", data.key_count().to_string())]
+                ),
+                None => "".into(),
+            }),
+        ]
+        .spacing(4)
+        .align_items(iced::alignment::Alignment::Center)]
+        .spacing(4);
+
+        if self.macro_recorded.is_some() {
+            view = view.push(
+                row![
+                    text_input(
+                        &tr("main-macro-name"),
+                        &self.macro_name,
+                        MainViewCommand::OnMacroNameChanged
+                    )
+                    .on_submit(MainViewCommand::OnMacroSaveClicked),
+                    button(text(tr("main-macro-save")))
+                        .style(iced::theme::Button::Secondary)
+                        .on_press(MainViewCommand::OnMacroSaveClicked),
+                    button(text(tr("main-macro-discard")))
+                        .style(iced::theme::Button::Secondary)
+                        .on_press(MainViewCommand::OnMacroDiscardClicked),
+                ]
+                .spacing(4),
+            );
+        }
+
+        for saved in &self.prefs.saved_macros {
+            let play_button = button(text(&saved.name).size(14))
+                .padding(2)
+                .style(iced::theme::Button::Secondary);
+            view = view.push(
+                row![
+                    if connected && self.macro_run.is_none() && self.macro_recorder.is_none() {
+                        play_button
+                            .on_press(MainViewCommand::OnSavedMacroClicked(saved.name.clone()))
+                    } else {
+                        play_button
+                    },
+                    Space::with_width(Length::Fill),
+                    button(text(tr("main-macro-delete")).size(14))
+                        .padding(2)
+                        .style(iced::theme::Button::Destructive)
+                        .on_press(MainViewCommand::OnMacroDeleteClicked(saved.name.clone())),
+                ]
+                .spacing(4),
+            );
+        }
+
+        view.into()
+    }
+
+    fn input_capture_view(&self) -> Element<MainViewCommand> {
+        let enabled = self.adb_devices_selected.is_some() && !self.input_capturing;
+        let capture_button = |label: String, target| {
+            let view = button(text(label)).style(iced::theme::Button::Secondary);
+            if enabled {
+                view.on_press(MainViewCommand::OnInputCaptureClicked(target))
+            } else {
+                view
+            }
+        };
+
+        let view = column![row![
+            text(tr("main-capture")),
+            Space::with_width(Length::Fill),
+            text_input(
+                &tr("main-capture-secs"),
+                &self.input_capture_secs,
+                MainViewCommand::OnInputCaptureSecsChanged
+            )
+            .width(Length::Units(40)),
+            capture_button(tr("main-capture-copy"), InputCaptureTarget::Clipboard),
+            capture_button(tr("main-capture-save"), InputCaptureTarget::File),
+        ]
+        .spacing(4)
+        .align_items(iced::alignment::Alignment::Center)];
+
+        let diagnose_button =
+            button(text(tr("main-diagnostics-run"))).style(iced::theme::Button::Secondary);
+        let view = view.push(
             row![
-                Space::with_width(4.into()),
-                button(container("7").width(Length::Fill).center_x())
-                    .width(button_width)
-                    .height(button_height)
-                    .style(iced::theme::Button::Secondary)
-                    .on_press(MainViewCommand::RequestSendEvent(SendEventKey::Num7)),
-                button(container("8").width(Length::Fill).center_x())
-                    .width(button_width)
-                    .height(button_height)
-                    .style(iced::theme::Button::Secondary)
-                    .on_press(MainViewCommand::RequestSendEvent(SendEventKey::Num8)),
-                button(container("9").width(Length::Fill).center_x())
-                    .width(button_width)
-                    .height(button_height)
-                    .style(iced::theme::Button::Secondary)
-                    .on_press(MainViewCommand::RequestSendEvent(SendEventKey::Num9)),
+                text(tr("main-diagnostics")),
+                Space::with_width(Length::Fill),
+                if self.adb_devices_selected.is_some() && !self.input_diagnosing {
+                    diagnose_button.on_press(MainViewCommand::OnInputDiagnosticsClicked)
+                } else {
+                    diagnose_button
+                },
             ]
-            .spacing(4),
-            Space::with_height(4.into()),
+            .spacing(4)
+            .align_items(iced::alignment::Alignment::Center),
+        );
+
+        let view = match &self.input_diagnostics {
+            Some(data) => {
+                let mut lines = Column::new();
+                for line in data.to_lines() {
+                    lines = lines.push(text(line).size(14));
+                }
+                view.push(scrollable(lines).height(Length::Units(120)))
+                    .push(
+                        button(text(tr("main-diagnostics-hide")))
+                            .style(iced::theme::Button::Secondary)
+                            .on_press(MainViewCommand::OnInputDiagnosticsHideClicked),
+                    )
+            }
+            None => view,
+        };
+
+        match &self.input_capture_status {
+            Some(data) => view.push(text(data)),
+            None => view,
+        }
+        .into()
+    }
+
+    /// the entries sent to the devices, which the logcat view shows as the markers.
+    pub fn history(&self) -> &History {
+        &self.history
+    }
+
+    fn history_view(&self) -> Element<MainViewCommand> {
+        let now = chrono::Local::now();
+        let mut entries = Column::new();
+        for entry in self.history.iter() {
+            let mut line = format!(
+                "{} {} @{}",
+                format_relative_time(now - entry.sent_at),
+                entry.kind,
+                entry.serial
+            );
+            if let Some(data) = entry.device_received_at {
+                line.push_str(&format!(
+                    " (+{}ms)",
+                    (data - entry.sent_at).num_milliseconds()
+                ));
+            }
+            entries = if self.adb_connectivity.is_connected() && entry.kind.is_resendable() {
+                entries.push(
+                    button(text(line).size(16))
+                        .style(iced::theme::Button::Text)
+                        .padding(0)
+                        .on_press(MainViewCommand::OnHistoryEntryClicked(entry.kind.clone())),
+                )
+            } else {
+                entries.push(text(line).size(16))
+            };
+        }
+
+        column![
             row![
-                Space::with_width((90 + 8).into()),
-                button(container("0").width(Length::Fill).center_x())
-                    .width(button_width)
-                    .height(button_height)
-                    .style(iced::theme::Button::Secondary)
-                    .on_press(MainViewCommand::RequestSendEvent(SendEventKey::Num0)),
+                text(tr("main-history")),
+                Space::with_width(Length::Fill),
+                if self.history.is_empty() {
+                    button(text(tr("main-history-export"))).style(iced::theme::Button::Secondary)
+                } else {
+                    button(text(tr("main-history-export")))
+                        .style(iced::theme::Button::Secondary)
+                        .on_press(MainViewCommand::OnHistoryExportClicked)
+                },
+                if self.adb_connectivity.is_connected() && self.capabilities().supports_shell {
+                    button(text(tr("main-history-mark-log")))
+                        .style(iced::theme::Button::Secondary)
+                        .on_press(MainViewCommand::OnMarkLogClicked)
+                } else {
+                    button(text(tr("main-history-mark-log"))).style(iced::theme::Button::Secondary)
+                },
             ]
-            .spacing(4),
+            .spacing(4)
+            .align_items(iced::alignment::Alignment::Center),
+            scrollable(entries).height(Length::Units(100)),
         ]
+        .spacing(4)
+        .into()
+    }
+
+    /// assumes the capability is supported if the version is unknown.
+    fn adb_supports(&self, capability: AdbCapability) -> bool {
+        match &self.adb_version {
+            Some(Some(data)) => data.supports(capability),
+            Some(None) | None => true,
+        }
+    }
+
+    /// the capabilities resolved at connecting, or assumed from the selected device until then.
+    fn capabilities(&self) -> DeviceCapabilities {
+        match (&self.connected_capabilities, &self.adb_devices_selected) {
+            (Some(data), _) => *data,
+            (None, Some(data)) => data.kind.capabilities(),
+            (None, None) => DeviceCapabilities::ADB,
+        }
+    }
+
+    fn status_indicator_style(&self) -> StatusIndicatorStyle {
+        match self.adb_connectivity {
+            AdbConnectivity::Connected if !self.capabilities().supports_hold => {
+                StatusIndicatorStyle::Yellow
+            }
+            AdbConnectivity::Connected => StatusIndicatorStyle::Green,
+            AdbConnectivity::Connecting
+            | AdbConnectivity::Degraded
+            | AdbConnectivity::Reconnecting => StatusIndicatorStyle::Yellow,
+            AdbConnectivity::Disconnected if self.connect_failed || self.server_crash.is_some() => {
+                StatusIndicatorStyle::Red
+            }
+            AdbConnectivity::Disconnected => StatusIndicatorStyle::Gray,
+        }
+    }
+
+    /// the adb devices and the devices driven without adb.
+    fn devices(&self) -> Vec<Arc<AndroidDevice>> {
+        self.adb_devices
+            .iter()
+            .chain(self.target_devices.iter())
+            .map(|data| self.labeled_device(data))
+            .collect()
+    }
+
+    /// resolves the label of the device from the preferences.
+    fn labeled_device(&self, device: &Arc<AndroidDevice>) -> Arc<AndroidDevice> {
+        let label = self.prefs.device_labels.get(&device.serial);
+        if device.label.as_ref() == label {
+            return device.clone();
+        }
+
+        Arc::new(AndroidDevice {
+            serial: device.serial.clone(),
+            kind: device.kind.clone(),
+            label: label.cloned(),
+            details: device.details.clone(),
+        })
+    }
+
+    fn is_adb_device_selected(&self) -> bool {
+        matches!(
+            self.adb_devices_selected.as_deref(),
+            Some(AndroidDevice {
+                kind: DeviceKind::Adb,
+                ..
+            })
+        )
+    }
+
+    fn needs_power_confirmation(&self, key: &SendEventKey) -> bool {
+        key == &SendEventKey::Power && self.prefs.confirm_power_key
+    }
+
+    /// the keyboard mode is kept while the device can't inject the text but has no effect.
+    fn is_keyboard_mode(&self) -> bool {
+        self.keyboard_mode && self.capabilities().supports_text_injection
+    }
+
+    /// prefers [Preferences::key_shortcuts] to the built-in shortcuts.
+    fn send_event_key(&self, key: KeyCode, modifiers: Modifiers) -> Option<SendEventKey> {
+        let shortcut = find_key_shortcut(&self.prefs.key_shortcuts, key, modifiers);
+        if !self.is_keyboard_mode() {
+            return shortcut.or_else(|| create_send_event_key(key, modifiers));
+        }
+
+        // maps only the keys that type no character in the keyboard mode.
+        if modifiers.control() || modifiers.alt() {
+            return shortcut;
+        }
+
+        match key {
+            KeyCode::Down => Some(SendEventKey::DpadDown),
+            KeyCode::Up => Some(SendEventKey::DpadUp),
+            KeyCode::Left => Some(SendEventKey::DpadLeft),
+            KeyCode::Right => Some(SendEventKey::DpadRight),
+            KeyCode::Enter => Some(SendEventKey::DpadOk),
+            KeyCode::Backspace => Some(SendEventKey::Custom("KEYCODE_DEL".into())),
+            KeyCode::Escape => Some(SendEventKey::Back),
+            _ => None,
+        }
+    }
+
+    fn selected_serial(&self) -> String {
+        self.adb_devices_selected
+            .as_ref()
+            .map(|data| data.serial.clone())
+            .unwrap_or_default()
+    }
+
+    fn send_to_server(&mut self, commands: Vec<ServerCommand>) -> Fallible<()> {
+        // formats only while the WebSocket clients are connected.
+        let pushed = (0 < self.api_commands.receiver_count()).then(|| encode_commands(&commands));
+
+        // fails if the server is too slow to take the keys, but the ups that the callers have
+        // already forgotten are still queued into the reserved room.
+        let ret = if !is_release_only(&commands)
+            && self.adb_server_tx.capacity() <= RELEASE_QUEUE_RESERVE
+        {
+            Err(anyhow::anyhow!("the queue is reserved for the ups"))
+        } else {
+            self.adb_server_tx.try_send(commands).map_err(Into::into)
+        };
+        if let Err(e) = ret {
+            if self.adb_connectivity.is_connected() {
+                self.adb_connectivity = AdbConnectivity::Degraded;
+            }
+            return Err(e).context("failed to send the sendevent");
+        }
+
+        if let Some(data) = pushed {
+            self.api_commands.send(data).ok();
+        }
+
+        if let AdbConnectivity::Degraded = self.adb_connectivity {
+            self.adb_connectivity = AdbConnectivity::Connected;
+        }
+
+        Ok(())
+    }
+
+    /// returns true if the server hasn't taken half of the queue, e.g. the adb over Wi-Fi is slow.
+    fn is_queue_busy(&self) -> bool {
+        self.adb_server_tx.capacity() < COMMAND_QUEUE_CAPACITY / 2
+    }
+
+    /// sends the ups of the keys held on the keyboard and the held button.
+    fn release_pressed_keys(&mut self) {
+        let mut commands = std::mem::take(&mut self.pressed_keys)
+            .iter()
+            .filter_map(|(_, key)| create_release_key_command(&self.prefs.key_map, key).ok())
+            .flatten()
+            .collect::<Vec<_>>();
+        if let Some(data) = self.held_key.take() {
+            commands.push(ServerCommand::KeyUp(data.keycode));
+        }
+        // nothing is down until the long key is released or timed out.
+        self.long_key = None;
+        self.pressed_button = None;
+        if commands.is_empty() || !self.adb_connectivity.is_connected() {
+            return;
+        }
+
+        info!(len = commands.len(), "release the pressed keys");
+        if let Err(e) = self.send_to_server(commands) {
+            warn!(?e, "failed to release the pressed keys");
+        }
+    }
+
+    fn send_click_key(&mut self, key: &SendEventKey) -> Fallible<()> {
+        let command = create_click_key_command(&self.prefs.key_map, key)?;
+        self.send_to_server(command)?;
+
+        let keycode: Keycode = self.prefs.key_map.keycode(key).parse()?;
+        self.expect_ack(ServerCommand::KeyUp(keycode.clone()));
+        // the macro being played isn't recorded again.
+        if self.macro_run.is_none() {
+            if let Some(recorder) = &mut self.macro_recorder {
+                recorder.record(keycode.clone(), Instant::now());
+            }
+        }
+        self.history.push(HistoryEntry::new(
+            HistoryEntryKind::Key(keycode),
+            self.selected_serial(),
+        ));
+
+        Ok(())
+    }
+
+    /// highlights the button of the key until the server acks the command.
+    fn expect_ack(&mut self, command: ServerCommand) {
+        if self.capabilities().supports_ack {
+            self.pending_acks.push(command);
+        }
+    }
+
+    fn is_ack_pending(&self, key: &SendEventKey) -> bool {
+        let keycode = self.prefs.key_map.keycode(key);
+        self.pending_acks
+            .iter()
+            .any(|data| data.keycode().map(Keycode::as_str) == Some(keycode))
+    }
+
+    /// `held_for` shortens the wait of the long-press.
+    fn run_button_actions(
+        &mut self,
+        actions: Vec<ButtonAction>,
+        held_for: Duration,
+    ) -> Command<MainViewCommand> {
+        let mut commands = vec![];
+        for action in actions {
+            match action {
+                ButtonAction::Click(keycode) => {
+                    match self.send_click_key(&SendEventKey::Custom(keycode)) {
+                        Ok(_) => commands.push(self.haptic_command()),
+                        Err(e) => warn!(?e, "failed to send the key"),
+                    }
+                }
+                ButtonAction::LongPress(keycode, mode) => {
+                    let delay = LONG_PRESS_TIMEOUT.saturating_sub(held_for);
+                    match self.start_long_press(&keycode, mode, delay) {
+                        Ok(data) => commands.push(data),
+                        Err(e) => warn!(?e, "failed to send the long-press"),
+                    }
+                }
+                ButtonAction::Wait(id) => commands.push(Command::perform(
+                    tokio::time::sleep(GestureRecognizer::DOUBLE_CLICK_INTERVAL),
+                    move |_| MainViewCommand::GestureTimedOut(id),
+                )),
+            }
+        }
+        Command::batch(commands)
+    }
+
+    /// vibrates the phone as the confirmation of the click if enabled for the device.
+    fn haptic_command(&self) -> Command<MainViewCommand> {
+        let serial = match &self.adb_devices_selected {
+            Some(data)
+                if self.vibrator_available == Some(true)
+                    && self.prefs.haptic_devices.contains(&data.serial) =>
+            {
+                data.serial.clone()
+            }
+            _ => return Command::none(),
+        };
+
+        Command::perform(vibrate(serial, HAPTIC_DURATION), |ret| {
+            if let Err(e) = ret {
+                warn!(?e, "failed to vibrate");
+            }
+            MainViewCommand::Sink
+        })
+    }
+
+    fn device_health_command(&mut self) -> Command<MainViewCommand> {
+        if self.device_health_loading {
+            return Command::none();
+        }
+
+        self.device_health_loading = true;
+        Command::perform(retrieve_device_health(self.selected_serial()), |ret| {
+            MainViewCommand::DeviceHealthRetrieved(match ret {
+                Ok(data) => Some(data),
+                Err(e) => {
+                    warn!(?e, "failed to retrieve the device health");
+                    None
+                }
+            })
+        })
+    }
+
+    fn device_usage_command(&mut self) -> Command<MainViewCommand> {
+        if self.device_usage_loading {
+            return Command::none();
+        }
+
+        self.device_usage_loading = true;
+        Command::perform(retrieve_device_usage(self.selected_serial()), |ret| {
+            MainViewCommand::DeviceUsageRetrieved(match ret {
+                Ok(data) => Some(data),
+                Err(e) => {
+                    warn!(?e, "failed to retrieve the device usage");
+                    None
+                }
+            })
+        })
+    }
+
+    /// retries connecting after the delay that doubles on each retry, and gives up after
+    /// [RECONNECT_MAX_RETRIES].
+    fn reconnect_command(&mut self) -> Command<MainViewCommand> {
+        if RECONNECT_MAX_RETRIES <= self.reconnect_retry {
+            info!("give up reconnecting");
+            self.adb_connectivity = AdbConnectivity::Disconnected;
+            self.connect_failed = true;
+            self.connected_capabilities = None;
+            self.reconnect_retry = 0;
+            self.reconnect_waiting = None;
+            return self.notification_command(
+                tr("notification-error"),
+                tr("notification-reconnect-failed"),
+            );
+        }
+
+        self.reconnect_retry += 1;
+        let delay = reconnect_delay(self.reconnect_retry);
+        info!(
+            retry = self.reconnect_retry,
+            ?delay,
+            "wait for reconnecting"
+        );
+        self.adb_connectivity = AdbConnectivity::Reconnecting;
+        self.connect_step = None;
+        self.reconnect_waiting = Some(delay);
+        let attempt = self.connect_attempt;
+        Command::perform(tokio::time::sleep(delay), move |_| {
+            MainViewCommand::ReconnectDelayElapsed(attempt)
+        })
+    }
+
+    fn connect_command(&mut self) -> Command<MainViewCommand> {
+        self.adb_connectivity = AdbConnectivity::Connecting;
+        self.connect_attempt += 1;
+        self.connect_failed = false;
+        self.reconnect_retry = 0;
+        self.reconnect_waiting = None;
+        self.connected_capabilities = None;
+        self.server_crash = None;
+        self.connect_step = None;
+        self.server_outdated = false;
+
+        if !self.is_adb_device_selected() {
+            // no server to build without adb.
+            self.server_build_dir = None;
+            return Command::none();
+        }
+
+        let server_source_dir = resolve_server_source_dir(&self.prefs);
+        if self.prefs.server_auto_build {
+            self.server_build_dir = server_source_dir;
+        } else {
+            self.server_build_dir = None;
+            if let Some(data) = server_source_dir {
+                return Command::perform(is_server_asset_outdated(data), |ret| match ret {
+                    Ok(data) => MainViewCommand::ServerAssetChecked(data),
+                    Err(e) => {
+                        warn!(?e, "failed to check server asset");
+                        MainViewCommand::Sink
+                    }
+                });
+            }
+        }
+        Command::none()
+    }
+
+    /// the layout and the connection of the selected device to share.
+    fn device_profile(&self) -> Option<DeviceProfile> {
+        let device = self.adb_devices_selected.as_ref()?;
+        Some(DeviceProfile {
+            serial: device.serial.clone(),
+            layout: self.prefs.layout_profile(Some(&device.serial)),
+            network_target: self
+                .prefs
+                .network_targets
+                .iter()
+                .find(|data| data.name == device.serial)
+                .cloned(),
+        })
+    }
+
+    /// repeats the down of the held key if the interval is specified.
+    fn key_repeat_command(&self, id: u64) -> Command<MainViewCommand> {
+        match self.prefs.key_repeat_interval {
+            Some(interval) => Command::perform(tokio::time::sleep(interval), move |_| {
+                MainViewCommand::KeyRepeatTimedOut(id)
+            }),
+            None => Command::none(),
+        }
+    }
+
+    /// repeats the down of the last key pressed on the keyboard if the interval is specified.
+    fn keyboard_repeat_command(&self, id: u64, repeated: bool) -> Command<MainViewCommand> {
+        let interval = match self.prefs.keyboard_repeat_interval {
+            Some(data) => data,
+            None => return Command::none(),
+        };
+        let delay = if repeated {
+            interval
+        } else {
+            self.prefs.keyboard_repeat_delay
+        };
+        Command::perform(tokio::time::sleep(delay), move |_| {
+            MainViewCommand::KeyboardRepeatTimedOut(id)
+        })
+    }
+
+    /// waits for the release to send the key, or sends the long keycode after the timeout.
+    fn start_long_key(
+        &mut self,
+        key: SendEventKey,
+        key_code: Option<KeyCode>,
+    ) -> Command<MainViewCommand> {
+        // another key is pressed before releasing the previous one.
+        self.finish_long_key();
+
+        self.long_key_id += 1;
+        let id = self.long_key_id;
+        self.long_key = Some(PendingLongKey {
+            id,
+            key,
+            key_code,
+            sent: false,
+        });
+        Command::perform(tokio::time::sleep(LONG_PRESS_TIMEOUT), move |_| {
+            MainViewCommand::LongKeyTimedOut(id)
+        })
+    }
+
+    /// `key_code` is `None` for the button held in the hold mode.
+    fn is_long_key(&self, key_code: Option<KeyCode>) -> bool {
+        matches!(&self.long_key, Some(data) if data.key_code == key_code)
+    }
+
+    /// sends the key if released before the long keycode is sent.
+    fn finish_long_key(&mut self) {
+        let long_key = match self.long_key.take() {
+            Some(data) if !data.sent => data,
+            _ => return,
+        };
+        if let Err(e) = self.send_click_key(&long_key.key) {
+            warn!(?e, "failed to send the key");
+        }
+    }
+
+    /// tells whether the button is still held after [LONG_PRESS_TIMEOUT].
+    fn button_long_press_command(&self, id: u64) -> Command<MainViewCommand> {
+        Command::perform(tokio::time::sleep(LONG_PRESS_TIMEOUT), move |_| {
+            MainViewCommand::ButtonLongPressTimedOut(id)
+        })
+    }
+
+    /// the down of the long-press in the mode of the gesture of the key.
+    fn long_press_server_command(&self, keycode: Keycode) -> ServerCommand {
+        let mode = self
+            .prefs
+            .gestures
+            .iter()
+            .find(|data| data.keycode == keycode.as_str())
+            .map(|data| data.long_press)
+            .unwrap_or_default();
+        match mode {
+            LongPressMode::Flag => ServerCommand::KeyLongPress(keycode),
+            LongPressMode::Repeat => ServerCommand::KeyDown(keycode),
+        }
+    }
+
+    /// holds the key and sends the long-press after the `delay`.
+    fn start_long_press(
+        &mut self,
+        keycode: &str,
+        mode: LongPressMode,
+        delay: Duration,
+    ) -> Fallible<Command<MainViewCommand>> {
+        let keycode = keycode.parse::<Keycode>()?;
+        self.send_to_server(vec![ServerCommand::KeyDown(keycode.clone())])?;
+
+        Ok(Command::perform(tokio::time::sleep(delay), move |_| {
+            MainViewCommand::LongPressTimedOut(keycode, mode)
+        }))
+    }
+
+    /// notifies the WebSocket clients only if the state was changed.
+    fn publish_api_state(&self) {
+        let state = ApiState {
+            serial: self.selected_serial(),
+            connectivity: self.adb_connectivity.as_str(),
+        };
+        if *self.api_state.borrow() != state {
+            self.api_state.send_replace(state);
+        }
+    }
+
+    /// keys are sent through the connection of the selected device only.
+    fn api_response(&mut self, route: ApiRoute) -> ApiResponse {
+        match route {
+            ApiRoute::Devices => {
+                let selected = self.selected_serial();
+                let connected = self.adb_connectivity.is_connected();
+                ApiResponse::ok(serde_json::Value::Array(
+                    self.devices()
+                        .iter()
+                        .map(|data| {
+                            serde_json::json!({
+                                "serial": data.serial,
+                                "name": data.to_string(),
+                                "connected": connected && data.serial == selected,
+                            })
+                        })
+                        .collect(),
+                ))
+            }
+            ApiRoute::Key { serial, keycode } => {
+                if !self.adb_connectivity.is_connected() || serial != self.selected_serial() {
+                    return ApiResponse::error(409, "device is not connected");
+                }
+
+                match self.send_click_key(&SendEventKey::Custom(keycode.to_string())) {
+                    Ok(_) => ApiResponse::ok(serde_json::json!({ "sent": keycode.to_string() })),
+                    Err(e) => {
+                        warn!(?e, "failed to send the api key");
+                        ApiResponse::error(500, &format!("{:#}", e))
+                    }
+                }
+            }
+        }
+    }
+
+    fn start_macro(&mut self, steps: Vec<MacroStep>) -> Command<MainViewCommand> {
+        self.macro_run_id += 1;
+        self.macro_run = Some(MacroRun {
+            id: self.macro_run_id,
+            position: 0,
+            steps,
+        });
+        self.run_macro_step()
+    }
+
+    fn run_macro_step(&mut self) -> Command<MainViewCommand> {
+        let run = match &self.macro_run {
+            Some(data) => data,
+            None => return Command::none(),
+        };
+
+        let step = match run.steps.get(run.position) {
+            Some(data) => data.clone(),
+            None => {
+                let steps = run.steps.len();
+                self.macro_status =
+                    Some(tr_args("main-macro-done", &[("steps", steps.to_string())]));
+                self.macro_run = None;
+                return Command::batch([
+                    self.notification_command(
+                        tr("notification-macro"),
+                        tr_args("notification-macro-done", &[("steps", steps.to_string())]),
+                    ),
+                    self.webhook_command(WebhookEvent::MacroFinished {
+                        serial: self.selected_serial(),
+                        steps,
+                    }),
+                    self.finish_scheduled_macro(None, None),
+                ]);
+            }
+        };
+
+        let progress = format!("{}/{}", run.position + 1, run.steps.len());
+        if !self.adb_connectivity.is_connected() {
+            return self.fail_macro(format!("disconnected at {}", progress));
+        }
+
+        let id = run.id;
+        let (label, delay) = match &step {
+            MacroStep::Key(keycode) => (keycode.to_string(), MACRO_KEY_INTERVAL),
+            MacroStep::Wait(duration) => (
+                tr_args(
+                    "main-macro-wait",
+                    &[("millis", duration.as_millis().to_string())],
+                ),
+                *duration,
+            ),
+        };
+        self.macro_status = Some(tr_args(
+            "main-macro-step",
+            &[("progress", progress.clone()), ("step", label)],
+        ));
+
+        if let MacroStep::Key(keycode) = step {
+            if let Err(e) = self.send_click_key(&SendEventKey::Custom(keycode.to_string())) {
+                warn!(?e, "failed to send the macro step");
+                return self.fail_macro(format!("failed at {}: {}", progress, e));
+            }
+        }
+
+        Command::perform(tokio::time::sleep(delay), move |_| {
+            MainViewCommand::MacroStepFinished(id)
+        })
+    }
+
+    /// posts the event to the webhooks that accept it.
+    fn webhook_command(&self, event: WebhookEvent) -> Command<MainViewCommand> {
+        let body = event.to_json();
+        Command::batch(
+            self.prefs
+                .webhooks
+                .iter()
+                .filter(|data| data.accepts(event.kind()))
+                .cloned()
+                .map(|webhook| {
+                    let body = body.clone();
+                    Command::perform(
+                        async move {
+                            post_webhook(&webhook.url, webhook.secret.as_deref(), &body).await
+                        },
+                        |ret| {
+                            if let Err(e) = ret {
+                                warn!(?e, "failed to post the webhook");
+                            }
+                            MainViewCommand::Sink
+                        },
+                    )
+                })
+                .collect::<Vec<_>>(),
+        )
+    }
+
+    /// reads the macro file if the device is connected, otherwise reports it as skipped.
+    fn start_scheduled_macro(&mut self, schedule: ScheduledMacro) -> Command<MainViewCommand> {
+        match &self.adb_devices_selected {
+            Some(data)
+                if self.adb_connectivity.is_connected()
+                    && schedule.serials.contains(&data.serial) => {}
+            _ => return self.skip_scheduled_macro(schedule, "not connected".into()),
+        }
+        if self.macro_run.is_some() {
+            return self.skip_scheduled_macro(schedule, "another macro is running".into());
+        }
+
+        let path = schedule.macro_path.clone();
+        Command::perform(tokio::fs::read_to_string(path), move |ret| {
+            MainViewCommand::ScheduledMacroRead(schedule, ret.map_err(|e| e.to_string()))
+        })
+    }
+
+    fn skip_scheduled_macro(
+        &mut self,
+        schedule: ScheduledMacro,
+        reason: String,
+    ) -> Command<MainViewCommand> {
+        info!(name = %schedule.name, %reason, "skip scheduled macro");
+
+        let now = chrono::Local::now();
+        let report = MacroReport {
+            name: schedule.name.clone(),
+            serial: schedule.serials.join(","),
+            started_at: now,
+            finished_at: now,
+            steps: 0,
+            failure: Some(format!("skipped: {}", reason)),
+            screenshot: None,
+        };
+        self.export_macro_report(schedule, report)
+    }
+
+    /// exports the report if the finished macro was started by the schedule.
+    fn finish_scheduled_macro(
+        &mut self,
+        failure: Option<String>,
+        screenshot: Option<PathBuf>,
+    ) -> Command<MainViewCommand> {
+        let run = match self.macro_schedule.take() {
+            Some(data) => data,
+            None => return Command::none(),
+        };
+
+        let report = MacroReport {
+            name: run.schedule.name.clone(),
+            serial: run.serial,
+            started_at: run.started_at,
+            finished_at: chrono::Local::now(),
+            steps: run.steps,
+            failure,
+            screenshot,
+        };
+        self.export_macro_report(run.schedule, report)
+    }
+
+    fn export_macro_report(
+        &self,
+        schedule: ScheduledMacro,
+        report: MacroReport,
+    ) -> Command<MainViewCommand> {
+        let history_tsv = self.history.to_tsv_since(report.started_at);
+        Command::perform(
+            export_macro_report(
+                report,
+                history_tsv,
+                schedule.report_dir,
+                schedule.webhook_url,
+            ),
+            |ret| {
+                MainViewCommand::MacroReportExported(match ret {
+                    Ok(data) => Some(data),
+                    Err(e) => {
+                        warn!(?e, "failed to export the macro report");
+                        None
+                    }
+                })
+            },
+        )
+    }
+
+    /// stops the macro and captures a screenshot for the report.
+    fn fail_macro(&mut self, reason: String) -> Command<MainViewCommand> {
+        self.macro_run = None;
+        if !self.adb_supports(AdbCapability::ExecOut) || !self.capabilities().supports_screenshot {
+            return self.update(MainViewCommand::MacroFailed(reason, None));
+        }
+
+        self.macro_status = Some(tr_args(
+            "main-macro-capturing",
+            &[("reason", reason.clone())],
+        ));
+
+        let path = std::env::temp_dir().join(format!(
+            "android-commander-{}.png",
+            chrono::Local::now().format("%Y%m%d-%H%M%S")
+        ));
+        Command::perform(
+            capture_screenshot(self.selected_serial(), path),
+            move |ret| {
+                let screenshot = match ret {
+                    Ok(data) => Some(data),
+                    Err(e) => {
+                        warn!(?e, "failed to capture the screenshot");
+                        None
+                    }
+                };
+                MainViewCommand::MacroFailed(reason, screenshot)
+            },
+        )
+    }
+
+    fn connect_steps_view(&self) -> Element<MainViewCommand> {
+        let mut view = Column::new().push(if self.connect_failed {
+            text(tr("main-status-failed"))
+        } else if self.adb_connectivity == AdbConnectivity::Reconnecting {
+            text(tr_args(
+                "main-status-reconnecting",
+                &[
+                    ("retry", self.reconnect_retry.to_string()),
+                    ("max", RECONNECT_MAX_RETRIES.to_string()),
+                ],
+            ))
+        } else {
+            text(tr("main-status-connecting"))
+        });
+        for step in ConnectStep::all() {
+            if step == ConnectStep::BuildServer && self.server_build_dir.is_none() {
+                continue;
+            }
+
+            // no server to push without adb.
+            if step < ConnectStep::Handshake && !self.is_adb_device_selected() {
+                continue;
+            }
+
+            let marker = match self.connect_step {
+                Some(current) if current == step && self.connect_failed => "[!]",
+                Some(current) if current == step => "[>]",
+                Some(current) if step < current => "[x]",
+                Some(_) | None => "[ ]",
+            };
+            view = view.push(text(format!("{} {}", marker, connect_step_label(step))));
+        }
+        view.into()
+    }
+
+    fn notification_command(&self, summary: String, body: String) -> Command<MainViewCommand> {
+        if self.window_focused || !self.prefs.notification {
+            return Command::none();
+        }
+
+        Command::perform(
+            async move {
+                if let Err(e) = show_notification(summary, body).await {
+                    warn!(?e, "failed to show notification");
+                }
+            },
+            |_| MainViewCommand::Sink,
+        )
+    }
+}
+
+fn status_indicator<'a>(style: StatusIndicatorStyle) -> Element<'a, MainViewCommand> {
+    button(Space::new(Length::Units(12), Length::Units(12)))
+        .padding(0)
+        .style(iced::theme::Button::Custom(Box::new(style)))
+        .into()
+}
+
+/// `reconnectable` shows the button to reconnect at once.
+fn server_crash_view(excerpt: &[String], reconnectable: bool) -> Element<MainViewCommand> {
+    let mut header = row![text(tr("main-crash")).width(Length::Fill)]
+        .align_items(iced::alignment::Alignment::Center);
+    if reconnectable {
+        header = header.push(
+            button(text(tr("main-reconnect")))
+                .style(iced::theme::Button::Secondary)
+                .on_press(MainViewCommand::OnServerCrashReconnectClicked),
+        );
+    }
+    let mut view = Column::new().push(header);
+    if excerpt.is_empty() {
+        view = view.push(text(tr("main-crash-no-output")).size(14));
+    }
+    for line in excerpt {
+        view = view.push(text(line).size(14));
+    }
+    container(scrollable(view).height(Length::Units(80)))
+        .padding(4)
+        .style(iced::theme::Container::Box)
         .into()
+}
+
+fn connect_step_label(step: ConnectStep) -> String {
+    tr(match step {
+        ConnectStep::BuildServer => "main-step-build-server",
+        ConnectStep::PushServer => "main-step-push-server",
+        ConnectStep::StartServer => "main-step-start-server",
+        ConnectStep::Handshake => "main-step-handshake",
+        ConnectStep::Ready => "main-step-ready",
+    })
+}
+
+/// keeps the aspect ratio of the screen within the window.
+fn pointer_pad_view<'a>(width: u32, height: u32) -> Element<'a, MainViewCommand> {
+    const MAX_WIDTH: u32 = 280;
+    const MAX_HEIGHT: u32 = 200;
+
+    let scale = f64::min(
+        MAX_WIDTH as f64 / width.max(1) as f64,
+        MAX_HEIGHT as f64 / height.max(1) as f64,
+    );
+    let pad = button(
+        container(text(tr("main-pointer-pad")).size(14))
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .center_x()
+            .center_y(),
+    )
+    .width(Length::Units((width as f64 * scale) as u16))
+    .height(Length::Units((height as f64 * scale) as u16))
+    .style(iced::theme::Button::Secondary)
+    // keeps on_press to draw the button as enabled, the pad takes the events instead.
+    .on_press(MainViewCommand::Sink);
+
+    PointerPad::new(pad, MainViewCommand::PointerGestured).into()
+}
+
+fn rows_view<'a>(
+    rows: Vec<Vec<ButtonSpec>>,
+    hold: bool,
+    press: bool,
+) -> Column<'a, MainViewCommand> {
+    let mut view = Column::new().spacing(4);
+    for specs in rows {
+        let mut row_view = Row::new().spacing(4);
+        for spec in specs {
+            row_view = row_view.push(button_spec_view(spec, hold, press));
+        }
+        view = view.push(row_view);
     }
+    view
+}
 
-    pub fn view_size() -> (u32, u32) {
-        (300, 480)
+/// `hold` sends the down and the up by [HoldArea] instead of the click, and `press` tells the
+/// press of the click to send the long-press while held.
+fn button_spec_view<'a>(spec: ButtonSpec, hold: bool, press: bool) -> Element<'a, MainViewCommand> {
+    let (label, key, style, width, enabled) = match spec {
+        ButtonSpec::Key {
+            label,
+            key,
+            style,
+            width,
+            enabled,
+        } => (label, key, style, width, enabled),
+        ButtonSpec::Space { width } => return Space::with_width(width.into()).into(),
+    };
+
+    let content: Element<'a, MainViewCommand> = if label.is_empty() {
+        Space::new(Length::Fill, Length::Fill).into()
+    } else {
+        container(text(label)).width(Length::Fill).center_x().into()
+    };
+
+    let style = match style {
+        ButtonStyle::Pending => iced::theme::Button::Primary,
+        ButtonStyle::Secondary => iced::theme::Button::Secondary,
+        ButtonStyle::ColorRed => {
+            iced::theme::Button::Custom(Box::new(ColorKeyButtonStyle::ColorKeyRed))
+        }
+        ButtonStyle::ColorGreen => {
+            iced::theme::Button::Custom(Box::new(ColorKeyButtonStyle::ColorKeyGreen))
+        }
+        ButtonStyle::ColorBlue => {
+            iced::theme::Button::Custom(Box::new(ColorKeyButtonStyle::ColorKeyBlue))
+        }
+        ButtonStyle::ColorYellow => {
+            iced::theme::Button::Custom(Box::new(ColorKeyButtonStyle::ColorKeyYellow))
+        }
+    };
+
+    let view = button(content)
+        .width(width.into())
+        .height(ButtonSpec::HEIGHT.into())
+        .style(style);
+
+    if enabled && hold {
+        // keeps on_press to draw the button as enabled.
+        HoldArea::new(
+            view.on_press(MainViewCommand::Sink),
+            MainViewCommand::ButtonHeld(key),
+            MainViewCommand::ButtonReleased,
+        )
+        .into()
+    } else if enabled && press {
+        HoldArea::new(
+            view.on_press(MainViewCommand::RequestSendEvent(key.clone())),
+            MainViewCommand::ButtonPressed(key),
+            MainViewCommand::ButtonReleased,
+        )
+        .into()
+    } else if enabled {
+        view.on_press(MainViewCommand::RequestSendEvent(key)).into()
+    } else {
+        // no on_press to disable the button.
+        view.into()
+    }
+}
+
+/// the name of the key in [crate::model::shortcut::Shortcut], which is the lowercase name of
+/// [KeyCode] except the digits.
+fn shortcut_key_name(key: KeyCode) -> String {
+    let name = format!("{:?}", key).to_lowercase();
+    match name.strip_prefix("key") {
+        Some(digit) => digit.into(),
+        None => name,
+    }
+}
+
+fn find_key_shortcut(
+    shortcuts: &[KeyShortcut],
+    key: KeyCode,
+    modifiers: Modifiers,
+) -> Option<SendEventKey> {
+    if modifiers.logo() {
+        return None;
     }
+
+    let name = shortcut_key_name(key);
+    shortcuts
+        .iter()
+        .find(|data| {
+            data.shortcut.matches(
+                &name,
+                modifiers.control(),
+                modifiers.alt(),
+                modifiers.shift(),
+            )
+        })
+        .map(|data| data.key.clone())
 }
 
-fn create_send_event_key(key: KeyCode) -> Option<SendEventKey> {
+/// leaves the combinations with ctrl, alt or logo to [Preferences::key_shortcuts].
+fn create_send_event_key(key: KeyCode, modifiers: Modifiers) -> Option<SendEventKey> {
+    if modifiers.control() || modifiers.alt() || modifiers.logo() {
+        return None;
+    }
+
     match key {
         KeyCode::Key1 => Some(SendEventKey::Num1),
         KeyCode::Key2 => Some(SendEventKey::Num2),
@@ -464,89 +3943,229 @@ fn create_send_event_key(key: KeyCode) -> Option<SendEventKey> {
         KeyCode::T => Some(SendEventKey::Home),
         KeyCode::Enter => Some(SendEventKey::DpadOk),
         KeyCode::Backspace => Some(SendEventKey::Back),
+        KeyCode::Slash => Some(SendEventKey::Assist),
+        KeyCode::Space => Some(SendEventKey::MediaPlayPause),
+        KeyCode::Comma => Some(SendEventKey::MediaRewind),
+        KeyCode::Period => Some(SendEventKey::MediaFastForward),
         _ => None,
     }
 }
 
-fn get_key<'a>(key_map: &'a KeyMap, key: &SendEventKey) -> &'a str {
-    match key {
-        SendEventKey::Back => &key_map.back,
-        SendEventKey::ColorRed => &key_map.color_red,
-        SendEventKey::ColorGreen => &key_map.color_green,
-        SendEventKey::ColorBlue => &key_map.color_blue,
-        SendEventKey::ColorYellow => &key_map.color_yellow,
-        SendEventKey::DpadUp => &key_map.dpad_up,
-        SendEventKey::DpadDown => &key_map.dpad_down,
-        SendEventKey::DpadLeft => &key_map.dpad_left,
-        SendEventKey::DpadRight => &key_map.dpad_right,
-        SendEventKey::DpadOk => &key_map.dpad_ok,
-        SendEventKey::Num0 => &key_map.num_0,
-        SendEventKey::Num1 => &key_map.num_1,
-        SendEventKey::Num2 => &key_map.num_2,
-        SendEventKey::Num3 => &key_map.num_3,
-        SendEventKey::Num4 => &key_map.num_4,
-        SendEventKey::Num5 => &key_map.num_5,
-        SendEventKey::Num6 => &key_map.num_6,
-        SendEventKey::Num7 => &key_map.num_7,
-        SendEventKey::Num8 => &key_map.num_8,
-        SendEventKey::Num9 => &key_map.num_9,
-        SendEventKey::Home => &key_map.home,
-    }
+/// fails if the keycode of the key is unknown, e.g. the custom key has a typo.
+fn create_pressed_key_command(
+    key_map: &KeyMap,
+    key: &SendEventKey,
+) -> Fallible<Vec<ServerCommand>> {
+    Ok(vec![ServerCommand::KeyDown(key_map.keycode(key).parse()?)])
+}
+
+fn create_release_key_command(
+    key_map: &KeyMap,
+    key: &SendEventKey,
+) -> Fallible<Vec<ServerCommand>> {
+    Ok(vec![ServerCommand::KeyUp(key_map.keycode(key).parse()?)])
+}
+
+fn create_click_key_command(key_map: &KeyMap, key: &SendEventKey) -> Fallible<Vec<ServerCommand>> {
+    Ok(ServerCommand::click(key_map.keycode(key).parse()?).to_vec())
 }
 
-fn create_pressed_key_command(key_map: &KeyMap, key: &SendEventKey) -> String {
-    format!("down {}", get_key(key_map, key))
+/// returns true if the commands only release the keys, which may take [RELEASE_QUEUE_RESERVE].
+fn is_release_only(commands: &[ServerCommand]) -> bool {
+    commands
+        .iter()
+        .all(|data| matches!(data, ServerCommand::KeyUp(_) | ServerCommand::Quit))
 }
 
-fn create_release_key_command(key_map: &KeyMap, key: &SendEventKey) -> String {
-    format!("up {}", get_key(key_map, key))
+/// the delay before the `retry`th reconnection, starting from 1.
+fn reconnect_delay(retry: u32) -> Duration {
+    RECONNECT_DELAY_INITIAL
+        .checked_mul(2u32.saturating_pow(retry.saturating_sub(1)))
+        .unwrap_or(RECONNECT_DELAY_MAX)
+        .min(RECONNECT_DELAY_MAX)
 }
 
-fn create_click_key_command(key_map: &KeyMap, key: &SendEventKey) -> String {
-    format!("down {code}\nup {code}", code = get_key(key_map, key))
+fn target_devices(prefs: &Preferences) -> Vec<Arc<AndroidDevice>> {
+    let devices = prefs.network_targets.iter().map(|data| data.to_device());
+    if cfg!(feature = "cec") {
+        devices
+            .chain(prefs.cec_targets.iter().map(|data| data.to_device()))
+            .map(Arc::new)
+            .collect()
+    } else {
+        devices.map(Arc::new).collect()
+    }
 }
 
 fn retrieve_devices_command() -> Command<MainViewCommand> {
-    Command::perform(
-        async {
-            match retrieve_devices().await {
-                Ok(data) => data.into_iter().map(Arc::new).collect(),
-                Err(e) => {
-                    warn!(?e, "failed to retrieve devices");
-                    vec![]
-                }
+    Command::perform(retrieve_devices(), |ret| {
+        MainViewCommand::InvokeDevicesResult(match ret {
+            Ok(data) => Ok(data.into_iter().map(Arc::new).collect()),
+            Err(e) => {
+                warn!(?e, "failed to retrieve devices");
+                Err(describe_adb_error(&e))
             }
-        },
-        MainViewCommand::InvokeDevicesResult,
-    )
+        })
+    })
 }
 
-async fn retrieve_devices() -> Fallible<Vec<AndroidDevice>> {
-    let child = std::process::Command::new("adb")
-        .arg("devices")
-        .stdout(std::process::Stdio::piped())
-        .spawn()
-        .context("failed to invoke adb command")?;
-
-    let mut reader = std::io::BufReader::new(child.stdout.context("adb stdout")?);
-    let mut buf = String::new();
-    let mut devices = vec![];
-    loop {
-        buf.clear();
-        let bytes = reader.read_line(&mut buf).context("failed to read line")?;
-        if bytes == 0 {
-            break;
-        }
-
-        let segments = buf.split('\t').collect::<Vec<_>>();
-        if segments.len() != 2 {
-            debug!(%buf, "skip line");
-            continue;
-        }
-        devices.push(AndroidDevice {
-            serial: segments[0].to_string(),
-        });
+fn show_error_command(message: String) -> Command<MainViewCommand> {
+    Command::perform(async {}, move |_| MainViewCommand::ShowError(message))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::keycode::keycode_value;
+    use crate::model::protocol::encode_frame;
+
+    const FUZZ_CHARS: &[char] = &[
+        'A', 'Z', 'a', 'z', '0', '9', '_', ' ', '\t', '\n', '\r', '"', '\'', '\\', '\0', '/', '-',
+        '=', '\u{3000}', 'あ', 'é', '\u{200b}',
+    ];
+
+    /// xorshift to make the fuzzing reproducible without extra dependencies.
+    struct Random(u64);
+
+    impl Random {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+
+        fn next_keycode(&mut self) -> String {
+            let len = self.next() % 16;
+            (0..len)
+                .map(|_| FUZZ_CHARS[(self.next() % FUZZ_CHARS.len() as u64) as usize])
+                .collect()
+        }
+    }
+
+    fn assert_wire_lines(command: &str, expected: &[&str], keycode: &str) {
+        let lines = command.split('\n').collect::<Vec<_>>();
+        assert_eq!(expected.len(), lines.len(), "command: {:?}", command);
+
+        for (line, action) in lines.iter().zip(expected) {
+            let segments = line.split(' ').collect::<Vec<_>>();
+            assert_eq!(2, segments.len(), "line: {:?}", line);
+            assert_eq!(*action, segments[0]);
+            assert_eq!(keycode, segments[1]);
+            assert!(!segments[1].contains(char::is_whitespace));
+        }
+    }
+
+    /// checks the frames that the server reads, which carry the numeric keycode.
+    fn assert_wire_frames(commands: &[ServerCommand], expected: &[u8], keycode: &str) {
+        assert_eq!(expected.len(), commands.len(), "commands: {:?}", commands);
+
+        let value = keycode_value(keycode)
+            .or_else(|| keycode.parse::<i32>().ok())
+            .unwrap();
+        for (command, id) in commands.iter().zip(expected) {
+            let frame = encode_frame(0, command);
+            assert_eq!(14, frame.len(), "frame: {:?}", frame);
+            assert_eq!([0, 0, 0, 10], frame[..4]);
+            assert_eq!(*id, frame[4]);
+            assert_eq!(value, i32::from_be_bytes(frame[5..9].try_into().unwrap()));
+            assert_eq!(0, frame[9]);
+        }
+    }
+
+    fn key_map_with_back(back: &str) -> KeyMap {
+        KeyMap {
+            back: back.into(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn create_command_default_key_map() {
+        let key_map = KeyMap::default();
+        assert_eq!(
+            "down KEYCODE_BACK",
+            encode_commands(&create_pressed_key_command(&key_map, &SendEventKey::Back).unwrap())
+        );
+        assert_eq!(
+            "up KEYCODE_BACK",
+            encode_commands(&create_release_key_command(&key_map, &SendEventKey::Back).unwrap())
+        );
+        assert_eq!(
+            "down KEYCODE_BACK\nup KEYCODE_BACK",
+            encode_commands(&create_click_key_command(&key_map, &SendEventKey::Back).unwrap())
+        );
+    }
+
+    #[test]
+    fn create_command_malformed_keycode() {
+        for keycode in [
+            "",
+            " ",
+            "KEYCODE_BACK ",
+            " KEYCODE_BACK",
+            "KEYCODE_BACK\nup KEYCODE_HOME",
+            "KEYCODE_BACK\r",
+            "\"KEYCODE_BACK\"",
+            "KEYCODE_BACK\u{3000}",
+            "KEYCODE_あ",
+        ] {
+            let key_map = key_map_with_back(keycode);
+            assert!(create_pressed_key_command(&key_map, &SendEventKey::Back).is_err());
+            assert!(create_release_key_command(&key_map, &SendEventKey::Back).is_err());
+            assert!(create_click_key_command(&key_map, &SendEventKey::Back).is_err());
+        }
+    }
+
+    #[test]
+    fn create_command_fuzz() {
+        let mut random = Random(0x2545_f491_4f6c_dd1d);
+        for _ in 0..10_000 {
+            let keycode = random.next_keycode();
+            let key_map = key_map_with_back(&keycode);
+
+            if let Ok(data) = create_pressed_key_command(&key_map, &SendEventKey::Back) {
+                assert_wire_lines(&encode_commands(&data), &["down"], &keycode);
+                assert_wire_frames(&data, &[1], &keycode);
+            }
+
+            if let Ok(data) = create_release_key_command(&key_map, &SendEventKey::Back) {
+                assert_wire_lines(&encode_commands(&data), &["up"], &keycode);
+                assert_wire_frames(&data, &[2], &keycode);
+            }
+
+            if let Ok(data) = create_click_key_command(&key_map, &SendEventKey::Back) {
+                assert_wire_lines(&encode_commands(&data), &["down", "up"], &keycode);
+                assert_wire_frames(&data, &[1, 2], &keycode);
+            }
+
+            let custom_key = SendEventKey::Custom(keycode.clone());
+            if let Ok(data) = create_click_key_command(&KeyMap::default(), &custom_key) {
+                assert_wire_lines(&encode_commands(&data), &["down", "up"], &keycode);
+                assert_wire_frames(&data, &[1, 2], &keycode);
+            }
+        }
+    }
+
+    #[test]
+    fn release_only_commands() {
+        let keycode = "KEYCODE_BACK".parse::<Keycode>().unwrap();
+        assert!(is_release_only(&[ServerCommand::KeyUp(keycode.clone())]));
+        assert!(is_release_only(&[
+            ServerCommand::KeyUp(keycode.clone()),
+            ServerCommand::Quit,
+        ]));
+        assert!(!is_release_only(&[ServerCommand::KeyDown(keycode.clone())]));
+        assert!(!is_release_only(&ServerCommand::click(keycode)));
+        assert!(!is_release_only(&[ServerCommand::text("a").unwrap()]));
     }
 
-    Ok(devices)
+    #[test]
+    fn reconnect_delay_backoff() {
+        assert_eq!(Duration::from_secs(1), reconnect_delay(1));
+        assert_eq!(Duration::from_secs(2), reconnect_delay(2));
+        assert_eq!(Duration::from_secs(16), reconnect_delay(5));
+        assert_eq!(RECONNECT_DELAY_MAX, reconnect_delay(6));
+        assert_eq!(RECONNECT_DELAY_MAX, reconnect_delay(u32::MAX));
+    }
 }
@@ -0,0 +1,171 @@
+/*
+ * Copyright 2022 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::data::i18n::tr;
+use crate::function::adb_functions::{put_accessibility_services, retrieve_accessibility_services};
+use crate::model::accessibility::{service_label, AccessibilityServices, TALKBACK_SERVICE};
+use iced::widget::{button, checkbox, column, row, text, Row, Space};
+use iced::{Command, Element, Length};
+use tracing::{info, warn};
+
+#[derive(Clone, Debug)]
+pub enum AccessibilityPanelCommand {
+    OnRestoreClicked,
+    OnServiceToggled(String, bool),
+    Restored(bool),
+    ServicesRetrieved(Option<AccessibilityServices>),
+    ServicesUpdated(Option<AccessibilityServices>),
+}
+
+/// toggles the accessibility services of the connected device, e.g. TalkBack.
+#[derive(Debug, Default)]
+pub struct AccessibilityPanel {
+    /// the value before the first toggle to restore.
+    original: Option<AccessibilityServices>,
+    /// `None` until retrieved.
+    services: Option<AccessibilityServices>,
+}
+
+impl AccessibilityPanel {
+    pub fn retrieve_command(serial: String) -> Command<AccessibilityPanelCommand> {
+        Command::perform(retrieve_accessibility_services(serial), |ret| {
+            AccessibilityPanelCommand::ServicesRetrieved(match ret {
+                Ok(data) => Some(data),
+                Err(e) => {
+                    warn!(?e, "failed to retrieve the accessibility services");
+                    None
+                }
+            })
+        })
+    }
+
+    pub fn is_retrieved(&self) -> bool {
+        self.services.is_some()
+    }
+
+    /// forgets the services of the disconnected device and returns the value to restore.
+    pub fn disconnect(&mut self) -> Option<AccessibilityServices> {
+        self.services = None;
+        self.original.take()
+    }
+
+    /// returns the value to restore, e.g. before the reboot.
+    pub fn take_original(&mut self) -> Option<AccessibilityServices> {
+        self.original.take()
+    }
+
+    pub fn update(
+        &mut self,
+        command: AccessibilityPanelCommand,
+        serial: String,
+    ) -> Command<AccessibilityPanelCommand> {
+        match command {
+            AccessibilityPanelCommand::OnRestoreClicked => {
+                let original = match &self.original {
+                    Some(data) => data.clone(),
+                    None => return Command::none(),
+                };
+                return Command::perform(put_accessibility_services(serial, original), |ret| {
+                    AccessibilityPanelCommand::Restored(match ret {
+                        Ok(_) => true,
+                        Err(e) => {
+                            warn!(?e, "failed to restore the accessibility services");
+                            false
+                        }
+                    })
+                });
+            }
+            AccessibilityPanelCommand::OnServiceToggled(service, enabled) => {
+                let current = match &self.services {
+                    Some(data) => data.clone(),
+                    None => return Command::none(),
+                };
+                let mut services = current.clone();
+                services.set_enabled(&service, enabled);
+                if self.original.is_none() {
+                    self.original = Some(current);
+                }
+
+                return Command::perform(
+                    put_accessibility_services(serial, services.clone()),
+                    move |ret| {
+                        AccessibilityPanelCommand::ServicesUpdated(match ret {
+                            Ok(_) => Some(services),
+                            Err(e) => {
+                                warn!(?e, "failed to update the accessibility services");
+                                None
+                            }
+                        })
+                    },
+                );
+            }
+            AccessibilityPanelCommand::Restored(restored) => {
+                if restored {
+                    self.services = self.original.take();
+                }
+            }
+            AccessibilityPanelCommand::ServicesRetrieved(data) => {
+                info!(?data, "accessibility services retrieved");
+                self.services = data;
+            }
+            AccessibilityPanelCommand::ServicesUpdated(data) => {
+                if let Some(data) = data {
+                    self.services = Some(data);
+                }
+            }
+        }
+        Command::none()
+    }
+
+    /// shows TalkBack and the services of [crate::model::Preferences::accessibility_services].
+    pub fn view(&self, extra_services: &[String]) -> Element<AccessibilityPanelCommand> {
+        let services = match &self.services {
+            Some(data) => data,
+            None => return Space::with_height(0.into()).into(),
+        };
+
+        let mut toggles = Row::new().spacing(8);
+        for service in
+            std::iter::once(TALKBACK_SERVICE).chain(extra_services.iter().map(String::as_str))
+        {
+            let owned = service.to_string();
+            toggles = toggles.push(checkbox(
+                service_label(service),
+                services.contains(service),
+                move |enabled| AccessibilityPanelCommand::OnServiceToggled(owned.clone(), enabled),
+            ));
+        }
+
+        let restore_button =
+            button(text(tr("main-accessibility-restore"))).style(iced::theme::Button::Secondary);
+        column![
+            row![
+                text(tr("main-accessibility")),
+                Space::with_width(Length::Fill),
+                if self.original.is_some() {
+                    restore_button.on_press(AccessibilityPanelCommand::OnRestoreClicked)
+                } else {
+                    restore_button
+                },
+            ]
+            .spacing(4)
+            .align_items(iced::alignment::Alignment::Center),
+            toggles,
+        ]
+        .spacing(4)
+        .into()
+    }
+}
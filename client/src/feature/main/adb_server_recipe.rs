@@ -15,39 +15,224 @@
  */
 
 use crate::data::asset::Asset;
+use crate::data::command_target::CommandTarget;
+use crate::data::session_dir::SessionDir;
+use crate::feature::main::command_target_recipe::forward_commands;
+use crate::function::adb_functions::AdbInputTarget;
+use crate::function::command_functions::{
+    adb_command, describe_adb_error, register_child, run_command, ChildRegistration,
+    DEFAULT_TIMEOUT,
+};
+use crate::function::server_functions::build_server;
+use crate::model::adb_error::AdbError;
+use crate::model::protocol::{encode_frame, ServerCommand, ServerReply};
+use crate::model::target::DeviceCapabilities;
 use crate::model::AndroidDevice;
+use crate::prelude::*;
 use iced::subscription::{unfold, Subscription};
-use std::io::prelude::*;
-use std::sync::Arc;
-use tempfile::tempdir;
-use tokio::fs::{create_dir_all, File};
-use tokio::io::{AsyncWriteExt, BufWriter};
-use tokio::sync::watch::Receiver;
+use sha2::{Digest, Sha256};
+use std::collections::VecDeque;
+use std::fmt::{Display, Formatter};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::fs::File;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader, BufWriter, Lines};
+use tokio::process::{Child, ChildStderr, ChildStdout};
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver};
+use tokio::sync::{mpsc, oneshot};
+use tokio::time::{Interval, MissedTickBehavior};
 use tracing::{debug, info, warn};
 
+const SERVER_DEVICE_PATH: &str = "/data/local/tmp/android-commander-server";
+pub(super) const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(5);
+/// the server is regarded as hung if it doesn't answer the pings in a row.
+const HEARTBEAT_MAX_MISSES: u32 = 3;
+/// the commands that the main view can send ahead of the server.
+pub const COMMAND_QUEUE_CAPACITY: usize = 64;
+/// waits for the server to exit after the quit.
+pub const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// the queue of the commands that keeps every down and up in order, e.g. a click is queued as
+/// the down and the up at once.
+///
+/// shared by the subscriptions across the reconnections, and locked by the connected one.
+pub type CommandReceiver = Arc<tokio::sync::Mutex<mpsc::Receiver<Vec<ServerCommand>>>>;
+
 #[derive(Clone, Debug)]
 pub enum AdbServerRecipeEvent {
-    Connected,
+    /// the server has injected the command.
+    Acked(ServerCommand),
+    /// with the capabilities that the connected device supports.
+    Connected(DeviceCapabilities),
+    /// the server exited while connected, with the last lines of its stderr.
+    Crashed(Vec<String>),
     Disconnected,
-    Error,
+    /// with the message to show.
+    Error(String),
+    Progress(ConnectStep),
+    /// the server is running but stopped answering the heartbeat.
+    Unresponsive,
+}
+
+#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
+pub enum ConnectStep {
+    BuildServer,
+    PushServer,
+    StartServer,
+    Handshake,
+    Ready,
+}
+
+impl ConnectStep {
+    pub fn all() -> [ConnectStep; 5] {
+        [
+            ConnectStep::BuildServer,
+            ConnectStep::PushServer,
+            ConnectStep::StartServer,
+            ConnectStep::Handshake,
+            ConnectStep::Ready,
+        ]
+    }
+}
+
+impl Display for ConnectStep {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConnectStep::BuildServer => write!(f, "build server"),
+            ConnectStep::PushServer => write!(f, "push server"),
+            ConnectStep::StartServer => write!(f, "start server"),
+            ConnectStep::Handshake => write!(f, "handshake"),
+            ConnectStep::Ready => write!(f, "ready"),
+        }
+    }
 }
 
 enum StreamState {
-    Init(Receiver<String>, Arc<AndroidDevice>),
-    Ready(Receiver<String>, std::process::Child),
+    Init(
+        CommandReceiver,
+        Arc<AndroidDevice>,
+        Arc<SessionDir>,
+        Option<PathBuf>,
+    ),
+    BuildServer(
+        CommandReceiver,
+        Arc<AndroidDevice>,
+        Arc<SessionDir>,
+        PathBuf,
+    ),
+    PushServer(CommandReceiver, Arc<AndroidDevice>, Arc<SessionDir>),
+    StartServer(CommandReceiver, Arc<AndroidDevice>),
+    Handshake(
+        CommandReceiver,
+        Arc<AndroidDevice>,
+        Child,
+        ChildRegistration,
+    ),
+    Ready(
+        CommandReceiver,
+        Child,
+        ChildRegistration,
+        ServerErrors,
+        ServerOutput,
+    ),
+    /// sends the keys by `input` since the server can't run on the device.
+    Fallback(CommandReceiver, Box<dyn CommandTarget>),
     Disconnecting,
     Finish,
 }
 
 struct AdbServerRecipeType;
 
+/// the last lines of the server stderr, and the receiver notified when the stderr is closed.
+struct ServerErrors {
+    lines: Arc<Mutex<VecDeque<String>>>,
+    closed: oneshot::Receiver<()>,
+}
+
+impl ServerErrors {
+    const CAPACITY: usize = 10;
+
+    fn excerpt(&self) -> Vec<String> {
+        match self.lines.lock() {
+            Ok(data) => data.iter().cloned().collect(),
+            Err(_) => vec![],
+        }
+    }
+}
+
+/// the replies of the server, and the heartbeat that is kept across the acks.
+struct ServerOutput {
+    replies: UnboundedReceiver<ServerReply>,
+    /// false after the stdout is closed.
+    open: bool,
+    heartbeat: Interval,
+    misses: u32,
+    next_sequence: u32,
+    /// the commands waiting for the ack in the order of the sequence number.
+    in_flight: VecDeque<(u32, ServerCommand)>,
+}
+
+impl ServerOutput {
+    fn new(replies: UnboundedReceiver<ServerReply>) -> Self {
+        let mut heartbeat = tokio::time::interval(HEARTBEAT_INTERVAL);
+        heartbeat.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        Self {
+            replies,
+            open: true,
+            heartbeat,
+            misses: 0,
+            next_sequence: 0,
+            in_flight: VecDeque::new(),
+        }
+    }
+
+    fn next_sequence(&mut self) -> u32 {
+        let sequence = self.next_sequence;
+        self.next_sequence = self.next_sequence.wrapping_add(1);
+        sequence
+    }
+
+    /// encodes the commands into the frames, and remembers the commands to ack.
+    fn encode(&mut self, commands: Vec<ServerCommand>) -> Vec<u8> {
+        let mut buf = vec![];
+        for command in commands {
+            let sequence = self.next_sequence();
+            buf.extend(encode_frame(sequence, &command));
+            if command.keycode().is_some() || matches!(command, ServerCommand::Text(_)) {
+                self.in_flight.push_back((sequence, command));
+            }
+        }
+        buf
+    }
+
+    /// forgets the commands before the acked one since the server handles them in order, e.g.
+    /// the text that the keyboard can't type isn't acked.
+    fn take_acked(&mut self, sequence: u32) -> Option<ServerCommand> {
+        while let Some((data, command)) = self.in_flight.pop_front() {
+            if data == sequence {
+                return Some(command);
+            }
+        }
+        None
+    }
+}
+
+/// builds the server before connecting if `server_build_dir` is specified.
+///
+/// a new `attempt` restarts the subscription even if the previous one has finished.
 pub fn adb_server(
     device: Arc<AndroidDevice>,
-    rx: Receiver<String>,
+    rx: CommandReceiver,
+    session_dir: Arc<SessionDir>,
+    server_build_dir: Option<PathBuf>,
+    attempt: u32,
 ) -> Subscription<AdbServerRecipeEvent> {
     unfold(
-        std::any::TypeId::of::<AdbServerRecipeType>(),
-        StreamState::Init(rx, device),
+        (std::any::TypeId::of::<AdbServerRecipeType>(), attempt),
+        StreamState::Init(rx, device, session_dir, server_build_dir),
         execute,
     )
 }
@@ -55,128 +240,222 @@ pub fn adb_server(
 async fn execute(state: StreamState) -> (Option<AdbServerRecipeEvent>, StreamState) {
     use AdbServerRecipeEvent as YieldValue;
     match state {
-        StreamState::Init(rx, device) => {
-            let server_path = match tempdir() {
-                Ok(data) => data.path().join("android-commander-server"),
-                Err(e) => {
-                    warn!(?e, "failed to prepare temporary directory");
-                    return (Some(YieldValue::Error), StreamState::Finish);
-                }
-            };
-
-            info!(?server_path);
-
-            match create_dir_all(&server_path.parent().unwrap()).await {
-                Ok(_) => (),
+        StreamState::Init(rx, device, session_dir, Some(server_build_dir)) => (
+            Some(YieldValue::Progress(ConnectStep::BuildServer)),
+            StreamState::BuildServer(rx, device, session_dir, server_build_dir),
+        ),
+        StreamState::Init(rx, device, session_dir, None) => (
+            Some(YieldValue::Progress(ConnectStep::PushServer)),
+            StreamState::PushServer(rx, device, session_dir),
+        ),
+        StreamState::BuildServer(rx, device, session_dir, server_build_dir) => {
+            match build_server(server_build_dir).await {
+                Ok(_) => (
+                    Some(YieldValue::Progress(ConnectStep::PushServer)),
+                    StreamState::PushServer(rx, device, session_dir),
+                ),
                 Err(e) => {
-                    warn!(?e, "failed to create temporary directory");
-                    return (Some(YieldValue::Error), StreamState::Finish);
+                    warn!(?e, "failed to build server");
+                    let message = format!("failed to build the server: {:#}", e);
+                    (Some(YieldValue::Error(message)), StreamState::Finish)
                 }
             }
-
-            let server_file = match File::create(&server_path).await {
+        }
+        StreamState::PushServer(rx, device, session_dir) => {
+            let server_path = match prepare_server_file(&session_dir).await {
                 Ok(data) => data,
                 Err(e) => {
-                    warn!(?e, "failed to create temporary file");
-                    return (Some(YieldValue::Error), StreamState::Finish);
+                    warn!(?e, "failed to prepare server file");
+                    let message = format!("failed to prepare the server: {:#}", e);
+                    return (Some(YieldValue::Error(message)), StreamState::Finish);
                 }
             };
 
-            let server_bin = match Asset::get("android-commander-server") {
-                Some(data) => data as rust_embed::EmbeddedFile,
-                None => {
-                    warn!("failed to get asset");
-                    return (Some(YieldValue::Error), StreamState::Finish);
-                }
-            };
+            info!(?server_path);
 
-            let mut buf = BufWriter::new(server_file);
-            match buf.write_all(&server_bin.data).await {
-                Ok(_) => (),
+            match push_server(&device.serial, &server_path).await {
+                Ok(_) => (
+                    Some(YieldValue::Progress(ConnectStep::StartServer)),
+                    StreamState::StartServer(rx, device),
+                ),
                 Err(e) => {
-                    warn!(?e, "failed to write server data");
-                    return (Some(YieldValue::Error), StreamState::Finish);
+                    warn!(?e, "failed to push server file");
+                    (
+                        Some(YieldValue::Error(describe_adb_error(&e))),
+                        StreamState::Finish,
+                    )
                 }
             }
-
-            match buf.flush().await {
-                Ok(_) => (),
-                Err(e) => {
-                    warn!(?e, "failed to flush server data");
-                    return (Some(YieldValue::Error), StreamState::Finish);
+        }
+        StreamState::StartServer(rx, device) => {
+            // kills the server when the subscription is dropped.
+            match spawn_server(&device.serial) {
+                Ok(data) => {
+                    let registration = register_child(data.id());
+                    (
+                        Some(YieldValue::Progress(ConnectStep::Handshake)),
+                        StreamState::Handshake(rx, device, data, registration),
+                    )
                 }
-            }
-
-            match std::process::Command::new("adb")
-                .args([
-                    "-s",
-                    &device.serial,
-                    "push",
-                    server_path.to_str().unwrap(),
-                    "/data/local/tmp/android-commander-server",
-                ])
-                .spawn()
-            {
-                Ok(_) => (),
                 Err(e) => {
-                    warn!(?e, "failed to push server file");
-                    return (Some(YieldValue::Error), StreamState::Finish);
+                    warn!(?e);
+                    (
+                        Some(YieldValue::Error(describe_adb_error(&e))),
+                        StreamState::Finish,
+                    )
                 }
             }
+        }
+        StreamState::Handshake(rx, device, mut child, registration) => {
+            let stdout = match child.stdout.take() {
+                Some(data) => data,
+                None => {
+                    warn!("stdout not found");
+                    child.kill().await.ok();
+                    let message = "failed to read the server output".into();
+                    return (Some(YieldValue::Error(message)), StreamState::Finish);
+                }
+            };
 
-            match std::process::Command::new("adb")
-                .args([
-                    "-s",
-                    &device.serial,
-                    "shell",
-                    "CLASSPATH=/data/local/tmp/android-commander-server app_process / jp.tinyport.androidcommander.server.MainKt"
-                ])
-                .stdin(std::process::Stdio::piped())
-                .spawn()
-            {
-                Ok(mut data) => match &data.stdin {
-                    Some(_) => (Some(YieldValue::Connected), StreamState::Ready(rx, data)),
-                    None => {
-                        warn!("stdin not found");
-                        data.kill().ok();
-                        data.wait().ok();
-                        (Some(YieldValue::Error), StreamState::Finish)
+            let mut lines = BufReader::new(stdout).lines();
+            match tokio::time::timeout(HANDSHAKE_TIMEOUT, wait_line(&mut lines, "Hello")).await {
+                Ok(true) => {
+                    let output = ServerOutput::new(watch_server_output(lines));
+                    let errors = match child.stderr.take() {
+                        Some(data) => collect_server_errors(data),
+                        None => {
+                            warn!("stderr not found");
+                            child.kill().await.ok();
+                            let message = "failed to read the server output".into();
+                            return (Some(YieldValue::Error(message)), StreamState::Finish);
+                        }
+                    };
+                    discard_commands(&rx);
+                    (
+                        Some(YieldValue::Connected(DeviceCapabilities::ADB)),
+                        StreamState::Ready(rx, child, registration, errors, output),
+                    )
+                }
+                Ok(false) => {
+                    // the fallback doesn't help the device that adb can't reach.
+                    if let Some(e) = read_adb_error(&mut child).await {
+                        warn!(%e, "server exited before handshake");
+                        child.kill().await.ok();
+                        let message = format!("{}\nhint: {}", e, e.hint());
+                        return (Some(YieldValue::Error(message)), StreamState::Finish);
                     }
-                },
-                Err(e) => {
-                    warn!(?e);
-                    (Some(YieldValue::Error), StreamState::Finish)
+
+                    warn!("server exited before handshake, fall back to input");
+                    child.kill().await.ok();
+                    fall_back(rx, &device)
+                }
+                Err(_) => {
+                    warn!("handshake timed out, fall back to input");
+                    child.kill().await.ok();
+                    fall_back(rx, &device)
                 }
             }
         }
-        StreamState::Ready(mut rx, mut child) => {
+        StreamState::Ready(rx, mut child, registration, mut errors, mut output) => {
+            let mut queue = rx.clone().lock_owned().await;
             loop {
-                if rx.changed().await.is_err() {
-                    break;
-                }
+                let data = tokio::select! {
+                    ret = queue.recv() => match ret {
+                        Some(data) => data,
+                        None => break,
+                    },
+                    _ = &mut errors.closed => {
+                        let mut excerpt = errors.excerpt();
+                        // the stderr is closed just before the exit.
+                        match tokio::time::timeout(Duration::from_secs(1), child.wait()).await {
+                            Ok(Ok(status)) => {
+                                warn!(%status, "server exited unexpectedly");
+                                excerpt.push(format!("({})", status));
+                            }
+                            Ok(Err(e)) => warn!(?e, "server exited unexpectedly"),
+                            Err(_) => warn!("server closed the stderr unexpectedly"),
+                        }
+                        child.kill().await.ok();
+                        return (Some(YieldValue::Crashed(excerpt)), StreamState::Finish);
+                    }
+                    ret = output.replies.recv(), if output.open => {
+                        match ret {
+                            Some(ServerReply::Ack(sequence)) => {
+                                output.misses = 0;
+                                let command = match output.take_acked(sequence) {
+                                    Some(data) => data,
+                                    None => {
+                                        debug!(sequence, "unknown ack");
+                                        continue;
+                                    }
+                                };
+                                return (
+                                    Some(YieldValue::Acked(command)),
+                                    StreamState::Ready(rx, child, registration, errors, output),
+                                );
+                            }
+                            Some(ServerReply::Pong) => output.misses = 0,
+                            // the exit is detected by the stderr.
+                            None => output.open = false,
+                        }
+                        continue;
+                    }
+                    _ = output.heartbeat.tick() => {
+                        if HEARTBEAT_MAX_MISSES <= output.misses {
+                            warn!(misses = output.misses, "server stopped answering");
+                            child.kill().await.ok();
+                            return (Some(YieldValue::Unresponsive), StreamState::Finish);
+                        }
 
-                let data = rx.borrow();
-                debug!(?data, "send data");
+                        output.misses += 1;
+                        let ping = encode_frame(output.next_sequence(), &ServerCommand::Ping);
+                        if let Err(e) = write_data(&mut child, &ping).await {
+                            warn!(?e, "failed to send the heartbeat");
+                            child.kill().await.ok();
+                            let message = format!("failed to send the heartbeat: {:#}", e);
+                            return (
+                                Some(YieldValue::Error(message)),
+                                StreamState::Disconnecting,
+                            );
+                        }
+                        continue;
+                    }
+                };
+                let (batch, quit) = take_batch(data, &mut queue);
+                debug!(?batch, "send data");
 
-                // for ignore init value.
-                if data.is_empty() {
-                    continue;
+                let frames = output.encode(batch);
+                if !frames.is_empty() {
+                    if let Err(e) = write_data(&mut child, &frames).await {
+                        warn!(?e);
+                        child.kill().await.ok();
+                        let message = format!("failed to send the command: {:#}", e);
+                        return (Some(YieldValue::Error(message)), StreamState::Disconnecting);
+                    }
                 }
 
-                let ret = writeln!(child.stdin.as_mut().unwrap(), "{}", data.as_str());
-                if let Err(e) = ret {
-                    warn!(?e);
-                    child.kill().ok();
-                    child.wait().ok();
-                    return (Some(YieldValue::Error), StreamState::Disconnecting);
+                // shuts down the server gracefully, e.g. the window is closing.
+                if quit {
+                    break;
                 }
             }
 
             debug!("channel closed");
-            child.kill().ok();
-            child.wait().ok();
+            let quit = encode_frame(output.next_sequence(), &ServerCommand::Quit);
+            write_data(&mut child, &quit).await.ok();
+            // app_process may remain on the device if the shell is killed before the quit.
+            match tokio::time::timeout(SHUTDOWN_TIMEOUT, child.wait()).await {
+                Ok(Ok(status)) => info!(%status, "server exited"),
+                Ok(Err(e)) => warn!(?e, "failed to wait for the server"),
+                Err(_) => warn!("server didn't exit in time"),
+            }
+            child.kill().await.ok();
             (Some(YieldValue::Disconnected), StreamState::Finish)
         }
+        StreamState::Fallback(rx, target) => match forward_commands(rx, target).await {
+            (event, Some((rx, target))) => (Some(event), StreamState::Fallback(rx, target)),
+            (event, None) => (Some(event), StreamState::Finish),
+        },
         StreamState::Disconnecting => (Some(YieldValue::Disconnected), StreamState::Finish),
         StreamState::Finish => {
             debug!("finish");
@@ -184,3 +463,346 @@ async fn execute(state: StreamState) -> (Option<AdbServerRecipeEvent>, StreamSta
         }
     }
 }
+
+/// connects without the server, e.g. the device restricts `app_process`.
+fn fall_back(
+    rx: CommandReceiver,
+    device: &AndroidDevice,
+) -> (Option<AdbServerRecipeEvent>, StreamState) {
+    let target = AdbInputTarget::new(device.serial.clone());
+    discard_commands(&rx);
+    (
+        Some(AdbServerRecipeEvent::Connected(target.capabilities())),
+        StreamState::Fallback(rx, Box::new(target)),
+    )
+}
+
+/// discards the commands queued while disconnected, e.g. the keys of the lost connection.
+pub(super) fn discard_commands(rx: &CommandReceiver) {
+    // the previous subscription is still being dropped.
+    if let Ok(mut queue) = rx.try_lock() {
+        while queue.try_recv().is_ok() {}
+    }
+}
+
+/// writes the server binary into the session directory and reuses it across connects.
+pub(super) async fn prepare_server_file(session_dir: &SessionDir) -> Fallible<PathBuf> {
+    let server_bin = Asset::get("android-commander-server").context("failed to get asset")?;
+    write_server_file(session_dir, &server_bin.data).await
+}
+
+/// rewrites the cached file if the asset differs, e.g. [build_server] has refreshed it.
+async fn write_server_file(session_dir: &SessionDir, data: &[u8]) -> Fallible<PathBuf> {
+    let server_path = session_dir.join("android-commander-server");
+    if let Ok(cached) = tokio::fs::read(&server_path).await {
+        if cached == data {
+            return Ok(server_path);
+        }
+        info!("the server asset was changed");
+    }
+
+    // write into the other file so that a broken file isn't reused.
+    let temp_path = session_dir.join("android-commander-server.tmp");
+    let mut buf = BufWriter::new(
+        File::create(&temp_path)
+            .await
+            .context("failed to create temporary file")?,
+    );
+    buf.write_all(data)
+        .await
+        .context("failed to write server data")?;
+    buf.flush().await.context("failed to flush server data")?;
+
+    tokio::fs::rename(&temp_path, &server_path)
+        .await
+        .context("failed to rename server file")?;
+
+    Ok(server_path)
+}
+
+/// compares the hashes since pushing the server takes seconds on the slow TVs.
+async fn is_server_pushed(serial: &str, server_path: &Path) -> bool {
+    let data = match tokio::fs::read(server_path).await {
+        Ok(data) => data,
+        Err(e) => {
+            warn!(?e, "failed to read server file");
+            return false;
+        }
+    };
+    let local = Sha256::digest(&data)
+        .iter()
+        .map(|data| format!("{:02x}", data))
+        .collect::<String>();
+
+    let mut command = adb_command();
+    command.args(["-s", serial, "shell", "sha256sum", SERVER_DEVICE_PATH]);
+    match run_command(command, DEFAULT_TIMEOUT).await {
+        Ok(data) => parse_sha256sum(&data.stdout_lossy()) == Some(local),
+        Err(e) => {
+            // the server hasn't been pushed yet, or the device has no sha256sum.
+            debug!(?e, "failed to hash the pushed server");
+            false
+        }
+    }
+}
+
+/// returns the hash of the `sha256sum` output like `<hash>  <path>`.
+fn parse_sha256sum(output: &str) -> Option<String> {
+    let hash = output.split_whitespace().next()?;
+    if hash.len() == 64 && hash.chars().all(|data| data.is_ascii_hexdigit()) {
+        Some(hash.to_ascii_lowercase())
+    } else {
+        None
+    }
+}
+
+/// pushes the server unless the device already has the same one.
+pub(super) async fn push_server(serial: &str, server_path: &Path) -> Fallible<()> {
+    if is_server_pushed(serial, server_path).await {
+        info!("skip pushing the server");
+        return Ok(());
+    }
+
+    let mut command = adb_command();
+    command.args([
+        "-s",
+        serial,
+        "push",
+        server_path.to_str().context("invalid server path")?,
+        SERVER_DEVICE_PATH,
+    ]);
+    run_command(command, Duration::from_secs(60))
+        .await
+        .context("failed to push the server")?;
+
+    Ok(())
+}
+
+/// starts the pushed server that is killed when the child is dropped.
+pub(super) fn spawn_server(serial: &str) -> Fallible<Child> {
+    let child = adb_command()
+        .args([
+            "-s",
+            serial,
+            "shell",
+            "CLASSPATH=/data/local/tmp/android-commander-server app_process / jp.tinyport.androidcommander.server.MainKt"
+        ])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("failed to start the server")?;
+
+    if child.stdin.is_none() {
+        // the child is killed on drop.
+        anyhow::bail!("stdin not found");
+    }
+
+    Ok(child)
+}
+
+/// takes the commands queued behind `first` so that a burst of keys is sent in one write, which
+/// saves the round trips of the slow adb over TCP.
+///
+/// returns true with the commands before the quit if the queue has the quit.
+fn take_batch(
+    first: Vec<ServerCommand>,
+    queue: &mut mpsc::Receiver<Vec<ServerCommand>>,
+) -> (Vec<ServerCommand>, bool) {
+    let mut batch = vec![];
+    let mut next = Some(first);
+    while let Some(data) = next {
+        for command in data {
+            if command == ServerCommand::Quit {
+                return (batch, true);
+            }
+            batch.push(command);
+        }
+        next = queue.try_recv().ok();
+    }
+    (batch, false)
+}
+
+/// returns true when the server prints the `expected` line, e.g. "Hello" to be ready to receive
+/// the commands.
+pub(super) async fn wait_line(lines: &mut Lines<BufReader<ChildStdout>>, expected: &str) -> bool {
+    loop {
+        match lines.next_line().await {
+            Ok(Some(line)) if line.trim_end() == expected => return true,
+            Ok(Some(line)) => debug!(%line, "skip line"),
+            Ok(None) => return false,
+            Err(e) => {
+                warn!(?e, "failed to read server output");
+                return false;
+            }
+        }
+    }
+}
+
+/// writes the frames of [encode_frame] to the server.
+pub(super) async fn write_data(child: &mut Child, data: &[u8]) -> Fallible<()> {
+    let stdin = child.stdin.as_mut().context("stdin not found")?;
+    stdin.write_all(data).await?;
+    stdin.flush().await?;
+    Ok(())
+}
+
+/// classifies the stderr of the exited server, e.g. the device is unauthorized.
+async fn read_adb_error(child: &mut Child) -> Option<AdbError> {
+    let mut stderr = child.stderr.take()?;
+    let mut buf = String::new();
+    // the stderr is closed soon since the server has exited.
+    match tokio::time::timeout(Duration::from_secs(1), stderr.read_to_string(&mut buf)).await {
+        Ok(Ok(_)) => AdbError::classify(&buf),
+        Ok(Err(e)) => {
+            warn!(?e, "failed to read the server stderr");
+            None
+        }
+        Err(_) => None,
+    }
+}
+
+/// keeps the last lines of the server stderr to show why the server exited.
+fn collect_server_errors(stderr: ChildStderr) -> ServerErrors {
+    let lines = Arc::new(Mutex::new(VecDeque::with_capacity(ServerErrors::CAPACITY)));
+    let (tx, closed) = oneshot::channel();
+
+    let task_lines = lines.clone();
+    tokio::spawn(async move {
+        let mut reader = BufReader::new(stderr).lines();
+        while let Ok(Some(line)) = reader.next_line().await {
+            debug!(%line, "server error output");
+
+            if let Ok(mut data) = task_lines.lock() {
+                if data.len() == ServerErrors::CAPACITY {
+                    data.pop_front();
+                }
+                data.push_back(line);
+            }
+        }
+        tx.send(()).ok();
+    });
+
+    ServerErrors { lines, closed }
+}
+
+/// reads the stdout of the server and notifies the acks and the answers of the heartbeat.
+fn watch_server_output(mut lines: Lines<BufReader<ChildStdout>>) -> UnboundedReceiver<ServerReply> {
+    let (tx, rx) = unbounded_channel();
+    tokio::spawn(async move {
+        while let Ok(Some(line)) = lines.next_line().await {
+            match line.trim_end().parse::<ServerReply>() {
+                Ok(data) => {
+                    tx.send(data).ok();
+                }
+                Err(_) => debug!(%line, "server output"),
+            }
+        }
+    });
+    rx
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_sha256sum_output() {
+        let hash = "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855";
+        assert_eq!(
+            Some(hash.to_string()),
+            parse_sha256sum(&format!("{}  {}\n", hash, SERVER_DEVICE_PATH))
+        );
+        assert_eq!(
+            None,
+            parse_sha256sum("sha256sum: /data/local/tmp/android-commander-server: No such file")
+        );
+        assert_eq!(None, parse_sha256sum(""));
+    }
+
+    fn commands(lines: &[&str]) -> Vec<ServerCommand> {
+        lines.iter().map(|data| data.parse().unwrap()).collect()
+    }
+
+    #[test]
+    fn take_batch_in_order() {
+        let (tx, mut rx) = mpsc::channel(COMMAND_QUEUE_CAPACITY);
+        for data in [
+            commands(&["up KEYCODE_DPAD_UP"]),
+            commands(&["down KEYCODE_DPAD_DOWN", "up KEYCODE_DPAD_DOWN"]),
+        ] {
+            tx.try_send(data).unwrap();
+        }
+
+        let (batch, quit) = take_batch(commands(&["down KEYCODE_DPAD_UP"]), &mut rx);
+        assert_eq!(
+            commands(&[
+                "down KEYCODE_DPAD_UP",
+                "up KEYCODE_DPAD_UP",
+                "down KEYCODE_DPAD_DOWN",
+                "up KEYCODE_DPAD_DOWN",
+            ]),
+            batch
+        );
+        assert!(!quit);
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn take_batch_quit() {
+        let (tx, mut rx) = mpsc::channel(COMMAND_QUEUE_CAPACITY);
+        for data in [
+            commands(&["up KEYCODE_BACK"]),
+            commands(&["quit"]),
+            commands(&["down KEYCODE_HOME"]),
+        ] {
+            tx.try_send(data).unwrap();
+        }
+
+        assert_eq!(
+            (commands(&["down KEYCODE_BACK", "up KEYCODE_BACK"]), true),
+            take_batch(commands(&["down KEYCODE_BACK"]), &mut rx)
+        );
+        assert_eq!((vec![], true), take_batch(commands(&["quit"]), &mut rx));
+    }
+
+    #[tokio::test]
+    async fn server_output_encode() {
+        let (_tx, replies) = unbounded_channel();
+        let mut output = ServerOutput::new(replies);
+        let batch = commands(&["down KEYCODE_BACK", "ping", "text hi", "up KEYCODE_BACK"]);
+
+        let expected = batch
+            .iter()
+            .enumerate()
+            .flat_map(|(index, data)| encode_frame(index as u32, data))
+            .collect::<Vec<_>>();
+        assert_eq!(expected, output.encode(batch));
+
+        // the ping is answered by the pong instead of the ack.
+        assert_eq!(
+            vec![
+                (0, "down KEYCODE_BACK".parse().unwrap()),
+                (2, "text hi".parse().unwrap()),
+                (3, "up KEYCODE_BACK".parse().unwrap()),
+            ],
+            output.in_flight.iter().cloned().collect::<Vec<_>>()
+        );
+        assert_eq!(Some("text hi".parse().unwrap()), output.take_acked(2));
+        assert_eq!(1, output.in_flight.len());
+    }
+
+    #[tokio::test]
+    async fn write_server_file_refresh() {
+        let session_dir = SessionDir::new().unwrap();
+
+        let path = write_server_file(&session_dir, b"old").await.unwrap();
+        assert_eq!(b"old".to_vec(), std::fs::read(&path).unwrap());
+
+        let path = write_server_file(&session_dir, b"old").await.unwrap();
+        assert_eq!(b"old".to_vec(), std::fs::read(&path).unwrap());
+
+        let path = write_server_file(&session_dir, b"new").await.unwrap();
+        assert_eq!(b"new".to_vec(), std::fs::read(&path).unwrap());
+    }
+}
@@ -0,0 +1,638 @@
+/*
+ * Copyright 2022 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::model::api::{
+    bearer_token, command_ws_message, is_local_host, is_local_origin, offers_ws_protocol,
+    protocol_token, query_param, redact_query, ApiResponse, ApiRoute, ApiScope, ApiState,
+    ApiTokens, WS_PROTOCOL,
+};
+use crate::model::websocket::{
+    accept_key, apply_mask, encode_frame, WsFrameHead, WsOpcode, MAX_PAYLOAD_LEN,
+};
+use crate::prelude::*;
+use iced::subscription::{unfold, Subscription};
+use std::fmt::{Debug, Formatter};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{
+    AsyncBufRead, AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader,
+    ReadHalf,
+};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{broadcast, mpsc, oneshot, watch};
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+/// the time to read the request and to wait for the response of the view.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+/// the limit of the request line and each header.
+const MAX_LINE_LEN: u64 = 8 * 1024;
+/// the routes need a few headers only.
+const MAX_HEADERS: usize = 32;
+
+type RequestSender = mpsc::Sender<(ApiRoute, oneshot::Sender<ApiResponse>)>;
+
+#[derive(Clone, Debug)]
+pub enum ApiServerRecipeEvent {
+    Failed(String),
+    /// the number of the WebSocket clients of [ApiScope::Observe].
+    Observers(usize),
+    Request(ApiRoute, ApiResponder),
+}
+
+/// sends the response of the request back to the connection once.
+#[derive(Clone)]
+pub struct ApiResponder(Arc<Mutex<Option<oneshot::Sender<ApiResponse>>>>);
+
+impl ApiResponder {
+    pub fn respond(&self, response: ApiResponse) {
+        let tx = match self.0.lock() {
+            Ok(mut data) => data.take(),
+            Err(e) => {
+                warn!(?e, "failed to lock the responder");
+                return;
+            }
+        };
+        if let Some(tx) = tx {
+            if tx.send(response).is_err() {
+                debug!("the connection was closed");
+            }
+        }
+    }
+}
+
+impl Debug for ApiResponder {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("ApiResponder")
+    }
+}
+
+/// aborts the task when the subscription or the connection is dropped.
+struct TaskGuard(JoinHandle<()>);
+
+impl Drop for TaskGuard {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+/// counts the observer while it is connected.
+struct ObserverGuard(Arc<watch::Sender<usize>>);
+
+impl ObserverGuard {
+    fn new(observers: Arc<watch::Sender<usize>>) -> Self {
+        observers.send_modify(|data| *data += 1);
+        Self(observers)
+    }
+}
+
+impl Drop for ObserverGuard {
+    fn drop(&mut self) {
+        self.0.send_modify(|data| *data -= 1);
+    }
+}
+
+/// what the connections share.
+#[derive(Clone)]
+struct ApiContext {
+    port: u16,
+    tx: RequestSender,
+    tokens: ApiTokens,
+    state: watch::Receiver<ApiState>,
+    commands: broadcast::Sender<String>,
+    observers: Arc<watch::Sender<usize>>,
+}
+
+enum StreamState {
+    Bind(
+        u16,
+        ApiTokens,
+        watch::Receiver<ApiState>,
+        broadcast::Sender<String>,
+    ),
+    Serving(
+        mpsc::Receiver<(ApiRoute, oneshot::Sender<ApiResponse>)>,
+        watch::Receiver<usize>,
+        TaskGuard,
+    ),
+    Finished,
+}
+
+struct ApiServerRecipeType;
+
+/// serves the local REST API and the WebSocket on `127.0.0.1:port`.
+///
+/// the WebSocket clients receive the changes of `state` and the `commands` sent to the device.
+/// the WebSocket and the routes that send keys need one of `tokens`.
+pub fn api_server(
+    port: u16,
+    tokens: ApiTokens,
+    state: watch::Receiver<ApiState>,
+    commands: broadcast::Sender<String>,
+) -> Subscription<ApiServerRecipeEvent> {
+    unfold(
+        (std::any::TypeId::of::<ApiServerRecipeType>(), port),
+        StreamState::Bind(port, tokens, state, commands),
+        execute,
+    )
+}
+
+async fn execute(state: StreamState) -> (Option<ApiServerRecipeEvent>, StreamState) {
+    match state {
+        StreamState::Bind(port, tokens, state, commands) => {
+            let listener = match TcpListener::bind(("127.0.0.1", port)).await {
+                Ok(data) => data,
+                Err(e) => {
+                    warn!(?e, port, "failed to bind the api server");
+                    return (
+                        Some(ApiServerRecipeEvent::Failed(format!(
+                            "failed to listen on {}: {}",
+                            port, e
+                        ))),
+                        StreamState::Finished,
+                    );
+                }
+            };
+
+            info!(port, "api server listening");
+            let (tx, rx) = mpsc::channel(16);
+            let (observers_tx, observers_rx) = watch::channel(0);
+            let context = ApiContext {
+                port,
+                tx,
+                tokens,
+                state,
+                commands,
+                observers: Arc::new(observers_tx),
+            };
+            let task = TaskGuard(tokio::spawn(accept(listener, context)));
+            // resets the count of the previous port.
+            (
+                Some(ApiServerRecipeEvent::Observers(0)),
+                StreamState::Serving(rx, observers_rx, task),
+            )
+        }
+        StreamState::Serving(mut rx, mut observers, task) => tokio::select! {
+            data = rx.recv() => match data {
+                Some((route, tx)) => (
+                    Some(ApiServerRecipeEvent::Request(
+                        route,
+                        ApiResponder(Arc::new(Mutex::new(Some(tx)))),
+                    )),
+                    StreamState::Serving(rx, observers, task),
+                ),
+                None => (None, StreamState::Finished),
+            },
+            Ok(_) = observers.changed() => {
+                let count = *observers.borrow_and_update();
+                (
+                    Some(ApiServerRecipeEvent::Observers(count)),
+                    StreamState::Serving(rx, observers, task),
+                )
+            }
+        },
+        StreamState::Finished => iced::futures::future::pending().await,
+    }
+}
+
+async fn accept(listener: TcpListener, context: ApiContext) {
+    loop {
+        let stream = match listener.accept().await {
+            Ok((stream, _)) => stream,
+            Err(e) => {
+                warn!(?e, "failed to accept the api connection");
+                continue;
+            }
+        };
+
+        let context = context.clone();
+        tokio::spawn(async move {
+            if let Err(e) = handle_connection(stream, context).await {
+                debug!(?e, "api connection failed");
+            }
+        });
+    }
+}
+
+async fn handle_connection(stream: TcpStream, context: ApiContext) -> Fallible<()> {
+    let mut stream = BufReader::new(stream);
+    let request = match tokio::time::timeout(REQUEST_TIMEOUT, read_request(&mut stream)).await {
+        Ok(ret) => match ret? {
+            Ok(data) => data,
+            Err(response) => return write_response(&mut stream, response).await,
+        },
+        Err(_) => {
+            let response = ApiResponse::error(400, "request timed out");
+            return write_response(&mut stream, response).await;
+        }
+    };
+
+    debug!(method = %request.method, path = %redact_query(&request.path), "api request");
+
+    // the browsers send the host of the other site that resolves to 127.0.0.1 by DNS rebinding.
+    if !matches!(request.header("host"), Some(data) if is_local_host(&data, context.port)) {
+        let response = ApiResponse::error(403, "forbidden host");
+        return write_response(&mut stream, response).await;
+    }
+
+    if request.method == "GET" && request.path.split('?').next() == Some("/ws") {
+        // the web pages in the browser can connect to 127.0.0.1 without the preflight.
+        if matches!(request.header("origin"), Some(data) if !is_local_origin(&data)) {
+            let response = ApiResponse::error(403, "forbidden origin");
+            return write_response(&mut stream, response).await;
+        }
+        // the browsers can't set the header to the WebSocket, so the token is offered as the
+        // subprotocol. the query is for the clients that can't set the subprotocol either.
+        let protocols = request.header("sec-websocket-protocol");
+        let scope = match request
+            .bearer_token()
+            .or_else(|| {
+                protocols
+                    .as_deref()
+                    .and_then(protocol_token)
+                    .map(str::to_string)
+            })
+            .or_else(|| query_param(&request.path, "token"))
+            .and_then(|data| context.tokens.scope(&data))
+        {
+            Some(data) => data,
+            None => {
+                let response = ApiResponse::error(401, "unauthorized");
+                return write_response(&mut stream, response).await;
+            }
+        };
+        return match request.header("sec-websocket-key") {
+            Some(key) if request.is_upgrade() => {
+                let protocol = matches!(protocols, Some(data) if offers_ws_protocol(&data));
+                serve_websocket(stream, &key, protocol, scope, context).await
+            }
+            _ => {
+                let response = ApiResponse::error(400, "websocket upgrade required");
+                write_response(&mut stream, response).await
+            }
+        };
+    }
+
+    // the custom header also makes the browsers send the preflight, which this server rejects.
+    let scope = request
+        .bearer_token()
+        .and_then(|data| context.tokens.scope(&data));
+    let response = match ApiRoute::parse(&request.method, &request.path) {
+        Ok(route) if route.is_mutating() && scope == Some(ApiScope::Observe) => {
+            ApiResponse::error(403, "read-only observer")
+        }
+        Ok(route) if route.is_mutating() && scope.is_none() => {
+            ApiResponse::error(401, "unauthorized")
+        }
+        Ok(route) => send_request(&context.tx, route).await?,
+        Err(response) => response,
+    };
+    write_response(&mut stream, response).await
+}
+
+/// passes the request to the view and waits for the response.
+async fn send_request(tx: &RequestSender, route: ApiRoute) -> Fallible<ApiResponse> {
+    let (response_tx, response_rx) = oneshot::channel();
+    tx.send((route, response_tx))
+        .await
+        .context("the api server was stopped")?;
+    let ret = match tokio::time::timeout(REQUEST_TIMEOUT, response_rx).await {
+        Ok(Ok(data)) => data,
+        Ok(Err(_)) | Err(_) => ApiResponse::error(503, "no response"),
+    };
+    Ok(ret)
+}
+
+async fn write_response<S>(stream: &mut S, response: ApiResponse) -> Fallible<()>
+where
+    S: AsyncWrite + Unpin,
+{
+    stream
+        .write_all(response.to_http().as_bytes())
+        .await
+        .context("failed to write the response")?;
+    stream.shutdown().await.ok();
+    Ok(())
+}
+
+struct HttpRequest {
+    method: String,
+    path: String,
+    /// the names are lowercase.
+    headers: Vec<(String, String)>,
+}
+
+impl HttpRequest {
+    fn header(&self, name: &str) -> Option<String> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.clone())
+    }
+
+    fn bearer_token(&self) -> Option<String> {
+        self.header("authorization")
+            .as_deref()
+            .and_then(bearer_token)
+            .map(str::to_string)
+    }
+
+    fn is_upgrade(&self) -> bool {
+        matches!(self.header("upgrade"), Some(data) if data.eq_ignore_ascii_case("websocket"))
+    }
+}
+
+/// reads the request line and the headers since the endpoints have no body, or returns the error
+/// response if the request exceeds the limits.
+///
+/// the server parses HTTP/1.1 by itself rather than using a framework like axum, since it listens
+/// on the loopback only and serves a few routes without the body, which isn't worth adding hyper
+/// and tower to the GUI. the limits keep a client from buffering the memory instead.
+async fn read_request<R>(stream: &mut R) -> Fallible<Result<HttpRequest, ApiResponse>>
+where
+    R: AsyncBufRead + Unpin,
+{
+    let request_line = match read_line(stream).await? {
+        Some(data) => data,
+        None => return Ok(Err(ApiResponse::error(400, "request line too long"))),
+    };
+    let mut segments = request_line.split_whitespace();
+    let (method, path) = match (segments.next(), segments.next(), segments.next()) {
+        (Some(method), Some(path), Some(version)) if version.starts_with("HTTP/1.") => {
+            (method.to_string(), path.to_string())
+        }
+        _ => return Ok(Err(ApiResponse::error(400, "malformed request line"))),
+    };
+
+    let mut headers = vec![];
+    loop {
+        let header = match read_line(stream).await? {
+            Some(data) => data,
+            None => return Ok(Err(ApiResponse::error(431, "header too large"))),
+        };
+        if header.trim().is_empty() {
+            break;
+        }
+        if headers.len() == MAX_HEADERS {
+            return Ok(Err(ApiResponse::error(431, "too many headers")));
+        }
+        match header.split_once(':') {
+            Some((key, value)) => {
+                headers.push((key.trim().to_ascii_lowercase(), value.trim().to_string()))
+            }
+            None => return Ok(Err(ApiResponse::error(400, "malformed header"))),
+        }
+    }
+
+    Ok(Ok(HttpRequest {
+        method,
+        path,
+        headers,
+    }))
+}
+
+/// reads the line up to [MAX_LINE_LEN], or returns `None` if the line is longer.
+async fn read_line<R>(stream: &mut R) -> Fallible<Option<String>>
+where
+    R: AsyncBufRead + Unpin,
+{
+    let mut line = String::new();
+    let len = (&mut *stream)
+        .take(MAX_LINE_LEN)
+        .read_line(&mut line)
+        .await
+        .context("failed to read the request")?;
+    if len as u64 == MAX_LINE_LEN && !line.ends_with('\n') {
+        return Ok(None);
+    }
+    Ok(Some(line))
+}
+
+/// sends the key events from the text messages and pushes the state changes and the commands.
+///
+/// the observers can't send the key events. `protocol` answers [WS_PROTOCOL] that the client
+/// offered.
+async fn serve_websocket(
+    stream: BufReader<TcpStream>,
+    key: &str,
+    protocol: bool,
+    scope: ApiScope,
+    context: ApiContext,
+) -> Fallible<()> {
+    let ApiContext {
+        tx,
+        mut state,
+        commands,
+        observers,
+        ..
+    } = context;
+    let _observer = match scope {
+        ApiScope::Control => None,
+        ApiScope::Observe => Some(ObserverGuard::new(observers)),
+    };
+    let mut commands = commands.subscribe();
+
+    let (reader, mut writer) = tokio::io::split(stream);
+    let protocol = if protocol {
+        format!("Sec-WebSocket-Protocol: {}\r\n", WS_PROTOCOL)
+    } else {
+        String::new()
+    };
+    writer
+        .write_all(
+            format!(
+                "HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\nConnection: Upgrade\r\nSec-WebSocket-Accept: {}\r\n{}\r\n",
+                accept_key(key),
+                protocol,
+            )
+            .as_bytes(),
+        )
+        .await
+        .context("failed to write the handshake")?;
+
+    // reads on the other task since read_frame isn't cancel safe.
+    let (frame_tx, mut frame_rx) = mpsc::channel(16);
+    let _reader = TaskGuard(tokio::spawn(read_frames(reader, frame_tx)));
+
+    let message = state.borrow_and_update().to_ws_message();
+    write_frame(&mut writer, WsOpcode::Text, message.as_bytes()).await?;
+
+    loop {
+        tokio::select! {
+            frame = frame_rx.recv() => match frame {
+                Some((WsOpcode::Text, _)) if scope == ApiScope::Observe => {
+                    let response = ApiResponse::error(403, "read-only observer");
+                    write_frame(&mut writer, WsOpcode::Text, response.to_ws_message().as_bytes())
+                        .await?;
+                }
+                Some((WsOpcode::Text, payload)) => {
+                    let response = match std::str::from_utf8(&payload)
+                        .map_err(|_| ApiResponse::error(400, "invalid message"))
+                        .and_then(ApiRoute::from_ws_message)
+                    {
+                        Ok(route) => send_request(&tx, route).await?,
+                        Err(response) => response,
+                    };
+                    write_frame(&mut writer, WsOpcode::Text, response.to_ws_message().as_bytes())
+                        .await?;
+                }
+                Some((WsOpcode::Ping, payload)) => {
+                    write_frame(&mut writer, WsOpcode::Pong, &payload).await?;
+                }
+                Some((WsOpcode::Binary | WsOpcode::Pong, _)) => {
+                    // do nothing.
+                }
+                Some((WsOpcode::Close, _)) | None => {
+                    write_frame(&mut writer, WsOpcode::Close, &[]).await.ok();
+                    break;
+                }
+            },
+            ret = state.changed() => {
+                if ret.is_err() {
+                    write_frame(&mut writer, WsOpcode::Close, &[]).await.ok();
+                    break;
+                }
+                let message = state.borrow_and_update().to_ws_message();
+                write_frame(&mut writer, WsOpcode::Text, message.as_bytes()).await?;
+            }
+            ret = commands.recv() => match ret {
+                Ok(data) => {
+                    let message = command_ws_message(&data);
+                    write_frame(&mut writer, WsOpcode::Text, message.as_bytes()).await?;
+                }
+                Err(broadcast::error::RecvError::Lagged(len)) => {
+                    debug!(len, "skip the lagged commands");
+                }
+                Err(broadcast::error::RecvError::Closed) => {
+                    write_frame(&mut writer, WsOpcode::Close, &[]).await.ok();
+                    break;
+                }
+            },
+        }
+    }
+
+    Ok(())
+}
+
+async fn read_frames(
+    mut reader: ReadHalf<BufReader<TcpStream>>,
+    tx: mpsc::Sender<(WsOpcode, Vec<u8>)>,
+) {
+    loop {
+        let frame = match read_frame(&mut reader).await {
+            Ok(data) => data,
+            Err(e) => {
+                debug!(?e, "websocket closed");
+                return;
+            }
+        };
+        let closed = frame.0 == WsOpcode::Close;
+        if tx.send(frame).await.is_err() || closed {
+            return;
+        }
+    }
+}
+
+async fn read_frame<R>(reader: &mut R) -> Fallible<(WsOpcode, Vec<u8>)>
+where
+    R: AsyncRead + Unpin,
+{
+    let mut head = [0; 2];
+    reader.read_exact(&mut head).await?;
+    let head = WsFrameHead::parse(head)?;
+    if !head.masked {
+        anyhow::bail!("the client frame isn't masked");
+    }
+
+    let len = match head.len {
+        126 => reader.read_u16().await? as u64,
+        127 => reader.read_u64().await?,
+        len => len as u64,
+    };
+    if MAX_PAYLOAD_LEN < len {
+        anyhow::bail!("the frame is too large: {}", len);
+    }
+
+    let mut mask = [0; 4];
+    reader.read_exact(&mut mask).await?;
+    let mut payload = vec![0; len as usize];
+    reader.read_exact(&mut payload).await?;
+    apply_mask(&mut payload, mask);
+    Ok((head.opcode, payload))
+}
+
+async fn write_frame<W>(writer: &mut W, opcode: WsOpcode, payload: &[u8]) -> Fallible<()>
+where
+    W: AsyncWrite + Unpin,
+{
+    writer
+        .write_all(&encode_frame(opcode, payload))
+        .await
+        .context("failed to write the frame")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn read(data: &[u8]) -> Result<HttpRequest, ApiResponse> {
+        let mut data = data;
+        read_request(&mut data).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn read_request_headers() {
+        let request =
+            read(b"GET /devices HTTP/1.1\r\nHost: 127.0.0.1:8080\r\nX-Token:  abc \r\n\r\n")
+                .await
+                .unwrap();
+        assert_eq!("GET", request.method);
+        assert_eq!("/devices", request.path);
+        assert_eq!(Some("127.0.0.1:8080".into()), request.header("host"));
+        assert_eq!(Some("abc".into()), request.header("x-token"));
+    }
+
+    #[tokio::test]
+    async fn read_request_limits() {
+        let status = |ret: Result<HttpRequest, ApiResponse>| ret.err().unwrap().status;
+
+        let path = "a".repeat(MAX_LINE_LEN as usize);
+        let request = format!("GET /{} HTTP/1.1\r\n\r\n", path);
+        assert_eq!(400, status(read(request.as_bytes()).await));
+
+        let request = format!("GET / HTTP/1.1\r\nX-Long: {}\r\n\r\n", path);
+        assert_eq!(431, status(read(request.as_bytes()).await));
+
+        let request = format!(
+            "GET / HTTP/1.1\r\n{}\r\n",
+            "X-Header: a\r\n".repeat(MAX_HEADERS + 1)
+        );
+        assert_eq!(431, status(read(request.as_bytes()).await));
+
+        let request = format!(
+            "GET / HTTP/1.1\r\n{}\r\n",
+            "X-Header: a\r\n".repeat(MAX_HEADERS)
+        );
+        assert!(read(request.as_bytes()).await.is_ok());
+
+        assert_eq!(400, status(read(b"GET /\r\n\r\n").await));
+        assert_eq!(
+            400,
+            status(read(b"GET / HTTP/1.1\r\nX-Header\r\n\r\n").await)
+        );
+        assert_eq!(400, status(read(b"").await));
+    }
+}
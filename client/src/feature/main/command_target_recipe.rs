@@ -0,0 +1,131 @@
+/*
+ * Copyright 2022 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::data::command_target::{connect_target, CommandTarget};
+use crate::feature::main::adb_server_recipe::{
+    discard_commands, AdbServerRecipeEvent, CommandReceiver, ConnectStep,
+};
+use crate::model::protocol::ServerCommand;
+use crate::model::target::DeviceKind;
+use iced::subscription::{unfold, Subscription};
+use std::path::PathBuf;
+use tracing::{debug, info, warn};
+
+enum StreamState {
+    Init(CommandReceiver, DeviceKind, PathBuf),
+    Connect(CommandReceiver, DeviceKind, PathBuf),
+    Ready(CommandReceiver, Box<dyn CommandTarget>),
+    Finish,
+}
+
+struct CommandTargetRecipeType;
+
+/// sends the lines of the `rx` to the device driven without adb, as the same events as the adb
+/// server.
+///
+/// a new `attempt` restarts the subscription even if the previous one has finished.
+pub fn command_target(
+    kind: DeviceKind,
+    rx: CommandReceiver,
+    config_dir: PathBuf,
+    attempt: u32,
+) -> Subscription<AdbServerRecipeEvent> {
+    unfold(
+        (std::any::TypeId::of::<CommandTargetRecipeType>(), attempt),
+        StreamState::Init(rx, kind, config_dir),
+        execute,
+    )
+}
+
+async fn execute(state: StreamState) -> (Option<AdbServerRecipeEvent>, StreamState) {
+    use AdbServerRecipeEvent as YieldValue;
+    match state {
+        StreamState::Init(rx, kind, config_dir) => (
+            Some(YieldValue::Progress(ConnectStep::Handshake)),
+            StreamState::Connect(rx, kind, config_dir),
+        ),
+        StreamState::Connect(rx, kind, config_dir) => {
+            match connect_target(&kind, &config_dir).await {
+                Ok(target) => {
+                    discard_commands(&rx);
+                    (
+                        Some(YieldValue::Connected(target.capabilities())),
+                        StreamState::Ready(rx, target),
+                    )
+                }
+                Err(e) => {
+                    warn!(?e, ?kind, "failed to connect");
+                    let message = format!("failed to connect: {:#}", e);
+                    (Some(YieldValue::Error(message)), StreamState::Finish)
+                }
+            }
+        }
+        StreamState::Ready(rx, target) => match forward_commands(rx, target).await {
+            (event, Some((rx, target))) => (Some(event), StreamState::Ready(rx, target)),
+            (event, None) => (Some(event), StreamState::Finish),
+        },
+        StreamState::Finish => {
+            debug!("finish");
+            iced::futures::future::pending().await
+        }
+    }
+}
+
+/// sends the commands of the `rx` to the target until the channel is closed.
+///
+/// returns the target with the event to keep sending if only the command failed.
+pub(super) async fn forward_commands(
+    rx: CommandReceiver,
+    mut target: Box<dyn CommandTarget>,
+) -> (
+    AdbServerRecipeEvent,
+    Option<(CommandReceiver, Box<dyn CommandTarget>)>,
+) {
+    use AdbServerRecipeEvent as YieldValue;
+    let mut queue = rx.clone().lock_owned().await;
+    loop {
+        let data = tokio::select! {
+            ret = queue.recv() => match ret {
+                Some(data) => data,
+                None => break,
+            },
+            reason = target.closed() => {
+                // e.g. the TV is turned off or unpaired.
+                warn!(%reason, "connection lost");
+                return (YieldValue::Crashed(vec![reason]), None);
+            }
+        };
+        debug!(?data, "send data");
+
+        for command in data {
+            if command == ServerCommand::Quit {
+                info!("quit");
+                target.close().await;
+                return (YieldValue::Disconnected, None);
+            }
+            if let Err(e) = target.send(&command).await {
+                // the lost connection is detected by closed().
+                warn!(?e, "failed to send the command");
+                let message = format!("failed to send the command: {:#}", e);
+                return (YieldValue::Error(message), Some((rx, target)));
+            }
+        }
+    }
+
+    info!("channel closed");
+    target.close().await;
+    (YieldValue::Disconnected, None)
+}
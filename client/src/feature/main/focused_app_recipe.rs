@@ -0,0 +1,108 @@
+/*
+ * Copyright 2022 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::function::command_functions::{adb_command, run_command, DEFAULT_TIMEOUT};
+use crate::model::AndroidDevice;
+use iced::subscription::{unfold, Subscription};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, warn};
+
+#[derive(Clone, Debug)]
+pub enum FocusedAppRecipeEvent {
+    Changed(Option<String>),
+}
+
+struct FocusedAppRecipeType;
+
+pub fn focused_app(device: Arc<AndroidDevice>) -> Subscription<FocusedAppRecipeEvent> {
+    unfold(
+        std::any::TypeId::of::<FocusedAppRecipeType>(),
+        (device, None),
+        execute,
+    )
+}
+
+async fn execute(
+    (device, last_package): (Arc<AndroidDevice>, Option<String>),
+) -> (
+    Option<FocusedAppRecipeEvent>,
+    (Arc<AndroidDevice>, Option<String>),
+) {
+    loop {
+        tokio::time::sleep(Duration::from_secs(2)).await;
+
+        let mut command = adb_command();
+        command.args([
+            "-s",
+            &device.serial,
+            "shell",
+            "dumpsys window | grep mCurrentFocus",
+        ]);
+        let output = run_command(command, DEFAULT_TIMEOUT).await;
+
+        let output = match output {
+            Ok(data) => data,
+            Err(e) => {
+                warn!(?e, "failed to retrieve the focused window");
+                continue;
+            }
+        };
+
+        let package = parse_focused_package(&output.stdout_lossy());
+        if package != last_package {
+            debug!(?package, "focused package changed");
+            return (
+                Some(FocusedAppRecipeEvent::Changed(package.clone())),
+                (device, package),
+            );
+        }
+    }
+}
+
+/// parses `mCurrentFocus=Window{1a2b3c u0 com.example/com.example.MainActivity}`.
+fn parse_focused_package(dumpsys: &str) -> Option<String> {
+    let line = dumpsys
+        .lines()
+        .find(|data| data.trim_start().starts_with("mCurrentFocus="))?;
+    let window = line.split_whitespace().last()?.trim_end_matches('}');
+    let (package, _) = window.split_once('/')?;
+    Some(package.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_focused_package_activity() {
+        let dumpsys = "  mCurrentFocus=Window{5f1c2d0 u0 com.google.android.tvlauncher/com.google.android.tvlauncher.MainActivity}\n";
+        assert_eq!(
+            Some("com.google.android.tvlauncher".to_string()),
+            parse_focused_package(dumpsys)
+        );
+    }
+
+    #[test]
+    fn parse_focused_package_without_activity() {
+        assert_eq!(
+            None,
+            parse_focused_package("  mCurrentFocus=Window{5f1c2d0 u0 StatusBar}\n")
+        );
+        assert_eq!(None, parse_focused_package("  mCurrentFocus=null\n"));
+        assert_eq!(None, parse_focused_package(""));
+    }
+}
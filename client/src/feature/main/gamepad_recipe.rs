@@ -0,0 +1,209 @@
+/*
+ * Copyright 2022 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::model::send_event_key::SendEventKey;
+use iced::subscription::{unfold, Subscription};
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+use tracing::debug;
+
+#[derive(Clone, Debug)]
+pub enum GamepadRecipeEvent {
+    Pressed(SendEventKey),
+    Released(SendEventKey),
+}
+
+enum StreamState {
+    Init,
+    Ready(UnboundedReceiver<GamepadRecipeEvent>),
+    Finish,
+}
+
+struct GamepadRecipeType;
+
+/// the stick tilts further than this to press the direction.
+#[cfg_attr(not(feature = "gamepad"), allow(dead_code))]
+const STICK_THRESHOLD: f32 = 0.5;
+
+/// maps the D-pad, the action buttons and the left stick of the controllers to the keys.
+pub fn gamepad() -> Subscription<GamepadRecipeEvent> {
+    unfold(
+        std::any::TypeId::of::<GamepadRecipeType>(),
+        StreamState::Init,
+        execute,
+    )
+}
+
+async fn execute(state: StreamState) -> (Option<GamepadRecipeEvent>, StreamState) {
+    match state {
+        StreamState::Init => {
+            let (tx, rx) = unbounded_channel();
+            spawn_gamepad_thread(tx);
+            (None, StreamState::Ready(rx))
+        }
+        StreamState::Ready(mut rx) => match rx.recv().await {
+            Some(data) => (Some(data), StreamState::Ready(rx)),
+            None => {
+                debug!("gamepad closed");
+                (None, StreamState::Finish)
+            }
+        },
+        StreamState::Finish => iced::futures::future::pending().await,
+    }
+}
+
+/// gilrs polls on the own thread since it isn't `Send` on every platform.
+#[cfg(feature = "gamepad")]
+fn spawn_gamepad_thread(tx: UnboundedSender<GamepadRecipeEvent>) {
+    use gilrs::{Axis, Button, EventType, Gilrs};
+    use std::time::Duration;
+    use tracing::warn;
+
+    std::thread::spawn(move || {
+        let mut gilrs = match Gilrs::new() {
+            Ok(data) => data,
+            Err(e) => {
+                warn!(?e, "failed to initialize the gamepad");
+                return;
+            }
+        };
+
+        let mut stick_x = StickAxis::new(SendEventKey::DpadLeft, SendEventKey::DpadRight);
+        let mut stick_y = StickAxis::new(SendEventKey::DpadDown, SendEventKey::DpadUp);
+        while !tx.is_closed() {
+            while let Some(event) = gilrs.next_event() {
+                let events = match event.event {
+                    EventType::ButtonPressed(button, _) => button_key(button)
+                        .map(GamepadRecipeEvent::Pressed)
+                        .into_iter()
+                        .collect(),
+                    EventType::ButtonReleased(button, _) => button_key(button)
+                        .map(GamepadRecipeEvent::Released)
+                        .into_iter()
+                        .collect(),
+                    EventType::AxisChanged(Axis::LeftStickX, value, _) => stick_x.update(value),
+                    EventType::AxisChanged(Axis::LeftStickY, value, _) => stick_y.update(value),
+                    _ => vec![],
+                };
+                for data in events {
+                    if tx.send(data).is_err() {
+                        return;
+                    }
+                }
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+    });
+
+    fn button_key(button: Button) -> Option<SendEventKey> {
+        match button {
+            Button::DPadUp => Some(SendEventKey::DpadUp),
+            Button::DPadDown => Some(SendEventKey::DpadDown),
+            Button::DPadLeft => Some(SendEventKey::DpadLeft),
+            Button::DPadRight => Some(SendEventKey::DpadRight),
+            Button::South => Some(SendEventKey::DpadOk),
+            Button::East => Some(SendEventKey::Back),
+            Button::Mode => Some(SendEventKey::Home),
+            Button::Start => Some(SendEventKey::Custom("KEYCODE_MENU".into())),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(not(feature = "gamepad"))]
+fn spawn_gamepad_thread(_tx: UnboundedSender<GamepadRecipeEvent>) {
+    debug!("gamepad feature disabled");
+}
+
+/// converts the tilt of the stick into the press and the release of the direction.
+#[cfg_attr(not(feature = "gamepad"), allow(dead_code))]
+struct StickAxis {
+    negative: SendEventKey,
+    positive: SendEventKey,
+    pressed: Option<SendEventKey>,
+}
+
+#[cfg_attr(not(feature = "gamepad"), allow(dead_code))]
+impl StickAxis {
+    fn new(negative: SendEventKey, positive: SendEventKey) -> Self {
+        Self {
+            negative,
+            positive,
+            pressed: None,
+        }
+    }
+
+    /// `value` is in `[-1.0, 1.0]`.
+    fn update(&mut self, value: f32) -> Vec<GamepadRecipeEvent> {
+        let key = if value <= -STICK_THRESHOLD {
+            Some(self.negative.clone())
+        } else if STICK_THRESHOLD <= value {
+            Some(self.positive.clone())
+        } else {
+            None
+        };
+
+        if key == self.pressed {
+            return vec![];
+        }
+
+        let mut events = vec![];
+        if let Some(data) = self.pressed.take() {
+            events.push(GamepadRecipeEvent::Released(data));
+        }
+        if let Some(data) = key {
+            events.push(GamepadRecipeEvent::Pressed(data.clone()));
+            self.pressed = Some(data);
+        }
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys(events: Vec<GamepadRecipeEvent>) -> Vec<(bool, SendEventKey)> {
+        events
+            .into_iter()
+            .map(|data| match data {
+                GamepadRecipeEvent::Pressed(key) => (true, key),
+                GamepadRecipeEvent::Released(key) => (false, key),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn stick_axis_update() {
+        let mut axis = StickAxis::new(SendEventKey::DpadLeft, SendEventKey::DpadRight);
+        assert!(axis.update(0.3).is_empty());
+        assert_eq!(
+            vec![(true, SendEventKey::DpadRight)],
+            keys(axis.update(0.8))
+        );
+        assert!(axis.update(1.0).is_empty());
+        assert_eq!(
+            vec![
+                (false, SendEventKey::DpadRight),
+                (true, SendEventKey::DpadLeft)
+            ],
+            keys(axis.update(-0.9))
+        );
+        assert_eq!(
+            vec![(false, SendEventKey::DpadLeft)],
+            keys(axis.update(0.0))
+        );
+    }
+}
@@ -0,0 +1,135 @@
+/*
+ * Copyright 2022 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::data::i18n::tr;
+use crate::function::adb_functions::{
+    put_font_scale, put_locale, retrieve_font_scale, retrieve_locale,
+};
+use crate::model::locale::{FontScale, LOCALE_PRESETS};
+use iced::widget::{pick_list, row, text, Space};
+use iced::{Command, Element, Length};
+use tracing::warn;
+
+#[derive(Clone, Debug)]
+pub enum LocalePanelCommand {
+    FontScaleRetrieved(Option<FontScale>),
+    LocaleRetrieved(Option<String>),
+    OnFontScaleSelected(FontScale),
+    OnLocaleSelected(String),
+}
+
+/// changes the locale and the font scale of the connected device to check the layouts.
+#[derive(Debug, Default)]
+pub struct LocalePanel {
+    /// `None` until retrieved.
+    font_scale: Option<FontScale>,
+    /// `None` until retrieved.
+    locale: Option<String>,
+}
+
+impl LocalePanel {
+    pub fn retrieve_command(serial: String) -> Command<LocalePanelCommand> {
+        Command::batch([
+            Command::perform(retrieve_locale(serial.clone()), |ret| {
+                LocalePanelCommand::LocaleRetrieved(match ret {
+                    Ok(data) => Some(data),
+                    Err(e) => {
+                        warn!(?e, "failed to retrieve the locale");
+                        None
+                    }
+                })
+            }),
+            Command::perform(retrieve_font_scale(serial), |ret| {
+                LocalePanelCommand::FontScaleRetrieved(match ret {
+                    Ok(data) => Some(data),
+                    Err(e) => {
+                        warn!(?e, "failed to retrieve the font scale");
+                        None
+                    }
+                })
+            }),
+        ])
+    }
+
+    pub fn update(
+        &mut self,
+        command: LocalePanelCommand,
+        serial: String,
+    ) -> Command<LocalePanelCommand> {
+        match command {
+            LocalePanelCommand::FontScaleRetrieved(data) => self.font_scale = data,
+            LocalePanelCommand::LocaleRetrieved(data) => self.locale = data,
+            LocalePanelCommand::OnFontScaleSelected(data) => {
+                return Command::perform(
+                    async move {
+                        put_font_scale(serial.clone(), data).await?;
+                        retrieve_font_scale(serial).await
+                    },
+                    |ret| {
+                        LocalePanelCommand::FontScaleRetrieved(match ret {
+                            Ok(data) => Some(data),
+                            Err(e) => {
+                                warn!(?e, "failed to change the font scale");
+                                None
+                            }
+                        })
+                    },
+                );
+            }
+            LocalePanelCommand::OnLocaleSelected(data) => {
+                return Command::perform(
+                    async move {
+                        put_locale(serial.clone(), data).await?;
+                        retrieve_locale(serial).await
+                    },
+                    |ret| {
+                        LocalePanelCommand::LocaleRetrieved(match ret {
+                            Ok(data) => Some(data),
+                            Err(e) => {
+                                warn!(?e, "failed to change the locale");
+                                None
+                            }
+                        })
+                    },
+                );
+            }
+        }
+        Command::none()
+    }
+
+    pub fn view(&self) -> Element<LocalePanelCommand> {
+        row![
+            text(tr("main-locale")),
+            pick_list(
+                LOCALE_PRESETS
+                    .iter()
+                    .map(|data| data.to_string())
+                    .collect::<Vec<_>>(),
+                self.locale.clone(),
+                LocalePanelCommand::OnLocaleSelected,
+            ),
+            Space::with_width(Length::Units(8)),
+            text(tr("main-font-scale")),
+            pick_list(
+                FontScale::PRESETS,
+                self.font_scale,
+                LocalePanelCommand::OnFontScaleSelected,
+            ),
+        ]
+        .align_items(iced::alignment::Alignment::Center)
+        .into()
+    }
+}
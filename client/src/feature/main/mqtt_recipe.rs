@@ -0,0 +1,166 @@
+/*
+ * Copyright 2022 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::function::mqtt_functions::{MqttConnection, MqttPacket, KEEP_ALIVE};
+use crate::model::api::ApiState;
+use crate::model::keycode::Keycode;
+use crate::model::mqtt::MqttConfig;
+use iced::subscription::{unfold, Subscription};
+use std::time::Duration;
+use tokio::sync::watch;
+use tokio::time::{interval_at, Instant, Interval};
+use tracing::{debug, info, warn};
+
+const RETRY_INTERVAL: Duration = Duration::from_secs(10);
+
+#[derive(Clone, Debug)]
+pub enum MqttRecipeEvent {
+    Connected,
+    /// retries after [RETRY_INTERVAL].
+    Failed(String),
+    Key(Keycode),
+}
+
+enum StreamState {
+    Connect(MqttConfig, watch::Receiver<ApiState>),
+    Connected {
+        config: MqttConfig,
+        state: watch::Receiver<ApiState>,
+        connection: MqttConnection,
+        ping: Interval,
+    },
+    Retry(MqttConfig, watch::Receiver<ApiState>),
+}
+
+struct MqttRecipeType;
+
+/// sends the keys from the broker and publishes the connectivity of the device in `state`.
+pub fn mqtt(config: MqttConfig, state: watch::Receiver<ApiState>) -> Subscription<MqttRecipeEvent> {
+    unfold(
+        (std::any::TypeId::of::<MqttRecipeType>(), config.clone()),
+        StreamState::Connect(config, state),
+        execute,
+    )
+}
+
+async fn execute(state: StreamState) -> (Option<MqttRecipeEvent>, StreamState) {
+    match state {
+        StreamState::Connect(config, mut state) => {
+            let ret = async {
+                let mut connection = MqttConnection::connect(&config).await?;
+                let payload = state_payload(&config, &state.borrow_and_update());
+                connection
+                    .publish(&config.state_topic(), payload.as_bytes(), true)
+                    .await?;
+                Ok::<_, anyhow::Error>(connection)
+            }
+            .await;
+
+            match ret {
+                Ok(connection) => {
+                    info!(topic = %config.key_topic(), "mqtt connected");
+                    let ping = interval_at(Instant::now() + KEEP_ALIVE / 2, KEEP_ALIVE / 2);
+                    (
+                        Some(MqttRecipeEvent::Connected),
+                        StreamState::Connected {
+                            config,
+                            state,
+                            connection,
+                            ping,
+                        },
+                    )
+                }
+                Err(e) => {
+                    warn!(?e, "failed to connect mqtt");
+                    (
+                        Some(MqttRecipeEvent::Failed(format!("{:#}", e))),
+                        StreamState::Retry(config, state),
+                    )
+                }
+            }
+        }
+        StreamState::Connected {
+            config,
+            mut state,
+            mut connection,
+            mut ping,
+        } => loop {
+            let ret = tokio::select! {
+                ret = connection.recv() => match ret {
+                    Ok(MqttPacket::Publish { topic, payload }) if topic == config.key_topic() => {
+                        let payload = String::from_utf8_lossy(&payload);
+                        match payload.trim().parse::<Keycode>() {
+                            Ok(keycode) => {
+                                return (
+                                    Some(MqttRecipeEvent::Key(keycode)),
+                                    StreamState::Connected {
+                                        config,
+                                        state,
+                                        connection,
+                                        ping,
+                                    },
+                                );
+                            }
+                            Err(e) => {
+                                warn!(?e, "skip the mqtt payload");
+                                Ok(())
+                            }
+                        }
+                    }
+                    Ok(packet) => {
+                        debug!(?packet, "skip the mqtt packet");
+                        Ok(())
+                    }
+                    Err(e) => Err(e),
+                },
+                ret = state.changed() => match ret {
+                    Ok(_) => {
+                        let payload = state_payload(&config, &state.borrow_and_update());
+                        connection
+                            .publish(&config.state_topic(), payload.as_bytes(), true)
+                            .await
+                    }
+                    Err(_) => {
+                        connection.disconnect().await;
+                        iced::futures::future::pending().await
+                    }
+                },
+                _ = ping.tick() => connection.ping().await,
+            };
+
+            if let Err(e) = ret {
+                warn!(?e, "mqtt disconnected");
+                return (
+                    Some(MqttRecipeEvent::Failed(format!("{:#}", e))),
+                    StreamState::Retry(config, state),
+                );
+            }
+        },
+        StreamState::Retry(config, state) => {
+            tokio::time::sleep(RETRY_INTERVAL).await;
+            (None, StreamState::Connect(config, state))
+        }
+    }
+}
+
+/// the connectivity of the configured device rather than the selected one.
+fn state_payload(config: &MqttConfig, state: &ApiState) -> String {
+    if state.serial == config.serial {
+        state.connectivity.to_string()
+    } else {
+        "disconnected".into()
+    }
+}
@@ -0,0 +1,223 @@
+/*
+ * Copyright 2022 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::data::i18n::{tr, tr_args};
+use crate::function::adb_functions::pair_device;
+use crate::function::atv_remote_functions::pair;
+use iced::widget::{button, column, row, text, text_input, Space};
+use iced::{Command, Element, Length};
+use std::path::Path;
+use tracing::{debug, warn};
+
+#[derive(Clone, Debug)]
+pub enum PairingPanelCommand {
+    /// handled by MainView to reload the devices if paired.
+    AdbFinished(Result<(), String>),
+    AtvFinished(Result<(), String>),
+    OnAdbAddressChanged(String),
+    OnAdbCodeChanged(String),
+    OnAdbPairClicked,
+    OnAdbSubmitted,
+    OnAtvCodeChanged(String),
+    OnAtvCodeSubmitted,
+    OnAtvPairClicked,
+}
+
+/// pairs with the wireless debugging of adb and with the TV remote service.
+#[derive(Debug, Default)]
+pub struct PairingPanel {
+    /// the `host:port` of the wireless debugging, shown while pairing.
+    adb_address: Option<String>,
+    adb_code: String,
+    adb_status: Option<String>,
+    adb_submitted: bool,
+    atv_code: String,
+    atv_status: Option<String>,
+    /// sends the code shown on the TV to the running pairing.
+    atv_tx: Option<tokio::sync::oneshot::Sender<String>>,
+}
+
+impl PairingPanel {
+    /// `atv_host` is the host of the selected TV remote device.
+    pub fn update(
+        &mut self,
+        command: PairingPanelCommand,
+        atv_host: Option<String>,
+        config_dir: &Path,
+    ) -> Command<PairingPanelCommand> {
+        match command {
+            PairingPanelCommand::AdbFinished(ret) => {
+                self.adb_submitted = false;
+                match ret {
+                    Ok(_) => {
+                        self.adb_address = None;
+                        self.adb_status = Some(tr("main-pairing-paired"));
+                    }
+                    Err(e) => {
+                        self.adb_status = Some(tr_args("main-pairing-failed", &[("error", e)]))
+                    }
+                }
+            }
+            PairingPanelCommand::AtvFinished(ret) => {
+                self.atv_tx = None;
+                self.atv_status = Some(match ret {
+                    Ok(_) => tr("main-pairing-paired"),
+                    Err(e) => tr_args("main-pairing-failed", &[("error", e)]),
+                });
+            }
+            PairingPanelCommand::OnAdbAddressChanged(data) => self.adb_address = Some(data),
+            PairingPanelCommand::OnAdbCodeChanged(data) => self.adb_code = data,
+            PairingPanelCommand::OnAdbPairClicked => {
+                // toggles the dialog.
+                if self.adb_address.take().is_none() {
+                    self.adb_address = Some("".into());
+                    self.adb_code.clear();
+                }
+                self.adb_status = None;
+            }
+            PairingPanelCommand::OnAdbSubmitted => {
+                let address = match &self.adb_address {
+                    Some(data) if !self.adb_submitted => data.clone(),
+                    _ => return Command::none(),
+                };
+
+                self.adb_submitted = true;
+                self.adb_status = Some(tr("main-pairing-pairing"));
+                return Command::perform(pair_device(address, self.adb_code.clone()), |ret| {
+                    PairingPanelCommand::AdbFinished(ret.map_err(|e| {
+                        warn!(?e, "failed to pair");
+                        format!("{:#}", e)
+                    }))
+                });
+            }
+            PairingPanelCommand::OnAtvCodeChanged(data) => self.atv_code = data,
+            PairingPanelCommand::OnAtvCodeSubmitted => {
+                if let Some(tx) = self.atv_tx.take() {
+                    tx.send(self.atv_code.trim().to_string()).ok();
+                    self.atv_status = Some(tr("main-pairing-pairing"));
+                }
+            }
+            PairingPanelCommand::OnAtvPairClicked => {
+                let host = match atv_host {
+                    Some(data) => data,
+                    None => return Command::none(),
+                };
+                if self.atv_tx.is_some() {
+                    debug!("already pairing");
+                    return Command::none();
+                }
+
+                let (tx, rx) = tokio::sync::oneshot::channel();
+                self.atv_tx = Some(tx);
+                self.atv_code.clear();
+                self.atv_status = Some(tr("main-pairing-atv-enter-code"));
+                return Command::perform(pair(host, config_dir.to_path_buf(), rx), |ret| {
+                    PairingPanelCommand::AtvFinished(ret.map_err(|e| {
+                        warn!(?e, "failed to pair");
+                        format!("{:#}", e)
+                    }))
+                });
+            }
+        }
+        Command::none()
+    }
+
+    pub fn adb_view(&self) -> Element<PairingPanelCommand> {
+        let view = column![row![
+            text(tr("main-pairing-adb")),
+            Space::with_width(Length::Fill),
+            button(text(if self.adb_address.is_some() {
+                tr("main-pairing-cancel")
+            } else {
+                tr("main-pairing-pair")
+            }))
+            .style(iced::theme::Button::Secondary)
+            .on_press(PairingPanelCommand::OnAdbPairClicked),
+        ]
+        .spacing(4)
+        .align_items(iced::alignment::Alignment::Center)];
+
+        let view = match &self.adb_address {
+            Some(address) => {
+                let submit_button =
+                    button(text(tr("main-pairing-send"))).style(iced::theme::Button::Secondary);
+                view.push(
+                    row![
+                        text_input(
+                            &tr("main-pairing-address"),
+                            address,
+                            PairingPanelCommand::OnAdbAddressChanged
+                        ),
+                        text_input(
+                            &tr("main-pairing-code"),
+                            &self.adb_code,
+                            PairingPanelCommand::OnAdbCodeChanged
+                        )
+                        .on_submit(PairingPanelCommand::OnAdbSubmitted)
+                        .width(Length::Units(70)),
+                        if self.adb_submitted {
+                            submit_button
+                        } else {
+                            submit_button.on_press(PairingPanelCommand::OnAdbSubmitted)
+                        },
+                    ]
+                    .spacing(4)
+                    .align_items(iced::alignment::Alignment::Center),
+                )
+            }
+            None => view,
+        };
+
+        match &self.adb_status {
+            Some(data) => view.push(text(data)),
+            None => view,
+        }
+        .into()
+    }
+
+    pub fn atv_view(&self) -> Element<PairingPanelCommand> {
+        let view = match self.atv_tx {
+            Some(_) => row![
+                text_input(
+                    &tr("main-pairing-code"),
+                    &self.atv_code,
+                    PairingPanelCommand::OnAtvCodeChanged
+                )
+                .on_submit(PairingPanelCommand::OnAtvCodeSubmitted)
+                .width(Length::Units(80)),
+                button(text(tr("main-pairing-send")))
+                    .style(iced::theme::Button::Secondary)
+                    .on_press(PairingPanelCommand::OnAtvCodeSubmitted),
+            ],
+            None => row![
+                text(tr("main-pairing-atv")),
+                Space::with_width(Length::Fill),
+                button(text(tr("main-pairing-pair")))
+                    .style(iced::theme::Button::Secondary)
+                    .on_press(PairingPanelCommand::OnAtvPairClicked),
+            ],
+        };
+        let view = column![view
+            .spacing(4)
+            .align_items(iced::alignment::Alignment::Center)];
+
+        match &self.atv_status {
+            Some(data) => view.push(text(data)),
+            None => view,
+        }
+        .into()
+    }
+}
@@ -0,0 +1,180 @@
+/*
+ * Copyright 2022 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::model::pointer::PointerGesture;
+use iced_native::widget::{tree, Tree};
+use iced_native::{
+    event, layout, mouse, renderer, touch, Clipboard, Element, Event, Layout, Length, Point,
+    Rectangle, Shell, Widget,
+};
+use std::time::Instant;
+
+/// tells the drag on the content as the [PointerGesture] when released.
+pub struct PointerPad<'a, Message, Renderer> {
+    content: Element<'a, Message, Renderer>,
+    on_gesture: Box<dyn Fn(PointerGesture) -> Message + 'a>,
+}
+
+impl<'a, Message, Renderer> PointerPad<'a, Message, Renderer> {
+    pub fn new(
+        content: impl Into<Element<'a, Message, Renderer>>,
+        on_gesture: impl Fn(PointerGesture) -> Message + 'a,
+    ) -> Self {
+        Self {
+            content: content.into(),
+            on_gesture: Box::new(on_gesture),
+        }
+    }
+}
+
+#[derive(Default)]
+struct State {
+    pressed: Option<((f32, f32), Instant)>,
+}
+
+/// the position in the bounds normalized to `[0.0, 1.0]`, or beyond if outside.
+fn normalize(bounds: Rectangle, position: Point) -> (f32, f32) {
+    (
+        (position.x - bounds.x) / bounds.width.max(1.0),
+        (position.y - bounds.y) / bounds.height.max(1.0),
+    )
+}
+
+impl<'a, Message, Renderer> Widget<Message, Renderer> for PointerPad<'a, Message, Renderer>
+where
+    Renderer: iced_native::Renderer,
+{
+    fn width(&self) -> Length {
+        self.content.as_widget().width()
+    }
+
+    fn height(&self) -> Length {
+        self.content.as_widget().height()
+    }
+
+    fn layout(&self, renderer: &Renderer, limits: &layout::Limits) -> layout::Node {
+        self.content.as_widget().layout(renderer, limits)
+    }
+
+    fn draw(
+        &self,
+        state: &Tree,
+        renderer: &mut Renderer,
+        theme: &Renderer::Theme,
+        style: &renderer::Style,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        viewport: &Rectangle,
+    ) {
+        self.content.as_widget().draw(
+            &state.children[0],
+            renderer,
+            theme,
+            style,
+            layout,
+            cursor_position,
+            viewport,
+        )
+    }
+
+    fn tag(&self) -> tree::Tag {
+        tree::Tag::of::<State>()
+    }
+
+    fn state(&self) -> tree::State {
+        tree::State::new(State::default())
+    }
+
+    fn children(&self) -> Vec<Tree> {
+        vec![Tree::new(&self.content)]
+    }
+
+    fn diff(&self, tree: &mut Tree) {
+        tree.diff_children(std::slice::from_ref(&self.content))
+    }
+
+    fn on_event(
+        &mut self,
+        tree: &mut Tree,
+        event: Event,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        _renderer: &Renderer,
+        _clipboard: &mut dyn Clipboard,
+        shell: &mut Shell<'_, Message>,
+    ) -> event::Status {
+        let bounds = layout.bounds();
+        let state = tree.state.downcast_mut::<State>();
+        let (position, pressed) = match event {
+            Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) => {
+                (cursor_position, true)
+            }
+            Event::Touch(touch::Event::FingerPressed { position, .. }) => (position, true),
+            Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)) => {
+                (cursor_position, false)
+            }
+            Event::Touch(touch::Event::FingerLifted { position, .. })
+            | Event::Touch(touch::Event::FingerLost { position, .. }) => (position, false),
+            _ => return event::Status::Ignored,
+        };
+
+        if pressed {
+            if !bounds.contains(position) {
+                return event::Status::Ignored;
+            }
+            state.pressed = Some((normalize(bounds, position), Instant::now()));
+            return event::Status::Captured;
+        }
+
+        match state.pressed.take() {
+            Some((from, pressed_at)) => {
+                shell.publish((self.on_gesture)(PointerGesture {
+                    from,
+                    to: normalize(bounds, position),
+                    duration: pressed_at.elapsed(),
+                }));
+                event::Status::Captured
+            }
+            None => event::Status::Ignored,
+        }
+    }
+
+    fn mouse_interaction(
+        &self,
+        _tree: &Tree,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        _viewport: &Rectangle,
+        _renderer: &Renderer,
+    ) -> mouse::Interaction {
+        if layout.bounds().contains(cursor_position) {
+            mouse::Interaction::Crosshair
+        } else {
+            mouse::Interaction::default()
+        }
+    }
+}
+
+impl<'a, Message, Renderer> From<PointerPad<'a, Message, Renderer>>
+    for Element<'a, Message, Renderer>
+where
+    Message: 'a,
+    Renderer: 'a + iced_native::Renderer,
+{
+    fn from(value: PointerPad<'a, Message, Renderer>) -> Self {
+        Element::new(value)
+    }
+}
@@ -0,0 +1,182 @@
+/*
+ * Copyright 2022 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::data::i18n::{tr, tr_args};
+use crate::function::adb_functions::{
+    put_accessibility_services, reboot_device, wait_for_boot_completed, wait_for_device,
+};
+use crate::model::accessibility::AccessibilityServices;
+use iced::widget::{button, row, text};
+use iced::{Command, Element};
+use std::fmt::{Display, Formatter};
+use std::time::Duration;
+use tracing::{info, warn};
+
+/// how long each of waiting for the device and the boot after the reboot.
+const REBOOT_TIMEOUT: Duration = Duration::from_secs(180);
+
+#[derive(Clone, Debug)]
+pub enum RebootPanelCommand {
+    /// the device has booted, handled by MainView to notify and connect again.
+    Booted(String),
+    /// the reboot has failed, handled by MainView to notify.
+    Failed(String),
+    /// handled by MainView to disconnect before [RebootPanel::start].
+    OnRebootClicked,
+    StepFinished(RebootStep, Result<(), String>),
+}
+
+/// the steps of the reboot from the app, followed by the reconnection.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum RebootStep {
+    Reboot,
+    WaitForDevice,
+    WaitForBoot,
+}
+
+impl Display for RebootStep {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RebootStep::Reboot => write!(f, "{}", tr("main-reboot-step-reboot")),
+            RebootStep::WaitForDevice => write!(f, "{}", tr("main-reboot-step-wait-for-device")),
+            RebootStep::WaitForBoot => write!(f, "{}", tr("main-reboot-step-wait-for-boot")),
+        }
+    }
+}
+
+/// reboots the selected device and waits for the boot.
+#[derive(Debug, Default)]
+pub struct RebootPanel {
+    status: Option<String>,
+    /// `None` unless rebooting from the app.
+    step: Option<RebootStep>,
+}
+
+impl RebootPanel {
+    pub fn is_rebooting(&self) -> bool {
+        self.step.is_some()
+    }
+
+    /// restores the accessibility services before the reboot instead of racing with it in the
+    /// disconnection.
+    pub fn start(
+        &mut self,
+        serial: String,
+        accessibility_original: Option<AccessibilityServices>,
+    ) -> Command<RebootPanelCommand> {
+        self.step = Some(RebootStep::Reboot);
+        self.status = None;
+        Command::perform(
+            async move {
+                if let Some(data) = accessibility_original {
+                    if let Err(e) = put_accessibility_services(serial.clone(), data).await {
+                        warn!(?e, "failed to restore the accessibility services");
+                    }
+                }
+                reboot_device(serial).await
+            },
+            |ret| {
+                RebootPanelCommand::StepFinished(
+                    RebootStep::Reboot,
+                    ret.map_err(|e| format!("{:#}", e)),
+                )
+            },
+        )
+    }
+
+    pub fn update(
+        &mut self,
+        command: RebootPanelCommand,
+        serial: String,
+    ) -> Command<RebootPanelCommand> {
+        match command {
+            // handled by MainView.
+            RebootPanelCommand::Booted(_)
+            | RebootPanelCommand::Failed(_)
+            | RebootPanelCommand::OnRebootClicked => (),
+            RebootPanelCommand::StepFinished(step, ret) => {
+                info!(?step, ?ret, "reboot step finished");
+                if self.step != Some(step) {
+                    // the device was changed while rebooting.
+                    return Command::none();
+                }
+
+                if let Err(e) = ret {
+                    self.step = None;
+                    let status = tr_args(
+                        "main-reboot-failed",
+                        &[("step", step.to_string()), ("error", e)],
+                    );
+                    self.status = Some(status.clone());
+                    return Command::perform(async {}, move |_| RebootPanelCommand::Failed(status));
+                }
+
+                let (next, command) = match step {
+                    RebootStep::Reboot => (
+                        RebootStep::WaitForDevice,
+                        Command::perform(wait_for_device(serial, REBOOT_TIMEOUT), |ret| {
+                            RebootPanelCommand::StepFinished(
+                                RebootStep::WaitForDevice,
+                                ret.map_err(|e| format!("{:#}", e)),
+                            )
+                        }),
+                    ),
+                    RebootStep::WaitForDevice => (
+                        RebootStep::WaitForBoot,
+                        Command::perform(wait_for_boot_completed(serial, REBOOT_TIMEOUT), |ret| {
+                            RebootPanelCommand::StepFinished(
+                                RebootStep::WaitForBoot,
+                                ret.map_err(|e| format!("{:#}", e)),
+                            )
+                        }),
+                    ),
+                    RebootStep::WaitForBoot => {
+                        self.step = None;
+                        return Command::perform(async {}, move |_| {
+                            RebootPanelCommand::Booted(serial)
+                        });
+                    }
+                };
+                self.step = Some(next);
+                return command;
+            }
+        }
+        Command::none()
+    }
+
+    pub fn view(&self) -> Element<RebootPanelCommand> {
+        let reboot_button = button(text(tr("main-reboot"))).style(iced::theme::Button::Secondary);
+        row![
+            if self.step.is_none() {
+                reboot_button.on_press(RebootPanelCommand::OnRebootClicked)
+            } else {
+                reboot_button
+            },
+            match (&self.step, &self.status) {
+                (Some(step), _) => text(tr_args(
+                    "main-reboot-running",
+                    &[("step", step.to_string())]
+                )),
+                (None, Some(status)) => text(status),
+                (None, None) => text(""),
+            }
+            .size(14),
+        ]
+        .spacing(4)
+        .align_items(iced::alignment::Alignment::Center)
+        .into()
+    }
+}
@@ -0,0 +1,197 @@
+/*
+ * Copyright 2022 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::data::i18n::{tr, tr_args};
+use crate::function::adb_functions::{pull_screen_recording, record_screen, stop_screen_recording};
+use crate::model::Preferences;
+use iced::widget::{button, row, text};
+use iced::{Command, Element};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+use tracing::warn;
+
+#[derive(Clone, Debug)]
+pub enum RecordingPanelCommand {
+    /// the status of the finished recording, handled by MainView to notify.
+    Completed(String),
+    Finished(Result<(), String>),
+    /// starts or stops the screen recording.
+    OnRecordClicked,
+    Pulled(Result<PathBuf, String>),
+    /// `screenrecord` has been stopped, followed by [RecordingPanelCommand::Finished].
+    Stopped,
+}
+
+/// the screen recording of the selected device.
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum RecordingState {
+    Recording {
+        device_path: String,
+        started_at: Instant,
+    },
+    Stopping {
+        device_path: String,
+    },
+    Pulling,
+}
+
+/// records the screen of the selected device and pulls it to
+/// [Preferences::recording_dir].
+#[derive(Debug, Default)]
+pub struct RecordingPanel {
+    /// `None` unless recording the screen.
+    state: Option<RecordingState>,
+    status: Option<String>,
+}
+
+impl RecordingPanel {
+    pub fn is_recording(&self) -> bool {
+        matches!(self.state, Some(RecordingState::Recording { .. }))
+    }
+
+    /// forgets the recording, which is left on the previous device.
+    pub fn reset(&mut self, serial: Option<String>) -> Command<RecordingPanelCommand> {
+        let state = self.state.take();
+        self.status = None;
+        match (state, serial) {
+            (Some(RecordingState::Recording { .. }), Some(serial)) => {
+                Command::perform(stop_screen_recording(serial), |ret| {
+                    if let Err(e) = ret {
+                        warn!(?e, "failed to stop the screen recording");
+                    }
+                    RecordingPanelCommand::Stopped
+                })
+            }
+            _ => Command::none(),
+        }
+    }
+
+    pub fn update(
+        &mut self,
+        command: RecordingPanelCommand,
+        serial: String,
+        prefs: &Preferences,
+    ) -> Command<RecordingPanelCommand> {
+        match command {
+            // handled by MainView.
+            RecordingPanelCommand::Completed(_) => (),
+            RecordingPanelCommand::Finished(ret) => {
+                let device_path = match self.state.take() {
+                    Some(RecordingState::Recording { device_path, .. })
+                    | Some(RecordingState::Stopping { device_path }) => device_path,
+                    // the device was changed while recording.
+                    Some(RecordingState::Pulling) | None => return Command::none(),
+                };
+
+                if let Err(e) = ret {
+                    return self.complete(tr_args("main-recording-failed", &[("error", e)]));
+                }
+
+                self.state = Some(RecordingState::Pulling);
+                let dir = prefs
+                    .recording_dir
+                    .clone()
+                    .unwrap_or_else(std::env::temp_dir);
+                return Command::perform(pull_screen_recording(serial, device_path, dir), |ret| {
+                    RecordingPanelCommand::Pulled(ret.map_err(|e| format!("{:#}", e)))
+                });
+            }
+            RecordingPanelCommand::OnRecordClicked => match self.state.take() {
+                None => {
+                    let device_path = format!(
+                        "/sdcard/android-commander-{}.mp4",
+                        chrono::Local::now().format("%Y%m%d-%H%M%S")
+                    );
+                    self.state = Some(RecordingState::Recording {
+                        device_path: device_path.clone(),
+                        started_at: Instant::now(),
+                    });
+                    self.status = None;
+                    return Command::perform(
+                        record_screen(serial, device_path, prefs.screen_record_config()),
+                        |ret| RecordingPanelCommand::Finished(ret.map_err(|e| format!("{:#}", e))),
+                    );
+                }
+                Some(RecordingState::Recording { device_path, .. }) => {
+                    self.state = Some(RecordingState::Stopping { device_path });
+                    return Command::perform(stop_screen_recording(serial), |ret| {
+                        if let Err(e) = ret {
+                            warn!(?e, "failed to stop the screen recording");
+                        }
+                        RecordingPanelCommand::Stopped
+                    });
+                }
+                Some(data) => self.state = Some(data),
+            },
+            RecordingPanelCommand::Pulled(ret) => {
+                if self.state != Some(RecordingState::Pulling) {
+                    return Command::none();
+                }
+
+                self.state = None;
+                return self.complete(match ret {
+                    Ok(data) => tr_args(
+                        "main-recording-saved",
+                        &[("path", data.display().to_string())],
+                    ),
+                    Err(e) => tr_args("main-recording-pull-failed", &[("error", e)]),
+                });
+            }
+            RecordingPanelCommand::Stopped => (),
+        }
+        Command::none()
+    }
+
+    pub fn view(&self, time_limit: Duration) -> Element<RecordingPanelCommand> {
+        let record_button = button(text(match self.state {
+            Some(RecordingState::Recording { .. }) => tr("main-recording-stop"),
+            Some(RecordingState::Stopping { .. } | RecordingState::Pulling) | None => {
+                tr("main-recording-start")
+            }
+        }))
+        .style(iced::theme::Button::Secondary);
+        row![
+            match self.state {
+                Some(RecordingState::Stopping { .. } | RecordingState::Pulling) => record_button,
+                Some(RecordingState::Recording { .. }) | None => {
+                    record_button.on_press(RecordingPanelCommand::OnRecordClicked)
+                }
+            },
+            match (&self.state, &self.status) {
+                (Some(RecordingState::Recording { started_at, .. }), _) => text(tr_args(
+                    "main-recording-elapsed",
+                    &[
+                        ("elapsed", started_at.elapsed().as_secs().to_string()),
+                        ("limit", time_limit.as_secs().to_string()),
+                    ],
+                )),
+                (Some(RecordingState::Stopping { .. }), _) => text(tr("main-recording-stopping")),
+                (Some(RecordingState::Pulling), _) => text(tr("main-recording-pulling")),
+                (None, Some(status)) => text(status),
+                (None, None) => text(""),
+            }
+            .size(14),
+        ]
+        .spacing(4)
+        .align_items(iced::alignment::Alignment::Center)
+        .into()
+    }
+
+    fn complete(&mut self, status: String) -> Command<RecordingPanelCommand> {
+        self.status = Some(status.clone());
+        Command::perform(async {}, move |_| RecordingPanelCommand::Completed(status))
+    }
+}
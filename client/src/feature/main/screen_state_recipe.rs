@@ -0,0 +1,108 @@
+/*
+ * Copyright 2022 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::function::command_functions::{adb_command, run_command, DEFAULT_TIMEOUT};
+use crate::model::AndroidDevice;
+use iced::subscription::{unfold, Subscription};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, warn};
+
+#[derive(Clone, Debug)]
+pub enum ScreenStateRecipeEvent {
+    Changed(bool),
+}
+
+struct ScreenStateRecipeType;
+
+/// polls whether the screen of the device is on.
+pub fn screen_state(device: Arc<AndroidDevice>) -> Subscription<ScreenStateRecipeEvent> {
+    unfold(
+        std::any::TypeId::of::<ScreenStateRecipeType>(),
+        (device, None),
+        execute,
+    )
+}
+
+async fn execute(
+    (device, last_awake): (Arc<AndroidDevice>, Option<bool>),
+) -> (
+    Option<ScreenStateRecipeEvent>,
+    (Arc<AndroidDevice>, Option<bool>),
+) {
+    loop {
+        if last_awake.is_some() {
+            tokio::time::sleep(Duration::from_secs(2)).await;
+        }
+
+        let mut command = adb_command();
+        command.args([
+            "-s",
+            &device.serial,
+            "shell",
+            "dumpsys power | grep mWakefulness=",
+        ]);
+        let output = run_command(command, DEFAULT_TIMEOUT).await;
+
+        let output = match output {
+            Ok(data) => data,
+            Err(e) => {
+                warn!(?e, "failed to retrieve the wakefulness");
+                tokio::time::sleep(Duration::from_secs(2)).await;
+                continue;
+            }
+        };
+
+        let awake = match parse_awake(&output.stdout_lossy()) {
+            Some(data) => data,
+            None => {
+                debug!("wakefulness not found");
+                tokio::time::sleep(Duration::from_secs(2)).await;
+                continue;
+            }
+        };
+
+        if Some(awake) != last_awake {
+            debug!(awake, "screen state changed");
+            return (
+                Some(ScreenStateRecipeEvent::Changed(awake)),
+                (device, Some(awake)),
+            );
+        }
+    }
+}
+
+/// parses `mWakefulness=Awake`. `Asleep` and `Dozing` mean the screen is off.
+fn parse_awake(dumpsys: &str) -> Option<bool> {
+    let line = dumpsys
+        .lines()
+        .find(|data| data.trim_start().starts_with("mWakefulness="))?;
+    let (_, wakefulness) = line.split_once('=')?;
+    Some(wakefulness.trim() == "Awake")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_awake_wakefulness() {
+        assert_eq!(Some(true), parse_awake("  mWakefulness=Awake\n"));
+        assert_eq!(Some(false), parse_awake("  mWakefulness=Asleep\n"));
+        assert_eq!(Some(false), parse_awake("  mWakefulness=Dozing\n"));
+        assert_eq!(None, parse_awake(""));
+    }
+}
@@ -0,0 +1,126 @@
+/*
+ * Copyright 2022 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::data::session_dir::SessionDir;
+use crate::feature::main::adb_server_recipe::{
+    prepare_server_file, push_server, spawn_server, wait_line, write_data, HANDSHAKE_TIMEOUT,
+};
+use crate::function::adb_functions::capture_screenshot;
+use crate::model::keycode::Keycode;
+use crate::model::protocol::{encode_frame, ServerCommand};
+use crate::model::self_test::{SelfTestReport, SelfTestStage, SelfTestStageResult};
+use crate::prelude::*;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tracing::{info, warn};
+
+const ACK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// runs a separate server from the connected one, so the connection is left as it is.
+pub(super) async fn run_self_test(serial: String, session_dir: Arc<SessionDir>) -> SelfTestReport {
+    info!(%serial, "run self-test");
+    let mut report = SelfTestReport::new(serial.clone());
+
+    let started = Instant::now();
+    let ret = async {
+        let server_path = prepare_server_file(&session_dir).await?;
+        push_server(&serial, &server_path).await
+    }
+    .await;
+    if record(&mut report, SelfTestStage::PushServer, started, ret).is_none() {
+        return report;
+    }
+
+    let started = Instant::now();
+    let ret = async {
+        let mut child = spawn_server(&serial)?;
+        let stdout = child.stdout.take().context("stdout not found")?;
+        let mut lines = BufReader::new(stdout).lines();
+        match tokio::time::timeout(HANDSHAKE_TIMEOUT, wait_line(&mut lines, "Hello")).await {
+            Ok(true) => Ok((child, lines)),
+            Ok(false) => anyhow::bail!("server exited before handshake"),
+            Err(_) => anyhow::bail!("handshake timed out"),
+        }
+    }
+    .await;
+    let (mut child, mut lines) = match record(&mut report, SelfTestStage::Handshake, started, ret) {
+        Some(data) => data,
+        None => return report,
+    };
+
+    // the key that no app handles.
+    let keycode = "KEYCODE_UNKNOWN".parse::<Keycode>().expect("known keycode");
+    let started = Instant::now();
+    let [down, up] = ServerCommand::click(keycode);
+    let mut frames = encode_frame(0, &down);
+    frames.extend(encode_frame(1, &up));
+    let ret = write_data(&mut child, &frames).await;
+    if record(&mut report, SelfTestStage::SendKey, started, ret).is_none() {
+        return report;
+    }
+
+    let started = Instant::now();
+    let ret = async {
+        match tokio::time::timeout(ACK_TIMEOUT, wait_line(&mut lines, "ack 1")).await {
+            Ok(true) => Ok(()),
+            Ok(false) => anyhow::bail!("server exited before the ack"),
+            Err(_) => anyhow::bail!("ack timed out"),
+        }
+    }
+    .await;
+    let acked = record(&mut report, SelfTestStage::Ack, started, ret).is_some();
+    write_data(&mut child, &encode_frame(2, &ServerCommand::Quit))
+        .await
+        .ok();
+    child.kill().await.ok();
+    if !acked {
+        return report;
+    }
+
+    let started = Instant::now();
+    let path = std::env::temp_dir().join(format!(
+        "android-commander-self-test-{}.png",
+        chrono::Local::now().format("%Y%m%d-%H%M%S")
+    ));
+    let ret = capture_screenshot(serial, path).await;
+    report.screenshot = record(&mut report, SelfTestStage::Screenshot, started, ret);
+
+    report
+}
+
+/// adds the result of the stage and returns the value if passed.
+fn record<T>(
+    report: &mut SelfTestReport,
+    stage: SelfTestStage,
+    started: Instant,
+    ret: Fallible<T>,
+) -> Option<T> {
+    let elapsed = started.elapsed();
+    let (value, error) = match ret {
+        Ok(data) => (Some(data), None),
+        Err(e) => {
+            warn!(?e, %stage, "self-test failed");
+            (None, Some(format!("{:#}", e)))
+        }
+    };
+    report.results.push(SelfTestStageResult {
+        stage,
+        elapsed,
+        error,
+    });
+    value
+}
@@ -0,0 +1,122 @@
+/*
+ * Copyright 2022 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::data::i18n::tr;
+use crate::data::session_dir::SessionDir;
+use crate::feature::main::self_test::run_self_test;
+use crate::model::self_test::SelfTestReport;
+use crate::model::Preferences;
+use iced::widget::{button, column, row, text};
+use iced::{Command, Element};
+use std::sync::Arc;
+use tracing::info;
+
+#[derive(Clone, Debug)]
+pub enum SelfTestPanelCommand {
+    /// handled by MainView to notify before the report is shown.
+    Finished(SelfTestReport),
+    OnCopyClicked,
+    OnRunClicked,
+}
+
+/// checks the server on the selected device without the connection.
+#[derive(Debug, Default)]
+pub struct SelfTestPanel {
+    /// `None` until the self-test of the selected device finishes.
+    report: Option<SelfTestReport>,
+    running: bool,
+}
+
+impl SelfTestPanel {
+    /// forgets the report of the previous device.
+    pub fn reset(&mut self) {
+        self.report = None;
+    }
+
+    /// `serial` is `None` unless the adb device is selected.
+    pub fn update(
+        &mut self,
+        command: SelfTestPanelCommand,
+        serial: Option<String>,
+        prefs: &Preferences,
+        session_dir: &Arc<SessionDir>,
+    ) -> Command<SelfTestPanelCommand> {
+        match command {
+            SelfTestPanelCommand::Finished(data) => {
+                info!(passed = data.is_passed(), "self-test finished");
+                self.running = false;
+                // the device was changed while testing.
+                if serial.as_deref() == Some(data.serial.as_str()) {
+                    self.report = Some(data);
+                }
+            }
+            SelfTestPanelCommand::OnCopyClicked => {
+                if let Some(data) = &self.report {
+                    return iced::clipboard::write(data.to_text());
+                }
+            }
+            SelfTestPanelCommand::OnRunClicked => {
+                let serial = match serial {
+                    Some(data) => data,
+                    None => return Command::none(),
+                };
+                // pushes the server as well as connecting.
+                if prefs.blocked_serials.contains(&serial) {
+                    info!("blocked device");
+                    return Command::none();
+                }
+
+                self.report = None;
+                self.running = true;
+                return Command::perform(
+                    run_self_test(serial, session_dir.clone()),
+                    SelfTestPanelCommand::Finished,
+                );
+            }
+        }
+        Command::none()
+    }
+
+    pub fn view(&self) -> Element<SelfTestPanelCommand> {
+        let test_button =
+            button(text(tr("main-self-test-run"))).style(iced::theme::Button::Secondary);
+        let mut view = column![row![
+            if self.running {
+                test_button
+            } else {
+                test_button.on_press(SelfTestPanelCommand::OnRunClicked)
+            },
+            text(if self.running {
+                tr("main-self-test-running")
+            } else {
+                String::new()
+            })
+            .size(14),
+        ]
+        .spacing(4)
+        .align_items(iced::alignment::Alignment::Center)]
+        .spacing(4);
+
+        if let Some(report) = &self.report {
+            view = view.push(text(report.to_text()).size(14)).push(
+                button(text(tr("main-self-test-copy")))
+                    .style(iced::theme::Button::Secondary)
+                    .on_press(SelfTestPanelCommand::OnCopyClicked),
+            );
+        }
+        view.into()
+    }
+}
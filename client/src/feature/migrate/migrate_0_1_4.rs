@@ -0,0 +1,278 @@
+/*
+ * Copyright 2022 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::feature::migrate::migrate_functions::{load_toml, write_toml};
+use crate::model::FileVersion;
+use crate::prelude::*;
+use std::path::Path;
+use tracing::info;
+
+const NEW_KEYS: [(&str, &str); 18] = [
+    ("volume_up", "KEYCODE_VOLUME_UP"),
+    ("volume_down", "KEYCODE_VOLUME_DOWN"),
+    ("mute", "KEYCODE_VOLUME_MUTE"),
+    ("media_play_pause", "KEYCODE_MEDIA_PLAY_PAUSE"),
+    ("media_stop", "KEYCODE_MEDIA_STOP"),
+    ("media_rewind", "KEYCODE_MEDIA_REWIND"),
+    ("media_fast_forward", "KEYCODE_MEDIA_FAST_FORWARD"),
+    ("media_next", "KEYCODE_MEDIA_NEXT"),
+    ("media_previous", "KEYCODE_MEDIA_PREVIOUS"),
+    ("power", "KEYCODE_POWER"),
+    ("sleep", "KEYCODE_SLEEP"),
+    ("wakeup", "KEYCODE_WAKEUP"),
+    ("tv_input", "KEYCODE_TV_INPUT"),
+    ("guide", "KEYCODE_GUIDE"),
+    ("info", "KEYCODE_INFO"),
+    ("channel_up", "KEYCODE_CHANNEL_UP"),
+    ("channel_down", "KEYCODE_CHANNEL_DOWN"),
+    ("assist", "KEYCODE_ASSIST"),
+];
+
+pub fn migrate_0_1_4(preferences_dir: &Path) -> Fallible<()> {
+    let preferences_path = preferences_dir.join("preferences.toml");
+
+    if !preferences_path.exists() {
+        info!("preferences.toml not found");
+        return Ok(());
+    }
+
+    info!("check preferences.toml");
+
+    let mut preferences = load_toml(&preferences_path)?;
+
+    let prefs_version = preferences["version"]
+        .as_str()
+        .context("preferences.version")?
+        .parse::<FileVersion>()?;
+
+    if "0.1.4".parse::<FileVersion>()? <= prefs_version {
+        info!(%prefs_version, "skip migration");
+        return Ok(());
+    }
+
+    info!("set version to preferences.toml");
+
+    let prefs_table = preferences
+        .as_table_mut()
+        .context("failed to parse to table")?;
+
+    prefs_table.insert("version".into(), toml::Value::String("0.1.4".into()));
+
+    info!("set key_map to preferences.toml");
+
+    insert_new_keys(
+        prefs_table
+            .get_mut("key_map")
+            .context("preferences.key_map")?,
+    )?;
+
+    if let Some(profiles) = prefs_table.get_mut("key_map_profiles") {
+        info!("set key_map_profiles to preferences.toml");
+
+        for profile in profiles
+            .as_array_mut()
+            .context("failed to parse to key_map_profiles array")?
+        {
+            insert_new_keys(
+                profile
+                    .get_mut("key_map")
+                    .context("preferences.key_map_profiles.key_map")?,
+            )?;
+        }
+    }
+
+    write_toml(&preferences_path, &preferences)?;
+
+    info!("succeeded set version to preferences.toml");
+    Ok(())
+}
+
+/// keeps the keycode if the user already has it.
+fn insert_new_keys(key_map: &mut toml::Value) -> Fallible<()> {
+    let key_map_table = key_map
+        .as_table_mut()
+        .context("failed to parse to key_map table")?;
+
+    for (name, keycode) in NEW_KEYS {
+        key_map_table
+            .entry(name)
+            .or_insert_with(|| toml::Value::String(keycode.into()));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::feature::migrate::migrate_functions::tests::{check_version, prepare_preferences};
+    use tempfile::tempdir;
+
+    #[test]
+    fn migrate_0_1_4() {
+        // tracing_subscriber::fmt()
+        //     .with_max_level(tracing::Level::TRACE)
+        //     .init();
+
+        let old_preferences = r#"
+version = "0.1.3"
+
+[key_map]
+back = "KEYCODE_f"
+color_red = "red"
+color_green = "green"
+color_blue = "blue"
+color_yellow = "yellow"
+dpad_up = "KEYCODE_a"
+dpad_down = "KEYCODE_b"
+dpad_left = "KEYCODE_c"
+dpad_right = "KEYCODE_d"
+dpad_ok = "KEYCODE_e"
+num_1 = "KEYCODE_1"
+num_2 = "KEYCODE_2"
+num_3 = "KEYCODE_3"
+num_4 = "KEYCODE_4"
+num_5 = "KEYCODE_5"
+num_6 = "KEYCODE_6"
+num_7 = "KEYCODE_7"
+num_8 = "KEYCODE_8"
+num_9 = "KEYCODE_9"
+num_0 = "KEYCODE_0"
+home = "KEYCODE_g"
+
+[[key_map_profiles]]
+name = "tv"
+
+[key_map_profiles.key_map]
+back = "KEYCODE_f"
+color_red = "red"
+color_green = "green"
+color_blue = "blue"
+color_yellow = "yellow"
+dpad_up = "KEYCODE_a"
+dpad_down = "KEYCODE_b"
+dpad_left = "KEYCODE_c"
+dpad_right = "KEYCODE_d"
+dpad_ok = "KEYCODE_e"
+num_1 = "KEYCODE_1"
+num_2 = "KEYCODE_2"
+num_3 = "KEYCODE_3"
+num_4 = "KEYCODE_4"
+num_5 = "KEYCODE_5"
+num_6 = "KEYCODE_6"
+num_7 = "KEYCODE_7"
+num_8 = "KEYCODE_8"
+num_9 = "KEYCODE_9"
+num_0 = "KEYCODE_0"
+home = "KEYCODE_g"
+mute = "KEYCODE_MUTE"
+"#;
+
+        let temp_dir = tempdir().context("prepare tempfile::tempdir()").unwrap();
+        let prefs_dir = temp_dir.path();
+        info!(?prefs_dir);
+
+        prepare_preferences(prefs_dir, old_preferences);
+
+        super::migrate_0_1_4(prefs_dir).unwrap();
+
+        let preferences_toml = load_toml(&prefs_dir.join("preferences.toml")).unwrap();
+
+        check_version(&preferences_toml, "0.1.4");
+
+        let actual_key_map = preferences_toml["key_map"]
+            .as_table()
+            .context("new preferences.key_map")
+            .unwrap();
+
+        assert_eq!("KEYCODE_g", actual_key_map["home"].as_str().unwrap());
+        assert_eq!(
+            "KEYCODE_VOLUME_UP",
+            actual_key_map["volume_up"].as_str().unwrap()
+        );
+        assert_eq!(
+            "KEYCODE_VOLUME_DOWN",
+            actual_key_map["volume_down"].as_str().unwrap()
+        );
+        assert_eq!(
+            "KEYCODE_VOLUME_MUTE",
+            actual_key_map["mute"].as_str().unwrap()
+        );
+        assert_eq!(
+            "KEYCODE_MEDIA_PLAY_PAUSE",
+            actual_key_map["media_play_pause"].as_str().unwrap()
+        );
+        assert_eq!(
+            "KEYCODE_MEDIA_PREVIOUS",
+            actual_key_map["media_previous"].as_str().unwrap()
+        );
+        assert_eq!("KEYCODE_WAKEUP", actual_key_map["wakeup"].as_str().unwrap());
+        assert_eq!(
+            "KEYCODE_CHANNEL_DOWN",
+            actual_key_map["channel_down"].as_str().unwrap()
+        );
+
+        let actual_profile_key_map = preferences_toml["key_map_profiles"][0]["key_map"]
+            .as_table()
+            .context("new preferences.key_map_profiles.key_map")
+            .unwrap();
+
+        assert_eq!(
+            "KEYCODE_VOLUME_UP",
+            actual_profile_key_map["volume_up"].as_str().unwrap()
+        );
+        assert_eq!(
+            "KEYCODE_VOLUME_DOWN",
+            actual_profile_key_map["volume_down"].as_str().unwrap()
+        );
+        assert_eq!(
+            "KEYCODE_MUTE",
+            actual_profile_key_map["mute"].as_str().unwrap()
+        );
+        assert_eq!(
+            "KEYCODE_MEDIA_STOP",
+            actual_profile_key_map["media_stop"].as_str().unwrap()
+        );
+    }
+
+    #[test]
+    fn skip_migrate() {
+        // tracing_subscriber::fmt()
+        //     .with_max_level(tracing::Level::TRACE)
+        //     .init();
+
+        let preferences_str = r#"
+version = "0.1.5"
+
+[key_map]
+back = "KEYCODE_f"
+home = "KEYCODE_g"
+"#;
+
+        let temp_dir = tempdir().context("prepare tempfile::tempdir()").unwrap();
+        let prefs_dir = temp_dir.path();
+        info!(?prefs_dir);
+
+        prepare_preferences(prefs_dir, preferences_str);
+
+        super::migrate_0_1_4(prefs_dir).unwrap();
+
+        let new_prefs = load_toml(&prefs_dir.join("preferences.toml")).unwrap();
+
+        check_version(&new_prefs, "0.1.5");
+        assert_eq!(None, new_prefs["key_map"].get("volume_up"));
+    }
+}
@@ -0,0 +1,247 @@
+/*
+ * Copyright 2022 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+mod monkey_recipe;
+
+use crate::data::i18n::{tr, tr_args};
+use crate::feature::monkey::monkey_recipe::{monkey, MonkeyRecipeEvent};
+use crate::function::adb_functions::stop_monkey;
+use crate::model::monkey::{is_monkey_finished, MonkeyConfig};
+use crate::model::{AndroidDevice, XMessage};
+use iced::widget::{button, column, row, scrollable, text, text_input, Column};
+use iced::{Command, Element, Length, Subscription};
+use std::sync::Arc;
+use tracing::{info, warn};
+
+#[derive(Clone, Debug)]
+pub enum MonkeyViewCommand {
+    MonkeyRecipeResult(MonkeyRecipeEvent),
+    OnEventCountChanged(String),
+    OnPackageChanged(String),
+    OnSeedChanged(String),
+    OnStartClicked,
+    OnStopClicked,
+    OnThrottleChanged(String),
+    OnXMessage(XMessage),
+    /// shows the desktop notification, handled by App.
+    ShowNotification(String, String),
+    Sink,
+}
+
+pub struct MonkeyView {
+    device: Option<Arc<AndroidDevice>>,
+    event_count: String,
+    output: Vec<String>,
+    package: String,
+    running: Option<(String, MonkeyConfig)>,
+    run_id: u64,
+    seed: String,
+    status: String,
+    throttle: String,
+}
+
+impl Default for MonkeyView {
+    fn default() -> Self {
+        Self {
+            device: None,
+            event_count: "500".into(),
+            output: vec![],
+            package: "".into(),
+            running: None,
+            run_id: 0,
+            seed: "".into(),
+            status: "".into(),
+            throttle: "100".into(),
+        }
+    }
+}
+
+impl MonkeyView {
+    /// keeps the latest output lines only.
+    const OUTPUT_CAPACITY: usize = 500;
+
+    pub fn update(&mut self, command: MonkeyViewCommand) -> Command<MonkeyViewCommand> {
+        match command {
+            MonkeyViewCommand::MonkeyRecipeResult(data) => match data {
+                MonkeyRecipeEvent::Output(line) => {
+                    if self.output.len() == Self::OUTPUT_CAPACITY {
+                        self.output.remove(0);
+                    }
+                    self.output.push(line);
+                }
+                MonkeyRecipeEvent::Finished(code) => {
+                    info!(?code, "monkey finished");
+                    let package = (
+                        "package",
+                        self.running
+                            .take()
+                            .map(|(_, config)| config.package)
+                            .unwrap_or_default(),
+                    );
+                    let body = match code {
+                        Some(0) if is_monkey_finished(&self.output) => {
+                            self.status = tr("monkey-status-finished");
+                            tr_args("notification-monkey-finished", &[package])
+                        }
+                        Some(data) => {
+                            let code = ("code", data.to_string());
+                            self.status = tr_args("monkey-status-exit-code", &[code.clone()]);
+                            tr_args("notification-monkey-exit-code", &[package, code])
+                        }
+                        None => {
+                            self.status = tr("monkey-status-aborted");
+                            tr_args("notification-monkey-aborted", &[package])
+                        }
+                    };
+                    return notification_command(tr("notification-monkey"), body);
+                }
+            },
+            MonkeyViewCommand::OnEventCountChanged(data) => self.event_count = data,
+            MonkeyViewCommand::OnPackageChanged(data) => self.package = data,
+            MonkeyViewCommand::OnSeedChanged(data) => self.seed = data,
+            MonkeyViewCommand::OnThrottleChanged(data) => self.throttle = data,
+            MonkeyViewCommand::OnStartClicked => {
+                let serial = match &self.device {
+                    Some(data) => data.serial.clone(),
+                    None => {
+                        self.status = tr("monkey-status-no-device");
+                        return Command::none();
+                    }
+                };
+
+                match MonkeyConfig::parse(
+                    &self.package,
+                    &self.event_count,
+                    &self.throttle,
+                    &self.seed,
+                ) {
+                    Ok(config) => {
+                        self.output.clear();
+                        self.run_id += 1;
+                        self.running = Some((serial, config));
+                        self.status = tr("monkey-status-running");
+                    }
+                    Err(e) => {
+                        self.status = tr_args("monkey-status-error", &[("error", e.to_string())])
+                    }
+                }
+            }
+            MonkeyViewCommand::OnStopClicked => {
+                let serial = match &self.running {
+                    Some((serial, _)) => serial.clone(),
+                    None => return Command::none(),
+                };
+
+                self.status = tr("monkey-status-stopping");
+                return Command::perform(stop_monkey(serial), |ret| {
+                    if let Err(e) = ret {
+                        warn!(?e, "failed to stop monkey");
+                    }
+                    MonkeyViewCommand::Sink
+                });
+            }
+            MonkeyViewCommand::OnXMessage(data) => match data {
+                XMessage::OnDeviceSelected(device) => self.device = Some(device),
+                XMessage::OnKeycodeTestRequested(_)
+                | XMessage::OnNewPreferences(_)
+                | XMessage::OnPrefsFileUpdated
+                | XMessage::OnWindowFocusChanged(_) => {
+                    // do nothing.
+                }
+            },
+            MonkeyViewCommand::ShowNotification(..) => {
+                // handled by App.
+            }
+            MonkeyViewCommand::Sink => {
+                // do nothing.
+            }
+        }
+        Command::none()
+    }
+
+    pub fn subscription(&self) -> Subscription<MonkeyViewCommand> {
+        match &self.running {
+            Some((serial, config)) => monkey(serial.clone(), config.clone(), self.run_id)
+                .map(MonkeyViewCommand::MonkeyRecipeResult),
+            None => Subscription::none(),
+        }
+    }
+
+    pub fn view(&self) -> Element<MonkeyViewCommand> {
+        let mut output = Column::new();
+        for line in &self.output {
+            output = output.push(text(line).size(14));
+        }
+
+        column![
+            text(match &self.device {
+                Some(data) => tr_args("monkey-device", &[("device", data.to_string())]),
+                None => tr("monkey-device-none"),
+            }),
+            text_input(
+                &tr("monkey-package"),
+                &self.package,
+                MonkeyViewCommand::OnPackageChanged
+            ),
+            row![
+                text(tr("monkey-events")).width(Length::Units(70)),
+                text_input(
+                    "500",
+                    &self.event_count,
+                    MonkeyViewCommand::OnEventCountChanged
+                ),
+            ],
+            row![
+                text(tr("monkey-throttle")).width(Length::Units(70)),
+                text_input(
+                    &tr("monkey-throttle-unit"),
+                    &self.throttle,
+                    MonkeyViewCommand::OnThrottleChanged
+                ),
+            ],
+            row![
+                text(tr("monkey-seed")).width(Length::Units(70)),
+                text_input(
+                    &tr("monkey-seed-placeholder"),
+                    &self.seed,
+                    MonkeyViewCommand::OnSeedChanged
+                ),
+            ],
+            match self.running {
+                Some(_) => button(text(tr("monkey-stop")))
+                    .style(iced::theme::Button::Secondary)
+                    .on_press(MonkeyViewCommand::OnStopClicked),
+                None => button(text(tr("monkey-start")))
+                    .style(iced::theme::Button::Secondary)
+                    .on_press(MonkeyViewCommand::OnStartClicked),
+            },
+            text(&self.status),
+            scrollable(output).height(Length::Fill),
+        ]
+        .spacing(8)
+        .into()
+    }
+
+    pub fn view_size() -> (u32, u32) {
+        (300, 580)
+    }
+}
+
+fn notification_command(summary: String, body: String) -> Command<MonkeyViewCommand> {
+    Command::perform(async {}, move |_| {
+        MonkeyViewCommand::ShowNotification(summary, body)
+    })
+}
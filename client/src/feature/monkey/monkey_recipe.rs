@@ -0,0 +1,111 @@
+/*
+ * Copyright 2022 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::function::command_functions::{adb_command, register_child, ChildRegistration};
+use crate::model::monkey::MonkeyConfig;
+use iced::subscription::{unfold, Subscription};
+use std::process::Stdio;
+use tokio::io::{AsyncBufReadExt, BufReader, Lines};
+use tokio::process::{Child, ChildStdout};
+use tracing::{debug, info, warn};
+
+#[derive(Clone, Debug)]
+pub enum MonkeyRecipeEvent {
+    Finished(Option<i32>),
+    Output(String),
+}
+
+enum StreamState {
+    Init(String, MonkeyConfig),
+    Running(Child, ChildRegistration, Lines<BufReader<ChildStdout>>),
+    Finish,
+}
+
+struct MonkeyRecipeType;
+
+/// runs `monkey` on the device and streams its output line by line.
+pub fn monkey(
+    serial: String,
+    config: MonkeyConfig,
+    run_id: u64,
+) -> Subscription<MonkeyRecipeEvent> {
+    unfold(
+        (std::any::TypeId::of::<MonkeyRecipeType>(), run_id),
+        StreamState::Init(serial, config),
+        execute,
+    )
+}
+
+async fn execute(state: StreamState) -> (Option<MonkeyRecipeEvent>, StreamState) {
+    match state {
+        StreamState::Init(serial, config) => {
+            info!(%serial, ?config, "start monkey");
+
+            let mut command = adb_command();
+            command
+                .args(["-s", &serial, "shell"])
+                .args(config.args())
+                .stdout(Stdio::piped())
+                .stderr(Stdio::null());
+
+            match command.spawn() {
+                Ok(mut child) => match child.stdout.take() {
+                    Some(stdout) => {
+                        let registration = register_child(child.id());
+                        (
+                            None,
+                            StreamState::Running(
+                                child,
+                                registration,
+                                BufReader::new(stdout).lines(),
+                            ),
+                        )
+                    }
+                    None => {
+                        warn!("stdout not found");
+                        child.kill().await.ok();
+                        (Some(MonkeyRecipeEvent::Finished(None)), StreamState::Finish)
+                    }
+                },
+                Err(e) => {
+                    warn!(?e, "failed to start monkey");
+                    (Some(MonkeyRecipeEvent::Finished(None)), StreamState::Finish)
+                }
+            }
+        }
+        StreamState::Running(mut child, registration, mut lines) => match lines.next_line().await {
+            Ok(Some(line)) => (
+                Some(MonkeyRecipeEvent::Output(line)),
+                StreamState::Running(child, registration, lines),
+            ),
+            Ok(None) | Err(_) => {
+                let code = match child.wait().await {
+                    Ok(data) => data.code(),
+                    Err(e) => {
+                        warn!(?e, "failed to wait monkey");
+                        None
+                    }
+                };
+                debug!(?code, "monkey exited");
+                (Some(MonkeyRecipeEvent::Finished(code)), StreamState::Finish)
+            }
+        },
+        StreamState::Finish => {
+            debug!("finish");
+            iced::futures::future::pending().await
+        }
+    }
+}
@@ -0,0 +1,134 @@
+/*
+ * Copyright 2022 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::data::preferences_repository::{PreferencesRepository, PreferencesRepositoryImpl};
+use crate::function::adb_functions::send_keyevent;
+use crate::function::command_functions::set_adb_path;
+use crate::model::key_macro::{parse_macro, MacroStep};
+use crate::prelude::*;
+use std::path::Path;
+use std::time::Duration;
+use tracing::info;
+
+/// the same interval as the GUI so that the macro behaves the same.
+const MACRO_KEY_INTERVAL: Duration = Duration::from_millis(200);
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct RunMacroArgs {
+    /// the name of the scheduled macro in the preferences.
+    pub name: String,
+    pub serial: String,
+}
+
+/// parses the arguments after `run-macro` like `<name> --serial X`.
+pub fn parse_run_macro_args(args: &[String]) -> Fallible<RunMacroArgs> {
+    let mut name = None;
+    let mut serial = None;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--serial" | "-s" => {
+                serial = Some(args.next().context("--serial requires a value")?.clone());
+            }
+            data if data.starts_with('-') => anyhow::bail!("unknown option: {}", data),
+            _ if name.is_some() => anyhow::bail!("unexpected argument: {}", arg),
+            _ => name = Some(arg.clone()),
+        }
+    }
+
+    Ok(RunMacroArgs {
+        name: name.context("usage: run-macro <name> --serial <serial>")?,
+        serial: serial.context("usage: run-macro <name> --serial <serial>")?,
+    })
+}
+
+/// runs the macro without the window and prints the progress to stdout.
+///
+/// the keys are sent by `input keyevent` instead of the server since nothing needs to be
+/// held.
+pub async fn run_macro(config_dir: &Path, args: RunMacroArgs) -> Fallible<()> {
+    let prefs = PreferencesRepositoryImpl::new(config_dir.join("preferences.toml"))
+        .load()
+        .await
+        .context("failed to load the preferences")?;
+    set_adb_path(prefs.adb_path.clone());
+
+    let schedule = prefs
+        .scheduled_macros
+        .into_iter()
+        .find(|data| data.name == args.name)
+        .with_context(|| format!("macro not found: {}", args.name))?;
+
+    let source = tokio::fs::read_to_string(&schedule.macro_path)
+        .await
+        .with_context(|| format!("failed to read the macro: {:?}", schedule.macro_path))?;
+    let steps = parse_macro(&source)?;
+
+    info!(name = %args.name, serial = %args.serial, len = steps.len(), "run macro");
+
+    for (index, step) in steps.iter().enumerate() {
+        let progress = format!("{}/{}", index + 1, steps.len());
+        match step {
+            MacroStep::Key(keycode) => {
+                println!("{} {}", progress, keycode);
+                send_keyevent(args.serial.clone(), keycode.clone())
+                    .await
+                    .with_context(|| format!("failed at {}", progress))?;
+                tokio::time::sleep(MACRO_KEY_INTERVAL).await;
+            }
+            MacroStep::Wait(duration) => {
+                println!("{} wait {}ms", progress, duration.as_millis());
+                tokio::time::sleep(*duration).await;
+            }
+        }
+    }
+
+    println!("done ({} steps)", steps.len());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn to_args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|data| data.to_string()).collect()
+    }
+
+    #[test]
+    fn parse_run_macro_args_order() {
+        let expected = RunMacroArgs {
+            name: "smoke".into(),
+            serial: "emulator-5554".into(),
+        };
+        assert_eq!(
+            expected,
+            parse_run_macro_args(&to_args(&["smoke", "--serial", "emulator-5554"])).unwrap()
+        );
+        assert_eq!(
+            expected,
+            parse_run_macro_args(&to_args(&["-s", "emulator-5554", "smoke"])).unwrap()
+        );
+    }
+
+    #[test]
+    fn parse_run_macro_args_invalid() {
+        assert!(parse_run_macro_args(&to_args(&["smoke"])).is_err());
+        assert!(parse_run_macro_args(&to_args(&["smoke", "--serial"])).is_err());
+        assert!(parse_run_macro_args(&to_args(&["smoke", "other", "-s", "x"])).is_err());
+        assert!(parse_run_macro_args(&to_args(&["smoke", "--verbose"])).is_err());
+    }
+}
@@ -14,27 +14,125 @@
  * limitations under the License.
  */
 
-use crate::data::preferences_repository::PreferencesRepository;
-use crate::model::{AppTheme, XMessage};
-use crate::prelude::Fallible;
+use crate::data::i18n::{tr, tr_args};
+use crate::data::preferences_store::PreferencesStore;
+use crate::function::adb_functions::{
+    kill_adb_server, list_key_layouts, retrieve_adb_version, retrieve_key_layout, start_adb_server,
+};
+use crate::function::command_functions::{describe_adb_error, spawn_detached};
+use crate::function::release_functions::fetch_latest_release;
+use crate::model::api::{ApiScope, ApiTokens};
+use crate::model::device_profile::DeviceProfile;
+use crate::model::key_layout::{
+    apply_key_map_proposals, parse_key_layout, propose_key_map, KeyMapProposal,
+};
+use crate::model::keycode::{is_valid_keycode, KEYCODE_NAMES};
+use crate::model::language::Language;
+use crate::model::layout::{ButtonSize, ButtonSpec, LaunchShortcut, LayoutProfile, SectionId};
+use crate::model::release::Release;
+use crate::model::screen_record::parse_time_limit;
+use crate::model::send_event_key::SendEventKey;
+use crate::model::{AppTheme, CustomKey, InvalidKeycode, KeyMap, XMessage};
+use crate::prelude::*;
 use iced::theme::Theme;
-use iced::widget::{button, column, pick_list, row};
-use iced::{Command, Element};
+use iced::widget::{
+    button, checkbox, column, pick_list, row, scrollable, text, text_input, Column,
+};
+use iced::{Command, Element, Length};
 use std::fmt::{Display, Formatter};
 use std::path::PathBuf;
-use std::sync::Arc;
-use tokio::sync::Mutex;
-use tracing::{debug, warn};
+use tracing::{debug, info, warn};
 
 pub struct ViewState {
+    adb_path: String,
+    /// the result of the last action of the adb server.
+    adb_server_status: Option<String>,
+    api_port: String,
+    /// the tokens of the running session, which the clients of the api server need.
+    api_tokens: ApiTokens,
+    check_updates: bool,
     config_file_path: PathBuf,
+    confirm_power_key: bool,
+    confirm_unknown_devices: bool,
+    custom_key_keycode: Option<&'static str>,
+    custom_key_label: String,
+    custom_key_package: String,
+    custom_keys: Vec<CustomKey>,
+    /// the language of the font that was loaded on startup.
+    font_language: Language,
+    hold_buttons: bool,
+    invalid_keycodes: Vec<InvalidKeycode>,
+    /// the serial of the selected device that has the shell to read the key layouts.
+    key_layout_device: Option<String>,
+    key_layout_proposals: Vec<KeyMapProposal>,
+    key_layout_selected: Option<String>,
+    key_layout_status: Option<String>,
+    key_layouts: Vec<String>,
+    key_map: KeyMap,
+    key_map_profile: Option<String>,
+    key_map_profile_name: String,
+    key_map_profiles: Vec<String>,
+    language: Language,
+    /// the release that is newer than the running client.
+    latest_release: Option<Release>,
+    layout_profile_selected: Option<String>,
+    layout_profiles: Vec<LayoutProfile>,
+    notification: bool,
+    profile_import_status: Option<String>,
+    /// checks once per launch.
+    release_checked: bool,
+    recording_bit_rate: String,
+    recording_dir: String,
+    recording_time_limit: String,
+    shortcut_label: String,
+    shortcut_target: String,
     theme: Theme,
 }
 
 impl ViewState {
-    pub fn new(config_file_path: PathBuf, theme: Theme) -> Self {
+    pub fn new(
+        config_file_path: PathBuf,
+        theme: Theme,
+        font_language: Language,
+        api_tokens: ApiTokens,
+    ) -> Self {
         Self {
+            adb_path: "".into(),
+            adb_server_status: None,
+            api_port: "".into(),
+            api_tokens,
+            check_updates: true,
             config_file_path,
+            confirm_power_key: true,
+            confirm_unknown_devices: true,
+            custom_key_keycode: None,
+            custom_key_label: "".into(),
+            custom_key_package: "".into(),
+            custom_keys: vec![],
+            font_language,
+            hold_buttons: false,
+            invalid_keycodes: vec![],
+            key_layout_device: None,
+            key_layout_proposals: vec![],
+            key_layout_selected: None,
+            key_layout_status: None,
+            key_layouts: vec![],
+            key_map: Default::default(),
+            key_map_profile: None,
+            key_map_profile_name: "".into(),
+            key_map_profiles: vec![],
+            language: font_language,
+            latest_release: None,
+            layout_profile_selected: None,
+            layout_profiles: vec![],
+            notification: true,
+            profile_import_status: None,
+            release_checked: false,
+            recording_bit_rate: "".into(),
+            recording_dir: "".into(),
+            recording_time_limit: "".into(),
+            shortcut_label: "".into(),
+            shortcut_target: "".into(),
             theme,
         }
     }
@@ -42,19 +140,69 @@ impl ViewState {
 
 #[derive(Clone, Debug)]
 pub enum SettingsViewCommand {
+    AdbServerActionFinished(String),
+    /// the serial, the file name and the text of the key layout.
+    KeyLayoutLoaded(String, String, Result<String, String>),
+    KeyLayoutsListed(String, Result<Vec<String>, String>),
+    LatestReleaseFetched(Result<Release, String>),
+    OnAdbKillServerClicked,
+    OnAdbPathBrowseClicked,
+    OnAdbPathChanged(String),
+    OnAdbPathPicked(Option<PathBuf>),
+    OnAdbPathSubmitted,
+    OnAdbStartServerClicked,
+    OnAdbVersionClicked,
+    OnApiPortChanged(String),
+    OnApiTokenCopyClicked(ApiScope),
+    OnCheckUpdatesToggled(bool),
+    OnConfirmPowerKeyToggled(bool),
+    OnConfirmUnknownDevicesToggled(bool),
+    OnCustomKeyAddClicked,
+    OnCustomKeyKeycodeSelected(&'static str),
+    OnCustomKeyLabelChanged(String),
+    OnCustomKeyMoved(usize, isize),
+    OnCustomKeyPackageChanged(String),
+    OnCustomKeyRemoveClicked(usize),
+    OnHoldButtonsToggled(bool),
+    OnKeyLayoutListClicked,
+    OnKeyLayoutSaveClicked,
+    OnKeyLayoutSelected(String),
+    OnKeyMapChanged(SendEventKey, &'static str),
+    OnKeyMapProfileDeleteClicked,
+    OnKeyMapProfileNameChanged(String),
+    OnKeyMapProfileSaveClicked,
+    OnKeyMapProfileSelected(String),
+    OnKeycodeSelected(&'static str),
+    OnLanguageSelected(Language),
+    OnLayoutProfileSelected(String),
+    OnLayoutSectionMoved(SectionId, isize),
+    OnLayoutSectionSizeSelected(SectionId, ButtonSize),
+    OnLayoutSectionToggled(SectionId, bool),
+    OnNotificationToggled(bool),
     OnThemeSelected(AppTheme),
     OnOpenKeycodeReferencesButtonClicked,
     OnOpenPrefsButtonClicked,
     OnOpenPrefsDirButtonClicked,
+    OnProfileCodeRead(Option<String>),
+    OnProfileImportClicked,
+    OnReleasePageClicked,
+    OnRecordingBitRateChanged(String),
+    OnRecordingDirBrowseClicked,
+    OnRecordingDirChanged(String),
+    OnRecordingDirPicked(Option<PathBuf>),
+    OnRecordingDirSubmitted,
+    OnRecordingTimeLimitChanged(String),
+    OnShortcutLabelChanged(String),
+    OnShortcutPinClicked,
+    OnShortcutTargetChanged(String),
+    OnShortcutUnpinClicked(usize),
     OnXMessage(XMessage),
     SendXMessage(XMessage),
     Sink,
 }
 
 pub trait SettingsView {
-    type PrefsRepo: PreferencesRepository + 'static;
-
-    fn get_prefs_repo(&self) -> Arc<Mutex<Self::PrefsRepo>>;
+    fn get_prefs_store(&self) -> &PreferencesStore;
 
     fn get_state(&self) -> &ViewState;
 
@@ -62,29 +210,499 @@ pub trait SettingsView {
 
     fn update(&mut self, command: SettingsViewCommand) -> Command<SettingsViewCommand> {
         match command {
-            SettingsViewCommand::OnThemeSelected(theme) => {
+            SettingsViewCommand::AdbServerActionFinished(status) => {
+                info!(%status, "adb server action finished");
+                self.get_state_mut().adb_server_status = Some(status);
+            }
+            SettingsViewCommand::KeyLayoutLoaded(serial, name, ret) => {
+                let state = self.get_state_mut();
+                // the response of the previous device or file.
+                if state.key_layout_device.as_ref() != Some(&serial)
+                    || state.key_layout_selected.as_ref() != Some(&name)
+                {
+                    return Command::none();
+                }
+
+                match ret {
+                    Ok(data) => {
+                        state.key_layout_proposals = propose_key_map(&parse_key_layout(&data));
+                        state.key_layout_status = Some(tr_args(
+                            "settings-key-layout-found",
+                            &[
+                                ("count", state.key_layout_proposals.len().to_string()),
+                                ("name", name),
+                            ],
+                        ));
+                    }
+                    Err(e) => {
+                        state.key_layout_status =
+                            Some(tr_args("settings-key-layout-failed", &[("error", e)]));
+                    }
+                }
+            }
+            SettingsViewCommand::KeyLayoutsListed(serial, ret) => {
+                let state = self.get_state_mut();
+                if state.key_layout_device.as_ref() != Some(&serial) {
+                    return Command::none();
+                }
+
+                match ret {
+                    Ok(data) => {
+                        state.key_layouts = data;
+                        state.key_layout_status = None;
+                    }
+                    Err(e) => {
+                        state.key_layout_status =
+                            Some(tr_args("settings-key-layout-failed", &[("error", e)]));
+                    }
+                }
+            }
+            SettingsViewCommand::OnAdbKillServerClicked => {
+                self.get_state_mut().adb_server_status = Some(tr("settings-adb-server-running"));
+                return Command::perform(kill_adb_server(), |ret| {
+                    SettingsViewCommand::AdbServerActionFinished(match ret {
+                        Ok(_) => tr("settings-adb-server-killed"),
+                        Err(e) => describe_adb_error(&e),
+                    })
+                });
+            }
+            SettingsViewCommand::OnAdbStartServerClicked => {
+                self.get_state_mut().adb_server_status = Some(tr("settings-adb-server-running"));
+                return Command::perform(start_adb_server(), |ret| {
+                    SettingsViewCommand::AdbServerActionFinished(match ret {
+                        Ok(_) => tr("settings-adb-server-started"),
+                        Err(e) => describe_adb_error(&e),
+                    })
+                });
+            }
+            SettingsViewCommand::OnAdbVersionClicked => {
+                self.get_state_mut().adb_server_status = Some(tr("settings-adb-server-running"));
+                return Command::perform(retrieve_adb_version(), |ret| {
+                    SettingsViewCommand::AdbServerActionFinished(match ret {
+                        Ok(data) => tr_args(
+                            "settings-adb-server-version",
+                            &[("version", data.to_string())],
+                        ),
+                        Err(e) => describe_adb_error(&e),
+                    })
+                });
+            }
+            SettingsViewCommand::OnAdbPathBrowseClicked => {
+                return Command::perform(pick_adb_path(), SettingsViewCommand::OnAdbPathPicked);
+            }
+            SettingsViewCommand::OnAdbPathChanged(data) => {
+                self.get_state_mut().adb_path = data;
+            }
+            SettingsViewCommand::OnAdbPathPicked(data) => {
+                if let Some(data) = data {
+                    self.get_state_mut().adb_path = data.to_string_lossy().into_owned();
+                    self.get_prefs_store()
+                        .update(move |prefs| prefs.adb_path = Some(data));
+                }
+            }
+            SettingsViewCommand::OnAdbPathSubmitted => {
+                // clears the path to find adb again if empty.
+                let path = match self.get_state().adb_path.trim() {
+                    "" => None,
+                    data => Some(PathBuf::from(data)),
+                };
+                self.get_prefs_store()
+                    .update(move |prefs| prefs.adb_path = path);
+            }
+            SettingsViewCommand::OnConfirmPowerKeyToggled(enabled) => {
+                self.get_state_mut().confirm_power_key = enabled;
+                self.get_prefs_store()
+                    .update(move |prefs| prefs.confirm_power_key = enabled);
+            }
+            SettingsViewCommand::OnConfirmUnknownDevicesToggled(enabled) => {
+                self.get_state_mut().confirm_unknown_devices = enabled;
+                self.get_prefs_store()
+                    .update(move |prefs| prefs.confirm_unknown_devices = enabled);
+            }
+            SettingsViewCommand::OnCustomKeyAddClicked => {
+                let state = self.get_state_mut();
+                let keycode = match state.custom_key_keycode.take() {
+                    Some(data) => data,
+                    None => return Command::none(),
+                };
+                let custom_key = CustomKey {
+                    label: std::mem::take(&mut state.custom_key_label),
+                    keycode: keycode.into(),
+                    package: match std::mem::take(&mut state.custom_key_package).trim() {
+                        "" => None,
+                        data => Some(data.into()),
+                    },
+                    long_keycode: None,
+                };
+                self.get_prefs_store()
+                    .update(move |prefs| prefs.custom_keys.push(custom_key));
+            }
+            SettingsViewCommand::OnCustomKeyKeycodeSelected(data) => {
+                self.get_state_mut().custom_key_keycode = Some(data);
+            }
+            SettingsViewCommand::OnCustomKeyLabelChanged(data) => {
+                self.get_state_mut().custom_key_label = data;
+            }
+            SettingsViewCommand::OnCustomKeyMoved(index, offset) => {
+                self.get_prefs_store()
+                    .update(move |prefs| prefs.move_custom_key(index, offset));
+            }
+            SettingsViewCommand::OnCustomKeyPackageChanged(data) => {
+                self.get_state_mut().custom_key_package = data;
+            }
+            SettingsViewCommand::OnCustomKeyRemoveClicked(index) => {
+                self.get_prefs_store().update(move |prefs| {
+                    if index < prefs.custom_keys.len() {
+                        prefs.custom_keys.remove(index);
+                    }
+                });
+            }
+            SettingsViewCommand::OnHoldButtonsToggled(enabled) => {
+                self.get_state_mut().hold_buttons = enabled;
+                self.get_prefs_store()
+                    .update(move |prefs| prefs.hold_buttons = enabled);
+            }
+            SettingsViewCommand::OnKeyLayoutListClicked => {
+                let state = self.get_state_mut();
+                let serial = match &state.key_layout_device {
+                    Some(data) => data.clone(),
+                    None => return Command::none(),
+                };
+                state.key_layout_status = Some(tr("settings-key-layout-loading"));
+                return Command::perform(list_key_layouts(serial.clone()), move |ret| {
+                    if let Err(e) = &ret {
+                        warn!(?e, "failed to list the key layouts");
+                    }
+                    SettingsViewCommand::KeyLayoutsListed(
+                        serial.clone(),
+                        ret.map_err(|e| describe_adb_error(&e)),
+                    )
+                });
+            }
+            SettingsViewCommand::OnKeyLayoutSaveClicked => {
+                let state = self.get_state();
+                let name = match &state.key_layout_selected {
+                    Some(data) if !state.key_layout_proposals.is_empty() => {
+                        data.trim_end_matches(".kl").to_string()
+                    }
+                    _ => return Command::none(),
+                };
+                let proposals = state.key_layout_proposals.clone();
+                self.get_prefs_store().update(move |prefs| {
+                    apply_key_map_proposals(&mut prefs.key_map, &proposals);
+                    prefs.save_key_map_profile(name);
+                });
+            }
+            SettingsViewCommand::OnKeyLayoutSelected(name) => {
+                let state = self.get_state_mut();
+                let serial = match &state.key_layout_device {
+                    Some(data) => data.clone(),
+                    None => return Command::none(),
+                };
+                state.key_layout_selected = Some(name.clone());
+                state.key_layout_proposals.clear();
+                state.key_layout_status = Some(tr("settings-key-layout-loading"));
                 return Command::perform(
-                    save_theme(self.get_prefs_repo(), theme),
-                    |data| match data {
-                        Ok(_) => SettingsViewCommand::SendXMessage(XMessage::OnPrefsFileUpdated),
-                        Err(e) => {
-                            warn!(?e, "failed to save theme");
-                            SettingsViewCommand::Sink
+                    retrieve_key_layout(serial.clone(), name.clone()),
+                    move |ret| {
+                        if let Err(e) = &ret {
+                            warn!(?e, "failed to retrieve the key layout");
                         }
+                        SettingsViewCommand::KeyLayoutLoaded(
+                            serial.clone(),
+                            name.clone(),
+                            ret.map_err(|e| describe_adb_error(&e)),
+                        )
                     },
                 );
             }
+            SettingsViewCommand::OnKeyMapChanged(key, keycode) => {
+                self.get_prefs_store().update(move |prefs| {
+                    if let Some(data) = prefs.key_map.keycode_mut(&key) {
+                        *data = keycode.into();
+                    }
+                    if let Some(name) = prefs.key_map_profile.clone() {
+                        prefs.save_key_map_profile(name);
+                    }
+                });
+            }
+            SettingsViewCommand::OnKeyMapProfileDeleteClicked => {
+                let name = match &self.get_state().key_map_profile {
+                    Some(data) => data.clone(),
+                    None => return Command::none(),
+                };
+                self.get_prefs_store()
+                    .update(move |prefs| prefs.remove_key_map_profile(&name));
+            }
+            SettingsViewCommand::OnKeyMapProfileNameChanged(data) => {
+                self.get_state_mut().key_map_profile_name = data;
+            }
+            SettingsViewCommand::OnKeyMapProfileSaveClicked => {
+                let name = self.get_state().key_map_profile_name.trim().to_owned();
+                if name.is_empty() {
+                    return Command::none();
+                }
+                self.get_state_mut().key_map_profile_name.clear();
+                self.get_prefs_store()
+                    .update(move |prefs| prefs.save_key_map_profile(name));
+            }
+            SettingsViewCommand::OnKeyMapProfileSelected(name) => {
+                self.get_prefs_store()
+                    .update(move |prefs| prefs.select_key_map_profile(&name));
+            }
+            SettingsViewCommand::OnLayoutProfileSelected(name) => {
+                self.get_state_mut().layout_profile_selected = Some(name);
+            }
+            SettingsViewCommand::OnLayoutSectionMoved(id, offset) => {
+                update_layout_profile(self.get_prefs_store(), self.get_state(), move |profile| {
+                    profile.move_section(id, offset)
+                });
+            }
+            SettingsViewCommand::OnLayoutSectionSizeSelected(id, size) => {
+                update_layout_profile(self.get_prefs_store(), self.get_state(), move |profile| {
+                    profile.section_mut(id).button_size = size
+                });
+            }
+            SettingsViewCommand::OnLayoutSectionToggled(id, visible) => {
+                update_layout_profile(self.get_prefs_store(), self.get_state(), move |profile| {
+                    profile.section_mut(id).visible = visible
+                });
+            }
+            SettingsViewCommand::LatestReleaseFetched(data) => match data {
+                Ok(release) if release.is_newer_than_current() => {
+                    info!(version = %release.version, "new release found");
+                    self.get_state_mut().latest_release = Some(release);
+                }
+                Ok(release) => debug!(version = %release.version, "up to date"),
+                Err(e) => warn!(%e, "failed to check the latest release"),
+            },
+            SettingsViewCommand::OnCheckUpdatesToggled(enabled) => {
+                self.get_state_mut().check_updates = enabled;
+                self.get_prefs_store()
+                    .update(move |prefs| prefs.check_updates = enabled);
+            }
+            SettingsViewCommand::OnNotificationToggled(enabled) => {
+                self.get_state_mut().notification = enabled;
+                self.get_prefs_store()
+                    .update(move |prefs| prefs.notification = enabled);
+            }
+            SettingsViewCommand::OnLanguageSelected(language) => {
+                self.get_state_mut().language = language;
+                self.get_prefs_store()
+                    .update(move |prefs| prefs.language = language);
+            }
+            SettingsViewCommand::OnThemeSelected(theme) => {
+                self.get_prefs_store()
+                    .update(move |prefs| prefs.theme = theme);
+            }
             SettingsViewCommand::OnOpenPrefsButtonClicked => open_prefs(self.get_state()),
             SettingsViewCommand::OnOpenPrefsDirButtonClicked => {
                 open_prefs_directory(self.get_state())
             }
             SettingsViewCommand::OnOpenKeycodeReferencesButtonClicked => open_keycode_references(),
+            SettingsViewCommand::OnReleasePageClicked => {
+                if let Some(release) = &self.get_state().latest_release {
+                    open_release_page(&release.url);
+                }
+            }
+            SettingsViewCommand::OnProfileImportClicked => {
+                return iced::clipboard::read(SettingsViewCommand::OnProfileCodeRead);
+            }
+            SettingsViewCommand::OnApiPortChanged(data) => {
+                // an empty value disables the api server.
+                let port = match data.trim() {
+                    "" => Some(None),
+                    value => value.parse::<u16>().ok().filter(|data| *data > 0).map(Some),
+                };
+                self.get_state_mut().api_port = data;
+                if let Some(port) = port {
+                    self.get_prefs_store()
+                        .update(move |prefs| prefs.api_port = port);
+                }
+            }
+            SettingsViewCommand::OnApiTokenCopyClicked(scope) => {
+                let tokens = &self.get_state().api_tokens;
+                let token = match scope {
+                    ApiScope::Control => &tokens.control,
+                    ApiScope::Observe => &tokens.observe,
+                };
+                return iced::clipboard::write(token.as_str().into());
+            }
+            SettingsViewCommand::OnRecordingBitRateChanged(data) => {
+                // an empty value means the device default.
+                let bit_rate = match data.trim() {
+                    "" => Some(None),
+                    value => value.parse::<u32>().ok().filter(|data| *data > 0).map(Some),
+                };
+                self.get_state_mut().recording_bit_rate = data;
+                if let Some(bit_rate) = bit_rate {
+                    self.get_prefs_store()
+                        .update(move |prefs| prefs.recording_bit_rate_mbps = bit_rate);
+                }
+            }
+            SettingsViewCommand::OnRecordingDirBrowseClicked => {
+                return Command::perform(
+                    pick_recording_dir(),
+                    SettingsViewCommand::OnRecordingDirPicked,
+                );
+            }
+            SettingsViewCommand::OnRecordingDirChanged(data) => {
+                self.get_state_mut().recording_dir = data;
+            }
+            SettingsViewCommand::OnRecordingDirPicked(data) => {
+                if let Some(data) = data {
+                    self.get_state_mut().recording_dir = data.to_string_lossy().into_owned();
+                    self.get_prefs_store()
+                        .update(move |prefs| prefs.recording_dir = Some(data));
+                }
+            }
+            SettingsViewCommand::OnRecordingDirSubmitted => {
+                let dir = match self.get_state().recording_dir.trim() {
+                    "" => None,
+                    data => Some(PathBuf::from(data)),
+                };
+                self.get_prefs_store()
+                    .update(move |prefs| prefs.recording_dir = dir);
+            }
+            SettingsViewCommand::OnRecordingTimeLimitChanged(data) => {
+                // keeps the previous value until valid.
+                if let Ok(time_limit) = parse_time_limit(&data) {
+                    self.get_prefs_store()
+                        .update(move |prefs| prefs.recording_time_limit = time_limit);
+                }
+                self.get_state_mut().recording_time_limit = data;
+            }
+            SettingsViewCommand::OnProfileCodeRead(data) => {
+                let ret = data
+                    .context("clipboard is empty")
+                    .and_then(|data| DeviceProfile::decode(&data));
+                let status = match ret {
+                    Ok(profile) => {
+                        let status = tr_args(
+                            "settings-import-profile-imported",
+                            &[
+                                ("layout", profile.layout.name.clone()),
+                                ("serial", profile.serial.clone()),
+                            ],
+                        );
+                        self.get_prefs_store()
+                            .update(move |prefs| prefs.import_device_profile(profile));
+                        status
+                    }
+                    Err(e) => {
+                        warn!(?e, "failed to import the profile");
+                        tr_args(
+                            "settings-import-profile-failed",
+                            &[("error", e.to_string())],
+                        )
+                    }
+                };
+                self.get_state_mut().profile_import_status = Some(status);
+            }
+            SettingsViewCommand::OnKeycodeSelected(data) => {
+                return iced::clipboard::write(data.into());
+            }
+            SettingsViewCommand::OnShortcutLabelChanged(data) => {
+                self.get_state_mut().shortcut_label = data;
+            }
+            SettingsViewCommand::OnShortcutTargetChanged(data) => {
+                self.get_state_mut().shortcut_target = data;
+            }
+            SettingsViewCommand::OnShortcutPinClicked => {
+                let state = self.get_state_mut();
+                let shortcut = LaunchShortcut {
+                    label: std::mem::take(&mut state.shortcut_label),
+                    target: std::mem::take(&mut state.shortcut_target),
+                };
+                update_layout_profile(self.get_prefs_store(), self.get_state(), move |profile| {
+                    if !profile.pin_shortcut(shortcut) {
+                        warn!("too many shortcuts");
+                    }
+                });
+            }
+            SettingsViewCommand::OnShortcutUnpinClicked(index) => {
+                update_layout_profile(self.get_prefs_store(), self.get_state(), move |profile| {
+                    if index < profile.shortcuts.len() {
+                        profile.shortcuts.remove(index);
+                    }
+                });
+            }
             SettingsViewCommand::OnXMessage(data) => match data {
-                XMessage::OnPrefsFileUpdated => {
+                XMessage::OnDeviceSelected(device) => {
+                    let serial = Some(device.serial.clone())
+                        .filter(|_| device.kind.capabilities().supports_shell);
+                    let state = self.get_state_mut();
+                    if state.key_layout_device != serial {
+                        state.key_layout_device = serial;
+                        state.key_layout_proposals.clear();
+                        state.key_layout_selected = None;
+                        state.key_layout_status = None;
+                        state.key_layouts.clear();
+                    }
+                }
+                XMessage::OnKeycodeTestRequested(_)
+                | XMessage::OnPrefsFileUpdated
+                | XMessage::OnWindowFocusChanged(_) => {
                     // do nothing.
                 }
                 XMessage::OnNewPreferences(prefs) => {
-                    self.get_state_mut().theme = (&prefs.theme).into();
+                    let state = self.get_state_mut();
+                    state.adb_path = prefs
+                        .adb_path
+                        .as_ref()
+                        .map(|data| data.to_string_lossy().into_owned())
+                        .unwrap_or_default();
+                    state.api_port = prefs
+                        .api_port
+                        .map(|data| data.to_string())
+                        .unwrap_or_default();
+                    state.check_updates = prefs.check_updates;
+                    state.confirm_power_key = prefs.confirm_power_key;
+                    state.confirm_unknown_devices = prefs.confirm_unknown_devices;
+                    state.custom_keys = prefs.custom_keys.clone();
+                    state.hold_buttons = prefs.hold_buttons;
+                    state.invalid_keycodes = prefs.invalid_keycodes();
+                    state.key_map = prefs.key_map.clone();
+                    state.key_map_profile = prefs.key_map_profile.clone();
+                    state.key_map_profiles = prefs
+                        .key_map_profiles
+                        .iter()
+                        .map(|data| data.name.clone())
+                        .collect();
+                    state.language = prefs.language;
+                    state.notification = prefs.notification;
+                    state.recording_bit_rate = prefs
+                        .recording_bit_rate_mbps
+                        .map(|data| data.to_string())
+                        .unwrap_or_default();
+                    state.recording_dir = prefs
+                        .recording_dir
+                        .as_ref()
+                        .map(|data| data.to_string_lossy().into_owned())
+                        .unwrap_or_default();
+                    state.recording_time_limit = prefs.recording_time_limit.as_secs().to_string();
+                    state.theme = (&prefs.theme).into();
+                    state.layout_profiles = prefs.layout_profiles.clone();
+                    let selected_exists = state
+                        .layout_profile_selected
+                        .as_ref()
+                        .map_or(false, |name| {
+                            state.layout_profiles.iter().any(|data| &data.name == name)
+                        });
+                    if !selected_exists {
+                        state.layout_profile_selected =
+                            state.layout_profiles.first().map(|data| data.name.clone());
+                    }
+
+                    // waits for the preferences to respect the opt-out.
+                    if prefs.check_updates && !state.release_checked {
+                        state.release_checked = true;
+                        return Command::perform(fetch_latest_release(), |ret| {
+                            SettingsViewCommand::LatestReleaseFetched(
+                                ret.map_err(|e| format!("{:#}", e)),
+                            )
+                        });
+                    }
                 }
             },
             SettingsViewCommand::SendXMessage(_) | SettingsViewCommand::Sink => {
@@ -96,67 +714,604 @@ pub trait SettingsView {
     }
 
     fn view(&self) -> Element<SettingsViewCommand> {
+        let update_banner: Element<_> = match &self.get_state().latest_release {
+            Some(release) => row![
+                text(tr_args(
+                    "settings-update-available",
+                    &[("version", release.version.to_string())],
+                ))
+                .size(14)
+                .width(Length::Fill),
+                button(text(tr("settings-update-open")))
+                    .on_press(SettingsViewCommand::OnReleasePageClicked),
+            ]
+            .spacing(4)
+            .align_items(iced::alignment::Alignment::Center)
+            .into(),
+            None => Column::new().into(),
+        };
+
         column![
-            button("Reload preferences")
+            update_banner,
+            button(text(tr("settings-reload")))
                 .width(292.into())
                 .style(iced::theme::Button::Secondary)
                 .on_press(SettingsViewCommand::SendXMessage(
                     XMessage::OnPrefsFileUpdated
                 )),
-            button("Open preferences directory")
+            button(text(tr("settings-open-dir")))
                 .width(292.into())
                 .style(iced::theme::Button::Secondary)
                 .on_press(SettingsViewCommand::OnOpenPrefsDirButtonClicked),
-            button("Open KeyCode references")
+            button(text(tr("settings-keycode-references")))
                 .width(292.into())
                 .style(iced::theme::Button::Secondary)
                 .on_press(SettingsViewCommand::OnOpenKeycodeReferencesButtonClicked),
             row![
-                "Theme: ",
+                text(tr("settings-copy-keycode")),
+                pick_list(
+                    &KEYCODE_NAMES[..],
+                    None,
+                    SettingsViewCommand::OnKeycodeSelected,
+                ),
+            ]
+            .spacing(4)
+            .align_items(iced::alignment::Alignment::Center),
+            row![
+                text(tr("settings-theme")),
                 pick_list(
                     &[AppTheme::Light, AppTheme::Dark][..],
                     Some((&self.get_state().theme).into()),
                     SettingsViewCommand::OnThemeSelected,
                 ),
             ]
+            .spacing(4)
             .align_items(iced::alignment::Alignment::Center),
+            row![
+                text(tr("settings-language")),
+                pick_list(
+                    &Language::ALL[..],
+                    Some(self.get_state().language),
+                    SettingsViewCommand::OnLanguageSelected,
+                ),
+                text(
+                    if self.get_state().language == self.get_state().font_language {
+                        "".into()
+                    } else {
+                        tr("settings-language-font")
+                    }
+                )
+                .size(14),
+            ]
+            .spacing(4)
+            .align_items(iced::alignment::Alignment::Center),
+            row![
+                text(tr("settings-adb")),
+                text_input(
+                    &tr("settings-adb-placeholder"),
+                    &self.get_state().adb_path,
+                    SettingsViewCommand::OnAdbPathChanged,
+                )
+                .on_submit(SettingsViewCommand::OnAdbPathSubmitted),
+                button(text(tr("settings-browse")))
+                    .style(iced::theme::Button::Secondary)
+                    .on_press(SettingsViewCommand::OnAdbPathBrowseClicked),
+            ]
+            .spacing(4)
+            .align_items(iced::alignment::Alignment::Center),
+            // restarts the wedged daemon without the terminal.
+            row![
+                text(tr("settings-adb-server")),
+                button(text(tr("settings-adb-server-kill")))
+                    .style(iced::theme::Button::Secondary)
+                    .on_press(SettingsViewCommand::OnAdbKillServerClicked),
+                button(text(tr("settings-adb-server-start")))
+                    .style(iced::theme::Button::Secondary)
+                    .on_press(SettingsViewCommand::OnAdbStartServerClicked),
+                button(text(tr("settings-adb-server-version-button")))
+                    .style(iced::theme::Button::Secondary)
+                    .on_press(SettingsViewCommand::OnAdbVersionClicked),
+                text(
+                    self.get_state()
+                        .adb_server_status
+                        .as_deref()
+                        .unwrap_or_default()
+                )
+                .size(14),
+            ]
+            .spacing(4)
+            .align_items(iced::alignment::Alignment::Center),
+            row![
+                text(tr("settings-recording")),
+                text_input(
+                    &tr("settings-recording-secs"),
+                    &self.get_state().recording_time_limit,
+                    SettingsViewCommand::OnRecordingTimeLimitChanged,
+                )
+                .width(Length::Units(60)),
+                text(tr("settings-recording-secs-unit")),
+                text_input(
+                    &tr("settings-recording-bit-rate"),
+                    &self.get_state().recording_bit_rate,
+                    SettingsViewCommand::OnRecordingBitRateChanged,
+                )
+                .width(Length::Units(60)),
+                text(tr("settings-recording-bit-rate-unit")),
+            ]
+            .spacing(4)
+            .align_items(iced::alignment::Alignment::Center),
+            row![
+                text(tr("settings-api-port")),
+                text_input(
+                    &tr("settings-api-port-placeholder"),
+                    &self.get_state().api_port,
+                    SettingsViewCommand::OnApiPortChanged,
+                )
+                .width(Length::Units(60)),
+                text(tr("settings-api-token")),
+                text(self.get_state().api_tokens.control.as_str()),
+                button(text(tr("settings-api-token-copy"))).on_press(
+                    SettingsViewCommand::OnApiTokenCopyClicked(ApiScope::Control)
+                ),
+            ]
+            .spacing(4)
+            .align_items(iced::alignment::Alignment::Center),
+            row![
+                text(tr("settings-api-observer-token")),
+                text(self.get_state().api_tokens.observe.as_str()),
+                button(text(tr("settings-api-token-copy"))).on_press(
+                    SettingsViewCommand::OnApiTokenCopyClicked(ApiScope::Observe)
+                ),
+            ]
+            .spacing(4)
+            .align_items(iced::alignment::Alignment::Center),
+            row![
+                text(tr("settings-save-to")),
+                text_input(
+                    &tr("settings-save-to-placeholder"),
+                    &self.get_state().recording_dir,
+                    SettingsViewCommand::OnRecordingDirChanged,
+                )
+                .on_submit(SettingsViewCommand::OnRecordingDirSubmitted),
+                button(text(tr("settings-browse")))
+                    .style(iced::theme::Button::Secondary)
+                    .on_press(SettingsViewCommand::OnRecordingDirBrowseClicked),
+            ]
+            .spacing(4)
+            .align_items(iced::alignment::Alignment::Center),
+            checkbox(
+                tr("settings-notification"),
+                self.get_state().notification,
+                SettingsViewCommand::OnNotificationToggled,
+            ),
+            checkbox(
+                tr("settings-confirm-devices"),
+                self.get_state().confirm_unknown_devices,
+                SettingsViewCommand::OnConfirmUnknownDevicesToggled,
+            ),
+            checkbox(
+                tr("settings-confirm-power"),
+                self.get_state().confirm_power_key,
+                SettingsViewCommand::OnConfirmPowerKeyToggled,
+            ),
+            checkbox(
+                tr("settings-hold-buttons"),
+                self.get_state().hold_buttons,
+                SettingsViewCommand::OnHoldButtonsToggled,
+            ),
+            checkbox(
+                tr("settings-check-updates"),
+                self.get_state().check_updates,
+                SettingsViewCommand::OnCheckUpdatesToggled,
+            ),
+            row![
+                text(tr("settings-layout")),
+                pick_list(
+                    self.get_state()
+                        .layout_profiles
+                        .iter()
+                        .map(|data| data.name.clone())
+                        .collect::<Vec<_>>(),
+                    self.get_state().layout_profile_selected.clone(),
+                    SettingsViewCommand::OnLayoutProfileSelected,
+                ),
+            ]
+            .spacing(4)
+            .align_items(iced::alignment::Alignment::Center),
+            row![
+                button(text(tr("settings-import-profile")))
+                    .style(iced::theme::Button::Secondary)
+                    .on_press(SettingsViewCommand::OnProfileImportClicked),
+                text(
+                    self.get_state()
+                        .profile_import_status
+                        .as_deref()
+                        .unwrap_or_default()
+                )
+                .size(14),
+            ]
+            .spacing(4)
+            .align_items(iced::alignment::Alignment::Center),
+            layout_sections_view(self.get_state()),
+            shortcuts_view(self.get_state()),
+            custom_keys_view(self.get_state()),
+            key_map_view(self.get_state()),
         ]
         .spacing(8)
         .into()
     }
 
     fn view_size(&self) -> (u32, u32) {
-        (300, 260)
+        (300, 1420)
+    }
+}
+
+fn layout_sections_view(state: &ViewState) -> Element<SettingsViewCommand> {
+    let profile = match find_layout_profile(state) {
+        Some(data) => data,
+        None => return Column::new().into(),
+    };
+
+    let mut view = Column::new().spacing(4);
+    for section in profile.sections_with_defaults() {
+        let id = section.id;
+        view = view.push(
+            row![
+                checkbox(id.to_string(), section.visible, move |visible| {
+                    SettingsViewCommand::OnLayoutSectionToggled(id, visible)
+                })
+                .width(Length::Fill),
+                pick_list(
+                    &ButtonSize::ALL[..],
+                    Some(section.button_size),
+                    move |size| { SettingsViewCommand::OnLayoutSectionSizeSelected(id, size) }
+                ),
+                button(text(tr("settings-up")))
+                    .style(iced::theme::Button::Secondary)
+                    .on_press(SettingsViewCommand::OnLayoutSectionMoved(id, -1)),
+                button(text(tr("settings-down")))
+                    .style(iced::theme::Button::Secondary)
+                    .on_press(SettingsViewCommand::OnLayoutSectionMoved(id, 1)),
+            ]
+            .spacing(4)
+            .align_items(iced::alignment::Alignment::Center),
+        );
+    }
+    view.into()
+}
+
+fn shortcuts_view(state: &ViewState) -> Element<SettingsViewCommand> {
+    let profile = match find_layout_profile(state) {
+        Some(data) => data,
+        None => return Column::new().into(),
+    };
+
+    let mut view = Column::new()
+        .spacing(4)
+        .push(text(tr("settings-shortcuts")));
+    for (index, shortcut) in profile.shortcuts.iter().enumerate() {
+        view = view.push(
+            row![
+                text(format!("{} ({})", shortcut.label, shortcut.target)).width(Length::Fill),
+                button(text(tr("settings-unpin")))
+                    .style(iced::theme::Button::Secondary)
+                    .on_press(SettingsViewCommand::OnShortcutUnpinClicked(index)),
+            ]
+            .spacing(4)
+            .align_items(iced::alignment::Alignment::Center),
+        );
+    }
+
+    let pin_button = button(text(tr("settings-pin"))).style(iced::theme::Button::Secondary);
+    let pin_button = if profile.shortcuts.len() < LayoutProfile::MAX_SHORTCUTS
+        && !state.shortcut_label.is_empty()
+        && !state.shortcut_target.is_empty()
+    {
+        pin_button.on_press(SettingsViewCommand::OnShortcutPinClicked)
+    } else {
+        pin_button
+    };
+
+    view.push(
+        row![
+            text_input(
+                &tr("settings-shortcut-label"),
+                &state.shortcut_label,
+                SettingsViewCommand::OnShortcutLabelChanged
+            )
+            .width(Length::Units(80)),
+            text_input(
+                &tr("settings-shortcut-target"),
+                &state.shortcut_target,
+                SettingsViewCommand::OnShortcutTargetChanged
+            ),
+            pin_button,
+        ]
+        .spacing(4)
+        .align_items(iced::alignment::Alignment::Center),
+    )
+    .into()
+}
+
+/// the buttons are shown as the preview of the custom key section of the main view.
+fn custom_keys_view(state: &ViewState) -> Element<SettingsViewCommand> {
+    let mut view = Column::new()
+        .spacing(4)
+        .push(text(tr("settings-custom-keys")));
+    for (index, custom_key) in state.custom_keys.iter().enumerate() {
+        let preview = button(text(if custom_key.is_valid() {
+            custom_key.label.clone()
+        } else {
+            format!("{} (!)", custom_key.label)
+        }))
+        .width(ButtonSpec::DEFAULT_WIDTH.into())
+        .style(iced::theme::Button::Secondary);
+        let preview = if custom_key.is_valid() {
+            preview.on_press(SettingsViewCommand::SendXMessage(
+                XMessage::OnKeycodeTestRequested(custom_key.keycode.clone()),
+            ))
+        } else {
+            preview
+        };
+
+        view = view.push(
+            row![
+                preview,
+                text(match &custom_key.package {
+                    Some(package) => format!("{}\n{}", custom_key.keycode, package),
+                    None => custom_key.keycode.clone(),
+                })
+                .size(12)
+                .width(Length::Fill),
+                button(text(tr("settings-up")))
+                    .style(iced::theme::Button::Secondary)
+                    .on_press(SettingsViewCommand::OnCustomKeyMoved(index, -1)),
+                button(text(tr("settings-down")))
+                    .style(iced::theme::Button::Secondary)
+                    .on_press(SettingsViewCommand::OnCustomKeyMoved(index, 1)),
+                button(text(tr("settings-custom-key-remove")))
+                    .style(iced::theme::Button::Destructive)
+                    .on_press(SettingsViewCommand::OnCustomKeyRemoveClicked(index)),
+            ]
+            .spacing(4)
+            .align_items(iced::alignment::Alignment::Center),
+        );
+    }
+
+    let add_button =
+        button(text(tr("settings-custom-key-add"))).style(iced::theme::Button::Secondary);
+    let add_button = if !state.custom_key_label.is_empty() && state.custom_key_keycode.is_some() {
+        add_button.on_press(SettingsViewCommand::OnCustomKeyAddClicked)
+    } else {
+        add_button
+    };
+
+    view.push(
+        row![
+            text_input(
+                &tr("settings-custom-key-label"),
+                &state.custom_key_label,
+                SettingsViewCommand::OnCustomKeyLabelChanged
+            )
+            .width(Length::Units(80)),
+            pick_list(
+                &KEYCODE_NAMES[..],
+                state.custom_key_keycode,
+                SettingsViewCommand::OnCustomKeyKeycodeSelected,
+            ),
+        ]
+        .spacing(4)
+        .align_items(iced::alignment::Alignment::Center),
+    )
+    .push(
+        row![
+            text_input(
+                &tr("settings-custom-key-package"),
+                &state.custom_key_package,
+                SettingsViewCommand::OnCustomKeyPackageChanged
+            ),
+            add_button,
+        ]
+        .spacing(4)
+        .align_items(iced::alignment::Alignment::Center),
+    )
+    .into()
+}
+
+fn key_map_view(state: &ViewState) -> Element<SettingsViewCommand> {
+    let mut keys = Column::new().spacing(4);
+    for key in SendEventKey::MAPPED {
+        let keycode = state.key_map.keycode(&key);
+        keys = keys.push(
+            row![
+                text(if is_valid_keycode(keycode) {
+                    key.to_string()
+                } else {
+                    format!("{} (!)", key)
+                })
+                .width(Length::Units(60)),
+                // shows the keycode as the placeholder if it is a number.
+                pick_list(
+                    &KEYCODE_NAMES[..],
+                    KEYCODE_NAMES.iter().find(|data| **data == keycode).copied(),
+                    move |data| SettingsViewCommand::OnKeyMapChanged(key.clone(), data),
+                )
+                .placeholder(keycode),
+                button(text(tr("settings-test")))
+                    .style(iced::theme::Button::Secondary)
+                    .on_press(SettingsViewCommand::SendXMessage(
+                        XMessage::OnKeycodeTestRequested(keycode.into())
+                    )),
+            ]
+            .spacing(4)
+            .align_items(iced::alignment::Alignment::Center),
+        );
+    }
+
+    let mut delete_button =
+        button(text(tr("settings-key-map-profile-delete"))).style(iced::theme::Button::Destructive);
+    if state.key_map_profile.is_some() {
+        delete_button = delete_button.on_press(SettingsViewCommand::OnKeyMapProfileDeleteClicked);
     }
+
+    let mut save_button = button(text(tr("settings-key-map-profile-save")));
+    if !state.key_map_profile_name.trim().is_empty() {
+        save_button = save_button.on_press(SettingsViewCommand::OnKeyMapProfileSaveClicked);
+    }
+
+    let mut key_layout_list_button =
+        button(text(tr("settings-key-layout-list"))).style(iced::theme::Button::Secondary);
+    if state.key_layout_device.is_some() {
+        key_layout_list_button =
+            key_layout_list_button.on_press(SettingsViewCommand::OnKeyLayoutListClicked);
+    }
+
+    let mut key_layout_save_button = button(text(tr("settings-key-layout-save")));
+    if !state.key_layout_proposals.is_empty() {
+        key_layout_save_button =
+            key_layout_save_button.on_press(SettingsViewCommand::OnKeyLayoutSaveClicked);
+    }
+
+    let key_layout_status = match (&state.key_layout_device, &state.key_layout_status) {
+        (None, _) => tr("settings-key-layout-no-device"),
+        (Some(_), Some(data)) => data.clone(),
+        (Some(_), None) => "".into(),
+    };
+
+    let mut key_layout_proposals = Column::new().spacing(2);
+    for data in &state.key_layout_proposals {
+        key_layout_proposals = key_layout_proposals.push(
+            text(format!(
+                "{}: {} ({})",
+                data.key, data.keycode, data.scan_code
+            ))
+            .size(14),
+        );
+    }
+
+    let mut invalid_keycodes = Column::new().spacing(4);
+    for data in &state.invalid_keycodes {
+        let location = ("location", data.location.clone());
+        let keycode = ("keycode", data.keycode.clone());
+        invalid_keycodes = invalid_keycodes.push(
+            text(match data.suggestion() {
+                Some(suggestion) => tr_args(
+                    "settings-invalid-keycode-suggestion",
+                    &[location, keycode, ("suggestion", suggestion.into())],
+                ),
+                None => tr_args("settings-invalid-keycode", &[location, keycode]),
+            })
+            .size(14),
+        );
+    }
+
+    column![
+        text(tr("settings-key-map")),
+        invalid_keycodes,
+        row![
+            pick_list(
+                state.key_map_profiles.clone(),
+                state.key_map_profile.clone(),
+                SettingsViewCommand::OnKeyMapProfileSelected,
+            ),
+            delete_button,
+        ]
+        .spacing(4)
+        .align_items(iced::alignment::Alignment::Center),
+        row![
+            text_input(
+                &tr("settings-key-map-profile-name"),
+                &state.key_map_profile_name,
+                SettingsViewCommand::OnKeyMapProfileNameChanged,
+            )
+            .on_submit(SettingsViewCommand::OnKeyMapProfileSaveClicked),
+            save_button,
+        ]
+        .spacing(4)
+        .align_items(iced::alignment::Alignment::Center),
+        scrollable(keys).height(Length::Units(160)),
+        text(tr("settings-key-layout")),
+        row![
+            key_layout_list_button,
+            pick_list(
+                state.key_layouts.clone(),
+                state.key_layout_selected.clone(),
+                SettingsViewCommand::OnKeyLayoutSelected,
+            ),
+            key_layout_save_button,
+        ]
+        .spacing(4)
+        .align_items(iced::alignment::Alignment::Center),
+        text(key_layout_status).size(14),
+        scrollable(key_layout_proposals).height(Length::Units(80))
+    ]
+    .spacing(4)
+    .into()
+}
+
+async fn pick_adb_path() -> Option<PathBuf> {
+    rfd::AsyncFileDialog::new()
+        .set_title(&tr("settings-adb-pick-title"))
+        .pick_file()
+        .await
+        .map(|data| data.path().to_path_buf())
+}
+
+async fn pick_recording_dir() -> Option<PathBuf> {
+    rfd::AsyncFileDialog::new()
+        .set_title(&tr("settings-recording-pick-title"))
+        .pick_folder()
+        .await
+        .map(|data| data.path().to_path_buf())
+}
+
+fn find_layout_profile(state: &ViewState) -> Option<&LayoutProfile> {
+    let name = state.layout_profile_selected.as_ref()?;
+    state.layout_profiles.iter().find(|data| &data.name == name)
+}
+
+fn update_layout_profile(
+    prefs_store: &PreferencesStore,
+    state: &ViewState,
+    f: impl FnOnce(&mut LayoutProfile) + Send + 'static,
+) {
+    let name = match &state.layout_profile_selected {
+        Some(data) => data.clone(),
+        None => return,
+    };
+
+    prefs_store.update(move |prefs| {
+        match prefs
+            .layout_profiles
+            .iter_mut()
+            .find(|data| data.name == name)
+        {
+            Some(data) => f(data),
+            None => warn!(%name, "layout profile not found"),
+        }
+    });
 }
 
 fn open_prefs(state: &ViewState) {
     if let Ok(visual) = std::env::var("VISUAL") {
         debug!(%visual, "use VISUAL");
 
-        let visual_result = std::process::Command::new(visual)
-            .arg(state.config_file_path.as_os_str())
-            .spawn();
-
-        if visual_result.is_ok() {
-            debug!("succeeded");
-            return;
+        match spawn_detached(&visual, &state.config_file_path) {
+            Ok(_) => {
+                debug!("succeeded");
+                return;
+            }
+            Err(e) => debug!(?e, "failed to use VISUAL"),
         }
     }
 
     let filer = get_filer();
 
     debug!(%filer, "use filer");
-    let filer_result = std::process::Command::new(filer)
-        .arg(state.config_file_path.as_os_str())
-        .spawn();
-
-    if filer_result.is_ok() {
-        debug!("succeeded");
-        return;
+    match spawn_detached(filer, &state.config_file_path) {
+        Ok(_) => debug!("succeeded"),
+        Err(e) => warn!(?e, %filer, "failed to open preferences"),
     }
-
-    warn!(?filer_result, %filer, "failed to open preferences");
 }
 
 fn open_prefs_directory(state: &ViewState) {
@@ -167,11 +1322,7 @@ fn open_prefs_directory(state: &ViewState) {
         None => return,
     };
 
-    let ret = std::process::Command::new(filer)
-        .arg(dir.as_os_str())
-        .spawn();
-
-    match ret {
+    match spawn_detached(filer, dir) {
         Ok(_) => debug!("succeeded"),
         Err(e) => warn!(?e, %filer, "failed to open directory"),
     }
@@ -190,34 +1341,54 @@ fn get_filer() -> &'static str {
 fn open_keycode_references() {
     let filer = get_filer();
 
-    let ret = std::process::Command::new(filer)
-        .arg("https://developer.android.com/reference/android/view/KeyEvent#constants")
-        .spawn();
-
-    match ret {
+    match spawn_detached(
+        filer,
+        "https://developer.android.com/reference/android/view/KeyEvent#constants",
+    ) {
         Ok(_) => debug!("succeeded"),
         Err(e) => warn!(?e, %filer, "failed to open keycode references"),
     }
 }
 
-async fn save_theme<Repo: PreferencesRepository>(
-    repo: Arc<Mutex<Repo>>,
-    theme: AppTheme,
-) -> Fallible<()> {
-    let repo = repo.lock().await;
-    let mut prefs = match repo.load().await {
-        Ok(data) => data,
-        Err(e) => return Err(e),
-    };
-    prefs.theme = theme;
-    repo.save(prefs).await
+fn open_release_page(url: &str) {
+    let filer = get_filer();
+
+    match spawn_detached(filer, url) {
+        Ok(_) => debug!("succeeded"),
+        Err(e) => warn!(?e, %filer, "failed to open the release page"),
+    }
 }
 
 impl Display for AppTheme {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
-            AppTheme::Light => write!(f, "Light"),
-            AppTheme::Dark => write!(f, "Dark"),
+            AppTheme::Light => write!(f, "{}", tr("settings-theme-light")),
+            AppTheme::Dark => write!(f, "{}", tr("settings-theme-dark")),
+        }
+    }
+}
+
+impl Display for SectionId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SectionId::ColorKeys => write!(f, "{}", tr("settings-section-color-keys")),
+            SectionId::Dpad => write!(f, "{}", tr("settings-section-dpad")),
+            SectionId::Navigation => write!(f, "{}", tr("settings-section-navigation")),
+            SectionId::Numpad => write!(f, "{}", tr("settings-section-numpad")),
+            SectionId::Media => write!(f, "{}", tr("settings-section-media")),
+            SectionId::Power => write!(f, "{}", tr("settings-section-power")),
+            SectionId::Tv => write!(f, "{}", tr("settings-section-tv")),
+            SectionId::CustomKeys => write!(f, "{}", tr("settings-section-custom-keys")),
+        }
+    }
+}
+
+impl Display for ButtonSize {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ButtonSize::Small => write!(f, "{}", tr("settings-button-size-small")),
+            ButtonSize::Medium => write!(f, "{}", tr("settings-button-size-medium")),
+            ButtonSize::Large => write!(f, "{}", tr("settings-button-size-large")),
         }
     }
 }
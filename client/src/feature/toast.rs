@@ -0,0 +1,100 @@
+/*
+ * Copyright 2022 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::data::i18n::{tr, tr_args};
+use iced::widget::{button, container, row, text, Column};
+use iced::{Command, Element, Length};
+use std::time::Duration;
+
+/// hides the toast after the duration unless dismissed.
+const TOAST_DURATION: Duration = Duration::from_secs(8);
+
+/// keeps the newest toasts only so that they don't push the view out of the window.
+const MAX_TOASTS: usize = 3;
+
+#[derive(Clone, Debug)]
+pub enum ToastViewCommand {
+    OnDismissClicked(u64),
+    Show(String),
+    TimedOut(u64),
+}
+
+#[derive(Debug)]
+struct Toast {
+    id: u64,
+    message: String,
+}
+
+/// shows the errors above the active view, e.g. the failure of connecting.
+#[derive(Debug, Default)]
+pub struct ToastView {
+    next_id: u64,
+    toasts: Vec<Toast>,
+}
+
+impl ToastView {
+    pub fn update(&mut self, command: ToastViewCommand) -> Command<ToastViewCommand> {
+        match command {
+            ToastViewCommand::OnDismissClicked(id) | ToastViewCommand::TimedOut(id) => {
+                self.toasts.retain(|data| data.id != id);
+            }
+            ToastViewCommand::Show(message) => {
+                // the same error is repeated by the reconnection.
+                if self.toasts.iter().any(|data| data.message == message) {
+                    return Command::none();
+                }
+
+                self.next_id += 1;
+                let id = self.next_id;
+                self.toasts.push(Toast { id, message });
+                if MAX_TOASTS < self.toasts.len() {
+                    self.toasts.remove(0);
+                }
+                return Command::perform(tokio::time::sleep(TOAST_DURATION), move |_| {
+                    ToastViewCommand::TimedOut(id)
+                });
+            }
+        }
+        Command::none()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.toasts.is_empty()
+    }
+
+    pub fn view(&self) -> Element<ToastViewCommand> {
+        let mut view = Column::new().spacing(4);
+        for toast in &self.toasts {
+            view = view.push(
+                container(
+                    row![
+                        text(tr_args("toast-error", &[("error", toast.message.clone())]))
+                            .size(14)
+                            .width(Length::Fill),
+                        button(text(tr("toast-dismiss")).size(14))
+                            .style(iced::theme::Button::Text)
+                            .on_press(ToastViewCommand::OnDismissClicked(toast.id)),
+                    ]
+                    .spacing(4),
+                )
+                .width(Length::Fill)
+                .padding(4)
+                .style(iced::theme::Container::Box),
+            );
+        }
+        view.into()
+    }
+}
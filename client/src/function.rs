@@ -14,4 +14,17 @@
  * limitations under the License.
  */
 
+pub mod adb_functions;
+pub mod atv_remote_functions;
+#[cfg(feature = "cec")]
+pub mod cec_functions;
+pub mod command_functions;
+pub mod mqtt_functions;
+pub mod notification_functions;
+pub mod qr_functions;
+pub mod release_functions;
+pub mod report_functions;
+pub mod roku_functions;
 pub mod serde_functions;
+pub mod server_functions;
+pub mod webhook_functions;
@@ -0,0 +1,1333 @@
+/*
+ * Copyright 2022 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::data::command_target::CommandTarget;
+use crate::function::command_functions::{
+    adb_command, register_child, run_command, DEFAULT_TIMEOUT,
+};
+use crate::model::accessibility::AccessibilityServices;
+use crate::model::adb_version::AdbVersion;
+use crate::model::device_file::{join_device_path, parse_ls_la, DeviceFile};
+use crate::model::device_health::DeviceHealth;
+use crate::model::device_info::DeviceInfo;
+use crate::model::device_usage::{parse_df, parse_meminfo, DeviceUsage};
+use crate::model::intent::IntentSpec;
+use crate::model::key_layout::KEY_LAYOUT_DIR;
+use crate::model::keycode::Keycode;
+use crate::model::layout::LaunchShortcut;
+use crate::model::locale::FontScale;
+use crate::model::pointer::PointerGesture;
+use crate::model::protocol::ServerCommand;
+use crate::model::screen_record::ScreenRecordConfig;
+use crate::model::target::{DeviceCapabilities, DeviceKind};
+use crate::model::{AdbDeviceDetails, AdbDeviceState, AdbTransport, AndroidDevice, AndroidUser};
+use crate::prelude::*;
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tracing::{debug, info, warn};
+
+pub const LOG_TAG: &str = "AndroidCommander";
+
+/// writes the marker line into the device logcat with [LOG_TAG].
+pub async fn write_log_marker(serial: String, marker: String) -> Fallible<()> {
+    info!(%serial, %marker, "write log marker");
+
+    let mut command = adb_command();
+    command
+        .args(["-s", &serial, "shell", "log", "-t", LOG_TAG])
+        .arg(format!("'{}'", marker));
+    run_command(command, DEFAULT_TIMEOUT)
+        .await
+        .context("failed to write the log marker")?;
+
+    Ok(())
+}
+
+/// saves the screenshot of the device as png and returns the path.
+pub async fn capture_screenshot(serial: String, path: PathBuf) -> Fallible<PathBuf> {
+    info!(%serial, ?path, "capture screenshot");
+
+    let mut command = adb_command();
+    command.args(["-s", &serial, "exec-out", "screencap", "-p"]);
+    let output = run_command(command, DEFAULT_TIMEOUT)
+        .await
+        .context("failed to capture the screenshot")?;
+
+    if output.stdout.is_empty() {
+        anyhow::bail!("empty screenshot");
+    }
+
+    tokio::fs::write(&path, &output.stdout)
+        .await
+        .with_context(|| format!("failed to write the screenshot: {:?}", path))?;
+
+    Ok(path)
+}
+
+/// saves the bugreport zip of the device into `path`.
+pub async fn capture_bugreport(serial: String, path: PathBuf) -> Fallible<PathBuf> {
+    info!(%serial, ?path, "capture bugreport");
+
+    let mut command = adb_command();
+    command.args(["-s", &serial, "bugreport"]).arg(&path);
+    // dumpstate takes a few minutes on the slow devices.
+    run_command(command, Duration::from_secs(600))
+        .await
+        .context("failed to capture the bugreport")?;
+
+    Ok(path)
+}
+
+/// records the screen until the time limit or [stop_screen_recording].
+pub async fn record_screen(
+    serial: String,
+    device_path: String,
+    config: ScreenRecordConfig,
+) -> Fallible<()> {
+    info!(%serial, %device_path, ?config, "record screen");
+
+    let mut command = adb_command();
+    command
+        .args(["-s", &serial, "shell"])
+        .args(config.args(&device_path));
+    // screenrecord needs a little time to finish the file.
+    run_command(command, config.time_limit + Duration::from_secs(10))
+        .await
+        .context("failed to record the screen")?;
+
+    Ok(())
+}
+
+/// interrupts `screenrecord` so that it finishes the file.
+pub async fn stop_screen_recording(serial: String) -> Fallible<()> {
+    info!(%serial, "stop screen recording");
+
+    let mut command = adb_command();
+    command.args(["-s", &serial, "shell", "pkill", "-INT", "screenrecord"]);
+    run_command(command, DEFAULT_TIMEOUT)
+        .await
+        .context("failed to stop the screen recording")?;
+
+    Ok(())
+}
+
+/// pulls the recording into the `dir` and deletes it from the device.
+pub async fn pull_screen_recording(
+    serial: String,
+    device_path: String,
+    dir: PathBuf,
+) -> Fallible<PathBuf> {
+    info!(%serial, %device_path, ?dir, "pull screen recording");
+
+    let file_name = device_path
+        .rsplit('/')
+        .next()
+        .context("invalid device path")?;
+    let path = dir.join(file_name);
+
+    let mut command = adb_command();
+    command
+        .args(["-s", &serial, "pull", &device_path])
+        .arg(&path);
+    run_command(command, Duration::from_secs(120))
+        .await
+        .context("failed to pull the screen recording")?;
+
+    let mut command = adb_command();
+    command.args(["-s", &serial, "shell", "rm", "-f", &device_path]);
+    if let Err(e) = run_command(command, DEFAULT_TIMEOUT).await {
+        // the recording is already pulled.
+        warn!(?e, "failed to delete the screen recording");
+    }
+
+    Ok(path)
+}
+
+/// the timeout of pulling and pushing, which may take a while for the media files.
+const TRANSFER_TIMEOUT: Duration = Duration::from_secs(600);
+
+pub async fn list_device_dir(serial: String, dir: String) -> Fallible<Vec<DeviceFile>> {
+    info!(%serial, %dir, "list device dir");
+
+    // the trailing slash lists the directory instead of the symlink like `/sdcard`.
+    let dir = if dir.ends_with('/') {
+        dir
+    } else {
+        format!("{}/", dir)
+    };
+    let mut command = adb_command();
+    command.args(["-s", &serial, "shell", "ls", "-la", &quote_shell_arg(&dir)]);
+    let output = run_command(command, DEFAULT_TIMEOUT)
+        .await
+        .with_context(|| format!("failed to list {}", dir))?;
+    Ok(parse_ls_la(&output.stdout_lossy()))
+}
+
+/// pulls the file into `local_path` on the host.
+pub async fn pull_device_file(
+    serial: String,
+    device_path: String,
+    local_path: PathBuf,
+) -> Fallible<PathBuf> {
+    info!(%serial, %device_path, ?local_path, "pull device file");
+
+    let mut command = adb_command();
+    command
+        .args(["-s", &serial, "pull", &device_path])
+        .arg(&local_path);
+    run_command(command, TRANSFER_TIMEOUT)
+        .await
+        .with_context(|| format!("failed to pull {}", device_path))?;
+    Ok(local_path)
+}
+
+/// pushes the host file into `device_dir` and returns the pushed path.
+pub async fn push_device_file(
+    serial: String,
+    local_path: PathBuf,
+    device_dir: String,
+) -> Fallible<String> {
+    info!(%serial, ?local_path, %device_dir, "push device file");
+
+    let file_name = local_path
+        .file_name()
+        .and_then(|data| data.to_str())
+        .context("invalid file name")?;
+    let device_path = join_device_path(&device_dir, file_name);
+
+    let mut command = adb_command();
+    command
+        .args(["-s", &serial, "push"])
+        .arg(&local_path)
+        .arg(&device_path);
+    run_command(command, TRANSFER_TIMEOUT)
+        .await
+        .with_context(|| format!("failed to push {}", local_path.display()))?;
+    Ok(device_path)
+}
+
+/// returns false if the device is a TV, which has no vibrator.
+pub async fn has_vibrator(serial: String) -> Fallible<bool> {
+    info!(%serial, "detect vibrator");
+
+    let mut command = adb_command();
+    command.args([
+        "-s",
+        &serial,
+        "shell",
+        "getprop",
+        "ro.build.characteristics",
+    ]);
+    let output = run_command(command, DEFAULT_TIMEOUT)
+        .await
+        .context("failed to retrieve the device characteristics")?;
+
+    Ok(!is_tv_characteristics(&output.stdout_lossy()))
+}
+
+/// vibrates the device as the physical confirmation of the sent key.
+pub async fn vibrate(serial: String, duration: Duration) -> Fallible<()> {
+    debug!(%serial, ?duration, "vibrate");
+
+    let mut command = adb_command();
+    command
+        .args(["-s", &serial, "shell", "cmd", "vibrator", "vibrate"])
+        .arg(duration.as_millis().to_string());
+    run_command(command, DEFAULT_TIMEOUT)
+        .await
+        .context("failed to vibrate")?;
+
+    Ok(())
+}
+
+/// returns the `getevent -lt` output of the device for the `duration`.
+pub async fn capture_input_events(serial: String, duration: Duration) -> Fallible<String> {
+    info!(%serial, ?duration, "capture input events");
+
+    let mut child = adb_command()
+        .args(["-s", &serial, "shell", "getevent", "-lt"])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .context("failed to start getevent")?;
+    let _registration = register_child(child.id());
+    let stdout = child.stdout.take().context("stdout not found")?;
+
+    let deadline = tokio::time::Instant::now() + duration;
+    let mut lines = BufReader::new(stdout).lines();
+    let mut events = String::new();
+    loop {
+        match tokio::time::timeout_at(deadline, lines.next_line()).await {
+            Ok(Ok(Some(line))) => {
+                events.push_str(&line);
+                events.push('\n');
+            }
+            Ok(Ok(None)) => break,
+            Ok(Err(e)) => {
+                child.kill().await.ok();
+                return Err(e).context("failed to read getevent output");
+            }
+            Err(_) => break,
+        }
+    }
+    child.kill().await.ok();
+
+    Ok(events)
+}
+
+pub async fn retrieve_accessibility_services(serial: String) -> Fallible<AccessibilityServices> {
+    info!(%serial, "retrieve accessibility services");
+
+    let mut command = adb_command();
+    command.args([
+        "-s",
+        &serial,
+        "shell",
+        "settings",
+        "get",
+        "secure",
+        "enabled_accessibility_services",
+    ]);
+    let output = run_command(command, DEFAULT_TIMEOUT)
+        .await
+        .context("failed to retrieve the accessibility services")?;
+
+    Ok(AccessibilityServices::parse(&output.stdout_lossy()))
+}
+
+/// enables only the `services`, and deletes the setting if empty.
+pub async fn put_accessibility_services(
+    serial: String,
+    services: AccessibilityServices,
+) -> Fallible<()> {
+    info!(%serial, %services, "put accessibility services");
+
+    let mut command = adb_command();
+    command.args(["-s", &serial, "shell", "settings"]);
+    if services.is_empty() {
+        command.args(["delete", "secure", "enabled_accessibility_services"]);
+    } else {
+        // the inner class names contain `$`.
+        command
+            .args(["put", "secure", "enabled_accessibility_services"])
+            .arg(format!("'{}'", services));
+    }
+    run_command(command, DEFAULT_TIMEOUT)
+        .await
+        .context("failed to put the accessibility services")?;
+
+    let mut command = adb_command();
+    command.args([
+        "-s",
+        &serial,
+        "shell",
+        "settings",
+        "put",
+        "secure",
+        "accessibility_enabled",
+        if services.is_empty() { "0" } else { "1" },
+    ]);
+    run_command(command, DEFAULT_TIMEOUT)
+        .await
+        .context("failed to put accessibility_enabled")?;
+
+    Ok(())
+}
+
+/// reboots the device and returns after the device goes offline.
+pub async fn reboot_device(serial: String) -> Fallible<()> {
+    info!(%serial, "reboot");
+
+    let mut command = adb_command();
+    command.args(["-s", &serial, "reboot"]);
+    run_command(command, DEFAULT_TIMEOUT)
+        .await
+        .context("failed to reboot")?;
+
+    // the device remains online for a while after the reboot command.
+    let mut command = adb_command();
+    command.args(["-s", &serial, "wait-for-disconnect"]);
+    run_command(command, Duration::from_secs(60))
+        .await
+        .context("the device didn't go offline")?;
+
+    Ok(())
+}
+
+pub async fn wait_for_device(serial: String, timeout: Duration) -> Fallible<()> {
+    info!(%serial, ?timeout, "wait for device");
+
+    let mut command = adb_command();
+    command.args(["-s", &serial, "wait-for-device"]);
+    run_command(command, timeout)
+        .await
+        .context("the device didn't come back")?;
+
+    Ok(())
+}
+
+/// polls `sys.boot_completed` since the device becomes online before the framework starts.
+pub async fn wait_for_boot_completed(serial: String, timeout: Duration) -> Fallible<()> {
+    info!(%serial, ?timeout, "wait for boot completed");
+
+    let deadline = tokio::time::Instant::now() + timeout;
+    loop {
+        let mut command = adb_command();
+        command.args(["-s", &serial, "shell", "getprop", "sys.boot_completed"]);
+        match run_command(command, DEFAULT_TIMEOUT).await {
+            Ok(data) if data.stdout_lossy().trim() == "1" => return Ok(()),
+            Ok(_) => (),
+            Err(e) => debug!(?e, "failed to retrieve sys.boot_completed"),
+        }
+
+        if deadline <= tokio::time::Instant::now() {
+            anyhow::bail!("boot didn't complete in {:?}", timeout);
+        }
+        tokio::time::sleep(Duration::from_secs(2)).await;
+    }
+}
+
+/// returns the system locale like `ja-JP`.
+pub async fn retrieve_locale(serial: String) -> Fallible<String> {
+    info!(%serial, "retrieve locale");
+
+    // persist.sys.locale is empty until the locale is changed from the default.
+    for prop in ["persist.sys.locale", "ro.product.locale"] {
+        let mut command = adb_command();
+        command.args(["-s", &serial, "shell", "getprop", prop]);
+        let output = run_command(command, DEFAULT_TIMEOUT)
+            .await
+            .context("failed to retrieve the locale")?;
+        let locale = output.stdout_lossy().trim().to_string();
+        if !locale.is_empty() {
+            return Ok(locale);
+        }
+    }
+
+    anyhow::bail!("locale not found")
+}
+
+/// changes the system locale with `cmd locale`, or `setprop` on the rooted device without it.
+pub async fn put_locale(serial: String, locale: String) -> Fallible<()> {
+    info!(%serial, %locale, "put locale");
+
+    let mut command = adb_command();
+    command.args([
+        "-s",
+        &serial,
+        "shell",
+        "cmd",
+        "locale",
+        "set-system-locales",
+        &locale,
+    ]);
+    match run_command(command, DEFAULT_TIMEOUT).await {
+        Ok(_) => return Ok(()),
+        Err(e) => info!(?e, "cmd locale unavailable, fall back to setprop"),
+    }
+
+    let mut command = adb_command();
+    command.args([
+        "-s",
+        &serial,
+        "shell",
+        "setprop",
+        "persist.sys.locale",
+        &locale,
+    ]);
+    run_command(command, DEFAULT_TIMEOUT)
+        .await
+        .context("failed to set persist.sys.locale")?;
+
+    // the framework reads the property only on boot.
+    let mut command = adb_command();
+    command.args(["-s", &serial, "shell", "setprop", "ctl.restart", "zygote"]);
+    run_command(command, DEFAULT_TIMEOUT)
+        .await
+        .context("failed to restart the framework")?;
+
+    Ok(())
+}
+
+pub async fn retrieve_font_scale(serial: String) -> Fallible<FontScale> {
+    info!(%serial, "retrieve font scale");
+
+    let mut command = adb_command();
+    command.args([
+        "-s",
+        &serial,
+        "shell",
+        "settings",
+        "get",
+        "system",
+        "font_scale",
+    ]);
+    let output = run_command(command, DEFAULT_TIMEOUT)
+        .await
+        .context("failed to retrieve the font scale")?;
+
+    FontScale::parse(&output.stdout_lossy()).context("unexpected font scale")
+}
+
+pub async fn put_font_scale(serial: String, scale: FontScale) -> Fallible<()> {
+    info!(%serial, %scale, "put font scale");
+
+    let mut command = adb_command();
+    command
+        .args([
+            "-s",
+            &serial,
+            "shell",
+            "settings",
+            "put",
+            "system",
+            "font_scale",
+        ])
+        .arg(scale.to_setting());
+    run_command(command, DEFAULT_TIMEOUT)
+        .await
+        .context("failed to put the font scale")?;
+
+    Ok(())
+}
+
+/// returns the storage of `/data` and the memory of the device.
+///
+/// the one that failed to retrieve is `None`.
+pub async fn retrieve_device_health(serial: String) -> Fallible<DeviceHealth> {
+    debug!(%serial, "retrieve device health");
+
+    let mut command = adb_command();
+    command.args(["-s", &serial, "shell", "dumpsys", "battery"]);
+    let battery = run_command(command, DEFAULT_TIMEOUT)
+        .await
+        .context("failed to retrieve the battery")?;
+
+    // thermalservice is available since Android 10.
+    let mut command = adb_command();
+    command.args(["-s", &serial, "shell", "dumpsys", "thermalservice"]);
+    let thermal = match run_command(command, DEFAULT_TIMEOUT).await {
+        Ok(data) => data.stdout_lossy(),
+        Err(e) => {
+            debug!(?e, "failed to retrieve the thermal status");
+            "".into()
+        }
+    };
+
+    Ok(DeviceHealth::parse(&battery.stdout_lossy(), &thermal))
+}
+
+pub async fn retrieve_device_usage(serial: String) -> Fallible<DeviceUsage> {
+    info!(%serial, "retrieve device usage");
+
+    let mut command = adb_command();
+    command.args(["-s", &serial, "shell", "df", "-k", "/data"]);
+    let storage = match run_command(command, DEFAULT_TIMEOUT).await {
+        Ok(data) => parse_df(&data.stdout_lossy()),
+        Err(e) => {
+            warn!(?e, "failed to retrieve the storage");
+            None
+        }
+    };
+
+    // dumpsys meminfo takes seconds to collect the processes.
+    let mut command = adb_command();
+    command.args(["-s", &serial, "shell", "dumpsys", "meminfo"]);
+    let memory = match run_command(command, Duration::from_secs(30)).await {
+        Ok(data) => parse_meminfo(&data.stdout_lossy()),
+        Err(e) => {
+            warn!(?e, "failed to retrieve the memory");
+            None
+        }
+    };
+
+    if storage.is_none() && memory.is_none() {
+        anyhow::bail!("neither storage nor memory found");
+    }
+
+    Ok(DeviceUsage { storage, memory })
+}
+
+/// returns the `dumpsys input` output of the device.
+pub async fn retrieve_device_info(serial: String) -> Fallible<DeviceInfo> {
+    info!(%serial, "retrieve device info");
+
+    let mut outputs = vec![];
+    for args in [
+        &["getprop"][..],
+        &["wm", "size"],
+        &["wm", "density"],
+        &["dumpsys", "battery"],
+    ] {
+        let mut command = adb_command();
+        command.args(["-s", &serial, "shell"]).args(args);
+        let output = run_command(command, DEFAULT_TIMEOUT)
+            .await
+            .with_context(|| format!("failed to run {}", args.join(" ")))?;
+        outputs.push(output.stdout_lossy());
+    }
+
+    Ok(DeviceInfo::new(
+        &outputs[0],
+        &outputs[1],
+        &outputs[2],
+        &outputs[3],
+    ))
+}
+
+/// lists the key layout files like `Generic.kl` of the device.
+pub async fn list_key_layouts(serial: String) -> Fallible<Vec<String>> {
+    info!(%serial, "list key layouts");
+
+    let mut command = adb_command();
+    command.args(["-s", &serial, "shell", "ls", KEY_LAYOUT_DIR]);
+    let output = run_command(command, DEFAULT_TIMEOUT)
+        .await
+        .context("failed to list the key layouts")?;
+
+    let mut names = output
+        .stdout_lossy()
+        .lines()
+        .map(str::trim)
+        .filter(|data| data.ends_with(".kl"))
+        .map(str::to_string)
+        .collect::<Vec<_>>();
+    names.sort();
+    Ok(names)
+}
+
+pub async fn retrieve_key_layout(serial: String, name: String) -> Fallible<String> {
+    info!(%serial, %name, "retrieve key layout");
+
+    let path = join_device_path(KEY_LAYOUT_DIR, &name);
+    let mut command = adb_command();
+    command.args(["-s", &serial, "shell", "cat", &quote_shell_arg(&path)]);
+    let output = run_command(command, DEFAULT_TIMEOUT)
+        .await
+        .with_context(|| format!("failed to read {}", path))?;
+
+    Ok(output.stdout_lossy())
+}
+
+pub async fn dump_input(serial: String) -> Fallible<String> {
+    info!(%serial, "dump input");
+
+    let mut command = adb_command();
+    command.args(["-s", &serial, "shell", "dumpsys", "input"]);
+    let output = run_command(command, DEFAULT_TIMEOUT)
+        .await
+        .context("failed to dump input")?;
+
+    Ok(output.stdout_lossy())
+}
+
+/// launches the app by the package name, or opens the deeplink.
+///
+/// launches as the `user` if specified, otherwise as the current user.
+pub async fn launch_shortcut(
+    serial: String,
+    shortcut: LaunchShortcut,
+    user: Option<u32>,
+) -> Fallible<()> {
+    info!(%serial, ?shortcut, ?user, "launch shortcut");
+
+    let mut command = adb_command();
+    command.args(["-s", &serial, "shell"]);
+    if shortcut.is_deeplink() {
+        command.args(["am", "start"]);
+        if let Some(user) = user {
+            command.args(["--user", &user.to_string()]);
+        }
+        command
+            .args(["-a", "android.intent.action.VIEW", "-d"])
+            .arg(format!("'{}'", shortcut.target));
+    } else if let Some(user) = user {
+        // monkey doesn't support the user.
+        command.args([
+            "am",
+            "start",
+            "--user",
+            &user.to_string(),
+            "-a",
+            "android.intent.action.MAIN",
+            "-c",
+            "android.intent.category.LAUNCHER",
+            "-p",
+            &shortcut.target,
+        ]);
+    } else {
+        command.args([
+            "monkey",
+            "-p",
+            &shortcut.target,
+            "-c",
+            "android.intent.category.LAUNCHER",
+            "1",
+        ]);
+    }
+    run_command(command, DEFAULT_TIMEOUT)
+        .await
+        .with_context(|| format!("failed to launch {}", shortcut.target))?;
+
+    Ok(())
+}
+
+/// sends the click of the key without the server.
+pub async fn send_keyevent(serial: String, keycode: Keycode) -> Fallible<()> {
+    debug!(%serial, %keycode, "send keyevent");
+
+    let mut command = adb_command();
+    command
+        .args(["-s", &serial, "shell", "input", "keyevent"])
+        .arg(keycode.to_string());
+    run_command(command, DEFAULT_TIMEOUT)
+        .await
+        .with_context(|| format!("failed to send {}", keycode))?;
+
+    Ok(())
+}
+
+/// drives the device by `input` when the server can't run on it.
+///
+/// `input` only sends the whole click, so the down is ignored and the click is sent on the up.
+pub struct AdbInputTarget {
+    serial: String,
+    /// the key sent with `--longpress` that doesn't need the click on the up.
+    long_pressed: Option<Keycode>,
+}
+
+impl AdbInputTarget {
+    pub fn new(serial: String) -> Self {
+        Self {
+            serial,
+            long_pressed: None,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl CommandTarget for AdbInputTarget {
+    fn capabilities(&self) -> DeviceCapabilities {
+        DeviceCapabilities::ADB_INPUT
+    }
+
+    async fn send(&mut self, command: &ServerCommand) -> Fallible<()> {
+        let args = match input_args(command, &mut self.long_pressed) {
+            Some(data) => data,
+            None => return Ok(()),
+        };
+        debug!(serial = %self.serial, ?args, "send input");
+
+        let mut adb = adb_command();
+        adb.args(["-s", &self.serial, "shell", "input"]).args(args);
+        run_command(adb, DEFAULT_TIMEOUT)
+            .await
+            .with_context(|| format!("failed to send {}", command))?;
+
+        Ok(())
+    }
+}
+
+/// returns the width and the height of the screen in pixels.
+pub async fn retrieve_screen_size(serial: String) -> Fallible<(u32, u32)> {
+    info!(%serial, "retrieve screen size");
+
+    let mut command = adb_command();
+    command.args(["-s", &serial, "shell", "wm", "size"]);
+    let output = run_command(command, DEFAULT_TIMEOUT)
+        .await
+        .context("failed to retrieve the screen size")?;
+
+    let stdout = output.stdout_lossy();
+    parse_screen_size(&stdout).with_context(|| format!("unexpected screen size: {}", stdout))
+}
+
+/// taps or swipes the screen of `size` by `input`.
+pub async fn send_pointer_gesture(
+    serial: String,
+    gesture: PointerGesture,
+    size: (u32, u32),
+) -> Fallible<()> {
+    debug!(%serial, ?gesture, "send pointer gesture");
+
+    let mut command = adb_command();
+    command
+        .args(["-s", &serial, "shell", "input"])
+        .args(gesture.input_args(size.0, size.1));
+    run_command(command, DEFAULT_TIMEOUT)
+        .await
+        .context("failed to send the pointer gesture")?;
+
+    Ok(())
+}
+
+/// kills the `monkey` process on the device.
+pub async fn start_intent(serial: String, spec: IntentSpec) -> Fallible<()> {
+    info!(%serial, ?spec, "start intent");
+
+    let mut command = adb_command();
+    command
+        .args(["-s", &serial, "shell", "am", "start"])
+        .args(spec.args().iter().map(|data| quote_shell_arg(data)));
+    let output = run_command(command, DEFAULT_TIMEOUT)
+        .await
+        .context("failed to start the intent")?;
+
+    // am exits with 0 even if no activity is found.
+    let stdout = output.stdout_lossy();
+    if let Some(line) = stdout
+        .lines()
+        .chain(output.stderr.lines())
+        .find(|data| data.starts_with("Error"))
+    {
+        anyhow::bail!("{}", line);
+    }
+
+    Ok(())
+}
+
+/// returns the packages that the user installed.
+pub async fn retrieve_packages(serial: String) -> Fallible<Vec<String>> {
+    info!(%serial, "retrieve packages");
+
+    let mut command = adb_command();
+    command.args(["-s", &serial, "shell", "pm", "list", "packages", "-3"]);
+    let output = run_command(command, DEFAULT_TIMEOUT)
+        .await
+        .context("failed to retrieve packages")?;
+
+    Ok(parse_packages(&output.stdout_lossy()))
+}
+
+pub async fn force_stop_package(serial: String, package: String) -> Fallible<()> {
+    info!(%serial, %package, "force stop package");
+
+    let mut command = adb_command();
+    command.args(["-s", &serial, "shell", "am", "force-stop", &package]);
+    run_command(command, DEFAULT_TIMEOUT)
+        .await
+        .with_context(|| format!("failed to force stop {}", package))?;
+
+    Ok(())
+}
+
+pub async fn clear_package_data(serial: String, package: String) -> Fallible<()> {
+    info!(%serial, %package, "clear package data");
+
+    let mut command = adb_command();
+    command.args(["-s", &serial, "shell", "pm", "clear", &package]);
+    let output = run_command(command, DEFAULT_TIMEOUT)
+        .await
+        .with_context(|| format!("failed to clear {}", package))?;
+
+    // some versions exit with 0 on failure.
+    let output = output.stdout_lossy();
+    if !output.contains("Success") {
+        anyhow::bail!("failed to clear {}: {}", package, output.trim());
+    }
+
+    Ok(())
+}
+
+/// installs the apk, and replaces the installed one keeping its data.
+pub async fn install_package(serial: String, apk_path: PathBuf) -> Fallible<()> {
+    info!(%serial, ?apk_path, "install package");
+
+    let mut command = adb_command();
+    command
+        .args(["-s", &serial, "install", "-r"])
+        .arg(&apk_path);
+    let output = run_command(command, TRANSFER_TIMEOUT)
+        .await
+        .with_context(|| format!("failed to install {}", apk_path.display()))?;
+
+    // the old adb exits with 0 on failure.
+    let output = output.stdout_lossy();
+    if !output.contains("Success") {
+        anyhow::bail!(
+            "failed to install {}: {}",
+            apk_path.display(),
+            output.trim()
+        );
+    }
+
+    Ok(())
+}
+
+pub async fn uninstall_package(serial: String, package: String) -> Fallible<()> {
+    info!(%serial, %package, "uninstall package");
+
+    let mut command = adb_command();
+    command.args(["-s", &serial, "uninstall", &package]);
+    let output = run_command(command, Duration::from_secs(60))
+        .await
+        .with_context(|| format!("failed to uninstall {}", package))?;
+
+    // the old adb exits with 0 on failure.
+    let output = output.stdout_lossy();
+    if !output.contains("Success") {
+        anyhow::bail!("failed to uninstall {}: {}", package, output.trim());
+    }
+
+    Ok(())
+}
+
+pub async fn stop_monkey(serial: String) -> Fallible<()> {
+    info!(%serial, "stop monkey");
+
+    let mut command = adb_command();
+    command.args([
+        "-s",
+        &serial,
+        "shell",
+        "pkill",
+        "-f",
+        "com.android.commands.monkey",
+    ]);
+    run_command(command, DEFAULT_TIMEOUT)
+        .await
+        .context("failed to stop monkey")?;
+
+    Ok(())
+}
+
+/// returns the users of the device such as the owner and the work profile.
+pub async fn retrieve_users(serial: String) -> Fallible<Vec<AndroidUser>> {
+    info!(%serial, "retrieve users");
+
+    let mut command = adb_command();
+    command.args(["-s", &serial, "shell", "pm", "list", "users"]);
+    let output = run_command(command, DEFAULT_TIMEOUT)
+        .await
+        .context("failed to retrieve users")?;
+
+    Ok(parse_users(&output.stdout_lossy()))
+}
+
+/// pairs with the device by the wireless debugging of Android 11+.
+///
+/// `address` is the `host:port` of the pairing dialog, not the one to connect.
+pub async fn pair_device(address: String, code: String) -> Fallible<()> {
+    info!(%address, "pair device");
+
+    let code = code.trim();
+    if code.len() != 6 || !code.chars().all(|data| data.is_ascii_digit()) {
+        anyhow::bail!("the pairing code must be 6 digits");
+    }
+    if !is_host_port(address.trim()) {
+        anyhow::bail!("the address must be host:port");
+    }
+
+    let mut command = adb_command();
+    command.args(["pair", address.trim(), code]);
+    let output = run_command(command, Duration::from_secs(30))
+        .await
+        .context("failed to pair")?;
+
+    // adb exits with 0 even if the code is wrong.
+    let stdout = output.stdout_lossy();
+    if !stdout.contains("Successfully paired") {
+        anyhow::bail!("{}", stdout.trim());
+    }
+
+    Ok(())
+}
+
+pub async fn retrieve_adb_version() -> Fallible<AdbVersion> {
+    let mut command = adb_command();
+    command.arg("version");
+    let output = run_command(command, DEFAULT_TIMEOUT)
+        .await
+        .context("failed to retrieve the adb version")?;
+
+    let stdout = output.stdout_lossy();
+    AdbVersion::parse(&stdout).with_context(|| format!("unexpected adb version: {}", stdout))
+}
+
+/// stops the adb daemon, which disconnects every device.
+pub async fn kill_adb_server() -> Fallible<()> {
+    let mut command = adb_command();
+    command.arg("kill-server");
+    run_command(command, DEFAULT_TIMEOUT)
+        .await
+        .context("failed to kill the adb server")?;
+    Ok(())
+}
+
+pub async fn start_adb_server() -> Fallible<()> {
+    let mut command = adb_command();
+    command.arg("start-server");
+    run_command(command, DEFAULT_TIMEOUT)
+        .await
+        .context("failed to start the adb server")?;
+    Ok(())
+}
+
+pub async fn retrieve_devices() -> Fallible<Vec<AndroidDevice>> {
+    let mut command = adb_command();
+    command.args(["devices", "-l"]);
+    let output = run_command(command, DEFAULT_TIMEOUT)
+        .await
+        .context("failed to retrieve devices")?;
+
+    Ok(parse_devices(&output.stdout_lossy()))
+}
+
+/// accepts `192.168.0.2:37000` and `[fe80::1]:37000`.
+/// quotes the argument for `adb shell`, which joins the arguments into a command line.
+fn quote_shell_arg(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+fn is_host_port(address: &str) -> bool {
+    match address.rsplit_once(':') {
+        Some((host, port)) => !host.is_empty() && port.parse::<u16>().is_ok(),
+        None => false,
+    }
+}
+
+/// parses the lines like `UserInfo{10:Work profile:1030} running`.
+fn parse_users(output: &str) -> Vec<AndroidUser> {
+    let mut users = vec![];
+    for line in output.lines() {
+        let info = match line
+            .trim()
+            .strip_prefix("UserInfo{")
+            .and_then(|data| data.split('}').next())
+        {
+            Some(data) => data,
+            None => {
+                debug!(%line, "skip line");
+                continue;
+            }
+        };
+
+        // the name may contain the colon.
+        let mut segments = info.split(':');
+        let id = match segments.next().and_then(|data| data.parse().ok()) {
+            Some(data) => data,
+            None => {
+                debug!(%line, "skip line");
+                continue;
+            }
+        };
+        let mut name = segments.collect::<Vec<_>>();
+        name.pop();
+        users.push(AndroidUser {
+            id,
+            name: name.join(":"),
+        });
+    }
+    users
+}
+
+/// prefers `Override size: 1080x1920` to `Physical size: 1440x2560`.
+fn parse_packages(output: &str) -> Vec<String> {
+    let mut packages = output
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("package:"))
+        .filter(|data| !data.is_empty())
+        .map(|data| data.to_string())
+        .collect::<Vec<_>>();
+    packages.sort();
+    packages
+}
+
+fn parse_screen_size(output: &str) -> Option<(u32, u32)> {
+    let find = |prefix: &str| {
+        let line = output
+            .lines()
+            .find(|data| data.trim_start().starts_with(prefix))?;
+        let (width, height) = line.split_once(':')?.1.trim().split_once('x')?;
+        Some((width.parse().ok()?, height.parse().ok()?))
+    };
+    find("Override size:").or_else(|| find("Physical size:"))
+}
+
+/// `ro.build.characteristics` is a comma-separated list such as `nosdcard,tv`.
+fn is_tv_characteristics(characteristics: &str) -> bool {
+    characteristics.trim().split(',').any(|data| data == "tv")
+}
+
+/// parses the output of `adb devices -l` like
+/// `emulator-5554 device product:sdk_gphone64_x86_64 model:sdk_gphone64_x86_64 transport_id:1`.
+fn parse_devices(output: &str) -> Vec<AndroidDevice> {
+    let mut devices = vec![];
+    for line in output.lines() {
+        // e.g. "* daemon started successfully".
+        if line.starts_with("List of devices") || line.starts_with('*') {
+            debug!(%line, "skip line");
+            continue;
+        }
+
+        let mut segments = line.split_whitespace();
+        let (serial, state) = match (segments.next(), segments.next()) {
+            (Some(serial), Some(state)) => (serial, state),
+            _ => {
+                debug!(%line, "skip line");
+                continue;
+            }
+        };
+
+        let mut details = AdbDeviceDetails {
+            model: None,
+            product: None,
+            state: AdbDeviceState::from_column(state),
+            transport: if serial.starts_with("emulator-") {
+                Some(AdbTransport::Emulator)
+            } else if is_host_port(serial) || serial.contains("._tcp") {
+                Some(AdbTransport::Tcp)
+            } else {
+                None
+            },
+        };
+        for segment in segments {
+            match segment.split_once(':') {
+                Some(("model", value)) => details.model = Some(value.into()),
+                Some(("product", value)) => details.product = Some(value.into()),
+                Some(("usb", _)) => details.transport = Some(AdbTransport::Usb),
+                _ => (),
+            }
+        }
+
+        devices.push(AndroidDevice {
+            serial: serial.into(),
+            kind: DeviceKind::Adb,
+            label: None,
+            details: Some(details),
+        });
+    }
+    devices
+}
+
+/// returns the arguments of `input` for the command, or `None` if nothing is sent.
+fn input_args(command: &ServerCommand, long_pressed: &mut Option<Keycode>) -> Option<Vec<String>> {
+    match command {
+        ServerCommand::KeyDown(_) | ServerCommand::Ping | ServerCommand::Quit => None,
+        // the long press is repeated while holding, but `input` sends the whole long press.
+        ServerCommand::KeyLongPress(keycode) if long_pressed.as_ref() == Some(keycode) => None,
+        ServerCommand::KeyLongPress(keycode) => {
+            *long_pressed = Some(keycode.clone());
+            Some(vec![
+                "keyevent".into(),
+                "--longpress".into(),
+                keycode.value().to_string(),
+            ])
+        }
+        ServerCommand::KeyUp(keycode) if long_pressed.take().as_ref() == Some(keycode) => None,
+        ServerCommand::KeyUp(keycode) => Some(vec!["keyevent".into(), keycode.value().to_string()]),
+        // `input text` reads `%s` as the space, and the shell of the device splits the rest.
+        ServerCommand::Text(value) => Some(vec![
+            "text".into(),
+            format!("'{}'", value.replace(' ', "%s").replace('\'', "'\\''")),
+        ]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_devices_output() {
+        let output = "List of devices attached
+emulator-5554          device product:sdk_gphone64_x86_64 model:sdk_gphone64_x86_64 device:emu64x transport_id:1
+192.168.0.2:5555       device product:sabrina model:Chromecast device:sabrina transport_id:2
+R3CT40XXXX             device usb:1-1 product:panther model:Pixel_7 device:panther transport_id:3
+
+";
+        let devices = parse_devices(output);
+        assert_eq!(
+            vec![
+                AndroidDevice {
+                    serial: "emulator-5554".into(),
+                    kind: DeviceKind::Adb,
+                    label: None,
+                    details: Some(AdbDeviceDetails {
+                        model: Some("sdk_gphone64_x86_64".into()),
+                        product: Some("sdk_gphone64_x86_64".into()),
+                        state: AdbDeviceState::Device,
+                        transport: Some(AdbTransport::Emulator),
+                    }),
+                },
+                AndroidDevice {
+                    serial: "192.168.0.2:5555".into(),
+                    kind: DeviceKind::Adb,
+                    label: None,
+                    details: Some(AdbDeviceDetails {
+                        model: Some("Chromecast".into()),
+                        product: Some("sabrina".into()),
+                        state: AdbDeviceState::Device,
+                        transport: Some(AdbTransport::Tcp),
+                    }),
+                },
+                AndroidDevice {
+                    serial: "R3CT40XXXX".into(),
+                    kind: DeviceKind::Adb,
+                    label: None,
+                    details: Some(AdbDeviceDetails {
+                        model: Some("Pixel_7".into()),
+                        product: Some("panther".into()),
+                        state: AdbDeviceState::Device,
+                        transport: Some(AdbTransport::Usb),
+                    }),
+                },
+            ],
+            devices
+        );
+        assert_eq!("Pixel 7 (usb)", devices[2].to_string());
+    }
+
+    #[test]
+    fn parse_devices_state() {
+        let output = "List of devices attached
+R3CT40XXXX             unauthorized usb:1-1 transport_id:3
+emulator-5556          offline transport_id:4
+0123456789ABCDEF       no permissions (missing udev rules? user is in the plugdev group); see [http://developer.android.com/tools/device.html] usb:1-2 transport_id:5
+ZY22XXXX               recovery usb:1-3 product:panther model:Pixel_7 device:panther transport_id:6
+";
+        let devices = parse_devices(output);
+        assert_eq!(
+            vec![
+                AdbDeviceState::Unauthorized,
+                AdbDeviceState::Offline,
+                AdbDeviceState::NoPermissions,
+                AdbDeviceState::Other("recovery".into()),
+            ],
+            devices
+                .iter()
+                .map(|data| data.details.as_ref().unwrap().state.clone())
+                .collect::<Vec<_>>()
+        );
+        assert!(devices.iter().all(|data| !data.is_usable()));
+        assert_eq!("R3CT40XXXX (usb, unauthorized)", devices[0].to_string());
+        assert_eq!(
+            "0123456789ABCDEF (usb, no permissions)",
+            devices[2].to_string()
+        );
+        assert_eq!("Pixel 7 (usb, recovery)", devices[3].to_string());
+    }
+
+    #[test]
+    fn parse_devices_daemon_started() {
+        let output = "* daemon not running; starting now at tcp:5037
+* daemon started successfully
+List of devices attached
+emulator-5554\tdevice
+";
+        let devices = parse_devices(output);
+        assert_eq!(1, devices.len());
+        assert_eq!("emulator-5554 (emulator)", devices[0].to_string());
+    }
+
+    #[test]
+    fn parse_users_output() {
+        let output =
+            "Users:\n\tUserInfo{0:Owner:c13} running\n\tUserInfo{10:Work: profile:1030} running\n";
+        assert_eq!(
+            vec![
+                AndroidUser {
+                    id: 0,
+                    name: "Owner".into(),
+                },
+                AndroidUser {
+                    id: 10,
+                    name: "Work: profile".into(),
+                },
+            ],
+            parse_users(output)
+        );
+    }
+
+    #[test]
+    fn parse_packages_output() {
+        let output = "package:com.netflix.ninja\r\npackage:com.example.app\r\n\r\n";
+        assert_eq!(
+            vec!["com.example.app", "com.netflix.ninja"],
+            parse_packages(output)
+        );
+    }
+
+    #[test]
+    fn parse_screen_size_output() {
+        assert_eq!(
+            Some((1080, 1920)),
+            parse_screen_size("Physical size: 1080x1920\n")
+        );
+        assert_eq!(
+            Some((720, 1280)),
+            parse_screen_size("Physical size: 1080x1920\nOverride size: 720x1280\n")
+        );
+        assert_eq!(None, parse_screen_size(""));
+    }
+
+    #[test]
+    fn host_port() {
+        assert!(is_host_port("192.168.0.2:37000"));
+        assert!(is_host_port("[fe80::1]:37000"));
+        assert!(!is_host_port("192.168.0.2"));
+        assert!(!is_host_port(":37000"));
+        assert!(!is_host_port("192.168.0.2:port"));
+    }
+
+    #[test]
+    fn quote_shell_arg_value() {
+        assert_eq!(
+            "'/sdcard/My Movies/'",
+            quote_shell_arg("/sdcard/My Movies/")
+        );
+        assert_eq!("'it'\\''s'", quote_shell_arg("it's"));
+    }
+
+    #[test]
+    fn tv_characteristics() {
+        assert!(is_tv_characteristics("tv\n"));
+        assert!(is_tv_characteristics("nosdcard,tv"));
+        assert!(!is_tv_characteristics("default"));
+        assert!(!is_tv_characteristics("tablet"));
+    }
+
+    #[test]
+    fn input_args_keys() {
+        let mut long_pressed = None;
+        let back = "KEYCODE_BACK".parse::<Keycode>().unwrap();
+        let mut send = |command| input_args(&command, &mut long_pressed);
+
+        assert_eq!(None, send(ServerCommand::KeyDown(back.clone())));
+        assert_eq!(
+            Some(vec!["keyevent".into(), "4".into()]),
+            send(ServerCommand::KeyUp(back.clone()))
+        );
+
+        assert_eq!(None, send(ServerCommand::KeyDown(back.clone())));
+        assert_eq!(
+            Some(vec!["keyevent".into(), "--longpress".into(), "4".into()]),
+            send(ServerCommand::KeyLongPress(back.clone()))
+        );
+        assert_eq!(None, send(ServerCommand::KeyLongPress(back.clone())));
+        assert_eq!(None, send(ServerCommand::KeyUp(back)));
+    }
+
+    #[test]
+    fn input_args_text() {
+        assert_eq!(
+            Some(vec!["text".into(), "'it'\\''s%sfine'".into()]),
+            input_args(&ServerCommand::Text("it's fine".into()), &mut None)
+        );
+    }
+}
@@ -0,0 +1,808 @@
+/*
+ * Copyright 2022 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! the Android TV Remote Service protocol (v2) that consumer TVs provide without adb.
+//!
+//! the TV accepts the keys from the client certificate that was paired with the code shown on the
+//! TV. the messages are protobuf prefixed by a varint length.
+
+use crate::data::command_target::CommandTarget;
+use crate::model::keycode::Keycode;
+use crate::model::protocol::ServerCommand;
+use crate::model::target::DeviceCapabilities;
+use crate::prelude::*;
+use rsa::pkcs8::{DecodePrivateKey, DecodePublicKey, EncodePrivateKey};
+use rsa::{PublicKeyParts, RsaPrivateKey, RsaPublicKey};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, ReadHalf, WriteHalf};
+use tokio::net::TcpStream;
+use tokio::sync::{oneshot, Mutex};
+use tokio::task::JoinHandle;
+use tokio_rustls::client::TlsStream;
+use tokio_rustls::rustls;
+use tracing::{debug, info, warn};
+
+pub const PAIRING_PORT: u16 = 6467;
+pub const REMOTE_PORT: u16 = 6466;
+
+const CERT_FILE_NAME: &str = "atv-remote-cert.der";
+const KEY_FILE_NAME: &str = "atv-remote-key.der";
+/// the fingerprints of the server certificates that were paired, keyed by the host.
+const SERVER_CERTS_FILE_NAME: &str = "atv-remote-servers.toml";
+const CLIENT_NAME: &str = "android-commander";
+const SERVICE_NAME: &str = "atvremote";
+/// the TV doesn't verify the name since the certificate is self-signed.
+const SERVER_NAME: &str = "androidtv-remote";
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+const MAX_MESSAGE_LEN: usize = 64 * 1024;
+
+const PAIRING_PROTOCOL_VERSION: u64 = 2;
+const PAIRING_STATUS_OK: u64 = 200;
+const PAIRING_REQUEST: u32 = 10;
+const PAIRING_REQUEST_ACK: u32 = 11;
+const PAIRING_OPTION: u32 = 20;
+const PAIRING_CONFIGURATION: u32 = 30;
+const PAIRING_CONFIGURATION_ACK: u32 = 31;
+const PAIRING_SECRET: u32 = 40;
+const PAIRING_SECRET_ACK: u32 = 41;
+const ENCODING_HEXADECIMAL: u64 = 3;
+const ENCODING_SYMBOL_LENGTH: u64 = 6;
+const ROLE_INPUT: u64 = 1;
+
+const REMOTE_CONFIGURE: u32 = 1;
+const REMOTE_SET_ACTIVE: u32 = 2;
+const REMOTE_PING_REQUEST: u32 = 8;
+const REMOTE_PING_RESPONSE: u32 = 9;
+const REMOTE_KEY_INJECT: u32 = 10;
+/// the features that the client supports, used for both the configure and set active.
+const REMOTE_FEATURES: u64 = 622;
+const DIRECTION_START_LONG: u64 = 1;
+const DIRECTION_END_LONG: u64 = 2;
+
+/// the client certificate that the TV remembers after pairing.
+pub struct ClientCert {
+    cert_der: Vec<u8>,
+    key_der: Vec<u8>,
+}
+
+impl ClientCert {
+    /// loads the certificate from the config directory, or creates it for the first pairing.
+    pub async fn load_or_create(config_dir: &Path) -> Fallible<Self> {
+        let cert_path = config_dir.join(CERT_FILE_NAME);
+        let key_path = config_dir.join(KEY_FILE_NAME);
+        if cert_path.exists() && key_path.exists() {
+            return Ok(Self {
+                cert_der: tokio::fs::read(&cert_path)
+                    .await
+                    .context("failed to read the client certificate")?,
+                key_der: tokio::fs::read(&key_path)
+                    .await
+                    .context("failed to read the client key")?,
+            });
+        }
+
+        info!("create the client certificate");
+        let cert = tokio::task::spawn_blocking(Self::create)
+            .await
+            .context("failed to join")??;
+        tokio::fs::create_dir_all(config_dir)
+            .await
+            .context("failed to create the config directory")?;
+        write_private_file(&key_path, &cert.key_der)
+            .await
+            .context("failed to write the client key")?;
+        tokio::fs::write(&cert_path, &cert.cert_der)
+            .await
+            .context("failed to write the client certificate")?;
+
+        Ok(cert)
+    }
+
+    fn create() -> Fallible<Self> {
+        // the pairing secret is derived from the RSA public keys.
+        let key = RsaPrivateKey::new(&mut rand::thread_rng(), 2048)
+            .context("failed to generate the client key")?;
+        let key_der = key
+            .to_pkcs8_der()
+            .context("failed to encode the client key")?
+            .as_bytes()
+            .to_vec();
+
+        let mut params = rcgen::CertificateParams::new(vec![CLIENT_NAME.into()]);
+        params.alg = &rcgen::PKCS_RSA_SHA256;
+        params.key_pair =
+            Some(rcgen::KeyPair::from_der(&key_der).context("failed to load the client key")?);
+        let cert_der = rcgen::Certificate::from_params(params)
+            .context("failed to create the client certificate")?
+            .serialize_der()
+            .context("failed to encode the client certificate")?;
+
+        Ok(Self { cert_der, key_der })
+    }
+
+    fn public_key(&self) -> Fallible<RsaPublicKey> {
+        Ok(RsaPrivateKey::from_pkcs8_der(&self.key_der)
+            .context("failed to decode the client key")?
+            .to_public_key())
+    }
+}
+
+/// pairs the client certificate with the TV.
+///
+/// the TV shows the code after the configuration, so the code is received after connecting.
+pub async fn pair(
+    host: String,
+    config_dir: impl AsRef<Path>,
+    code: oneshot::Receiver<String>,
+) -> Fallible<()> {
+    info!(%host, "pair");
+
+    let cert = ClientCert::load_or_create(config_dir.as_ref()).await?;
+    let mut stream = connect(&host, PAIRING_PORT, &cert, None).await?;
+    let server_cert = server_certificate(&stream)?.clone();
+    let server_key = server_public_key(&server_cert)?;
+
+    let mut request = vec![];
+    put_bytes_field(&mut request, 1, SERVICE_NAME.as_bytes());
+    put_bytes_field(&mut request, 2, CLIENT_NAME.as_bytes());
+    exchange_pairing(&mut stream, PAIRING_REQUEST, &request, PAIRING_REQUEST_ACK).await?;
+
+    let mut encoding = vec![];
+    put_uint_field(&mut encoding, 1, ENCODING_HEXADECIMAL);
+    put_uint_field(&mut encoding, 2, ENCODING_SYMBOL_LENGTH);
+
+    let mut option = vec![];
+    put_bytes_field(&mut option, 1, &encoding);
+    put_uint_field(&mut option, 3, ROLE_INPUT);
+    exchange_pairing(&mut stream, PAIRING_OPTION, &option, PAIRING_OPTION).await?;
+
+    let mut configuration = vec![];
+    put_bytes_field(&mut configuration, 1, &encoding);
+    put_uint_field(&mut configuration, 2, ROLE_INPUT);
+    exchange_pairing(
+        &mut stream,
+        PAIRING_CONFIGURATION,
+        &configuration,
+        PAIRING_CONFIGURATION_ACK,
+    )
+    .await?;
+
+    let code = code.await.context("pairing cancelled")?;
+    let secret = pairing_secret(
+        &PublicKeyNumbers::from(&cert.public_key()?),
+        &PublicKeyNumbers::from(&server_key),
+        &code,
+    )?;
+
+    let mut message = vec![];
+    put_bytes_field(&mut message, 1, &secret);
+    exchange_pairing(&mut stream, PAIRING_SECRET, &message, PAIRING_SECRET_ACK).await?;
+
+    stream.shutdown().await.ok();
+
+    // the remote port uses the same certificate, so it is pinned since the code authenticated it.
+    save_server_fingerprint(
+        config_dir.as_ref(),
+        &host,
+        &cert_fingerprint(&server_cert.0),
+    )
+    .await?;
+    info!(%host, "paired");
+
+    Ok(())
+}
+
+/// a connection to the paired TV that is ready to receive the keys.
+pub struct RemoteConnection {
+    closed: oneshot::Receiver<String>,
+    reader: JoinHandle<()>,
+    writer: Arc<Mutex<WriteHalf<TlsStream<TcpStream>>>>,
+}
+
+impl RemoteConnection {
+    /// connects to the TV and waits until the TV activates the client.
+    pub async fn connect(host: &str, config_dir: &Path) -> Fallible<Self> {
+        info!(%host, "connect remote");
+
+        let cert = ClientCert::load_or_create(config_dir).await?;
+        let fingerprint = load_server_fingerprints(config_dir)
+            .await?
+            .remove(host)
+            .with_context(|| format!("{} is not paired", host))?;
+        let mut stream = connect(host, REMOTE_PORT, &cert, Some(fingerprint)).await?;
+
+        let handshake = async {
+            loop {
+                let message = read_message(&mut stream).await?;
+                let fields = parse_fields(&message)?;
+                if let Some(reply) = remote_reply(&fields) {
+                    write_message(&mut stream, &reply).await?;
+                }
+                if find_field(&fields, REMOTE_SET_ACTIVE).is_some() {
+                    return Ok::<_, anyhow::Error>(());
+                }
+            }
+        };
+        tokio::time::timeout(CONNECT_TIMEOUT, handshake)
+            .await
+            .context("handshake timed out")??;
+
+        let (reader, writer) = tokio::io::split(stream);
+        let writer = Arc::new(Mutex::new(writer));
+        let (closed, reader) = answer_messages(reader, writer.clone());
+        Ok(Self {
+            closed,
+            reader,
+            writer,
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl CommandTarget for RemoteConnection {
+    fn capabilities(&self) -> DeviceCapabilities {
+        DeviceCapabilities::ANDROID_TV_REMOTE
+    }
+
+    async fn send(&mut self, command: &ServerCommand) -> Fallible<()> {
+        match remote_command_message(command)? {
+            Some(message) => write_message(&mut *self.writer.lock().await, &message).await,
+            None => Ok(()),
+        }
+    }
+
+    async fn closed(&mut self) -> String {
+        (&mut self.closed)
+            .await
+            .unwrap_or_else(|_| "connection closed".into())
+    }
+
+    async fn close(&mut self) {
+        self.writer.lock().await.shutdown().await.ok();
+    }
+}
+
+impl Drop for RemoteConnection {
+    fn drop(&mut self) {
+        self.reader.abort();
+    }
+}
+
+/// the TV uses a self-signed certificate, so the pairing accepts any certificate that the pairing
+/// code authenticates, and the remote connection accepts only the certificate that was paired.
+struct PairedServerCert {
+    fingerprint: Option<String>,
+}
+
+impl rustls::client::ServerCertVerifier for PairedServerCert {
+    fn verify_server_cert(
+        &self,
+        end_entity: &rustls::Certificate,
+        _intermediates: &[rustls::Certificate],
+        _server_name: &rustls::ServerName,
+        _scts: &mut dyn Iterator<Item = &[u8]>,
+        _ocsp_response: &[u8],
+        _now: SystemTime,
+    ) -> Result<rustls::client::ServerCertVerified, rustls::Error> {
+        match &self.fingerprint {
+            Some(fingerprint) if *fingerprint != cert_fingerprint(&end_entity.0) => {
+                warn!("the server certificate is not the paired one");
+                Err(rustls::Error::General(
+                    "the server certificate is not the paired one, pair again".into(),
+                ))
+            }
+            _ => Ok(rustls::client::ServerCertVerified::assertion()),
+        }
+    }
+}
+
+async fn connect(
+    host: &str,
+    port: u16,
+    cert: &ClientCert,
+    fingerprint: Option<String>,
+) -> Fallible<TlsStream<TcpStream>> {
+    let config = rustls::ClientConfig::builder()
+        .with_safe_defaults()
+        .with_custom_certificate_verifier(Arc::new(PairedServerCert { fingerprint }))
+        .with_single_cert(
+            vec![rustls::Certificate(cert.cert_der.clone())],
+            rustls::PrivateKey(cert.key_der.clone()),
+        )
+        .context("failed to configure TLS")?;
+
+    let stream = tokio::time::timeout(CONNECT_TIMEOUT, TcpStream::connect((host, port)))
+        .await
+        .context("connection timed out")?
+        .with_context(|| format!("failed to connect to {}:{}", host, port))?;
+
+    tokio_rustls::TlsConnector::from(Arc::new(config))
+        .connect(SERVER_NAME.try_into().unwrap(), stream)
+        .await
+        .context("failed to handshake TLS")
+}
+
+fn server_certificate(stream: &TlsStream<TcpStream>) -> Fallible<&rustls::Certificate> {
+    stream
+        .get_ref()
+        .1
+        .peer_certificates()
+        .and_then(|data| data.first())
+        .context("server certificate not found")
+}
+
+fn server_public_key(cert: &rustls::Certificate) -> Fallible<RsaPublicKey> {
+    let (_, cert) = x509_parser::parse_x509_certificate(&cert.0)
+        .map_err(|e| anyhow::anyhow!("failed to parse the server certificate: {}", e))?;
+
+    RsaPublicKey::from_public_key_der(cert.public_key().raw).context("the server key is not RSA")
+}
+
+/// the hex SHA-256 of the DER certificate.
+fn cert_fingerprint(cert_der: &[u8]) -> String {
+    Sha256::digest(cert_der)
+        .iter()
+        .map(|data| format!("{:02x}", data))
+        .collect()
+}
+
+async fn load_server_fingerprints(config_dir: &Path) -> Fallible<BTreeMap<String, String>> {
+    let path = config_dir.join(SERVER_CERTS_FILE_NAME);
+    if !path.exists() {
+        return Ok(BTreeMap::new());
+    }
+
+    let data = tokio::fs::read_to_string(&path)
+        .await
+        .context("failed to read the server certificates")?;
+    toml::from_str(&data).context("failed to parse the server certificates")
+}
+
+async fn save_server_fingerprint(config_dir: &Path, host: &str, fingerprint: &str) -> Fallible<()> {
+    let mut fingerprints = load_server_fingerprints(config_dir).await?;
+    fingerprints.insert(host.into(), fingerprint.into());
+    tokio::fs::write(
+        config_dir.join(SERVER_CERTS_FILE_NAME),
+        toml::to_string(&fingerprints).context("failed to serialize the server certificates")?,
+    )
+    .await
+    .context("failed to write the server certificates")
+}
+
+/// creates the file that only the user can read since it has the private key.
+async fn write_private_file(path: &Path, data: &[u8]) -> std::io::Result<()> {
+    let mut options = tokio::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    options.mode(0o600);
+
+    let mut file = options.open(path).await?;
+    file.write_all(data).await?;
+    file.flush().await
+}
+
+/// sends the pairing message and returns the payload of the `expected` reply.
+async fn exchange_pairing<S: AsyncRead + AsyncWrite + Unpin>(
+    stream: &mut S,
+    field: u32,
+    payload: &[u8],
+    expected: u32,
+) -> Fallible<Vec<u8>> {
+    let mut message = vec![];
+    put_uint_field(&mut message, 1, PAIRING_PROTOCOL_VERSION);
+    put_uint_field(&mut message, 2, PAIRING_STATUS_OK);
+    put_bytes_field(&mut message, field, payload);
+    write_message(stream, &message).await?;
+
+    let reply = read_message(stream).await?;
+    let fields = parse_fields(&reply)?;
+    match find_field(&fields, 2) {
+        Some(FieldValue::Varint(PAIRING_STATUS_OK)) => (),
+        Some(FieldValue::Varint(status)) => anyhow::bail!("pairing rejected: status {}", status),
+        _ => anyhow::bail!("pairing status not found"),
+    }
+    match find_field(&fields, expected) {
+        Some(FieldValue::Bytes(data)) => Ok(data.clone()),
+        _ => anyhow::bail!("unexpected pairing message: {:?}", fields),
+    }
+}
+
+/// the big-endian modulus and exponent of an RSA public key.
+struct PublicKeyNumbers {
+    modulus: Vec<u8>,
+    exponent: Vec<u8>,
+}
+
+impl From<&RsaPublicKey> for PublicKeyNumbers {
+    fn from(value: &RsaPublicKey) -> Self {
+        Self {
+            modulus: value.n().to_bytes_be(),
+            exponent: value.e().to_bytes_be(),
+        }
+    }
+}
+
+/// hashes the public keys and the code, and checks the code by the first byte of the hash.
+fn pairing_secret(
+    client: &PublicKeyNumbers,
+    server: &PublicKeyNumbers,
+    code: &str,
+) -> Fallible<Vec<u8>> {
+    let code = code.trim();
+    if code.len() != ENCODING_SYMBOL_LENGTH as usize
+        || !code.chars().all(|data| data.is_ascii_hexdigit())
+    {
+        anyhow::bail!("the code must be 6 hex digits: {:?}", code);
+    }
+
+    // validated above.
+    let code = (0..code.len())
+        .step_by(2)
+        .map(|index| u8::from_str_radix(&code[index..index + 2], 16).unwrap())
+        .collect::<Vec<_>>();
+
+    let mut hasher = Sha256::new();
+    hasher.update(&client.modulus);
+    hasher.update(&client.exponent);
+    hasher.update(&server.modulus);
+    hasher.update(&server.exponent);
+    hasher.update(&code[1..]);
+    let secret = hasher.finalize();
+
+    if secret[0] != code[0] {
+        anyhow::bail!("wrong code");
+    }
+
+    Ok(secret.to_vec())
+}
+
+/// returns the reply to the message that the TV expects an answer.
+fn remote_reply(fields: &[(u32, FieldValue)]) -> Option<Vec<u8>> {
+    let mut message = vec![];
+    if find_field(fields, REMOTE_CONFIGURE).is_some() {
+        let mut device_info = vec![];
+        put_bytes_field(&mut device_info, 1, CLIENT_NAME.as_bytes());
+        put_bytes_field(&mut device_info, 2, b"sukawasatoru");
+        put_uint_field(&mut device_info, 3, 1);
+        put_bytes_field(&mut device_info, 4, b"1");
+        put_bytes_field(&mut device_info, 5, CLIENT_NAME.as_bytes());
+        put_bytes_field(&mut device_info, 6, env!("CARGO_PKG_VERSION").as_bytes());
+
+        let mut configure = vec![];
+        put_uint_field(&mut configure, 1, REMOTE_FEATURES);
+        put_bytes_field(&mut configure, 2, &device_info);
+        put_bytes_field(&mut message, REMOTE_CONFIGURE, &configure);
+    } else if find_field(fields, REMOTE_SET_ACTIVE).is_some() {
+        let mut set_active = vec![];
+        put_uint_field(&mut set_active, 1, REMOTE_FEATURES);
+        put_bytes_field(&mut message, REMOTE_SET_ACTIVE, &set_active);
+    } else if let Some(FieldValue::Bytes(ping)) = find_field(fields, REMOTE_PING_REQUEST) {
+        let value = parse_fields(ping)
+            .ok()
+            .and_then(|data| match find_field(&data, 1) {
+                Some(FieldValue::Varint(value)) => Some(*value),
+                _ => None,
+            })
+            .unwrap_or_default();
+        let mut pong = vec![];
+        put_uint_field(&mut pong, 1, value);
+        put_bytes_field(&mut message, REMOTE_PING_RESPONSE, &pong);
+    } else {
+        return None;
+    }
+    Some(message)
+}
+
+/// the remote protocol has no text input, so [ServerCommand::Text] is rejected.
+fn remote_command_message(command: &ServerCommand) -> Fallible<Option<Vec<u8>>> {
+    let (keycode, direction) = match command {
+        ServerCommand::KeyDown(keycode) | ServerCommand::KeyLongPress(keycode) => {
+            (keycode, DIRECTION_START_LONG)
+        }
+        ServerCommand::KeyUp(keycode) => (keycode, DIRECTION_END_LONG),
+        ServerCommand::Ping | ServerCommand::Quit => return Ok(None),
+        ServerCommand::Text(_) => anyhow::bail!("text is not supported by the TV remote"),
+    };
+
+    Ok(Some(key_inject_message(keycode, direction)))
+}
+
+fn key_inject_message(keycode: &Keycode, direction: u64) -> Vec<u8> {
+    let mut key_inject = vec![];
+    put_uint_field(&mut key_inject, 1, keycode.value() as u64);
+    put_uint_field(&mut key_inject, 2, direction);
+
+    let mut message = vec![];
+    put_bytes_field(&mut message, REMOTE_KEY_INJECT, &key_inject);
+    message
+}
+
+/// answers the messages such as the ping, and notifies the reason when the connection is lost.
+fn answer_messages(
+    mut reader: ReadHalf<TlsStream<TcpStream>>,
+    writer: Arc<Mutex<WriteHalf<TlsStream<TcpStream>>>>,
+) -> (oneshot::Receiver<String>, JoinHandle<()>) {
+    let (tx, rx) = oneshot::channel();
+    let handle = tokio::spawn(async move {
+        let reason = loop {
+            let ret = async {
+                let message = read_message(&mut reader).await?;
+                let fields = parse_fields(&message)?;
+                match remote_reply(&fields) {
+                    Some(reply) => write_message(&mut *writer.lock().await, &reply).await,
+                    None => {
+                        debug!(?fields, "skip message");
+                        Ok(())
+                    }
+                }
+            }
+            .await;
+            if let Err(e) = ret {
+                break format!("{:#}", e);
+            }
+        };
+        debug!(%reason, "stop answering messages");
+        tx.send(reason).ok();
+    });
+    (rx, handle)
+}
+
+async fn write_message<W: AsyncWrite + Unpin>(writer: &mut W, message: &[u8]) -> Fallible<()> {
+    let mut buf = Vec::with_capacity(message.len() + 2);
+    put_varint(&mut buf, message.len() as u64);
+    buf.extend_from_slice(message);
+    writer
+        .write_all(&buf)
+        .await
+        .context("failed to write the message")?;
+    writer.flush().await.context("failed to flush the message")
+}
+
+async fn read_message<R: AsyncRead + Unpin>(reader: &mut R) -> Fallible<Vec<u8>> {
+    let mut len = 0_u64;
+    for index in 0..10 {
+        let byte = reader
+            .read_u8()
+            .await
+            .context("failed to read the message length")?;
+        len |= ((byte & 0x7f) as u64) << (7 * index);
+        if byte & 0x80 == 0 {
+            break;
+        }
+    }
+
+    if len as usize > MAX_MESSAGE_LEN {
+        warn!(len, "message too large");
+        anyhow::bail!("message too large: {}", len);
+    }
+
+    let mut message = vec![0; len as usize];
+    reader
+        .read_exact(&mut message)
+        .await
+        .context("failed to read the message")?;
+    Ok(message)
+}
+
+#[derive(Debug, Eq, PartialEq)]
+enum FieldValue {
+    Varint(u64),
+    Bytes(Vec<u8>),
+}
+
+fn put_varint(buf: &mut Vec<u8>, mut value: u64) {
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            buf.push(byte);
+            return;
+        }
+        buf.push(byte | 0x80);
+    }
+}
+
+fn put_uint_field(buf: &mut Vec<u8>, field: u32, value: u64) {
+    put_varint(buf, (field as u64) << 3);
+    put_varint(buf, value);
+}
+
+fn put_bytes_field(buf: &mut Vec<u8>, field: u32, data: &[u8]) {
+    put_varint(buf, ((field as u64) << 3) | 2);
+    put_varint(buf, data.len() as u64);
+    buf.extend_from_slice(data);
+}
+
+fn get_varint(data: &[u8], position: &mut usize) -> Fallible<u64> {
+    let mut value = 0_u64;
+    for index in 0..10 {
+        let byte = *data.get(*position).context("truncated varint")?;
+        *position += 1;
+        value |= ((byte & 0x7f) as u64) << (7 * index);
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    anyhow::bail!("varint too long")
+}
+
+/// parses the varint and length-delimited fields, and skips the fixed-length fields.
+fn parse_fields(data: &[u8]) -> Fallible<Vec<(u32, FieldValue)>> {
+    let mut fields = vec![];
+    let mut position = 0;
+    while position < data.len() {
+        let key = get_varint(data, &mut position)?;
+        let field = (key >> 3) as u32;
+        match key & 0x7 {
+            0 => fields.push((field, FieldValue::Varint(get_varint(data, &mut position)?))),
+            1 => position += 8,
+            2 => {
+                let len = get_varint(data, &mut position)? as usize;
+                let value = data
+                    .get(position..position + len)
+                    .context("truncated field")?;
+                position += len;
+                fields.push((field, FieldValue::Bytes(value.to_vec())));
+            }
+            5 => position += 4,
+            wire_type => anyhow::bail!("unsupported wire type: {}", wire_type),
+        }
+    }
+    Ok(fields)
+}
+
+fn find_field(fields: &[(u32, FieldValue)], field: u32) -> Option<&FieldValue> {
+    fields
+        .iter()
+        .find(|(data, _)| *data == field)
+        .map(|(_, value)| value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fields_round_trip() {
+        let mut message = vec![];
+        put_uint_field(&mut message, 1, 300);
+        put_bytes_field(&mut message, 10, b"abc");
+        assert_eq!(
+            vec![0x08, 0xac, 0x02, 0x52, 0x03, b'a', b'b', b'c'],
+            message
+        );
+
+        assert_eq!(
+            vec![
+                (1, FieldValue::Varint(300)),
+                (10, FieldValue::Bytes(b"abc".to_vec())),
+            ],
+            parse_fields(&message).unwrap()
+        );
+        assert!(parse_fields(&message[..message.len() - 1]).is_err());
+    }
+
+    #[test]
+    fn key_inject() {
+        let message =
+            remote_command_message(&ServerCommand::KeyDown("KEYCODE_HOME".parse().unwrap()))
+                .unwrap();
+        assert_eq!(Some(vec![0x52, 0x04, 0x08, 0x03, 0x10, 0x01]), message);
+
+        assert_eq!(None, remote_command_message(&ServerCommand::Ping).unwrap());
+        assert!(remote_command_message(&ServerCommand::text("a").unwrap()).is_err());
+    }
+
+    #[test]
+    fn ping_reply() {
+        let mut ping = vec![];
+        put_uint_field(&mut ping, 1, 7);
+        let mut message = vec![];
+        put_bytes_field(&mut message, REMOTE_PING_REQUEST, &ping);
+
+        assert_eq!(
+            Some(vec![0x4a, 0x02, 0x08, 0x07]),
+            remote_reply(&parse_fields(&message).unwrap())
+        );
+    }
+
+    #[test]
+    fn check_pairing_code() {
+        let client = PublicKeyNumbers {
+            modulus: vec![0xc3, 0x5a, 0x01],
+            exponent: vec![0x01, 0x00, 0x01],
+        };
+        let server = PublicKeyNumbers {
+            modulus: vec![0xa7, 0x10, 0xff],
+            exponent: vec![0x01, 0x00, 0x01],
+        };
+
+        let secret = pairing_secret(&client, &server, "6c1f2e").unwrap();
+        assert_eq!(
+            "6c1181c04388ecb9ee1530005936a530514e9fdf10bdda7c09a25815c43a6e0e",
+            secret
+                .iter()
+                .map(|data| format!("{:02x}", data))
+                .collect::<String>()
+        );
+        assert!(pairing_secret(&client, &server, "6d1f2e").is_err());
+        assert!(pairing_secret(&client, &server, "6c1f2").is_err());
+        assert!(pairing_secret(&client, &server, "6c1f2g").is_err());
+    }
+
+    #[test]
+    fn verify_paired_server_cert() {
+        let verify = |fingerprint: Option<String>, cert: &[u8]| {
+            rustls::client::ServerCertVerifier::verify_server_cert(
+                &PairedServerCert { fingerprint },
+                &rustls::Certificate(cert.to_vec()),
+                &[],
+                &SERVER_NAME.try_into().unwrap(),
+                &mut std::iter::empty(),
+                &[],
+                SystemTime::now(),
+            )
+            .is_ok()
+        };
+
+        assert!(verify(None, b"cert"));
+        assert!(verify(Some(cert_fingerprint(b"cert")), b"cert"));
+        assert!(!verify(Some(cert_fingerprint(b"cert")), b"other"));
+    }
+
+    #[tokio::test]
+    async fn server_fingerprints_round_trip() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        assert!(load_server_fingerprints(temp_dir.path())
+            .await
+            .unwrap()
+            .is_empty());
+
+        save_server_fingerprint(temp_dir.path(), "192.168.0.2", "aa")
+            .await
+            .unwrap();
+        save_server_fingerprint(temp_dir.path(), "tv.local", "bb")
+            .await
+            .unwrap();
+        save_server_fingerprint(temp_dir.path(), "192.168.0.2", "cc")
+            .await
+            .unwrap();
+
+        let fingerprints = load_server_fingerprints(temp_dir.path()).await.unwrap();
+        assert_eq!(Some(&"cc".to_string()), fingerprints.get("192.168.0.2"));
+        assert_eq!(Some(&"bb".to_string()), fingerprints.get("tv.local"));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn private_file_mode() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join(KEY_FILE_NAME);
+        write_private_file(&path, b"key").await.unwrap();
+
+        assert_eq!(b"key".to_vec(), std::fs::read(&path).unwrap());
+        assert_eq!(
+            0o600,
+            std::fs::metadata(&path).unwrap().permissions().mode() & 0o777
+        );
+    }
+}
@@ -0,0 +1,362 @@
+/*
+ * Copyright 2022 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! the serial protocol of the Pulse-Eight USB-CEC adapter that libcec talks to.
+//!
+//! the adapter transmits a CEC frame byte by byte, and replies whether the frame is acknowledged.
+
+use crate::data::command_target::CommandTarget;
+use crate::model::keycode::Keycode;
+use crate::model::protocol::ServerCommand;
+use crate::model::target::DeviceCapabilities;
+use crate::prelude::*;
+use serialport::SerialPort;
+use std::io::Read;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::{debug, info};
+
+const BAUD_RATE: u32 = 38400;
+const RESPONSE_TIMEOUT: Duration = Duration::from_secs(1);
+
+const MSG_START: u8 = 0xff;
+const MSG_END: u8 = 0xfe;
+const MSG_ESC: u8 = 0xfd;
+const ESC_OFFSET: u8 = 3;
+
+const MSGCODE_PING: u8 = 0x01;
+const MSGCODE_COMMAND_ACCEPTED: u8 = 0x08;
+const MSGCODE_COMMAND_REJECTED: u8 = 0x09;
+const MSGCODE_SET_ACK_MASK: u8 = 0x0a;
+const MSGCODE_TRANSMIT: u8 = 0x0b;
+const MSGCODE_TRANSMIT_EOM: u8 = 0x0c;
+const MSGCODE_TRANSMIT_ACK_POLARITY: u8 = 0x0e;
+const MSGCODE_TRANSMIT_SUCCEEDED: u8 = 0x10;
+const MSGCODE_TRANSMIT_FAILED_LINE: u8 = 0x11;
+const MSGCODE_TRANSMIT_FAILED_TIMEOUT_LINE: u8 = 0x14;
+const MSGCODE_SET_CONTROLLED: u8 = 0x18;
+/// the upper bits are the flags of the received frame.
+const MSGCODE_MASK: u8 = 0x3f;
+
+/// the logical address of this client as a recording device.
+const INITIATOR: u8 = 1;
+const BROADCAST: u8 = 0x0f;
+const USER_CONTROL_PRESSED: u8 = 0x44;
+const USER_CONTROL_RELEASED: u8 = 0x45;
+
+/// the adapter that sends the user control keys to the device of the `logical_address`.
+pub struct CecAdapter {
+    logical_address: u8,
+    port: Arc<Mutex<Box<dyn SerialPort>>>,
+}
+
+impl CecAdapter {
+    pub async fn open(port: String, logical_address: u8) -> Fallible<Self> {
+        info!(%port, logical_address, "open CEC adapter");
+
+        if logical_address >= BROADCAST {
+            anyhow::bail!("invalid logical address: {}", logical_address);
+        }
+
+        let serial = tokio::task::spawn_blocking(move || {
+            let mut serial = serialport::new(&port, BAUD_RATE)
+                .timeout(RESPONSE_TIMEOUT)
+                .open()
+                .with_context(|| format!("failed to open {}", port))?;
+
+            send_adapter_command(&mut *serial, MSGCODE_PING, &[])?;
+
+            // the old firmware acknowledges the frames by itself.
+            if let Err(e) = send_adapter_command(&mut *serial, MSGCODE_SET_CONTROLLED, &[1]) {
+                debug!(?e, "controlled mode not supported");
+            }
+
+            let ack_mask = 1_u16 << INITIATOR;
+            send_adapter_command(&mut *serial, MSGCODE_SET_ACK_MASK, &ack_mask.to_be_bytes())?;
+
+            Ok::<_, anyhow::Error>(serial)
+        })
+        .await
+        .context("failed to join")??;
+
+        Ok(Self {
+            logical_address,
+            port: Arc::new(Mutex::new(serial)),
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl CommandTarget for CecAdapter {
+    fn capabilities(&self) -> DeviceCapabilities {
+        DeviceCapabilities::CEC
+    }
+
+    async fn send(&mut self, command: &ServerCommand) -> Fallible<()> {
+        let frame = match command_frame(command, self.logical_address)? {
+            Some(data) => data,
+            None => return Ok(()),
+        };
+
+        let port = self.port.clone();
+        tokio::task::spawn_blocking(move || {
+            let mut port = port
+                .lock()
+                .map_err(|_| anyhow::anyhow!("the adapter is poisoned"))?;
+            transmit(&mut **port, &frame)
+        })
+        .await
+        .context("failed to join")?
+    }
+}
+
+/// returns the CEC user control code of the keycode that the default key map uses.
+fn user_control_code(keycode: &Keycode) -> Option<u8> {
+    let code = match keycode.value() {
+        3 => 0x09,                                  // HOME -> root menu
+        4 => 0x0d,                                  // BACK -> exit
+        value @ 7..=16 => 0x20 + (value - 7) as u8, // 0-9
+        19 => 0x01,                                 // DPAD_UP
+        20 => 0x02,                                 // DPAD_DOWN
+        21 => 0x03,                                 // DPAD_LEFT
+        22 => 0x04,                                 // DPAD_RIGHT
+        23 => 0x00,                                 // DPAD_CENTER -> select
+        24 => 0x41,                                 // VOLUME_UP
+        25 => 0x42,                                 // VOLUME_DOWN
+        26 => 0x40,                                 // POWER
+        85 => 0x61,                                 // MEDIA_PLAY_PAUSE -> pause-play function
+        86 => 0x45,                                 // MEDIA_STOP
+        87 => 0x4b,                                 // MEDIA_NEXT -> forward
+        88 => 0x4c,                                 // MEDIA_PREVIOUS -> backward
+        89 => 0x48,                                 // MEDIA_REWIND
+        90 => 0x49,                                 // MEDIA_FAST_FORWARD
+        126 => 0x44,                                // MEDIA_PLAY
+        127 => 0x46,                                // MEDIA_PAUSE
+        164 => 0x43,                                // VOLUME_MUTE
+        165 => 0x35,                                // INFO -> display information
+        166 => 0x30,                                // CHANNEL_UP
+        167 => 0x31,                                // CHANNEL_DOWN
+        172 => 0x53,                                // GUIDE -> electronic program guide
+        178 => 0x34,                                // TV_INPUT -> input select
+        183 => 0x72,                                // PROG_RED -> F2
+        184 => 0x73,                                // PROG_GREEN -> F3
+        185 => 0x74,                                // PROG_YELLOW -> F4
+        186 => 0x71,                                // PROG_BLUE -> F1
+        223 => 0x6c,                                // SLEEP -> power off function
+        224 => 0x6d,                                // WAKEUP -> power on function
+        _ => return None,
+    };
+    Some(code)
+}
+
+/// returns the CEC frame of the command, or `None` if nothing to send.
+fn command_frame(command: &ServerCommand, destination: u8) -> Fallible<Option<Vec<u8>>> {
+    let header = (INITIATOR << 4) | destination;
+    match command {
+        ServerCommand::KeyDown(keycode) | ServerCommand::KeyLongPress(keycode) => {
+            let code = user_control_code(keycode)
+                .with_context(|| format!("no CEC user control for {}", keycode))?;
+            Ok(Some(vec![header, USER_CONTROL_PRESSED, code]))
+        }
+        // the press has already failed if the keycode isn't supported.
+        ServerCommand::KeyUp(keycode) => {
+            Ok(user_control_code(keycode).map(|_| vec![header, USER_CONTROL_RELEASED]))
+        }
+        ServerCommand::Ping | ServerCommand::Quit => Ok(None),
+        ServerCommand::Text(_) => anyhow::bail!("text is not supported by CEC"),
+    }
+}
+
+fn encode_message(code: u8, params: &[u8]) -> Vec<u8> {
+    let mut buf = vec![MSG_START];
+    for data in std::iter::once(&code).chain(params) {
+        if *data >= MSG_ESC {
+            buf.push(MSG_ESC);
+            buf.push(*data - ESC_OFFSET);
+        } else {
+            buf.push(*data);
+        }
+    }
+    buf.push(MSG_END);
+    buf
+}
+
+/// reads the next message without the start and end markers.
+fn read_message(reader: &mut (impl Read + ?Sized)) -> Fallible<Vec<u8>> {
+    let mut message = vec![];
+    let mut started = false;
+    let mut escaped = false;
+    loop {
+        let mut byte = [0_u8];
+        reader
+            .read_exact(&mut byte)
+            .context("failed to read the adapter message")?;
+        match byte[0] {
+            MSG_START => {
+                message.clear();
+                started = true;
+                escaped = false;
+            }
+            _ if !started => debug!(byte = byte[0], "skip byte"),
+            MSG_END => return Ok(message),
+            MSG_ESC => escaped = true,
+            data if escaped => {
+                message.push(data + ESC_OFFSET);
+                escaped = false;
+            }
+            data => message.push(data),
+        }
+    }
+}
+
+/// sends the adapter command and waits until the adapter accepts it.
+fn send_adapter_command(port: &mut dyn SerialPort, code: u8, params: &[u8]) -> Fallible<()> {
+    port.write_all(&encode_message(code, params))
+        .context("failed to write the adapter command")?;
+
+    let deadline = Instant::now() + RESPONSE_TIMEOUT;
+    while Instant::now() < deadline {
+        let message = read_message(port)?;
+        match message.first().map(|data| data & MSGCODE_MASK) {
+            Some(MSGCODE_COMMAND_ACCEPTED) => return Ok(()),
+            Some(MSGCODE_COMMAND_REJECTED) => anyhow::bail!("command rejected: {:#04x}", code),
+            _ => debug!(?message, "skip message"),
+        }
+    }
+    anyhow::bail!("command timed out: {:#04x}", code)
+}
+
+/// transmits the CEC frame and waits until the destination acknowledges it.
+fn transmit(port: &mut dyn SerialPort, frame: &[u8]) -> Fallible<()> {
+    let broadcast = frame.first().map(|data| data & 0x0f) == Some(BROADCAST);
+    let mut buf = encode_message(MSGCODE_TRANSMIT_ACK_POLARITY, &[broadcast as u8]);
+    for (index, data) in frame.iter().enumerate() {
+        let code = if index == frame.len() - 1 {
+            MSGCODE_TRANSMIT_EOM
+        } else {
+            MSGCODE_TRANSMIT
+        };
+        buf.extend(encode_message(code, &[*data]));
+    }
+    port.write_all(&buf).context("failed to write the frame")?;
+
+    let deadline = Instant::now() + RESPONSE_TIMEOUT;
+    while Instant::now() < deadline {
+        let message = read_message(port)?;
+        match message.first().map(|data| data & MSGCODE_MASK) {
+            Some(MSGCODE_TRANSMIT_SUCCEEDED) => return Ok(()),
+            Some(MSGCODE_COMMAND_REJECTED) => anyhow::bail!("frame rejected"),
+            Some(code @ MSGCODE_TRANSMIT_FAILED_LINE..=MSGCODE_TRANSMIT_FAILED_TIMEOUT_LINE) => {
+                anyhow::bail!("failed to transmit: {:#04x}", code)
+            }
+            // the accepted parts and the frames from the other devices.
+            _ => debug!(?message, "skip message"),
+        }
+    }
+    anyhow::bail!("transmit timed out")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::KeyMap;
+
+    #[test]
+    fn message_escape() {
+        let message = encode_message(MSGCODE_TRANSMIT, &[0xfe, 0x44]);
+        assert_eq!(vec![0xff, 0x0b, 0xfd, 0xfb, 0x44, 0xfe], message);
+
+        let mut reader = [&[0x00, 0x12][..], &message].concat();
+        assert_eq!(
+            vec![MSGCODE_TRANSMIT, 0xfe, 0x44],
+            read_message(&mut reader.as_slice()).unwrap()
+        );
+
+        reader.truncate(reader.len() - 1);
+        assert!(read_message(&mut reader.as_slice()).is_err());
+    }
+
+    #[test]
+    fn key_frame() {
+        let keycode = "KEYCODE_DPAD_UP".parse::<Keycode>().unwrap();
+        assert_eq!(
+            Some(vec![0x10, USER_CONTROL_PRESSED, 0x01]),
+            command_frame(&ServerCommand::KeyDown(keycode.clone()), 0).unwrap()
+        );
+        assert_eq!(
+            Some(vec![0x15, USER_CONTROL_RELEASED]),
+            command_frame(&ServerCommand::KeyUp(keycode), 5).unwrap()
+        );
+
+        let keycode = "KEYCODE_CAMERA".parse::<Keycode>().unwrap();
+        assert!(command_frame(&ServerCommand::KeyDown(keycode.clone()), 0).is_err());
+        assert_eq!(
+            None,
+            command_frame(&ServerCommand::KeyUp(keycode), 0).unwrap()
+        );
+    }
+
+    #[test]
+    fn default_key_map_supported() {
+        let key_map = KeyMap::default();
+        for keycode in [
+            &key_map.back,
+            &key_map.color_red,
+            &key_map.color_green,
+            &key_map.color_blue,
+            &key_map.color_yellow,
+            &key_map.dpad_up,
+            &key_map.dpad_down,
+            &key_map.dpad_left,
+            &key_map.dpad_right,
+            &key_map.dpad_ok,
+            &key_map.num_0,
+            &key_map.num_1,
+            &key_map.num_2,
+            &key_map.num_3,
+            &key_map.num_4,
+            &key_map.num_5,
+            &key_map.num_6,
+            &key_map.num_7,
+            &key_map.num_8,
+            &key_map.num_9,
+            &key_map.home,
+            &key_map.volume_up,
+            &key_map.volume_down,
+            &key_map.mute,
+            &key_map.media_play_pause,
+            &key_map.media_stop,
+            &key_map.media_rewind,
+            &key_map.media_fast_forward,
+            &key_map.media_next,
+            &key_map.media_previous,
+            &key_map.power,
+            &key_map.sleep,
+            &key_map.wakeup,
+            &key_map.tv_input,
+            &key_map.guide,
+            &key_map.info,
+            &key_map.channel_up,
+            &key_map.channel_down,
+        ] {
+            assert!(
+                user_control_code(&keycode.parse().unwrap()).is_some(),
+                "keycode: {}",
+                keycode
+            );
+        }
+    }
+}
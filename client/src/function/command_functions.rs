@@ -0,0 +1,273 @@
+/*
+ * Copyright 2022 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::model::adb_error::AdbError;
+use crate::prelude::*;
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+use std::process::{ExitStatus, Stdio};
+use std::sync::{Mutex, MutexGuard};
+use std::time::Duration;
+use tracing::{debug, info, info_span, warn, Instrument};
+
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// pids of the running adb processes.
+static CHILD_PROCESSES: Mutex<Vec<u32>> = Mutex::new(Vec::new());
+
+/// the adb binary specified by the preferences.
+static ADB_PATH: Mutex<Option<PathBuf>> = Mutex::new(None);
+
+#[derive(Debug)]
+pub struct CommandOutput {
+    pub status: ExitStatus,
+    pub stdout: Vec<u8>,
+    pub stderr: String,
+}
+
+impl CommandOutput {
+    pub fn stdout_lossy(&self) -> String {
+        String::from_utf8_lossy(&self.stdout).into_owned()
+    }
+}
+
+/// returns the adb binary, preferring the one set by [set_adb_path].
+///
+/// the apps launched from the Finder don't inherit the `PATH` of the shell on macOS.
+pub fn find_adb_path() -> PathBuf {
+    if let Some(data) = ADB_PATH
+        .lock()
+        .unwrap_or_else(|data| data.into_inner())
+        .clone()
+    {
+        return data;
+    }
+
+    if cfg!(target_os = "macos") {
+        let mut candidates = vec![];
+        if let Some(data) = std::env::var_os("ANDROID_HOME") {
+            candidates.push(Path::new(&data).join("platform-tools/adb"));
+        }
+        if let Some(data) = directories::BaseDirs::new() {
+            candidates.push(
+                data.home_dir()
+                    .join("Library/Android/sdk/platform-tools/adb"),
+            );
+        }
+        candidates.push("/opt/homebrew/bin/adb".into());
+        candidates.push("/usr/local/bin/adb".into());
+
+        if let Some(data) = candidates.into_iter().find(|data| data.exists()) {
+            return data;
+        }
+    }
+
+    "adb".into()
+}
+
+/// overrides the adb binary for the subsequent commands, or clears it with `None`.
+pub fn set_adb_path(path: Option<PathBuf>) {
+    info!(?path, "set adb path");
+    *ADB_PATH.lock().unwrap_or_else(|data| data.into_inner()) = path;
+}
+
+pub fn adb_command() -> tokio::process::Command {
+    let mut command = tokio::process::Command::new(find_adb_path());
+    command.kill_on_drop(true);
+    command
+}
+
+/// runs the command and maps the non-zero exit code to an error.
+pub async fn run_command(
+    mut command: tokio::process::Command,
+    timeout: Duration,
+) -> Fallible<CommandOutput> {
+    let span = info_span!("run_command", ?command);
+
+    async move {
+        let child = command
+            .kill_on_drop(true)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .context("failed to invoke the command")?;
+        let _registration = register_child(child.id());
+
+        let output = tokio::time::timeout(timeout, child.wait_with_output())
+            .await
+            .with_context(|| format!("timed out after {:?}", timeout))?
+            .context("failed to wait the command")?;
+
+        let output = CommandOutput {
+            status: output.status,
+            stdout: output.stdout,
+            stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+        };
+
+        debug!(status = %output.status, "finished");
+
+        if !output.status.success() {
+            let e = anyhow::anyhow!("exit with {}: {}", output.status, output.stderr.trim());
+            // keeps the kind to show the hint by describe_adb_error.
+            return Err(match AdbError::classify(&output.stderr) {
+                Some(data) => e.context(data),
+                None => e,
+            });
+        }
+
+        Ok(output)
+    }
+    .instrument(span)
+    .await
+}
+
+/// describes the error of the adb command for the user, e.g. adb isn't installed.
+pub fn describe_adb_error(e: &anyhow::Error) -> String {
+    let not_found = e.chain().any(|cause| {
+        matches!(
+            cause.downcast_ref::<std::io::Error>(),
+            Some(data) if data.kind() == std::io::ErrorKind::NotFound
+        )
+    });
+    if not_found {
+        return format!("adb not found: {}", find_adb_path().display());
+    }
+
+    match e.downcast_ref::<AdbError>() {
+        Some(data) => format!("{:#}\nhint: {}", e, data.hint()),
+        None => format!("{:#}", e),
+    }
+}
+
+/// removes the pid from the registry when dropped.
+#[derive(Debug)]
+pub struct ChildRegistration(Option<u32>);
+
+impl Drop for ChildRegistration {
+    fn drop(&mut self) {
+        if let Some(pid) = self.0 {
+            child_processes().retain(|data| *data != pid);
+        }
+    }
+}
+
+/// registers the child process to kill it by [ChildProcessWatchdog].
+///
+/// `pid` is `None` if the child process has already exited.
+pub fn register_child(pid: Option<u32>) -> ChildRegistration {
+    if let Some(pid) = pid {
+        child_processes().push(pid);
+    }
+    ChildRegistration(pid)
+}
+
+/// kills the registered child processes when dropped, including the unwinding by panic.
+pub struct ChildProcessWatchdog;
+
+impl Drop for ChildProcessWatchdog {
+    fn drop(&mut self) {
+        kill_child_processes();
+    }
+}
+
+/// kills the orphaned child processes such as the server shell.
+pub fn kill_child_processes() {
+    let pids = std::mem::take(&mut *child_processes());
+    for pid in pids {
+        info!(pid, "kill child process");
+
+        let mut command = if cfg!(target_os = "windows") {
+            let mut command = std::process::Command::new("taskkill");
+            command.args(["/F", "/T", "/PID"]);
+            command
+        } else {
+            let mut command = std::process::Command::new("kill");
+            command.arg("-KILL");
+            command
+        };
+
+        let ret = command
+            .arg(pid.to_string())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status();
+        if let Err(e) = ret {
+            warn!(?e, pid, "failed to kill the child process");
+        }
+    }
+}
+
+/// ignores the poisoning since the registry is used while panicking.
+fn child_processes() -> MutexGuard<'static, Vec<u32>> {
+    CHILD_PROCESSES
+        .lock()
+        .unwrap_or_else(|data| data.into_inner())
+}
+
+/// launches the GUI program such as a file manager without waiting for it.
+pub fn spawn_detached(program: &str, arg: impl AsRef<OsStr>) -> Fallible<()> {
+    debug!(%program, arg = ?arg.as_ref(), "spawn");
+
+    std::process::Command::new(program)
+        .arg(arg)
+        .spawn()
+        .with_context(|| format!("failed to launch {}", program))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn child_registration() {
+        let registration = register_child(Some(u32::MAX));
+        assert!(child_processes().contains(&u32::MAX));
+
+        drop(registration);
+        assert!(!child_processes().contains(&u32::MAX));
+
+        drop(register_child(None));
+    }
+
+    #[test]
+    fn describe_adb_error_not_found() {
+        let e = anyhow::Error::new(std::io::Error::from(std::io::ErrorKind::NotFound))
+            .context("failed to invoke the command");
+        assert!(describe_adb_error(&e).starts_with("adb not found: "));
+
+        let e = anyhow::anyhow!("exit with 1").context("failed to push the server");
+        assert_eq!(
+            "failed to push the server: exit with 1",
+            describe_adb_error(&e)
+        );
+    }
+
+    #[test]
+    fn describe_adb_error_hint() {
+        let e = anyhow::anyhow!("exit with 1")
+            .context(AdbError::Unauthorized)
+            .context("failed to push the server");
+        assert_eq!(
+            format!(
+                "failed to push the server: device unauthorized: exit with 1\nhint: {}",
+                AdbError::Unauthorized.hint()
+            ),
+            describe_adb_error(&e)
+        );
+    }
+}
@@ -0,0 +1,332 @@
+/*
+ * Copyright 2022 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! a minimal MQTT 3.1.1 client that publishes and subscribes with QoS 0 only.
+
+use crate::model::mqtt::MqttConfig;
+use crate::prelude::*;
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt, ReadHalf, WriteHalf};
+use tokio::net::TcpStream;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tracing::{debug, info};
+
+/// the interval of PINGREQ is the half of it.
+pub const KEEP_ALIVE: Duration = Duration::from_secs(60);
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+const MAX_PACKET_LEN: usize = 64 * 1024;
+const OFFLINE_PAYLOAD: &[u8] = b"offline";
+
+const CONNECT: u8 = 0x10;
+const CONNACK: u8 = 0x20;
+const PUBLISH: u8 = 0x30;
+const SUBSCRIBE: u8 = 0x82;
+const SUBACK: u8 = 0x90;
+const PINGREQ: u8 = 0xc0;
+const PINGRESP: u8 = 0xd0;
+const DISCONNECT: u8 = 0xe0;
+
+#[derive(Debug, Eq, PartialEq)]
+pub enum MqttPacket {
+    ConnAck {
+        return_code: u8,
+    },
+    Publish {
+        topic: String,
+        payload: Vec<u8>,
+    },
+    SubAck,
+    PingResp,
+    /// the packets that aren't used since QoS is 0.
+    Other(u8),
+}
+
+/// a connection that subscribes the key topic of [MqttConfig].
+pub struct MqttConnection {
+    packets: mpsc::Receiver<Fallible<MqttPacket>>,
+    reader: JoinHandle<()>,
+    writer: WriteHalf<TcpStream>,
+}
+
+impl MqttConnection {
+    pub async fn connect(config: &MqttConfig) -> Fallible<Self> {
+        info!(host = %config.host, port = config.port, "connect mqtt");
+
+        let mut stream = tokio::time::timeout(
+            CONNECT_TIMEOUT,
+            TcpStream::connect((config.host.as_str(), config.port)),
+        )
+        .await
+        .context("connection timed out")?
+        .with_context(|| format!("failed to connect to {}:{}", config.host, config.port))?;
+
+        let client_id = format!("android-cmd-{:08x}", rand::random::<u32>());
+        stream
+            .write_all(&encode_connect(&client_id, config))
+            .await
+            .context("failed to write CONNECT")?;
+        let connack = tokio::time::timeout(CONNECT_TIMEOUT, read_packet(&mut stream))
+            .await
+            .context("CONNACK timed out")??;
+        match connack {
+            MqttPacket::ConnAck { return_code: 0 } => (),
+            MqttPacket::ConnAck { return_code } => {
+                anyhow::bail!("the broker refused the connection: {}", return_code)
+            }
+            data => anyhow::bail!("unexpected packet: {:?}", data),
+        }
+
+        stream
+            .write_all(&encode_subscribe(1, &config.key_topic()))
+            .await
+            .context("failed to write SUBSCRIBE")?;
+
+        let (reader, writer) = tokio::io::split(stream);
+        let (tx, packets) = mpsc::channel(16);
+        let reader = tokio::spawn(read_packets(reader, tx));
+        Ok(Self {
+            packets,
+            reader,
+            writer,
+        })
+    }
+
+    /// returns an error if the connection was closed.
+    pub async fn recv(&mut self) -> Fallible<MqttPacket> {
+        self.packets.recv().await.context("connection closed")?
+    }
+
+    pub async fn publish(&mut self, topic: &str, payload: &[u8], retain: bool) -> Fallible<()> {
+        self.writer
+            .write_all(&encode_publish(topic, payload, retain))
+            .await
+            .context("failed to write PUBLISH")
+    }
+
+    pub async fn ping(&mut self) -> Fallible<()> {
+        self.writer
+            .write_all(&[PINGREQ, 0])
+            .await
+            .context("failed to write PINGREQ")
+    }
+
+    /// disconnects without the will message.
+    pub async fn disconnect(&mut self) {
+        self.writer.write_all(&[DISCONNECT, 0]).await.ok();
+        self.writer.shutdown().await.ok();
+    }
+}
+
+impl Drop for MqttConnection {
+    fn drop(&mut self) {
+        self.reader.abort();
+    }
+}
+
+async fn read_packets(mut reader: ReadHalf<TcpStream>, tx: mpsc::Sender<Fallible<MqttPacket>>) {
+    loop {
+        let ret = read_packet(&mut reader).await;
+        let failed = ret.is_err();
+        if tx.send(ret).await.is_err() || failed {
+            debug!("stop reading packets");
+            return;
+        }
+    }
+}
+
+async fn read_packet<R: AsyncRead + Unpin>(reader: &mut R) -> Fallible<MqttPacket> {
+    let header = reader
+        .read_u8()
+        .await
+        .context("failed to read the packet type")?;
+
+    let mut len = 0_usize;
+    for index in 0..4 {
+        let byte = reader
+            .read_u8()
+            .await
+            .context("failed to read the packet length")?;
+        len |= ((byte & 0x7f) as usize) << (7 * index);
+        if byte & 0x80 == 0 {
+            break;
+        }
+    }
+
+    if len > MAX_PACKET_LEN {
+        anyhow::bail!("packet too large: {}", len);
+    }
+
+    let mut body = vec![0; len];
+    reader
+        .read_exact(&mut body)
+        .await
+        .context("failed to read the packet")?;
+    parse_packet(header, &body)
+}
+
+fn parse_packet(header: u8, body: &[u8]) -> Fallible<MqttPacket> {
+    let packet = match header & 0xf0 {
+        CONNACK => MqttPacket::ConnAck {
+            return_code: *body.get(1).context("invalid CONNACK")?,
+        },
+        PUBLISH => {
+            let topic_len = match body {
+                [high, low, ..] => u16::from_be_bytes([*high, *low]) as usize,
+                _ => anyhow::bail!("invalid PUBLISH"),
+            };
+            let topic = body.get(2..2 + topic_len).context("invalid PUBLISH")?;
+            // the packet identifier follows the topic if QoS is 1 or 2.
+            let payload_start = match (header >> 1) & 0x03 {
+                0 => 2 + topic_len,
+                _ => 4 + topic_len,
+            };
+            MqttPacket::Publish {
+                topic: String::from_utf8(topic.to_vec()).context("invalid topic")?,
+                payload: body.get(payload_start..).unwrap_or_default().to_vec(),
+            }
+        }
+        SUBACK => MqttPacket::SubAck,
+        PINGRESP => MqttPacket::PingResp,
+        data => MqttPacket::Other(data),
+    };
+    Ok(packet)
+}
+
+fn encode_connect(client_id: &str, config: &MqttConfig) -> Vec<u8> {
+    // clean session and the retained will message.
+    let mut flags = 0x02 | 0x04 | 0x20;
+    if config.username.is_some() {
+        flags |= 0x80;
+    }
+    if config.password.is_some() {
+        flags |= 0x40;
+    }
+
+    let mut body = vec![];
+    put_bytes(&mut body, b"MQTT");
+    body.push(4);
+    body.push(flags);
+    body.extend_from_slice(&(KEEP_ALIVE.as_secs() as u16).to_be_bytes());
+    put_bytes(&mut body, client_id.as_bytes());
+    put_bytes(&mut body, config.state_topic().as_bytes());
+    put_bytes(&mut body, OFFLINE_PAYLOAD);
+    if let Some(username) = &config.username {
+        put_bytes(&mut body, username.as_bytes());
+    }
+    if let Some(password) = &config.password {
+        put_bytes(&mut body, password.as_bytes());
+    }
+    encode_packet(CONNECT, &body)
+}
+
+fn encode_subscribe(packet_id: u16, topic: &str) -> Vec<u8> {
+    let mut body = packet_id.to_be_bytes().to_vec();
+    put_bytes(&mut body, topic.as_bytes());
+    // QoS 0.
+    body.push(0);
+    encode_packet(SUBSCRIBE, &body)
+}
+
+fn encode_publish(topic: &str, payload: &[u8], retain: bool) -> Vec<u8> {
+    let mut body = vec![];
+    put_bytes(&mut body, topic.as_bytes());
+    body.extend_from_slice(payload);
+    encode_packet(if retain { PUBLISH | 0x01 } else { PUBLISH }, &body)
+}
+
+fn encode_packet(header: u8, body: &[u8]) -> Vec<u8> {
+    let mut packet = vec![header];
+    let mut len = body.len();
+    loop {
+        let byte = (len & 0x7f) as u8;
+        len >>= 7;
+        if len == 0 {
+            packet.push(byte);
+            break;
+        }
+        packet.push(byte | 0x80);
+    }
+    packet.extend_from_slice(body);
+    packet
+}
+
+fn put_bytes(buf: &mut Vec<u8>, value: &[u8]) {
+    buf.extend_from_slice(&(value.len() as u16).to_be_bytes());
+    buf.extend_from_slice(value);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encode_connect_flags() {
+        let config = MqttConfig {
+            host: "localhost".into(),
+            port: MqttConfig::DEFAULT_PORT,
+            topic: "tv".into(),
+            serial: "emulator-5554".into(),
+            username: Some("u".into()),
+            password: None,
+        };
+        let packet = encode_connect("c", &config);
+        assert_eq!(
+            vec![
+                0x10, 0x23, 0x00, 0x04, b'M', b'Q', b'T', b'T', 0x04, 0xa6, 0x00, 0x3c, 0x00, 0x01,
+                b'c', 0x00, 0x08, b't', b'v', b'/', b's', b't', b'a', b't', b'e', 0x00, 0x07, b'o',
+                b'f', b'f', b'l', b'i', b'n', b'e', 0x00, 0x01, b'u',
+            ],
+            packet
+        );
+    }
+
+    #[test]
+    fn encode_packet_len() {
+        let packet = encode_packet(PUBLISH, &[0; 200]);
+        assert_eq!([0x30, 0xc8, 0x01], packet[..3]);
+        assert_eq!(203, packet.len());
+    }
+
+    #[test]
+    fn parse_packet_publish() {
+        let packet = encode_publish("tv/key", b"KEYCODE_HOME", false);
+        assert_eq!(
+            MqttPacket::Publish {
+                topic: "tv/key".into(),
+                payload: b"KEYCODE_HOME".to_vec(),
+            },
+            parse_packet(packet[0], &packet[2..]).unwrap()
+        );
+
+        // QoS 1 with the packet identifier.
+        let body = [0x00, 0x01, b'a', 0x00, 0x05, b'x'];
+        assert_eq!(
+            MqttPacket::Publish {
+                topic: "a".into(),
+                payload: b"x".to_vec(),
+            },
+            parse_packet(0x32, &body).unwrap()
+        );
+
+        assert_eq!(
+            MqttPacket::ConnAck { return_code: 5 },
+            parse_packet(CONNACK, &[0x00, 0x05]).unwrap()
+        );
+        assert!(parse_packet(PUBLISH, &[0x00, 0x05, b'a']).is_err());
+    }
+}
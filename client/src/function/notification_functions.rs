@@ -0,0 +1,32 @@
+/*
+ * Copyright 2022 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::prelude::*;
+use notify_rust::Notification;
+
+pub async fn show_notification(summary: String, body: String) -> Fallible<()> {
+    tokio::task::spawn_blocking(move || {
+        Notification::new()
+            .appname("Android Commander")
+            .summary(&summary)
+            .body(&body)
+            .show()
+            .context("failed to show notification")?;
+        Ok(())
+    })
+    .await
+    .context("notification task")?
+}
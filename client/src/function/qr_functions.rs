@@ -0,0 +1,42 @@
+/*
+ * Copyright 2022 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::prelude::*;
+use qrcode::render::svg;
+use qrcode::QrCode;
+
+/// renders the data as a QR code in SVG, with the quiet zone for the scanners.
+pub fn render_qr_svg(data: &str) -> Fallible<String> {
+    let code = QrCode::new(data.as_bytes()).context("failed to encode the QR code")?;
+    Ok(code
+        .render::<svg::Color>()
+        .min_dimensions(200, 200)
+        .dark_color(svg::Color("#000000"))
+        .light_color(svg::Color("#ffffff"))
+        .build())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_qr_svg_document() {
+        let svg = render_qr_svg("acmd1;s=emulator-5554;n=Full;l=;k=").unwrap();
+        assert!(svg.contains("<svg"));
+        assert!(render_qr_svg(&"x".repeat(8000)).is_err());
+    }
+}
@@ -0,0 +1,47 @@
+/*
+ * Copyright 2022 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::model::release::Release;
+use crate::prelude::*;
+use std::time::Duration;
+use tracing::info;
+
+const LATEST_RELEASE_URL: &str =
+    "https://api.github.com/repos/sukawasatoru/android-commander/releases/latest";
+const RELEASE_TIMEOUT: Duration = Duration::from_secs(10);
+
+pub async fn fetch_latest_release() -> Fallible<Release> {
+    info!("fetch latest release");
+
+    let body = reqwest::Client::builder()
+        .timeout(RELEASE_TIMEOUT)
+        // the GitHub API rejects the requests without the user agent.
+        .user_agent(concat!("android-commander/", env!("CARGO_PKG_VERSION")))
+        .build()
+        .context("failed to build the http client")?
+        .get(LATEST_RELEASE_URL)
+        .header(reqwest::header::ACCEPT, "application/vnd.github+json")
+        .send()
+        .await
+        .context("failed to fetch the latest release")?
+        .error_for_status()
+        .context("failed to fetch the latest release")?
+        .text()
+        .await
+        .context("failed to read the latest release")?;
+
+    Release::from_github_json(&body)
+}
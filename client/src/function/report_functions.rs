@@ -0,0 +1,58 @@
+/*
+ * Copyright 2022 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::function::webhook_functions::post_webhook;
+use crate::model::schedule::MacroReport;
+use crate::prelude::*;
+use std::path::PathBuf;
+use tracing::{info, warn};
+
+/// writes the report into the `report_dir`, and posts the summary to the webhook if specified.
+///
+/// the webhook failure is logged only since the report is already written.
+pub async fn export_macro_report(
+    report: MacroReport,
+    history_tsv: String,
+    report_dir: PathBuf,
+    webhook_url: Option<String>,
+) -> Fallible<PathBuf> {
+    info!(name = %report.name, ?report_dir, "export macro report");
+
+    tokio::fs::create_dir_all(&report_dir)
+        .await
+        .with_context(|| format!("failed to create the report directory: {:?}", report_dir))?;
+    let path = report_dir.join(report.file_name());
+    tokio::fs::write(&path, report.to_text(&history_tsv))
+        .await
+        .with_context(|| format!("failed to write the report: {:?}", path))?;
+
+    if let Some(url) = webhook_url {
+        let body = serde_json::json!({
+            "name": report.name,
+            "serial": report.serial,
+            "started_at": report.started_at.to_rfc3339(),
+            "finished_at": report.finished_at.to_rfc3339(),
+            "steps": report.steps,
+            "failure": report.failure,
+            "report": path,
+        });
+        if let Err(e) = post_webhook(&url, None, &body).await {
+            warn!(?e, "failed to post the report");
+        }
+    }
+
+    Ok(path)
+}
@@ -0,0 +1,206 @@
+/*
+ * Copyright 2022 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! the External Control Protocol (ECP) of Roku devices over HTTP.
+
+use crate::data::command_target::CommandTarget;
+use crate::model::keycode::Keycode;
+use crate::model::protocol::ServerCommand;
+use crate::model::target::DeviceCapabilities;
+use crate::prelude::*;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpStream;
+use tracing::{debug, info};
+
+const ECP_PORT: u16 = 8060;
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+pub struct RokuDevice {
+    host: String,
+}
+
+impl RokuDevice {
+    /// checks that the device answers the ECP.
+    pub async fn connect(host: String) -> Fallible<Self> {
+        info!(%host, "connect roku");
+
+        request(&host, "GET", "/query/device-info").await?;
+        Ok(Self { host })
+    }
+}
+
+#[async_trait::async_trait]
+impl CommandTarget for RokuDevice {
+    fn capabilities(&self) -> DeviceCapabilities {
+        DeviceCapabilities::ROKU
+    }
+
+    async fn send(&mut self, command: &ServerCommand) -> Fallible<()> {
+        for path in command_paths(command)? {
+            request(&self.host, "POST", &path).await?;
+        }
+        Ok(())
+    }
+}
+
+/// returns the ECP key of the keycode.
+fn ecp_key(keycode: &Keycode) -> Option<String> {
+    let key = match keycode.value() {
+        3 => "Home",
+        4 => "Back",
+        value @ 7..=16 => return Some(format!("Lit_{}", value - 7)),
+        19 => "Up",
+        20 => "Down",
+        21 => "Left",
+        22 => "Right",
+        23 => "Select",
+        24 => "VolumeUp",
+        25 => "VolumeDown",
+        26 => "Power",
+        66 => "Enter",
+        67 => "Backspace",
+        84 | 219 => "Search",
+        85 | 126 | 127 => "Play",
+        89 => "Rev",
+        90 => "Fwd",
+        164 => "VolumeMute",
+        165 => "Info",
+        166 => "ChannelUp",
+        167 => "ChannelDown",
+        223 => "PowerOff",
+        224 => "PowerOn",
+        _ => return None,
+    };
+    Some(key.into())
+}
+
+/// returns the ECP paths to post for the command.
+fn command_paths(command: &ServerCommand) -> Fallible<Vec<String>> {
+    let key = |keycode: &Keycode| {
+        ecp_key(keycode).with_context(|| format!("no Roku key for {}", keycode))
+    };
+    let paths = match command {
+        ServerCommand::KeyDown(keycode) | ServerCommand::KeyLongPress(keycode) => {
+            vec![format!("/keydown/{}", key(keycode)?)]
+        }
+        ServerCommand::KeyUp(keycode) => vec![format!("/keyup/{}", key(keycode)?)],
+        ServerCommand::Ping | ServerCommand::Quit => vec![],
+        // the device types the literal characters one by one.
+        ServerCommand::Text(value) => value
+            .chars()
+            .map(|data| format!("/keypress/Lit_{}", percent_encode(data)))
+            .collect(),
+    };
+    Ok(paths)
+}
+
+fn percent_encode(value: char) -> String {
+    if value.is_ascii_alphanumeric() || matches!(value, '-' | '.' | '_' | '~') {
+        return value.to_string();
+    }
+
+    let mut buf = [0; 4];
+    value
+        .encode_utf8(&mut buf)
+        .bytes()
+        .map(|data| format!("%{:02X}", data))
+        .collect()
+}
+
+/// sends the request without the body, and fails if the status isn't successful.
+async fn request(host: &str, method: &str, path: &str) -> Fallible<()> {
+    debug!(%host, %method, %path, "request");
+
+    let status_line = tokio::time::timeout(REQUEST_TIMEOUT, async {
+        let mut stream = TcpStream::connect((host, ECP_PORT))
+            .await
+            .with_context(|| format!("failed to connect to {}:{}", host, ECP_PORT))?;
+        stream
+            .write_all(
+                format!(
+                    "{} {} HTTP/1.1\r\nHost: {}:{}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                    method, path, host, ECP_PORT
+                )
+                .as_bytes(),
+            )
+            .await
+            .context("failed to write the request")?;
+
+        let mut status_line = String::new();
+        BufReader::new(stream)
+            .read_line(&mut status_line)
+            .await
+            .context("failed to read the response")?;
+        Ok::<_, anyhow::Error>(status_line)
+    })
+    .await
+    .context("request timed out")??;
+
+    match parse_status(&status_line) {
+        Some(status) if (200..300).contains(&status) => Ok(()),
+        Some(status) => anyhow::bail!("{} {} failed: {}", method, path, status),
+        None => anyhow::bail!("unexpected response: {:?}", status_line),
+    }
+}
+
+/// parses the status code of the line like `HTTP/1.1 200 OK`.
+fn parse_status(status_line: &str) -> Option<u16> {
+    let mut segments = status_line.split_whitespace();
+    if !segments.next()?.starts_with("HTTP/") {
+        return None;
+    }
+    segments.next()?.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn key_paths() {
+        assert_eq!(
+            vec!["/keydown/Select"],
+            command_paths(&ServerCommand::KeyDown(
+                "KEYCODE_DPAD_CENTER".parse().unwrap()
+            ))
+            .unwrap()
+        );
+        assert_eq!(
+            vec!["/keyup/Lit_9"],
+            command_paths(&ServerCommand::KeyUp("KEYCODE_9".parse().unwrap())).unwrap()
+        );
+        assert!(
+            command_paths(&ServerCommand::KeyDown("KEYCODE_PROG_RED".parse().unwrap())).is_err()
+        );
+        assert_eq!(
+            vec![
+                "/keypress/Lit_a",
+                "/keypress/Lit_%20",
+                "/keypress/Lit_%C3%A9"
+            ],
+            command_paths(&ServerCommand::text("a é").unwrap()).unwrap()
+        );
+    }
+
+    #[test]
+    fn status_line() {
+        assert_eq!(Some(200), parse_status("HTTP/1.1 200 OK\r\n"));
+        assert_eq!(Some(404), parse_status("HTTP/1.0 404 Not Found\r\n"));
+        assert_eq!(None, parse_status("SSH-2.0-OpenSSH\r\n"));
+        assert_eq!(None, parse_status(""));
+    }
+}
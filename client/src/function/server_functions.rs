@@ -0,0 +1,90 @@
+/*
+ * Copyright 2022 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::data::asset::Asset;
+use crate::function::command_functions::run_command;
+use crate::model::Preferences;
+use crate::prelude::*;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use tracing::{debug, info};
+
+/// returns the directory of the server project if running from a development tree.
+pub fn resolve_server_source_dir(prefs: &Preferences) -> Option<PathBuf> {
+    if let Some(data) = &prefs.server_source_dir {
+        return Some(data.clone());
+    }
+
+    if !cfg!(debug_assertions) {
+        return None;
+    }
+
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("../server");
+    if dir.join("app/src").exists() {
+        Some(dir)
+    } else {
+        None
+    }
+}
+
+pub async fn is_server_asset_outdated(source_dir: PathBuf) -> Fallible<bool> {
+    let asset_modified = match Asset::get("android-commander-server")
+        .and_then(|data| data.metadata.last_modified())
+    {
+        Some(data) => SystemTime::UNIX_EPOCH + Duration::from_secs(data),
+        None => return Ok(true),
+    };
+
+    let source_modified =
+        tokio::task::spawn_blocking(move || find_newest_modified(&source_dir.join("app/src")))
+            .await
+            .context("find_newest_modified")??;
+
+    debug!(?asset_modified, ?source_modified);
+
+    Ok(asset_modified < source_modified)
+}
+
+pub async fn build_server(source_dir: PathBuf) -> Fallible<()> {
+    info!(?source_dir, "build server");
+
+    let mut command = tokio::process::Command::new("make");
+    command.arg("-C").arg(&source_dir);
+    run_command(command, Duration::from_secs(600))
+        .await
+        .context("failed to build server")?;
+
+    Ok(())
+}
+
+fn find_newest_modified(dir: &Path) -> Fallible<SystemTime> {
+    let mut newest = SystemTime::UNIX_EPOCH;
+    for entry in std::fs::read_dir(dir).with_context(|| format!("read_dir: {:?}", dir))? {
+        let entry = entry?;
+        let metadata = entry.metadata()?;
+        let modified = if metadata.is_dir() {
+            find_newest_modified(&entry.path())?
+        } else {
+            metadata.modified()?
+        };
+
+        if newest < modified {
+            newest = modified;
+        }
+    }
+
+    Ok(newest)
+}
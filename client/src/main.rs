@@ -14,65 +14,114 @@
  * limitations under the License.
  */
 
+use android_commander::data::i18n::{load_font, set_language, tr};
 #[allow(unused_imports)]
 use android_commander::data::preferences_repository::MockPreferencesRepository;
 use android_commander::data::preferences_repository::{
     PreferencesRepository, PreferencesRepositoryImpl,
 };
+use android_commander::data::preferences_store::PreferencesStore;
+use android_commander::data::session_dir::SessionDir;
+use android_commander::feature::apps::{AppsView, AppsViewCommand};
+use android_commander::feature::device_info::{DeviceInfoView, DeviceInfoViewCommand};
+use android_commander::feature::files::{FilesView, FilesViewCommand};
+use android_commander::feature::intent::{IntentView, IntentViewCommand};
+use android_commander::feature::layout_editor::{LayoutEditorView, LayoutEditorViewCommand};
+use android_commander::feature::logcat::{LogcatView, LogcatViewCommand};
 use android_commander::feature::main::{MainView, MainViewCommand};
 use android_commander::feature::migrate::migrate;
+use android_commander::feature::monkey::{MonkeyView, MonkeyViewCommand};
+use android_commander::feature::run_macro::{parse_run_macro_args, run_macro};
 use android_commander::feature::settings::{
     SettingsView, SettingsViewCommand, ViewState as SettingsViewState,
 };
+use android_commander::feature::toast::{ToastView, ToastViewCommand};
+use android_commander::function::command_functions::{set_adb_path, ChildProcessWatchdog};
+use android_commander::model::api::ApiTokens;
+use android_commander::model::language::Language;
 use android_commander::model::Preferences;
 use android_commander::model::XMessage;
 use android_commander::prelude::*;
+use iced::subscription::events_with;
 use iced::theme::Theme;
-use iced::widget::{button, column, container, row, Column, Space};
-use iced::window::{resize, Settings as WindowSettings};
-use iced::{executor, Application, Command, Element, Length, Settings, Subscription};
-use std::path::PathBuf;
+use iced::widget::{button, column, container, text, Button, Column, Row, Space};
+use iced::window::{resize, Event as WindowEvent, Settings as WindowSettings};
+use iced::{
+    executor, Application, Command, Element, Event as NativeEvent, Length, Settings, Subscription,
+};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use tokio::sync::Mutex;
 use tracing::{info, warn};
 
 #[derive(Clone, Debug, PartialEq)]
 enum ActiveView {
+    Apps,
+    DeviceInfo,
+    Files,
+    Intent,
+    LayoutEditor,
+    Logcat,
     Main,
+    Monkey,
     Settings,
 }
 
 #[derive(Clone, Debug)]
 enum AppCommand {
     ActiveView(ActiveView),
+    AppsViewCommand(AppsViewCommand),
+    DeviceInfoViewCommand(DeviceInfoViewCommand),
+    FilesViewCommand(FilesViewCommand),
+    IntentViewCommand(IntentViewCommand),
+    LayoutEditorViewCommand(LayoutEditorViewCommand),
+    LogcatViewCommand(LogcatViewCommand),
     MainViewCommand(MainViewCommand),
+    MonkeyViewCommand(MonkeyViewCommand),
+    OnCloseRequested,
+    OnFileDropped(PathBuf),
     OnInit,
+    OnShutdownFinished,
     OnXMessage(XMessage),
     SettingsViewCommand(SettingsViewCommand),
-    Sink,
+    ShowError(String),
+    /// the desktop notification of the views except MainView.
+    ShowNotification(String, String),
+    ToastViewCommand(ToastViewCommand),
 }
 
-#[derive(Default)]
 struct AppFlags {
     config_dir: PathBuf,
+    /// the language of the default font.
+    language: Language,
+    session_dir: Arc<SessionDir>,
 }
 
 struct App {
     active_view: ActiveView,
+    /// closes the window after the server has exited.
+    exiting: bool,
 
-    prefs_repo: Arc<Mutex<PreferencesRepositoryImpl>>,
-    // prefs_repo: Arc<Mutex<MockPreferencesRepository>>,
+    prefs_repo: Arc<PreferencesRepositoryImpl>,
+    // prefs_repo: Arc<MockPreferencesRepository>,
+    prefs_store: PreferencesStore,
+    /// hides the tabs that use the shell if the device has no shell.
+    shell_supported: bool,
     state_view_settings: SettingsViewState,
     theme: Theme,
+    view_apps: AppsView,
+    view_device_info: DeviceInfoView,
+    view_files: FilesView,
+    view_intent: IntentView,
+    view_layout_editor: LayoutEditorView,
+    view_logcat: LogcatView,
     view_main: MainView,
+    view_monkey: MonkeyView,
+    view_toast: ToastView,
 }
 
 impl SettingsView for App {
-    type PrefsRepo = PreferencesRepositoryImpl;
-    // type PrefsRepo = MockPreferencesRepository;
-
-    fn get_prefs_repo(&self) -> Arc<Mutex<Self::PrefsRepo>> {
-        self.prefs_repo.clone()
+    fn get_prefs_store(&self) -> &PreferencesStore {
+        &self.prefs_store
     }
 
     fn get_state(&self) -> &SettingsViewState {
@@ -94,15 +143,37 @@ impl Application for App {
         let config_file_path = flags.config_dir.join("preferences.toml");
         let prefs = Arc::new(Preferences::default());
         let theme = Theme::from(&prefs.theme);
+        let prefs_store = PreferencesStore::new();
+        let api_tokens = ApiTokens::generate();
         (
             Self {
                 active_view: ActiveView::Main,
-                prefs_repo: Arc::new(Mutex::new(PreferencesRepositoryImpl::new(
-                    config_file_path.to_owned(),
-                ))),
+                exiting: false,
+                prefs_repo: Arc::new(PreferencesRepositoryImpl::new(config_file_path.to_owned())),
+                prefs_store: prefs_store.clone(),
+                shell_supported: true,
                 theme: theme.clone(),
-                state_view_settings: SettingsViewState::new(config_file_path, theme),
-                view_main: MainView::new(prefs),
+                state_view_settings: SettingsViewState::new(
+                    config_file_path,
+                    theme,
+                    flags.language,
+                    api_tokens.clone(),
+                ),
+                view_apps: AppsView::default(),
+                view_device_info: DeviceInfoView::default(),
+                view_files: FilesView::default(),
+                view_intent: IntentView::new(prefs_store.clone()),
+                view_layout_editor: LayoutEditorView::new(prefs_store.clone()),
+                view_logcat: LogcatView::default(),
+                view_main: MainView::new(
+                    prefs,
+                    prefs_store,
+                    flags.config_dir,
+                    flags.session_dir,
+                    api_tokens,
+                ),
+                view_monkey: MonkeyView::default(),
+                view_toast: ToastView::default(),
             },
             Command::batch([
                 Command::perform(async {}, |_| AppCommand::OnInit),
@@ -122,32 +193,173 @@ impl Application for App {
                 self.active_view = data;
 
                 let (w, h) = match self.active_view {
+                    ActiveView::Apps => AppsView::view_size(),
+                    ActiveView::DeviceInfo => DeviceInfoView::view_size(),
+                    ActiveView::Files => FilesView::view_size(),
+                    ActiveView::Intent => IntentView::view_size(),
+                    ActiveView::LayoutEditor => LayoutEditorView::view_size(),
+                    ActiveView::Logcat => LogcatView::view_size(),
                     ActiveView::Main => MainView::view_size(),
+                    ActiveView::Monkey => MonkeyView::view_size(),
                     ActiveView::Settings => <Self as SettingsView>::view_size(self),
                 };
 
                 resize(w, h)
             }
-            AppCommand::MainViewCommand(command) => self
-                .view_main
+            AppCommand::AppsViewCommand(command) => {
+                self.view_apps.update(command).map(|command| match command {
+                    AppsViewCommand::ShowNotification(summary, body) => {
+                        AppCommand::ShowNotification(summary, body)
+                    }
+                    _ => AppCommand::AppsViewCommand(command),
+                })
+            }
+            AppCommand::DeviceInfoViewCommand(command) => self
+                .view_device_info
                 .update(command)
-                .map(AppCommand::MainViewCommand),
-            AppCommand::OnInit => self.load_prefs_command(),
+                .map(|command| match command {
+                    DeviceInfoViewCommand::ShowNotification(summary, body) => {
+                        AppCommand::ShowNotification(summary, body)
+                    }
+                    _ => AppCommand::DeviceInfoViewCommand(command),
+                }),
+            AppCommand::FilesViewCommand(command) => {
+                self.view_files
+                    .update(command)
+                    .map(|command| match command {
+                        FilesViewCommand::ShowNotification(summary, body) => {
+                            AppCommand::ShowNotification(summary, body)
+                        }
+                        _ => AppCommand::FilesViewCommand(command),
+                    })
+            }
+            AppCommand::IntentViewCommand(command) => self
+                .view_intent
+                .update(command)
+                .map(AppCommand::IntentViewCommand),
+            AppCommand::LayoutEditorViewCommand(command) => self
+                .view_layout_editor
+                .update(command)
+                .map(AppCommand::LayoutEditorViewCommand),
+            AppCommand::LogcatViewCommand(command) => self
+                .view_logcat
+                .update(command)
+                .map(AppCommand::LogcatViewCommand),
+            AppCommand::MainViewCommand(command) => {
+                self.view_main.update(command).map(|command| match command {
+                    MainViewCommand::SendXMessage(data) => AppCommand::OnXMessage(data),
+                    MainViewCommand::ShowError(data) => AppCommand::ShowError(data),
+                    MainViewCommand::ShutdownFinished => AppCommand::OnShutdownFinished,
+                    _ => AppCommand::MainViewCommand(command),
+                })
+            }
+            AppCommand::MonkeyViewCommand(command) => {
+                self.view_monkey
+                    .update(command)
+                    .map(|command| match command {
+                        MonkeyViewCommand::ShowNotification(summary, body) => {
+                            AppCommand::ShowNotification(summary, body)
+                        }
+                        _ => AppCommand::MonkeyViewCommand(command),
+                    })
+            }
+            AppCommand::OnCloseRequested => {
+                info!("close requested");
+                self.view_main
+                    .update(MainViewCommand::OnCloseRequested)
+                    .map(|command| match command {
+                        MainViewCommand::ShutdownFinished => AppCommand::OnShutdownFinished,
+                        _ => AppCommand::MainViewCommand(command),
+                    })
+            }
+            AppCommand::OnFileDropped(path) => {
+                // pushes only if the user is looking at the directory.
+                if self.active_view != ActiveView::Files {
+                    return Command::none();
+                }
+                self.view_files
+                    .update(FilesViewCommand::OnFileDropped(path))
+                    .map(AppCommand::FilesViewCommand)
+            }
+            AppCommand::OnInit => {
+                self.prefs_store.reload();
+                Command::none()
+            }
+            AppCommand::OnShutdownFinished => {
+                self.exiting = true;
+                Command::none()
+            }
             AppCommand::OnXMessage(x_message) => {
                 let mut commands = vec![];
                 match x_message {
                     XMessage::OnNewPreferences(ref prefs) => {
                         self.theme = (&prefs.theme).into();
+                        set_adb_path(prefs.adb_path.clone());
+                        set_language(prefs.language);
                     }
                     XMessage::OnPrefsFileUpdated => {
-                        commands.push(self.load_prefs_command());
+                        self.prefs_store.reload();
+                    }
+                    XMessage::OnDeviceSelected(ref device) => {
+                        self.shell_supported = device.kind.capabilities().supports_shell;
+                        if !self.shell_supported
+                            && matches!(
+                                self.active_view,
+                                ActiveView::Apps
+                                    | ActiveView::DeviceInfo
+                                    | ActiveView::Files
+                                    | ActiveView::Intent
+                                    | ActiveView::Logcat
+                                    | ActiveView::Monkey
+                            )
+                        {
+                            commands.push(Command::perform(async {}, |_| {
+                                AppCommand::ActiveView(ActiveView::Main)
+                            }));
+                        }
                     }
+                    XMessage::OnKeycodeTestRequested(_) | XMessage::OnWindowFocusChanged(_) => (),
                 }
                 commands.push(
                     self.view_main
                         .update(MainViewCommand::OnXMessage(x_message.clone()))
                         .map(AppCommand::MainViewCommand),
                 );
+                commands.push(
+                    self.view_apps
+                        .update(AppsViewCommand::OnXMessage(x_message.clone()))
+                        .map(AppCommand::AppsViewCommand),
+                );
+                commands.push(
+                    self.view_device_info
+                        .update(DeviceInfoViewCommand::OnXMessage(x_message.clone()))
+                        .map(AppCommand::DeviceInfoViewCommand),
+                );
+                commands.push(
+                    self.view_files
+                        .update(FilesViewCommand::OnXMessage(x_message.clone()))
+                        .map(AppCommand::FilesViewCommand),
+                );
+                commands.push(
+                    self.view_intent
+                        .update(IntentViewCommand::OnXMessage(x_message.clone()))
+                        .map(AppCommand::IntentViewCommand),
+                );
+                commands.push(
+                    self.view_layout_editor
+                        .update(LayoutEditorViewCommand::OnXMessage(x_message.clone()))
+                        .map(AppCommand::LayoutEditorViewCommand),
+                );
+                commands.push(
+                    self.view_logcat
+                        .update(LogcatViewCommand::OnXMessage(x_message.clone()))
+                        .map(AppCommand::LogcatViewCommand),
+                );
+                commands.push(
+                    self.view_monkey
+                        .update(MonkeyViewCommand::OnXMessage(x_message.clone()))
+                        .map(AppCommand::MonkeyViewCommand),
+                );
                 commands.push(
                     <Self as SettingsView>::update(
                         self,
@@ -165,33 +377,107 @@ impl Application for App {
                         AppCommand::SettingsViewCommand(command)
                     }
                 }),
-            AppCommand::Sink => Command::none(),
+            AppCommand::ShowError(message) => {
+                info!(%message, "show error");
+                self.view_toast
+                    .update(ToastViewCommand::Show(message))
+                    .map(AppCommand::ToastViewCommand)
+            }
+            AppCommand::ShowNotification(summary, body) => {
+                // MainView knows whether the window is focused.
+                self.view_main
+                    .update(MainViewCommand::OnNotificationRequested(summary, body))
+                    .map(AppCommand::MainViewCommand)
+            }
+            AppCommand::ToastViewCommand(command) => self
+                .view_toast
+                .update(command)
+                .map(AppCommand::ToastViewCommand),
         }
     }
 
     fn view(&self) -> Element<'_, Self::Message, iced::Renderer<Self::Theme>> {
-        let button_width = Length::Units(90);
-        let button_height = Length::Units(30);
-        let mut view: Column<Self::Message, iced::Renderer<Self::Theme>> = column![
-            row![
-                button("Main")
-                    .width(button_width)
-                    .height(button_height)
-                    .style(iced::theme::Button::Secondary)
-                    .on_press(AppCommand::ActiveView(ActiveView::Main)),
-                button("Settings")
-                    .width(button_width)
-                    .height(button_height)
-                    .style(iced::theme::Button::Secondary)
-                    .on_press(AppCommand::ActiveView(ActiveView::Settings)),
-            ],
-            Space::with_height(12.into()),
-        ];
+        let mut labels = vec![("tab-main", ActiveView::Main)];
+        if self.shell_supported {
+            labels.extend([
+                ("tab-monkey", ActiveView::Monkey),
+                ("tab-logcat", ActiveView::Logcat),
+                ("tab-files", ActiveView::Files),
+                ("tab-apps", ActiveView::Apps),
+                ("tab-intent", ActiveView::Intent),
+                ("tab-info", ActiveView::DeviceInfo),
+            ]);
+        }
+        labels.push(("tab-layout", ActiveView::LayoutEditor));
+        labels.push(("tab-settings", ActiveView::Settings));
+
+        // wraps the tabs since the main view fits 4 tabs only.
+        let mut tabs = Column::new();
+        for chunk in labels.chunks(4) {
+            let mut tab_row = Row::new();
+            for (label, view) in chunk {
+                tab_row = tab_row.push(tab_button(tr(label), view.clone()));
+            }
+            tabs = tabs.push(tab_row);
+        }
+        let mut view: Column<Self::Message, iced::Renderer<Self::Theme>> =
+            column![tabs, Space::with_height(12.into())];
+        if !self.view_toast.is_empty() {
+            view = view.push(
+                container(self.view_toast.view().map(Self::Message::ToastViewCommand)).padding(4),
+            );
+        }
 
         view = match self.active_view {
+            ActiveView::Apps => view.push(
+                container(self.view_apps.view().map(Self::Message::AppsViewCommand)).padding(4),
+            ),
+            ActiveView::DeviceInfo => view.push(
+                container(
+                    self.view_device_info
+                        .view()
+                        .map(Self::Message::DeviceInfoViewCommand),
+                )
+                .padding(4),
+            ),
+            ActiveView::Files => view.push(
+                container(self.view_files.view().map(Self::Message::FilesViewCommand)).padding(4),
+            ),
+            ActiveView::Intent => view.push(
+                container(
+                    self.view_intent
+                        .view()
+                        .map(Self::Message::IntentViewCommand),
+                )
+                .padding(4),
+            ),
+            ActiveView::LayoutEditor => view.push(
+                container(
+                    self.view_layout_editor
+                        .view()
+                        .map(Self::Message::LayoutEditorViewCommand),
+                )
+                .padding(4),
+            ),
+            ActiveView::Logcat => view.push(
+                container(
+                    self.view_logcat
+                        .view(self.view_main.history())
+                        .map(Self::Message::LogcatViewCommand),
+                )
+                .padding(4),
+            ),
             ActiveView::Main => view.push(
                 container(self.view_main.view().map(Self::Message::MainViewCommand)).padding(4),
             ),
+            ActiveView::Monkey => view.push(
+                container(
+                    self.view_monkey
+                        .view()
+                        .map(Self::Message::MonkeyViewCommand),
+                )
+                .padding(4),
+            ),
             ActiveView::Settings => view.push(
                 container(
                     <Self as SettingsView>::view(self).map(Self::Message::SettingsViewCommand),
@@ -207,35 +493,51 @@ impl Application for App {
         self.theme.clone()
     }
 
-    fn subscription(&self) -> Subscription<Self::Message> {
-        Subscription::batch([self
-            .view_main
-            .subscription()
-            .map(AppCommand::MainViewCommand)])
+    fn should_exit(&self) -> bool {
+        self.exiting
     }
-}
 
-impl App {
-    fn load_prefs_command(&self) -> Command<AppCommand> {
-        let repo = self.prefs_repo.clone();
-        Command::perform(
-            async move {
-                match repo.lock().await.load().await {
-                    Ok(data) => Some(Arc::new(data)),
-                    Err(e) => {
-                        warn!(?e, "failed to load key map");
-                        None
-                    }
+    fn subscription(&self) -> Subscription<Self::Message> {
+        Subscription::batch([
+            self.view_main
+                .subscription()
+                .map(AppCommand::MainViewCommand),
+            self.view_monkey
+                .subscription()
+                .map(AppCommand::MonkeyViewCommand),
+            self.view_logcat
+                .subscription()
+                .map(AppCommand::LogcatViewCommand),
+            self.prefs_store
+                .subscription(self.prefs_repo.clone())
+                .map(|data| AppCommand::OnXMessage(XMessage::OnNewPreferences(data))),
+            events_with(|event, _| match event {
+                NativeEvent::Window(WindowEvent::CloseRequested) => {
+                    Some(AppCommand::OnCloseRequested)
                 }
-            },
-            |data| match data {
-                Some(data) => AppCommand::OnXMessage(XMessage::OnNewPreferences(data)),
-                None => AppCommand::Sink,
-            },
-        )
+                NativeEvent::Window(WindowEvent::FileDropped(path)) => {
+                    Some(AppCommand::OnFileDropped(path))
+                }
+                NativeEvent::Window(WindowEvent::Focused) => {
+                    Some(AppCommand::OnXMessage(XMessage::OnWindowFocusChanged(true)))
+                }
+                NativeEvent::Window(WindowEvent::Unfocused) => Some(AppCommand::OnXMessage(
+                    XMessage::OnWindowFocusChanged(false),
+                )),
+                _ => None,
+            }),
+        ])
     }
 }
 
+fn tab_button(label: String, view: ActiveView) -> Button<'static, AppCommand> {
+    button(text(label))
+        .width(Length::Units(74))
+        .height(Length::Units(30))
+        .style(iced::theme::Button::Secondary)
+        .on_press(AppCommand::ActiveView(view))
+}
+
 fn main() -> Fallible<()> {
     // TODO: disable log.
     #[cfg(target_os = "windows")]
@@ -258,15 +560,62 @@ fn main() -> Fallible<()> {
 
     migrate()?;
 
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    if args.first().map(String::as_str) == Some("run-macro") {
+        let args = parse_run_macro_args(&args[1..])?;
+        let _watchdog = ChildProcessWatchdog;
+        return tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .context("failed to create the runtime")?
+            .block_on(run_macro(&config_dir, args));
+    }
+
+    // the server shell keeps the device busy if it remains after closing the window.
+    let _watchdog = ChildProcessWatchdog;
+
+    // deletes the session directory after the window is closed.
+    let session_dir = Arc::new(SessionDir::new()?);
+
+    let language = load_language(&config_dir);
+    set_language(language);
+
     App::run(Settings {
         window: WindowSettings {
             size: MainView::view_size(),
             ..Default::default()
         },
-        flags: AppFlags { config_dir },
-        ..Default::default()
+        default_font: load_font(language),
+        // quits the server by AppCommand::OnCloseRequested before closing.
+        exit_on_close_request: false,
+        ..Settings::with_flags(AppFlags {
+            config_dir,
+            language,
+            session_dir: session_dir.clone(),
+        })
     })?;
 
     info!("Bye");
     Ok(())
 }
+
+/// reads the language ahead of the window since the font can't be changed later.
+fn load_language(config_dir: &Path) -> Language {
+    let ret = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .context("failed to create the runtime")
+        .and_then(|runtime| {
+            runtime.block_on(
+                PreferencesRepositoryImpl::new(config_dir.join("preferences.toml")).load(),
+            )
+        });
+
+    match ret {
+        Ok(prefs) => prefs.language,
+        Err(e) => {
+            warn!(?e, "failed to load the language");
+            Language::default()
+        }
+    }
+}
@@ -14,26 +14,203 @@
  * limitations under the License.
  */
 
-pub use preferences::{KeyMap, Preferences};
-pub use theme::{AppTheme, ColorKeyButtonStyle};
+pub use custom_key::CustomKey;
+pub use preferences::{InvalidKeycode, KeyMap, KeyMapProfile, Preferences};
+pub use theme::{AppTheme, ColorKeyButtonStyle, StatusIndicatorStyle};
 pub use x_message::XMessage;
 
+pub mod accessibility;
+pub mod adb_error;
+pub mod adb_version;
+pub mod api;
+mod custom_key;
+pub mod device_file;
+pub mod device_health;
+pub mod device_info;
+pub mod device_profile;
+pub mod device_usage;
 mod file_version;
+pub mod gesture;
+pub mod history;
+pub mod input_diagnostics;
+pub mod intent;
+pub mod key_layout;
+pub mod key_macro;
+pub mod keycode;
+pub mod language;
+pub mod layout;
+pub mod locale;
+pub mod logcat;
+pub mod monkey;
+pub mod mqtt;
+pub mod pointer;
 mod preferences;
+pub mod protocol;
+pub mod release;
+pub mod schedule;
+pub mod screen_record;
+pub mod self_test;
 pub mod send_event_key;
+pub mod shortcut;
+pub mod target;
 pub mod theme;
+pub mod webhook;
+pub mod websocket;
 mod x_message;
 
+use adb_error::AdbError;
 pub use file_version::FileVersion;
 use std::fmt::{Display, Formatter};
+use target::DeviceKind;
 
 #[derive(Debug, Eq, PartialEq)]
 pub struct AndroidDevice {
     pub serial: String,
+    pub kind: DeviceKind,
+    /// the display name in the preferences, resolved when the device list is shown.
+    pub label: Option<String>,
+    /// listed by `adb devices -l`, or `None` for the devices driven without adb.
+    pub details: Option<AdbDeviceDetails>,
 }
 
 impl Display for AndroidDevice {
+    /// shows the label or the model like `Pixel 7 (usb)`, and the serial if neither is known.
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.serial)
+        let model = self
+            .details
+            .as_ref()
+            .and_then(|data| data.model.as_ref())
+            .map(|data| data.replace('_', " "));
+        let name = match (&self.label, model) {
+            (Some(label), _) => label.clone(),
+            (None, Some(model)) => model,
+            (None, None) => self.serial.clone(),
+        };
+
+        let notes = match (&self.kind, &self.details) {
+            (DeviceKind::Adb, Some(details)) => {
+                let mut notes = vec![];
+                if let Some(data) = details.transport {
+                    notes.push(data.to_string());
+                }
+                // shows why the device can't be selected.
+                if !details.state.is_usable() {
+                    notes.push(details.state.to_string());
+                }
+                notes
+            }
+            (DeviceKind::Adb, None) => vec![],
+            (DeviceKind::AndroidTvRemote { .. }, _) => vec!["TV remote".into()],
+            (DeviceKind::Cec { .. }, _) => vec!["CEC".into()],
+            (DeviceKind::Roku { .. }, _) => vec!["Roku".into()],
+        };
+
+        if notes.is_empty() {
+            f.write_str(&name)
+        } else {
+            write!(f, "{} ({})", name, notes.join(", "))
+        }
+    }
+}
+
+impl AndroidDevice {
+    /// false if adb lists the device but can't send the commands, e.g. unauthorized.
+    pub fn is_usable(&self) -> bool {
+        match &self.details {
+            Some(data) => data.state.is_usable(),
+            None => true,
+        }
+    }
+}
+
+/// the columns of `adb devices -l` other than the serial.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AdbDeviceDetails {
+    /// e.g. `Pixel_7`.
+    pub model: Option<String>,
+    pub product: Option<String>,
+    pub state: AdbDeviceState,
+    pub transport: Option<AdbTransport>,
+}
+
+/// the state column of `adb devices`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum AdbDeviceState {
+    Device,
+    /// the udev rules of Linux don't allow the user to access the device.
+    NoPermissions,
+    Offline,
+    Unauthorized,
+    /// e.g. `recovery` or `sideload`.
+    Other(String),
+}
+
+impl AdbDeviceState {
+    pub fn from_column(value: &str) -> Self {
+        match value {
+            "device" => AdbDeviceState::Device,
+            // followed by the reason like "no permissions (missing udev rules? ...)".
+            "no" => AdbDeviceState::NoPermissions,
+            "offline" => AdbDeviceState::Offline,
+            "unauthorized" => AdbDeviceState::Unauthorized,
+            _ => AdbDeviceState::Other(value.into()),
+        }
+    }
+
+    pub fn is_usable(&self) -> bool {
+        matches!(self, AdbDeviceState::Device)
+    }
+
+    /// how the user makes the device usable.
+    pub fn hint(&self) -> Option<&'static str> {
+        match self {
+            AdbDeviceState::Device | AdbDeviceState::Other(_) => None,
+            AdbDeviceState::NoPermissions => Some("add the udev rule for the device"),
+            AdbDeviceState::Offline => Some(AdbError::DeviceOffline.hint()),
+            AdbDeviceState::Unauthorized => Some(AdbError::Unauthorized.hint()),
+        }
+    }
+}
+
+impl Display for AdbDeviceState {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AdbDeviceState::Device => f.write_str("device"),
+            AdbDeviceState::NoPermissions => f.write_str("no permissions"),
+            AdbDeviceState::Offline => f.write_str("offline"),
+            AdbDeviceState::Unauthorized => f.write_str("unauthorized"),
+            AdbDeviceState::Other(value) => f.write_str(value),
+        }
+    }
+}
+
+/// how adb reaches the device.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum AdbTransport {
+    Emulator,
+    Tcp,
+    Usb,
+}
+
+impl Display for AdbTransport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AdbTransport::Emulator => f.write_str("emulator"),
+            AdbTransport::Tcp => f.write_str("tcp"),
+            AdbTransport::Usb => f.write_str("usb"),
+        }
+    }
+}
+
+/// an Android user profile such as the owner or the work profile.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AndroidUser {
+    pub id: u32,
+    pub name: String,
+}
+
+impl Display for AndroidUser {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ({})", self.name, self.id)
     }
 }
@@ -0,0 +1,100 @@
+/*
+ * Copyright 2022 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::fmt::{Display, Formatter};
+
+pub const TALKBACK_SERVICE: &str =
+    "com.google.android.marvin.talkback/com.google.android.marvin.talkback.TalkBackService";
+
+/// the colon-separated components of `enabled_accessibility_services`.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct AccessibilityServices(Vec<String>);
+
+impl AccessibilityServices {
+    /// `settings get` prints `null` if the value isn't set.
+    pub fn parse(value: &str) -> Self {
+        let value = value.trim();
+        if value == "null" {
+            return Self::default();
+        }
+
+        Self(
+            value
+                .split(':')
+                .filter(|data| !data.is_empty())
+                .map(Into::into)
+                .collect(),
+        )
+    }
+
+    pub fn contains(&self, service: &str) -> bool {
+        self.0.iter().any(|data| data == service)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn set_enabled(&mut self, service: &str, enabled: bool) {
+        if enabled {
+            if !self.contains(service) {
+                self.0.push(service.into());
+            }
+        } else {
+            self.0.retain(|data| data != service);
+        }
+    }
+}
+
+impl Display for AccessibilityServices {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0.join(":"))
+    }
+}
+
+/// the class name of the component like `TalkBackService`.
+pub fn service_label(service: &str) -> &str {
+    service
+        .rsplit(['/', '.'])
+        .find(|data| !data.is_empty())
+        .unwrap_or(service)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accessibility_services_toggle() {
+        let mut services = AccessibilityServices::parse("com.example/.SwitchService\n");
+        services.set_enabled(TALKBACK_SERVICE, true);
+        services.set_enabled(TALKBACK_SERVICE, true);
+        assert_eq!(
+            format!("com.example/.SwitchService:{}", TALKBACK_SERVICE),
+            services.to_string()
+        );
+
+        services.set_enabled("com.example/.SwitchService", false);
+        assert_eq!(TALKBACK_SERVICE, services.to_string());
+        assert!(AccessibilityServices::parse("null\n").is_empty());
+    }
+
+    #[test]
+    fn service_label_class_name() {
+        assert_eq!("TalkBackService", service_label(TALKBACK_SERVICE));
+        assert_eq!("SwitchService", service_label("com.example/.SwitchService"));
+    }
+}
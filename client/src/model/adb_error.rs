@@ -0,0 +1,115 @@
+/*
+ * Copyright 2022 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::fmt::{Display, Formatter};
+
+/// the adb failures that the user can fix, classified by the stderr.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum AdbError {
+    DaemonNotRunning,
+    DeviceNotFound,
+    DeviceOffline,
+    MoreThanOneDevice,
+    Unauthorized,
+}
+
+impl AdbError {
+    /// returns the error of the stderr like `adb: device unauthorized.`.
+    ///
+    /// "daemon not running" alone isn't an error since adb starts the daemon before the
+    /// command.
+    pub fn classify(stderr: &str) -> Option<Self> {
+        let stderr = stderr.to_ascii_lowercase();
+        if stderr.contains("unauthorized") {
+            Some(AdbError::Unauthorized)
+        } else if stderr.contains("more than one device")
+            || stderr.contains("more than one emulator")
+        {
+            Some(AdbError::MoreThanOneDevice)
+        } else if stderr.contains("device offline") {
+            Some(AdbError::DeviceOffline)
+        } else if stderr.contains("no devices/emulators found")
+            || (stderr.contains("device '") && stderr.contains("' not found"))
+        {
+            Some(AdbError::DeviceNotFound)
+        } else if stderr.contains("cannot connect to daemon")
+            || stderr.contains("failed to start daemon")
+        {
+            Some(AdbError::DaemonNotRunning)
+        } else {
+            None
+        }
+    }
+
+    /// how the user fixes the error.
+    pub fn hint(&self) -> &'static str {
+        match self {
+            AdbError::DaemonNotRunning => "run \"adb start-server\" and reload the devices",
+            AdbError::DeviceNotFound => "check the cable or the network and reload the devices",
+            AdbError::DeviceOffline => "reconnect the device, or run \"adb reconnect offline\"",
+            AdbError::MoreThanOneDevice => "select the device in the list",
+            AdbError::Unauthorized => "allow USB debugging in the dialog on the device",
+        }
+    }
+}
+
+impl Display for AdbError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AdbError::DaemonNotRunning => write!(f, "adb daemon not running"),
+            AdbError::DeviceNotFound => write!(f, "device not found"),
+            AdbError::DeviceOffline => write!(f, "device offline"),
+            AdbError::MoreThanOneDevice => write!(f, "more than one device"),
+            AdbError::Unauthorized => write!(f, "device unauthorized"),
+        }
+    }
+}
+
+impl std::error::Error for AdbError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classify_stderr() {
+        for (expected, stderr) in [
+            (
+                Some(AdbError::Unauthorized),
+                "* daemon not running; starting now at tcp:5037\n* daemon started successfully\nadb: device unauthorized.\nThis adb server's $ADB_VENDOR_KEYS is not set",
+            ),
+            (
+                Some(AdbError::MoreThanOneDevice),
+                "adb: more than one device/emulator",
+            ),
+            (Some(AdbError::DeviceOffline), "adb: device offline"),
+            (
+                Some(AdbError::DeviceNotFound),
+                "adb: device 'emulator-5556' not found",
+            ),
+            (Some(AdbError::DeviceNotFound), "adb: no devices/emulators found"),
+            (
+                Some(AdbError::DaemonNotRunning),
+                "* daemon not running; starting now at tcp:5037\nADB server didn't ACK\n* failed to start daemon\nerror: cannot connect to daemon",
+            ),
+            (None, "* daemon not running; starting now at tcp:5037"),
+            (None, "adb: error: failed to stat remote object"),
+            (None, ""),
+        ] {
+            assert_eq!(expected, AdbError::classify(stderr), "stderr: {:?}", stderr);
+        }
+    }
+}
@@ -0,0 +1,109 @@
+/*
+ * Copyright 2022 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::model::FileVersion;
+use std::fmt::{Display, Formatter};
+
+/// the features that depend on the version of the adb client.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum AdbCapability {
+    ExecOut,
+    /// the wireless debugging of Android 11+.
+    Pair,
+    TrackDevices,
+}
+
+impl AdbCapability {
+    fn min_version(&self) -> FileVersion {
+        match self {
+            AdbCapability::ExecOut => [1, 0, 32].into(),
+            AdbCapability::Pair => [1, 0, 42].into(),
+            AdbCapability::TrackDevices => [1, 0, 32].into(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AdbVersion(FileVersion);
+
+impl AdbVersion {
+    /// the oldest version that is known to work.
+    pub fn minimum() -> Self {
+        Self([1, 0, 39].into())
+    }
+
+    /// parses the first line of `adb version` like `Android Debug Bridge version 1.0.41`.
+    pub fn parse(output: &str) -> Option<Self> {
+        let version = output
+            .lines()
+            .find_map(|data| data.trim().strip_prefix("Android Debug Bridge version "))?;
+        let segments = version.trim().split('.').collect::<Vec<_>>();
+        if segments.len() != 3 {
+            return None;
+        }
+        version.trim().parse().ok().map(Self)
+    }
+
+    pub fn is_outdated(&self) -> bool {
+        self.0 < Self::minimum().0
+    }
+
+    pub fn supports(&self, capability: AdbCapability) -> bool {
+        self.0 >= capability.min_version()
+    }
+}
+
+impl Display for AdbVersion {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_version() {
+        let output = "Android Debug Bridge version 1.0.41
+Version 34.0.4-10411341
+Installed as /usr/local/bin/adb
+";
+        let version = AdbVersion::parse(output).unwrap();
+        assert_eq!("1.0.41", version.to_string());
+        assert!(!version.is_outdated());
+        assert!(version.supports(AdbCapability::ExecOut));
+        assert!(!version.supports(AdbCapability::Pair));
+    }
+
+    #[test]
+    fn parse_version_outdated() {
+        let version = AdbVersion::parse("Android Debug Bridge version 1.0.31\n").unwrap();
+        assert!(version.is_outdated());
+        assert!(!version.supports(AdbCapability::ExecOut));
+        assert!(!version.supports(AdbCapability::TrackDevices));
+    }
+
+    #[test]
+    fn parse_version_invalid() {
+        assert_eq!(None, AdbVersion::parse(""));
+        assert_eq!(None, AdbVersion::parse("adb: command not found"));
+        assert_eq!(
+            None,
+            AdbVersion::parse("Android Debug Bridge version 1.0\n")
+        );
+    }
+}
@@ -0,0 +1,483 @@
+/*
+ * Copyright 2022 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::model::keycode::Keycode;
+use rand::Rng;
+use std::fmt::{Debug, Formatter};
+
+/// the secret of the session that the clients send as `Authorization: Bearer {token}`.
+#[derive(Clone, Eq, PartialEq)]
+pub struct ApiToken(String);
+
+impl ApiToken {
+    const LEN: usize = 32;
+
+    /// generates the random token, which changes every launch.
+    pub fn generate() -> Self {
+        Self(
+            rand::thread_rng()
+                .sample_iter(&rand::distributions::Alphanumeric)
+                .take(Self::LEN)
+                .map(char::from)
+                .collect(),
+        )
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// compares in the constant time to not leak the token by the response time.
+    pub fn matches(&self, value: &str) -> bool {
+        self.0.len() == value.len()
+            && self
+                .0
+                .bytes()
+                .zip(value.bytes())
+                .fold(0, |acc, (a, b)| acc | (a ^ b))
+                == 0
+    }
+}
+
+impl Debug for ApiToken {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("ApiToken(..)")
+    }
+}
+
+/// what the client of the token can do.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ApiScope {
+    /// sends the keys and watches.
+    Control,
+    /// only watches the state and the sent commands, e.g. a colleague debugging remotely.
+    Observe,
+}
+
+/// the tokens of the session for each [ApiScope].
+#[derive(Clone, Debug)]
+pub struct ApiTokens {
+    pub control: ApiToken,
+    pub observe: ApiToken,
+}
+
+impl ApiTokens {
+    pub fn generate() -> Self {
+        Self {
+            control: ApiToken::generate(),
+            observe: ApiToken::generate(),
+        }
+    }
+
+    /// returns `None` if the token is neither of them.
+    pub fn scope(&self, token: &str) -> Option<ApiScope> {
+        if self.control.matches(token) {
+            Some(ApiScope::Control)
+        } else if self.observe.matches(token) {
+            Some(ApiScope::Observe)
+        } else {
+            None
+        }
+    }
+}
+
+/// the token of the `Authorization` header like `Bearer {token}`.
+pub fn bearer_token(authorization: &str) -> Option<&str> {
+    authorization.strip_prefix("Bearer ").map(str::trim)
+}
+
+/// the endpoints of the local REST API.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ApiRoute {
+    /// `GET /devices`.
+    Devices,
+    /// `POST /devices/{serial}/key/{keycode}`.
+    Key { serial: String, keycode: Keycode },
+}
+
+impl ApiRoute {
+    /// returns the error response if the request doesn't match the endpoints.
+    pub fn parse(method: &str, path: &str) -> Result<Self, ApiResponse> {
+        let path = path.split('?').next().unwrap_or_default();
+        let segments = path
+            .trim_matches('/')
+            .split('/')
+            .map(percent_decode)
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(|| ApiResponse::error(400, "invalid path"))?;
+        let segments = segments.iter().map(String::as_str).collect::<Vec<_>>();
+
+        match (method, segments.as_slice()) {
+            ("GET", ["devices"]) => Ok(Self::Devices),
+            ("POST", ["devices", serial, "key", keycode]) => Ok(Self::Key {
+                serial: serial.to_string(),
+                keycode: keycode
+                    .parse()
+                    .map_err(|_| ApiResponse::error(400, "invalid keycode"))?,
+            }),
+            (_, ["devices"]) | (_, ["devices", _, "key", _]) => {
+                Err(ApiResponse::error(405, "method not allowed"))
+            }
+            _ => Err(ApiResponse::error(404, "not found")),
+        }
+    }
+
+    /// the routes that change the device, which need the [ApiToken].
+    pub fn is_mutating(&self) -> bool {
+        match self {
+            Self::Devices => false,
+            Self::Key { .. } => true,
+        }
+    }
+
+    /// parses the WebSocket message like `{"serial": "emulator-5554", "keycode": "KEYCODE_HOME"}`.
+    pub fn from_ws_message(text: &str) -> Result<Self, ApiResponse> {
+        let value = serde_json::from_str::<serde_json::Value>(text)
+            .map_err(|_| ApiResponse::error(400, "invalid message"))?;
+        match (value["serial"].as_str(), value["keycode"].as_str()) {
+            (Some(serial), Some(keycode)) => Ok(Self::Key {
+                serial: serial.to_string(),
+                keycode: keycode
+                    .parse()
+                    .map_err(|_| ApiResponse::error(400, "invalid keycode"))?,
+            }),
+            _ => Err(ApiResponse::error(400, "serial and keycode are required")),
+        }
+    }
+}
+
+/// the command sent to the device, which is pushed to the WebSocket clients.
+pub fn command_ws_message(command: &str) -> String {
+    serde_json::json!({
+        "type": "command",
+        "command": command,
+    })
+    .to_string()
+}
+
+/// the connection state that is pushed to the WebSocket clients and the MQTT broker.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ApiState {
+    /// the selected device.
+    pub serial: String,
+    /// `connected`, `connecting`, `degraded`, `disconnected` or `reconnecting`.
+    pub connectivity: &'static str,
+}
+
+impl ApiState {
+    pub fn to_ws_message(&self) -> String {
+        serde_json::json!({
+            "type": "state",
+            "serial": self.serial,
+            "connectivity": self.connectivity,
+        })
+        .to_string()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ApiResponse {
+    pub status: u16,
+    pub body: serde_json::Value,
+}
+
+impl ApiResponse {
+    pub fn ok(body: serde_json::Value) -> Self {
+        Self { status: 200, body }
+    }
+
+    pub fn error(status: u16, message: &str) -> Self {
+        Self {
+            status,
+            body: serde_json::json!({ "error": message }),
+        }
+    }
+
+    pub fn to_ws_message(&self) -> String {
+        serde_json::json!({
+            "type": "response",
+            "status": self.status,
+            "body": self.body,
+        })
+        .to_string()
+    }
+
+    /// the whole HTTP/1.1 response that closes the connection.
+    pub fn to_http(&self) -> String {
+        let reason = match self.status {
+            200 => "OK",
+            400 => "Bad Request",
+            401 => "Unauthorized",
+            403 => "Forbidden",
+            404 => "Not Found",
+            405 => "Method Not Allowed",
+            409 => "Conflict",
+            431 => "Request Header Fields Too Large",
+            503 => "Service Unavailable",
+            _ => "Internal Server Error",
+        };
+        let body = self.body.to_string();
+        format!(
+            "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            self.status,
+            reason,
+            body.len(),
+            body,
+        )
+    }
+}
+
+/// the WebSocket subprotocol that the server answers when the client offers it with the token like
+/// `new WebSocket(url, ["android-commander", "token." + token])`, since the browsers can't set the
+/// `Authorization` to the WebSocket.
+pub const WS_PROTOCOL: &str = "android-commander";
+
+/// the token of the `token.{token}` in the `Sec-WebSocket-Protocol`.
+pub fn protocol_token(protocols: &str) -> Option<&str> {
+    protocols
+        .split(',')
+        .find_map(|data| data.trim().strip_prefix("token."))
+}
+
+/// whether the `Sec-WebSocket-Protocol` offers [WS_PROTOCOL].
+pub fn offers_ws_protocol(protocols: &str) -> bool {
+    protocols.split(',').any(|data| data.trim() == WS_PROTOCOL)
+}
+
+/// the path without the query for the logs, since the query may have the token.
+pub fn redact_query(path: &str) -> String {
+    match path.split_once('?') {
+        Some((path, _)) => format!("{}?…", path),
+        None => path.to_string(),
+    }
+}
+
+/// the WebSocket accepts the browsers only on this machine, which send the `Origin`.
+pub fn is_local_origin(origin: &str) -> bool {
+    let host = match origin
+        .strip_prefix("http://")
+        .or_else(|| origin.strip_prefix("https://"))
+    {
+        Some(data) => data,
+        None => return false,
+    };
+    let host = match host.strip_prefix('[') {
+        Some(data) => data.split(']').next().unwrap_or_default(),
+        None => host.split(':').next().unwrap_or_default(),
+    };
+    matches!(
+        host.to_ascii_lowercase().as_str(),
+        "localhost" | "127.0.0.1" | "::1"
+    )
+}
+
+/// the server accepts only the `Host` of this machine so that the other sites can't reach it through
+/// the DNS rebinding.
+pub fn is_local_host(host: &str, port: u16) -> bool {
+    let (name, host_port) = match host.rsplit_once(':') {
+        Some((name, data)) => (name, data.parse::<u16>().ok()),
+        None => (host, Some(80)),
+    };
+    host_port == Some(port)
+        && matches!(
+            name.to_ascii_lowercase().as_str(),
+            "localhost" | "127.0.0.1"
+        )
+}
+
+/// the decoded value of the query like `token` of `/ws?token=...`.
+pub fn query_param(path: &str, name: &str) -> Option<String> {
+    let (_, query) = path.split_once('?')?;
+    query
+        .split('&')
+        .filter_map(|data| data.split_once('='))
+        .find(|(key, _)| *key == name)
+        .and_then(|(_, value)| percent_decode(value))
+}
+
+/// decodes `%3A` and so on for the serial like `192.168.0.2%3A5555`.
+fn percent_decode(value: &str) -> Option<String> {
+    let mut bytes = vec![];
+    let mut iter = value.bytes();
+    while let Some(data) = iter.next() {
+        if data == b'%' {
+            let hex = [iter.next()?, iter.next()?];
+            bytes.push(u8::from_str_radix(std::str::from_utf8(&hex).ok()?, 16).ok()?);
+        } else {
+            bytes.push(data);
+        }
+    }
+    String::from_utf8(bytes).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn api_route_parse() {
+        assert_eq!(Ok(ApiRoute::Devices), ApiRoute::parse("GET", "/devices"));
+        assert_eq!(
+            Ok(ApiRoute::Devices),
+            ApiRoute::parse("GET", "/devices/?pretty")
+        );
+        assert_eq!(
+            Ok(ApiRoute::Key {
+                serial: "192.168.0.2:5555".into(),
+                keycode: "KEYCODE_HOME".parse().unwrap(),
+            }),
+            ApiRoute::parse("POST", "/devices/192.168.0.2%3A5555/key/KEYCODE_HOME")
+        );
+    }
+
+    #[test]
+    fn api_route_parse_error() {
+        let status = |method, path| ApiRoute::parse(method, path).unwrap_err().status;
+        assert_eq!(400, status("POST", "/devices/emulator-5554/key/HOME"));
+        assert_eq!(400, status("GET", "/devices%ZZ"));
+        assert_eq!(404, status("GET", "/"));
+        assert_eq!(405, status("POST", "/devices"));
+        assert_eq!(
+            405,
+            status("GET", "/devices/emulator-5554/key/KEYCODE_HOME")
+        );
+    }
+
+    #[test]
+    fn api_route_from_ws_message() {
+        assert_eq!(
+            Ok(ApiRoute::Key {
+                serial: "emulator-5554".into(),
+                keycode: "KEYCODE_HOME".parse().unwrap(),
+            }),
+            ApiRoute::from_ws_message(r#"{"serial": "emulator-5554", "keycode": "KEYCODE_HOME"}"#)
+        );
+
+        let status = |text| ApiRoute::from_ws_message(text).unwrap_err().status;
+        assert_eq!(400, status("KEYCODE_HOME"));
+        assert_eq!(400, status(r#"{"keycode": "KEYCODE_HOME"}"#));
+        assert_eq!(
+            400,
+            status(r#"{"serial": "emulator-5554", "keycode": "HOME"}"#)
+        );
+    }
+
+    #[test]
+    fn api_token_matches() {
+        let token = ApiToken::generate();
+        assert_eq!(ApiToken::LEN, token.as_str().len());
+        assert_ne!(token, ApiToken::generate());
+
+        assert!(token.matches(token.as_str()));
+        assert!(!token.matches(""));
+        assert!(!token.matches(&token.as_str()[1..]));
+        assert!(!token.matches(&"a".repeat(ApiToken::LEN)));
+    }
+
+    #[test]
+    fn api_tokens_scope() {
+        let tokens = ApiTokens::generate();
+        assert_eq!(
+            Some(ApiScope::Control),
+            tokens.scope(tokens.control.as_str())
+        );
+        assert_eq!(
+            Some(ApiScope::Observe),
+            tokens.scope(tokens.observe.as_str())
+        );
+        assert_eq!(None, tokens.scope(ApiToken::generate().as_str()));
+        assert_eq!(None, tokens.scope(""));
+    }
+
+    #[test]
+    fn api_bearer_token() {
+        assert_eq!(Some("abc"), bearer_token("Bearer abc"));
+        assert_eq!(Some(""), bearer_token("Bearer "));
+        assert_eq!(None, bearer_token("abc"));
+        assert_eq!(None, bearer_token("Basic abc"));
+    }
+
+    #[test]
+    fn api_route_is_mutating() {
+        assert!(!ApiRoute::Devices.is_mutating());
+        assert!(
+            ApiRoute::parse("POST", "/devices/emulator-5554/key/KEYCODE_HOME")
+                .unwrap()
+                .is_mutating()
+        );
+    }
+
+    #[test]
+    fn api_is_local_origin() {
+        assert!(is_local_origin("http://localhost"));
+        assert!(is_local_origin("http://localhost:8080"));
+        assert!(is_local_origin("https://127.0.0.1:8443"));
+        assert!(is_local_origin("http://[::1]:8080"));
+        assert!(!is_local_origin("null"));
+        assert!(!is_local_origin("file://"));
+        assert!(!is_local_origin("https://example.com"));
+        assert!(!is_local_origin("http://localhost.example.com"));
+        assert!(!is_local_origin("http://127.0.0.1.example.com:80"));
+    }
+
+    #[test]
+    fn api_ws_protocol() {
+        let protocols = "android-commander, token.abc";
+        assert_eq!(Some("abc"), protocol_token(protocols));
+        assert!(offers_ws_protocol(protocols));
+
+        assert_eq!(None, protocol_token("android-commander"));
+        assert!(!offers_ws_protocol("token.abc"));
+        assert!(!offers_ws_protocol("android-commander-v2"));
+    }
+
+    #[test]
+    fn api_redact_query() {
+        assert_eq!("/ws?…", redact_query("/ws?token=abc"));
+        assert_eq!("/devices", redact_query("/devices"));
+    }
+
+    #[test]
+    fn api_is_local_host() {
+        assert!(is_local_host("127.0.0.1:8080", 8080));
+        assert!(is_local_host("LocalHost:8080", 8080));
+        assert!(is_local_host("localhost", 80));
+        assert!(!is_local_host("localhost", 8080));
+        assert!(!is_local_host("127.0.0.1:8081", 8080));
+        assert!(!is_local_host("127.0.0.1:", 8080));
+        assert!(!is_local_host("attacker.example.com:8080", 8080));
+        assert!(!is_local_host("localhost.example.com:8080", 8080));
+    }
+
+    #[test]
+    fn api_query_param() {
+        assert_eq!(Some("abc".into()), query_param("/ws?token=abc", "token"));
+        assert_eq!(
+            Some("a b".into()),
+            query_param("/ws?pretty&token=a%20b", "token")
+        );
+        assert_eq!(None, query_param("/ws?tokens=abc", "token"));
+        assert_eq!(None, query_param("/ws", "token"));
+    }
+
+    #[test]
+    fn api_response_to_http() {
+        assert_eq!(
+            "HTTP/1.1 404 Not Found\r\nContent-Type: application/json\r\nContent-Length: 21\r\nConnection: close\r\n\r\n{\"error\":\"not found\"}",
+            ApiResponse::error(404, "not found").to_http()
+        );
+    }
+}
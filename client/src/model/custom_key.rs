@@ -0,0 +1,40 @@
+/*
+ * Copyright 2022 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::model::keycode::is_valid_keycode;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CustomKey {
+    pub label: String,
+    pub keycode: String,
+    /// shows the key only while the package is in the foreground.
+    pub package: Option<String>,
+    /// sends this keycode instead if the key is held longer than the long-press timeout.
+    pub long_keycode: Option<String>,
+}
+
+impl CustomKey {
+    pub fn is_valid(&self) -> bool {
+        is_valid_keycode(&self.keycode)
+    }
+
+    pub fn is_visible(&self, focused_package: Option<&str>) -> bool {
+        match &self.package {
+            Some(package) => Some(package.as_str()) == focused_package,
+            None => true,
+        }
+    }
+}
@@ -0,0 +1,212 @@
+/*
+ * Copyright 2022 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::cmp::Ordering;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DeviceFileKind {
+    Directory,
+    File,
+    Symlink,
+    /// a device node, a socket, or a pipe.
+    Other,
+}
+
+/// an entry of `ls -la` on the device.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DeviceFile {
+    pub kind: DeviceFileKind,
+    /// the target if [DeviceFileKind::Symlink].
+    pub link_target: Option<String>,
+    pub name: String,
+    pub permissions: String,
+    pub size: u64,
+}
+
+impl DeviceFile {
+    /// the symlink may be a directory such as `/sdcard`.
+    pub fn is_navigable(&self) -> bool {
+        matches!(
+            self.kind,
+            DeviceFileKind::Directory | DeviceFileKind::Symlink
+        )
+    }
+
+    pub fn size_text(&self) -> String {
+        const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+
+        if self.size < 1024 {
+            return format!("{} B", self.size);
+        }
+
+        let mut size = self.size as f64 / 1024.0;
+        let mut unit = UNITS[0];
+        for data in &UNITS[1..] {
+            if size < 1024.0 {
+                break;
+            }
+            size /= 1024.0;
+            unit = data;
+        }
+        format!("{:.1} {}", size, unit)
+    }
+}
+
+/// parses the output of toybox `ls -la` and returns the directories first.
+///
+/// `total`, `.`, `..` and the lines that failed to parse like `Permission denied` are
+/// skipped.
+pub fn parse_ls_la(output: &str) -> Vec<DeviceFile> {
+    let mut files = output.lines().filter_map(parse_ls_line).collect::<Vec<_>>();
+    files.sort_by(|a, b| match (a.kind, b.kind) {
+        (DeviceFileKind::Directory, DeviceFileKind::Directory) => a.name.cmp(&b.name),
+        (DeviceFileKind::Directory, _) => Ordering::Less,
+        (_, DeviceFileKind::Directory) => Ordering::Greater,
+        _ => a.name.cmp(&b.name),
+    });
+    files
+}
+
+/// e.g. `-rw-rw---- 1 root sdcard_rw 12345 2022-10-01 12:34 a b.mp4`.
+fn parse_ls_line(line: &str) -> Option<DeviceFile> {
+    let (permissions, rest) = next_field(line)?;
+    let kind = match permissions.chars().next()? {
+        'd' => DeviceFileKind::Directory,
+        '-' => DeviceFileKind::File,
+        'l' => DeviceFileKind::Symlink,
+        'b' | 'c' | 'p' | 's' => DeviceFileKind::Other,
+        _ => return None,
+    };
+    if permissions.len() < 10 {
+        return None;
+    }
+
+    // links, owner and group.
+    let (_, rest) = next_field(rest)?;
+    let (_, rest) = next_field(rest)?;
+    let (_, rest) = next_field(rest)?;
+
+    // the device node has `major, minor` instead of the size.
+    let (size, rest) = next_field(rest)?;
+    let (size, rest) = match size.strip_suffix(',') {
+        Some(_) => (0, next_field(rest)?.1),
+        None => (size.parse().ok()?, rest),
+    };
+
+    // date and time.
+    let (_, rest) = next_field(rest)?;
+    let (_, name) = next_field(rest)?;
+
+    let (name, link_target) = match kind {
+        DeviceFileKind::Symlink => match name.split_once(" -> ") {
+            Some((name, target)) => (name, Some(target.to_string())),
+            None => (name, None),
+        },
+        _ => (name, None),
+    };
+    if name.is_empty() || name == "." || name == ".." {
+        return None;
+    }
+
+    Some(DeviceFile {
+        kind,
+        link_target,
+        name: name.into(),
+        permissions: permissions.into(),
+        size,
+    })
+}
+
+/// returns the first field and the rest without the leading whitespaces.
+fn next_field(value: &str) -> Option<(&str, &str)> {
+    let value = value.trim_start();
+    if value.is_empty() {
+        return None;
+    }
+    match value.split_once(char::is_whitespace) {
+        Some((field, rest)) => Some((field, rest.trim_start())),
+        None => Some((value, "")),
+    }
+}
+
+pub fn join_device_path(dir: &str, name: &str) -> String {
+    if dir.ends_with('/') {
+        format!("{}{}", dir, name)
+    } else {
+        format!("{}/{}", dir, name)
+    }
+}
+
+/// returns `None` for the root.
+pub fn parent_device_path(path: &str) -> Option<String> {
+    let path = path.trim_end_matches('/');
+    if path.is_empty() {
+        return None;
+    }
+    match path.rsplit_once('/') {
+        Some(("", _)) => Some("/".into()),
+        Some((parent, _)) => Some(parent.into()),
+        None => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_ls_la_toybox() {
+        let output = "\
+total 64
+drwxrwx--x  4 root sdcard_rw     4096 2022-10-01 12:34 .
+drwx--x--x  4 root sdcard_rw     4096 2022-10-01 12:34 ..
+-rw-rw----  1 root sdcard_rw 12345678 2022-10-01 12:34 my  movie.mp4
+drwxrwx--x  2 root sdcard_rw     4096 2022-10-01 12:34 Download
+lrwxrwxrwx  1 root root            21 2008-12-31 15:00 sdcard -> /storage/self/primary
+crw-rw-rw-  1 root root        10,  3 2022-10-01 12:34 null
+ls: ./secret: Permission denied
+";
+        let files = parse_ls_la(output);
+        assert_eq!(
+            vec!["Download", "my  movie.mp4", "null", "sdcard"],
+            files
+                .iter()
+                .map(|data| data.name.as_str())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(DeviceFileKind::Directory, files[0].kind);
+        assert_eq!(12345678, files[1].size);
+        assert_eq!("11.8 MB", files[1].size_text());
+        assert_eq!(DeviceFileKind::Other, files[2].kind);
+        assert_eq!(
+            Some("/storage/self/primary".to_string()),
+            files[3].link_target
+        );
+        assert!(files[3].is_navigable());
+    }
+
+    #[test]
+    fn device_path() {
+        assert_eq!("/sdcard/a.txt", join_device_path("/sdcard", "a.txt"));
+        assert_eq!("/a.txt", join_device_path("/", "a.txt"));
+        assert_eq!(
+            Some("/sdcard".into()),
+            parent_device_path("/sdcard/Download/")
+        );
+        assert_eq!(Some("/".into()), parent_device_path("/sdcard"));
+        assert_eq!(None, parent_device_path("/"));
+    }
+}
@@ -0,0 +1,163 @@
+/*
+ * Copyright 2022 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::fmt::{Display, Formatter};
+
+/// `PowerManager.THERMAL_STATUS_*`.
+#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
+pub enum ThermalStatus {
+    None,
+    Light,
+    Moderate,
+    Severe,
+    Critical,
+    Emergency,
+    Shutdown,
+}
+
+impl ThermalStatus {
+    fn from_value(value: u32) -> Option<Self> {
+        let status = match value {
+            0 => Self::None,
+            1 => Self::Light,
+            2 => Self::Moderate,
+            3 => Self::Severe,
+            4 => Self::Critical,
+            5 => Self::Emergency,
+            6 => Self::Shutdown,
+            _ => return None,
+        };
+        Some(status)
+    }
+}
+
+impl Display for ThermalStatus {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::None => write!(f, "none"),
+            Self::Light => write!(f, "light"),
+            Self::Moderate => write!(f, "moderate"),
+            Self::Severe => write!(f, "severe"),
+            Self::Critical => write!(f, "critical"),
+            Self::Emergency => write!(f, "emergency"),
+            Self::Shutdown => write!(f, "shutdown"),
+        }
+    }
+}
+
+/// the battery and the thermal status that are polled during the connection.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DeviceHealth {
+    /// the TV without the battery reports 0 or nothing.
+    pub battery_level: Option<u32>,
+    pub battery_temperature_celsius: Option<f32>,
+    /// `None` before Android 10.
+    pub thermal_status: Option<ThermalStatus>,
+}
+
+impl DeviceHealth {
+    /// the battery temperature that is considered hot.
+    const HOT_TEMPERATURE_CELSIUS: f32 = 45.0;
+
+    /// parses `dumpsys battery` and `dumpsys thermalservice`.
+    pub fn parse(battery: &str, thermal: &str) -> Self {
+        let find = |output: &str, prefix: &str| {
+            output
+                .lines()
+                .find_map(|data| data.trim().strip_prefix(prefix))
+                .and_then(|data| data.trim().parse::<u32>().ok())
+        };
+
+        Self {
+            battery_level: find(battery, "level:").filter(|data| *data != 0),
+            // in tenths of a degree.
+            battery_temperature_celsius: find(battery, "temperature:")
+                .filter(|data| *data != 0)
+                .map(|data| data as f32 / 10.0),
+            thermal_status: find(thermal, "Thermal Status:").and_then(ThermalStatus::from_value),
+        }
+    }
+
+    /// the device is throttling or the battery is hot.
+    pub fn is_warning(&self) -> bool {
+        matches!(self.thermal_status, Some(data) if ThermalStatus::Light <= data)
+            || matches!(
+                self.battery_temperature_celsius,
+                Some(data) if Self::HOT_TEMPERATURE_CELSIUS <= data
+            )
+    }
+
+    /// like `battery 85% 32.5°C, thermal: none`.
+    pub fn summary(&self) -> String {
+        let mut segments = vec![];
+        match (self.battery_level, self.battery_temperature_celsius) {
+            (Some(level), Some(temperature)) => {
+                segments.push(format!("battery {}% {:.1}°C", level, temperature))
+            }
+            (Some(level), None) => segments.push(format!("battery {}%", level)),
+            (None, Some(temperature)) => segments.push(format!("{:.1}°C", temperature)),
+            (None, None) => {}
+        }
+        if let Some(data) = self.thermal_status {
+            segments.push(format!("thermal: {}", data));
+        }
+        if segments.is_empty() {
+            "health: unknown".into()
+        } else {
+            segments.join(", ")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn device_health_parse() {
+        let battery = "\
+Current Battery Service state:
+  AC powered: true
+  level: 85
+  scale: 100
+  temperature: 465
+";
+        let thermal = "\
+IsStatusOverride: false
+ThermalEventListeners:
+Thermal Status: 1
+";
+        let health = DeviceHealth::parse(battery, thermal);
+        assert_eq!(
+            DeviceHealth {
+                battery_level: Some(85),
+                battery_temperature_celsius: Some(46.5),
+                thermal_status: Some(ThermalStatus::Light),
+            },
+            health
+        );
+        assert!(health.is_warning());
+        assert_eq!("battery 85% 46.5°C, thermal: light", health.summary());
+    }
+
+    #[test]
+    fn device_health_without_battery() {
+        let health = DeviceHealth::parse("  level: 0\n  temperature: 0\n", "Thermal Status: 0\n");
+        assert!(!health.is_warning());
+        assert_eq!("thermal: none", health.summary());
+        assert_eq!("health: unknown", DeviceHealth::parse("", "").summary());
+    }
+}
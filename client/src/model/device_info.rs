@@ -0,0 +1,178 @@
+/*
+ * Copyright 2022 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::collections::BTreeMap;
+
+/// the properties that identify the device, in the display order.
+const DEVICE_PROPERTIES: [(&str, &str); 9] = [
+    ("manufacturer", "ro.product.manufacturer"),
+    ("model", "ro.product.model"),
+    ("device", "ro.product.device"),
+    ("serial", "ro.serialno"),
+    ("android", "ro.build.version.release"),
+    ("sdk", "ro.build.version.sdk"),
+    ("security patch", "ro.build.version.security_patch"),
+    ("build", "ro.build.display.id"),
+    ("fingerprint", "ro.build.fingerprint"),
+];
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct DeviceInfoSection {
+    pub title: String,
+    pub entries: Vec<(String, String)>,
+}
+
+impl DeviceInfoSection {
+    pub fn to_text(&self) -> String {
+        self.entries
+            .iter()
+            .map(|(key, value)| format!("{}: {}\n", key, value))
+            .collect()
+    }
+}
+
+/// the outputs of `getprop`, `wm size`, `wm density` and `dumpsys battery`.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct DeviceInfo {
+    pub device: DeviceInfoSection,
+    pub display: DeviceInfoSection,
+    pub battery: DeviceInfoSection,
+    /// all properties, which are too many to show.
+    pub properties: DeviceInfoSection,
+}
+
+impl DeviceInfo {
+    pub fn new(getprop: &str, wm_size: &str, wm_density: &str, battery: &str) -> Self {
+        let properties = parse_getprop(getprop);
+        let device = DEVICE_PROPERTIES
+            .iter()
+            .filter_map(|(label, key)| Some((label.to_string(), properties.get(*key)?.clone())))
+            .collect();
+        let display = parse_key_values(wm_size)
+            .into_iter()
+            .chain(parse_key_values(wm_density))
+            .collect();
+
+        Self {
+            device: DeviceInfoSection {
+                title: "Device".into(),
+                entries: device,
+            },
+            display: DeviceInfoSection {
+                title: "Display".into(),
+                entries: display,
+            },
+            battery: DeviceInfoSection {
+                title: "Battery".into(),
+                entries: parse_key_values(battery),
+            },
+            properties: DeviceInfoSection {
+                title: "Properties".into(),
+                entries: properties.into_iter().collect(),
+            },
+        }
+    }
+
+    pub fn to_text(&self) -> String {
+        [&self.device, &self.display, &self.battery, &self.properties]
+            .iter()
+            .map(|data| format!("[{}]\n{}", data.title, data.to_text()))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// parses the lines like `[ro.product.model]: [BRAVIA 4K UR3]`.
+pub fn parse_getprop(output: &str) -> BTreeMap<String, String> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let (key, value) = line.trim().split_once("]: [")?;
+            Some((
+                key.strip_prefix('[')?.to_string(),
+                value.strip_suffix(']')?.to_string(),
+            ))
+        })
+        .collect()
+}
+
+/// parses the lines like `Physical size: 1920x1080` and skips the lines without the value
+/// like `Current Battery Service state:`.
+pub fn parse_key_values(output: &str) -> Vec<(String, String)> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let (key, value) = line.split_once(':')?;
+            let (key, value) = (key.trim(), value.trim());
+            if key.is_empty() || value.is_empty() {
+                return None;
+            }
+            Some((key.to_string(), value.to_string()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn device_info_new() {
+        let getprop = "\
+[ro.build.fingerprint]: [Sony/BRAVIA_UR3_EU/BRAVIA_UR3:10/QTG3.200305.006/1:user/release-keys]
+[ro.build.version.release]: [10]
+[ro.product.manufacturer]: [Sony]
+[ro.product.model]: [BRAVIA 4K UR3]
+[persist.sys.locale]: []
+";
+        let battery = "Current Battery Service state:\n  AC powered: true\n  level: 100\n";
+        let info = DeviceInfo::new(
+            getprop,
+            "Physical size: 3840x2160\nOverride size: 1920x1080\n",
+            "Physical density: 320\n",
+            battery,
+        );
+
+        assert_eq!(
+            "manufacturer: Sony
+model: BRAVIA 4K UR3
+android: 10
+fingerprint: Sony/BRAVIA_UR3_EU/BRAVIA_UR3:10/QTG3.200305.006/1:user/release-keys
+",
+            info.device.to_text()
+        );
+        assert_eq!(
+            vec![
+                ("Physical size".into(), "3840x2160".into()),
+                ("Override size".into(), "1920x1080".into()),
+                ("Physical density".into(), "320".into()),
+            ],
+            info.display.entries
+        );
+        assert_eq!(
+            vec![
+                ("AC powered".into(), "true".into()),
+                ("level".into(), "100".into()),
+            ],
+            info.battery.entries
+        );
+        assert_eq!(5, info.properties.entries.len());
+        assert_eq!(
+            Some(&("persist.sys.locale".into(), "".into())),
+            info.properties.entries.first()
+        );
+    }
+}
@@ -0,0 +1,254 @@
+/*
+ * Copyright 2022 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::model::layout::{ButtonSize, LaunchShortcut, LayoutProfile, SectionConfig, SectionId};
+use crate::model::target::{NetworkTarget, NetworkTargetKind};
+use crate::prelude::*;
+
+/// the setup of a device to share as a QR code with another instance or the companion app.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DeviceProfile {
+    pub serial: String,
+    pub layout: LayoutProfile,
+    /// `None` for the adb device.
+    pub network_target: Option<NetworkTarget>,
+}
+
+impl DeviceProfile {
+    /// the prefix with the format version.
+    const PREFIX: &'static str = "acmd1";
+
+    /// serializes into the compact text like `acmd1;s=emulator-5554;n=Full;l=cm1dm1;k=` that
+    /// fits in a small QR code.
+    pub fn encode(&self) -> String {
+        let sections = self
+            .layout
+            .sections
+            .iter()
+            .map(|data| {
+                format!(
+                    "{}{}{}",
+                    section_code(data.id),
+                    size_code(data.button_size),
+                    if data.visible { '1' } else { '0' }
+                )
+            })
+            .collect::<String>();
+        let shortcuts = self
+            .layout
+            .shortcuts
+            .iter()
+            .flat_map(|data| [escape(&data.label), escape(&data.target)])
+            .collect::<Vec<_>>()
+            .join(",");
+
+        let mut code = format!(
+            "{};s={};n={};l={};k={}",
+            Self::PREFIX,
+            escape(&self.serial),
+            escape(&self.layout.name),
+            sections,
+            shortcuts,
+        );
+        if let Some(target) = &self.network_target {
+            code.push_str(&format!(
+                ";t={},{},{}",
+                match target.kind {
+                    NetworkTargetKind::AndroidTvRemote => "atv",
+                    NetworkTargetKind::Roku => "roku",
+                },
+                escape(&target.name),
+                escape(&target.host),
+            ));
+        }
+        code
+    }
+
+    pub fn decode(code: &str) -> Fallible<Self> {
+        let mut fields = code.trim().split(';');
+        if fields.next() != Some(Self::PREFIX) {
+            anyhow::bail!("not a device profile");
+        }
+
+        let mut serial = None;
+        let mut name = None;
+        let mut sections = vec![];
+        let mut shortcuts = vec![];
+        let mut network_target = None;
+        for field in fields {
+            let (key, value) = field.split_once('=').context("missing =")?;
+            match key {
+                "s" => serial = Some(unescape(value)?),
+                "n" => name = Some(unescape(value)?),
+                "l" => sections = decode_sections(value)?,
+                "k" if value.is_empty() => (),
+                "k" => {
+                    let values = value.split(',').collect::<Vec<_>>();
+                    if values.len() % 2 != 0 {
+                        anyhow::bail!("unpaired shortcut");
+                    }
+                    for pair in values.chunks(2) {
+                        shortcuts.push(LaunchShortcut {
+                            label: unescape(pair[0])?,
+                            target: unescape(pair[1])?,
+                        });
+                    }
+                }
+                "t" => {
+                    let values = value.split(',').collect::<Vec<_>>();
+                    let (kind, name, host) = match values[..] {
+                        [kind, name, host] => (kind, name, host),
+                        _ => anyhow::bail!("malformed target"),
+                    };
+                    network_target = Some(NetworkTarget {
+                        kind: match kind {
+                            "atv" => NetworkTargetKind::AndroidTvRemote,
+                            "roku" => NetworkTargetKind::Roku,
+                            _ => anyhow::bail!("unknown target kind: {}", kind),
+                        },
+                        name: unescape(name)?,
+                        host: unescape(host)?,
+                    });
+                }
+                // ignores the unknown fields to read the newer profile.
+                _ => (),
+            }
+        }
+
+        Ok(Self {
+            serial: serial.context("missing serial")?,
+            layout: LayoutProfile {
+                name: name.context("missing layout name")?,
+                sections,
+                shortcuts,
+                // the grid isn't shared since the code gets too long.
+                grid: vec![],
+            },
+            network_target,
+        })
+    }
+}
+
+fn section_code(id: SectionId) -> char {
+    match id {
+        SectionId::ColorKeys => 'c',
+        SectionId::Dpad => 'd',
+        SectionId::Navigation => 'n',
+        SectionId::Numpad => 'p',
+        SectionId::Media => 'm',
+        SectionId::Power => 'w',
+        SectionId::Tv => 't',
+        SectionId::CustomKeys => 'k',
+    }
+}
+
+fn size_code(size: ButtonSize) -> char {
+    match size {
+        ButtonSize::Small => 's',
+        ButtonSize::Medium => 'm',
+        ButtonSize::Large => 'l',
+    }
+}
+
+fn decode_sections(value: &str) -> Fallible<Vec<SectionConfig>> {
+    let chars = value.chars().collect::<Vec<_>>();
+    if chars.len() % 3 != 0 {
+        anyhow::bail!("malformed sections");
+    }
+
+    chars
+        .chunks(3)
+        .map(|data| {
+            Ok(SectionConfig {
+                id: SectionId::ALL
+                    .into_iter()
+                    .find(|id| section_code(*id) == data[0])
+                    .with_context(|| format!("unknown section: {}", data[0]))?,
+                button_size: ButtonSize::ALL
+                    .into_iter()
+                    .find(|size| size_code(*size) == data[1])
+                    .with_context(|| format!("unknown size: {}", data[1]))?,
+                visible: data[2] == '1',
+            })
+        })
+        .collect()
+}
+
+/// escapes the separators and `%` as the percent-encoding.
+fn escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '%' | ';' | ',' | '=' => escaped.push_str(&format!("%{:02X}", c as u8)),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn unescape(value: &str) -> Fallible<String> {
+    let mut unescaped = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            unescaped.push(c);
+            continue;
+        }
+
+        let hex = chars.by_ref().take(2).collect::<String>();
+        let byte = u8::from_str_radix(&hex, 16).with_context(|| format!("bad escape: %{}", hex))?;
+        unescaped.push(byte as char);
+    }
+    Ok(unescaped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn device_profile_round_trip() {
+        let mut layout = LayoutProfile::dpad_only();
+        layout.name = "Living room; TV".into();
+        layout.sections[0].button_size = ButtonSize::Large;
+        layout.pin_shortcut(LaunchShortcut {
+            label: "Search".into(),
+            target: "app://search?q=a,b".into(),
+        });
+        let profile = DeviceProfile {
+            serial: "192.168.0.10:5555".into(),
+            layout,
+            network_target: Some(NetworkTarget {
+                kind: NetworkTargetKind::Roku,
+                name: "Roku=1".into(),
+                host: "192.168.0.20".into(),
+            }),
+        };
+
+        let code = profile.encode();
+        assert!(code.starts_with("acmd1;s=192.168.0.10:5555;n=Living room%3B TV;l=cl0dm1"));
+        assert_eq!(profile, DeviceProfile::decode(&code).unwrap());
+    }
+
+    #[test]
+    fn device_profile_decode_errors() {
+        assert!(DeviceProfile::decode("acmd2;s=a;n=b;l=;k=").is_err());
+        assert!(DeviceProfile::decode("acmd1;n=b;l=;k=").is_err());
+        assert!(DeviceProfile::decode("acmd1;s=a;n=b;l=xm1;k=").is_err());
+        assert!(DeviceProfile::decode("acmd1;s=a;n=b;l=dm1;k=%zz,b").is_err());
+        assert!(DeviceProfile::decode("acmd1;s=a;n=b;l=dm1;k=;x=new").is_ok());
+    }
+}
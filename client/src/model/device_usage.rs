@@ -0,0 +1,143 @@
+/*
+ * Copyright 2022 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/// the storage of `/data` and the memory of the device in kilobytes.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct DeviceUsage {
+    pub storage: Option<UsageAmount>,
+    pub memory: Option<UsageAmount>,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct UsageAmount {
+    pub total_kb: u64,
+    pub available_kb: u64,
+}
+
+impl UsageAmount {
+    /// like `9.4G free of 23.8G (60% used)`.
+    pub fn summary(&self) -> String {
+        let used = self.total_kb.saturating_sub(self.available_kb);
+        format!(
+            "{} free of {} ({}% used)",
+            format_kb(self.available_kb),
+            format_kb(self.total_kb),
+            (used * 100).checked_div(self.total_kb).unwrap_or(0),
+        )
+    }
+}
+
+/// parses the `/data` line of `df -k /data`.
+pub fn parse_df(output: &str) -> Option<UsageAmount> {
+    let line = output
+        .lines()
+        .find(|data| data.split_whitespace().last() == Some("/data"))?;
+    let segments = line.split_whitespace().collect::<Vec<_>>();
+    if segments.len() != 6 {
+        return None;
+    }
+
+    Some(UsageAmount {
+        total_kb: segments[1].parse().ok()?,
+        available_kb: segments[3].parse().ok()?,
+    })
+}
+
+/// parses `Total RAM: 3,809,048K (status normal)` and `Free RAM: 2,018,676K (...)` of
+/// `dumpsys meminfo`.
+pub fn parse_meminfo(output: &str) -> Option<UsageAmount> {
+    let find = |prefix: &str| {
+        let value = output
+            .lines()
+            .find_map(|data| data.trim().strip_prefix(prefix))?;
+        value
+            .trim()
+            .chars()
+            .take_while(|data| data.is_ascii_digit() || *data == ',')
+            .filter(char::is_ascii_digit)
+            .collect::<String>()
+            .parse()
+            .ok()
+    };
+
+    Some(UsageAmount {
+        total_kb: find("Total RAM:")?,
+        available_kb: find("Free RAM:")?,
+    })
+}
+
+fn format_kb(kb: u64) -> String {
+    const UNITS: [&str; 3] = ["K", "M", "G"];
+
+    let mut value = kb as f64;
+    let mut unit = UNITS[0];
+    for data in &UNITS[1..] {
+        if value < 1024.0 {
+            break;
+        }
+        value /= 1024.0;
+        unit = data;
+    }
+    format!("{:.1}{}", value, unit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_df_data() {
+        let output = "Filesystem      1K-blocks    Used Available Use% Mounted on
+/dev/block/dm-5  24981444 9810132  15040240  40% /data
+";
+        let usage = parse_df(output).unwrap();
+        assert_eq!(
+            UsageAmount {
+                total_kb: 24981444,
+                available_kb: 15040240,
+            },
+            usage
+        );
+        assert_eq!("14.3G free of 23.8G (39% used)", usage.summary());
+        assert_eq!(None, parse_df("df: /data: Permission denied\n"));
+    }
+
+    #[test]
+    fn parse_meminfo_summary() {
+        let output = "Total PSS by category:
+    412,345K: Native
+
+Total RAM: 3,809,048K (status normal)
+ Free RAM: 2,018,676K (  114,532K cached pss + 1,904,144K free)
+ Used RAM: 1,582,420K
+";
+        assert_eq!(
+            Some(UsageAmount {
+                total_kb: 3809048,
+                available_kb: 2018676,
+            }),
+            parse_meminfo(output)
+        );
+        assert_eq!(
+            Some(UsageAmount {
+                total_kb: 1842356,
+                available_kb: 912000,
+            }),
+            parse_meminfo("Total RAM: 1842356 kB\n Free RAM: 912000 kB\n")
+        );
+        assert_eq!(None, parse_meminfo(""));
+    }
+}
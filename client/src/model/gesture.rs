@@ -0,0 +1,194 @@
+/*
+ * Copyright 2022 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::time::Duration;
+
+/// alternate actions of the button that sends `keycode`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ButtonGesture {
+    pub keycode: String,
+    /// sends this keycode instead of clicking twice.
+    pub double_click: Option<String>,
+    /// sends a long-press by Ctrl+click.
+    pub ctrl_click_long_press: bool,
+    pub long_press: LongPressMode,
+}
+
+/// how to tell the long-press to the app.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum LongPressMode {
+    /// sends the repeated down with `KeyEvent.FLAG_LONG_PRESS`.
+    #[default]
+    Flag,
+    /// sends the repeated down only for the apps that count the repeats.
+    Repeat,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ButtonAction {
+    Click(String),
+    LongPress(String, LongPressMode),
+    /// waits for the second click, and calls [GestureRecognizer::timeout] with the id.
+    Wait(u64),
+}
+
+/// recognizes the gestures from the button clicks.
+#[derive(Debug, Default)]
+pub struct GestureRecognizer {
+    next_id: u64,
+    pending: Option<(String, u64)>,
+}
+
+impl GestureRecognizer {
+    pub const DOUBLE_CLICK_INTERVAL: Duration = Duration::from_millis(300);
+
+    /// `held` is whether the button has been held longer than the long-press timeout.
+    pub fn click(
+        &mut self,
+        gestures: &[ButtonGesture],
+        keycode: &str,
+        ctrl: bool,
+        held: bool,
+    ) -> Vec<ButtonAction> {
+        let gesture = gestures.iter().find(|data| data.keycode == keycode);
+        let long_press_mode = gesture.map(|data| data.long_press).unwrap_or_default();
+
+        let mut actions = vec![];
+        if let Some((pending, _)) = self.pending.take() {
+            match gesture.and_then(|data| data.double_click.as_ref()) {
+                Some(alternate) if pending == keycode && !ctrl && !held => {
+                    return vec![ButtonAction::Click(alternate.clone())];
+                }
+                _ => actions.push(ButtonAction::Click(pending)),
+            }
+        }
+
+        match gesture {
+            _ if held => actions.push(ButtonAction::LongPress(keycode.into(), long_press_mode)),
+            Some(data) if ctrl && data.ctrl_click_long_press => {
+                actions.push(ButtonAction::LongPress(keycode.into(), long_press_mode));
+            }
+            Some(data) if !ctrl && data.double_click.is_some() => {
+                self.next_id += 1;
+                self.pending = Some((keycode.into(), self.next_id));
+                actions.push(ButtonAction::Wait(self.next_id));
+            }
+            _ => actions.push(ButtonAction::Click(keycode.into())),
+        }
+        actions
+    }
+
+    /// returns the keycode to click if the second click didn't come.
+    pub fn timeout(&mut self, id: u64) -> Option<String> {
+        match &self.pending {
+            Some((_, pending_id)) if *pending_id == id => self.pending.take().map(|data| data.0),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gestures() -> Vec<ButtonGesture> {
+        vec![ButtonGesture {
+            keycode: "KEYCODE_BACK".into(),
+            double_click: Some("KEYCODE_HOME".into()),
+            ctrl_click_long_press: true,
+            long_press: LongPressMode::Repeat,
+        }]
+    }
+
+    #[test]
+    fn double_click() {
+        let gestures = gestures();
+        let mut recognizer = GestureRecognizer::default();
+        assert_eq!(
+            vec![ButtonAction::Wait(1)],
+            recognizer.click(&gestures, "KEYCODE_BACK", false, false)
+        );
+        assert_eq!(
+            vec![ButtonAction::Click("KEYCODE_HOME".into())],
+            recognizer.click(&gestures, "KEYCODE_BACK", false, false)
+        );
+        assert_eq!(None, recognizer.timeout(1));
+    }
+
+    #[test]
+    fn single_click_after_timeout() {
+        let gestures = gestures();
+        let mut recognizer = GestureRecognizer::default();
+        assert_eq!(
+            vec![ButtonAction::Wait(1)],
+            recognizer.click(&gestures, "KEYCODE_BACK", false, false)
+        );
+        assert_eq!(Some("KEYCODE_BACK".into()), recognizer.timeout(1));
+        assert_eq!(None, recognizer.timeout(1));
+    }
+
+    #[test]
+    fn flush_pending_click() {
+        let gestures = gestures();
+        let mut recognizer = GestureRecognizer::default();
+        recognizer.click(&gestures, "KEYCODE_BACK", false, false);
+        assert_eq!(
+            vec![
+                ButtonAction::Click("KEYCODE_BACK".into()),
+                ButtonAction::Click("KEYCODE_DPAD_UP".into()),
+            ],
+            recognizer.click(&gestures, "KEYCODE_DPAD_UP", false, false)
+        );
+
+        recognizer.click(&gestures, "KEYCODE_BACK", false, false);
+        assert_eq!(
+            vec![
+                ButtonAction::Click("KEYCODE_BACK".into()),
+                ButtonAction::LongPress("KEYCODE_BACK".into(), LongPressMode::Repeat),
+            ],
+            recognizer.click(&gestures, "KEYCODE_BACK", true, false)
+        );
+    }
+
+    #[test]
+    fn no_gesture() {
+        let mut recognizer = GestureRecognizer::default();
+        assert_eq!(
+            vec![ButtonAction::Click("KEYCODE_BACK".into())],
+            recognizer.click(&[], "KEYCODE_BACK", true, false)
+        );
+    }
+
+    #[test]
+    fn hold() {
+        let gestures = gestures();
+        let mut recognizer = GestureRecognizer::default();
+        assert_eq!(
+            vec![ButtonAction::LongPress(
+                "KEYCODE_BACK".into(),
+                LongPressMode::Repeat
+            )],
+            recognizer.click(&gestures, "KEYCODE_BACK", false, true)
+        );
+        assert_eq!(
+            vec![ButtonAction::LongPress(
+                "KEYCODE_DPAD_CENTER".into(),
+                LongPressMode::Flag
+            )],
+            recognizer.click(&gestures, "KEYCODE_DPAD_CENTER", false, true)
+        );
+    }
+}
@@ -0,0 +1,277 @@
+/*
+ * Copyright 2022 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::model::keycode::Keycode;
+use chrono::{DateTime, Duration, Local};
+use std::collections::VecDeque;
+use std::fmt::{Display, Formatter};
+use std::path::PathBuf;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum HistoryEntryKind {
+    Key(Keycode),
+    Launch(String),
+    LongPress(Keycode),
+    MacroFailed {
+        reason: String,
+        screenshot: Option<PathBuf>,
+    },
+    Marker(String),
+}
+
+impl HistoryEntryKind {
+    /// whether the entry can be sent again from the history.
+    pub fn is_resendable(&self) -> bool {
+        match self {
+            HistoryEntryKind::Key(_)
+            | HistoryEntryKind::Launch(_)
+            | HistoryEntryKind::LongPress(_) => true,
+            HistoryEntryKind::MacroFailed { .. } | HistoryEntryKind::Marker(_) => false,
+        }
+    }
+}
+
+impl Display for HistoryEntryKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HistoryEntryKind::Key(keycode) => write!(f, "{}", keycode),
+            HistoryEntryKind::Launch(target) => write!(f, "launch {}", target),
+            HistoryEntryKind::LongPress(keycode) => write!(f, "long {}", keycode),
+            HistoryEntryKind::MacroFailed { reason, screenshot } => match screenshot {
+                Some(data) => write!(f, "macro {} ({})", reason, data.display()),
+                None => write!(f, "macro {}", reason),
+            },
+            HistoryEntryKind::Marker(marker) => write!(f, "--- {} ---", marker),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct HistoryEntry {
+    pub kind: HistoryEntryKind,
+    pub sent_at: DateTime<Local>,
+    pub serial: String,
+    /// the time the device received the entry if the device acknowledged it.
+    pub device_received_at: Option<DateTime<Local>>,
+}
+
+impl HistoryEntry {
+    pub fn new(kind: HistoryEntryKind, serial: String) -> Self {
+        Self {
+            kind,
+            sent_at: Local::now(),
+            serial,
+            device_received_at: None,
+        }
+    }
+}
+
+/// keeps the latest [History::CAPACITY] entries that were sent to the device.
+#[derive(Debug, Default)]
+pub struct History {
+    entries: VecDeque<HistoryEntry>,
+}
+
+impl History {
+    pub const CAPACITY: usize = 100;
+
+    pub fn push(&mut self, entry: HistoryEntry) {
+        if self.entries.len() == Self::CAPACITY {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(entry);
+    }
+
+    /// iterates the entries from the newest.
+    pub fn iter(&self) -> impl Iterator<Item = &HistoryEntry> {
+        self.entries.iter().rev()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// records the ack to the newest entry of the key, since the device acks the up of it.
+    pub fn mark_received(&mut self, keycode: &Keycode, received_at: DateTime<Local>) {
+        let entry = self.entries.iter_mut().rev().find(|data| match &data.kind {
+            HistoryEntryKind::Key(data) | HistoryEntryKind::LongPress(data) => data == keycode,
+            _ => false,
+        });
+        if let Some(entry) = entry {
+            if entry.device_received_at.is_none() {
+                entry.device_received_at = Some(received_at);
+            }
+        }
+    }
+
+    /// exports the entries from the oldest as tab-separated values.
+    pub fn to_tsv(&self) -> String {
+        self.to_tsv_filtered(|_| true)
+    }
+
+    /// exports the entries sent at or after `since`, such as the ones of a macro run.
+    pub fn to_tsv_since(&self, since: DateTime<Local>) -> String {
+        self.to_tsv_filtered(|entry| since <= entry.sent_at)
+    }
+
+    fn to_tsv_filtered(&self, predicate: impl Fn(&HistoryEntry) -> bool) -> String {
+        let mut tsv = "sent_at\tserial\tdevice_received_at\tentry\n".to_string();
+        for entry in self.entries.iter().filter(|data| predicate(data)) {
+            tsv.push_str(&format!(
+                "{}\t{}\t{}\t{}\n",
+                entry.sent_at.to_rfc3339(),
+                entry.serial,
+                entry
+                    .device_received_at
+                    .map(|data| data.to_rfc3339())
+                    .unwrap_or_default(),
+                entry.kind,
+            ));
+        }
+        tsv
+    }
+}
+
+/// formats the elapsed time like `5s ago`.
+pub fn format_relative_time(elapsed: Duration) -> String {
+    if elapsed < Duration::seconds(1) {
+        "now".into()
+    } else if elapsed < Duration::minutes(1) {
+        format!("{}s ago", elapsed.num_seconds())
+    } else if elapsed < Duration::hours(1) {
+        format!("{}m ago", elapsed.num_minutes())
+    } else {
+        format!("{}h ago", elapsed.num_hours())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn history_capacity() {
+        let mut history = History::default();
+        for num in 0..History::CAPACITY + 1 {
+            history.push(HistoryEntry::new(
+                HistoryEntryKind::Key(num.to_string().parse().unwrap()),
+                "emulator-5554".into(),
+            ));
+        }
+
+        assert_eq!(History::CAPACITY, history.iter().count());
+        assert_eq!(
+            HistoryEntryKind::Key(History::CAPACITY.to_string().parse().unwrap()),
+            history.iter().next().unwrap().kind
+        );
+        assert_eq!(
+            HistoryEntryKind::Key("1".parse().unwrap()),
+            history.iter().last().unwrap().kind
+        );
+    }
+
+    #[test]
+    fn history_mark_received() {
+        let mut history = History::default();
+        let home = "KEYCODE_HOME".parse::<Keycode>().unwrap();
+        let back = "KEYCODE_BACK".parse::<Keycode>().unwrap();
+        for kind in [
+            HistoryEntryKind::Key(home.clone()),
+            HistoryEntryKind::LongPress(home.clone()),
+            HistoryEntryKind::Key(back),
+        ] {
+            history.push(HistoryEntry::new(kind, "emulator-5554".into()));
+        }
+
+        let received_at = Local::now();
+        history.mark_received(&home, received_at);
+        history.mark_received(&home, received_at + Duration::seconds(1));
+        assert_eq!(
+            vec![None, Some(received_at), None],
+            history
+                .iter()
+                .map(|data| data.device_received_at)
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn history_to_tsv() {
+        let mut history = History::default();
+        let key = HistoryEntry::new(
+            HistoryEntryKind::Key("KEYCODE_HOME".parse().unwrap()),
+            "emulator-5554".into(),
+        );
+        let marker = HistoryEntry {
+            device_received_at: Some(key.sent_at),
+            ..HistoryEntry::new(
+                HistoryEntryKind::Marker("MARK 1".into()),
+                "emulator-5554".into(),
+            )
+        };
+        history.push(key.clone());
+        history.push(marker.clone());
+
+        assert_eq!(
+            format!(
+                "sent_at\tserial\tdevice_received_at\tentry
+{}\temulator-5554\t\tKEYCODE_HOME
+{}\temulator-5554\t{}\t--- MARK 1 ---
+",
+                key.sent_at.to_rfc3339(),
+                marker.sent_at.to_rfc3339(),
+                key.sent_at.to_rfc3339(),
+            ),
+            history.to_tsv()
+        );
+    }
+
+    #[test]
+    fn history_to_tsv_since() {
+        let mut history = History::default();
+        let old = HistoryEntry {
+            sent_at: Local::now() - Duration::minutes(1),
+            ..HistoryEntry::new(
+                HistoryEntryKind::Key("KEYCODE_HOME".parse().unwrap()),
+                "emulator-5554".into(),
+            )
+        };
+        let new = HistoryEntry::new(
+            HistoryEntryKind::Key("KEYCODE_BACK".parse().unwrap()),
+            "emulator-5554".into(),
+        );
+        history.push(old);
+        history.push(new.clone());
+
+        assert_eq!(
+            format!(
+                "sent_at\tserial\tdevice_received_at\tentry
+{}\temulator-5554\t\tKEYCODE_BACK
+",
+                new.sent_at.to_rfc3339(),
+            ),
+            history.to_tsv_since(new.sent_at)
+        );
+    }
+
+    #[test]
+    fn format_relative_time_units() {
+        assert_eq!("now", format_relative_time(Duration::milliseconds(999)));
+        assert_eq!("59s ago", format_relative_time(Duration::seconds(59)));
+        assert_eq!("1m ago", format_relative_time(Duration::seconds(60)));
+        assert_eq!("2h ago", format_relative_time(Duration::minutes(150)));
+    }
+}
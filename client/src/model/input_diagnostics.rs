@@ -0,0 +1,202 @@
+/*
+ * Copyright 2022 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/// the summary of `dumpsys input` to tell why the injected keycode does nothing.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct InputDiagnostics {
+    pub devices: Vec<InputDeviceSummary>,
+    /// the key events that the dispatcher handled recently, from the oldest.
+    pub recent_key_events: Vec<String>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct InputDeviceSummary {
+    pub id: i32,
+    pub name: String,
+    pub enabled: bool,
+    pub key_layout_file: Option<String>,
+    pub key_character_map_file: Option<String>,
+}
+
+impl InputDiagnostics {
+    /// reads the devices of `Event Hub State` and `RecentQueue` of `Input Dispatcher State`.
+    pub fn parse(dumpsys: &str) -> Self {
+        let mut diagnostics = Self::default();
+        let mut in_event_hub = false;
+        let mut recent_queue_indent = None;
+        for line in dumpsys.lines() {
+            let trimmed = line.trim();
+            let indent = line.len() - line.trim_start().len();
+            if indent == 0 && !trimmed.is_empty() {
+                in_event_hub = trimmed == "Event Hub State:";
+                recent_queue_indent = None;
+                continue;
+            }
+
+            if let Some(queue_indent) = recent_queue_indent {
+                if queue_indent < indent {
+                    if let Some(data) = summarize_key_event(trimmed) {
+                        diagnostics.recent_key_events.push(data);
+                    }
+                    continue;
+                }
+                recent_queue_indent = None;
+            }
+            if trimmed.starts_with("RecentQueue:") {
+                recent_queue_indent = Some(indent);
+                continue;
+            }
+
+            if !in_event_hub {
+                continue;
+            }
+
+            if let Some((id, name)) = trimmed
+                .split_once(": ")
+                .and_then(|(id, name)| Some((id.parse().ok()?, name)))
+            {
+                diagnostics.devices.push(InputDeviceSummary {
+                    id,
+                    name: name.into(),
+                    // the older versions print no `Enabled`.
+                    enabled: true,
+                    key_layout_file: None,
+                    key_character_map_file: None,
+                });
+                continue;
+            }
+
+            let device = match diagnostics.devices.last_mut() {
+                Some(data) => data,
+                None => continue,
+            };
+            let (key, value) = match trimmed.split_once(':') {
+                Some((key, value)) => (key, value.trim()),
+                None => continue,
+            };
+            match key {
+                "Enabled" => device.enabled = value == "true",
+                "KeyLayoutFile" if !value.is_empty() => device.key_layout_file = Some(value.into()),
+                "KeyCharacterMapFile" if !value.is_empty() => {
+                    device.key_character_map_file = Some(value.into())
+                }
+                _ => (),
+            }
+        }
+        diagnostics
+    }
+
+    /// the readable lines for the panel.
+    pub fn to_lines(&self) -> Vec<String> {
+        let mut lines = vec![format!("devices ({}):", self.devices.len())];
+        for device in &self.devices {
+            lines.push(format!(
+                "{}: {}{}",
+                device.id,
+                device.name,
+                if device.enabled { "" } else { " (disabled)" }
+            ));
+            if let Some(data) = &device.key_layout_file {
+                lines.push(format!("  kl: {}", data));
+            }
+            if let Some(data) = &device.key_character_map_file {
+                lines.push(format!("  kcm: {}", data));
+            }
+        }
+
+        lines.push(format!(
+            "recent key events ({}):",
+            self.recent_key_events.len()
+        ));
+        lines.extend(self.recent_key_events.iter().cloned());
+        lines
+    }
+}
+
+/// `KeyEvent(deviceId=-1, ..., action=DOWN, ..., keyCode=19(KEYCODE_DPAD_UP), ...)` to
+/// `DOWN 19(KEYCODE_DPAD_UP) device -1`.
+fn summarize_key_event(line: &str) -> Option<String> {
+    let fields = line.strip_prefix("KeyEvent(")?;
+    let field = |name: &str| {
+        fields.split(", ").find_map(|data| {
+            data.strip_prefix(name)
+                .and_then(|data| data.strip_prefix('='))
+        })
+    };
+
+    Some(format!(
+        "{} {} device {}",
+        field("action")?,
+        field("keyCode")?,
+        field("deviceId").unwrap_or("?"),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_dumpsys_input() {
+        let dumpsys = r#"INPUT MANAGER (dumpsys input)
+
+Event Hub State:
+  BuiltInKeyboardId: -2
+  Devices:
+    -1: Virtual
+      Classes: 0x40000023
+      Enabled: true
+      KeyLayoutFile: /system/usr/keylayout/Generic.kl
+      KeyCharacterMapFile: /system/usr/keychars/Virtual.kcm
+    4: vendor-remote
+      Enabled: false
+      KeyLayoutFile: 
+      KeyCharacterMapFile: /vendor/usr/keychars/remote.kcm
+
+Input Dispatcher State:
+  FocusedApplications:
+  RecentQueue: length=2
+    MotionEvent(deviceId=3, action=DOWN), policyFlags=0x62000000, age=20ms
+    KeyEvent(deviceId=-1, eventTime=1, source=0x00000101, displayId=0, action=DOWN, flags=0x00000008, keyCode=19(KEYCODE_DPAD_UP), scanCode=0, metaState=0x00000000, repeatCount=0), policyFlags=0x6b000000, age=10ms
+  PendingEvent: <none>
+"#;
+
+        let diagnostics = InputDiagnostics::parse(dumpsys);
+        assert_eq!(
+            vec![
+                InputDeviceSummary {
+                    id: -1,
+                    name: "Virtual".into(),
+                    enabled: true,
+                    key_layout_file: Some("/system/usr/keylayout/Generic.kl".into()),
+                    key_character_map_file: Some("/system/usr/keychars/Virtual.kcm".into()),
+                },
+                InputDeviceSummary {
+                    id: 4,
+                    name: "vendor-remote".into(),
+                    enabled: false,
+                    key_layout_file: None,
+                    key_character_map_file: Some("/vendor/usr/keychars/remote.kcm".into()),
+                },
+            ],
+            diagnostics.devices
+        );
+        assert_eq!(
+            vec!["DOWN 19(KEYCODE_DPAD_UP) device -1".to_string()],
+            diagnostics.recent_key_events
+        );
+    }
+}
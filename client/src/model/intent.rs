@@ -0,0 +1,230 @@
+/*
+ * Copyright 2022 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::prelude::*;
+use std::fmt::{Display, Formatter};
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum IntentExtraKind {
+    Bool,
+    Int,
+    String,
+}
+
+impl IntentExtraKind {
+    fn flag(&self) -> &'static str {
+        match self {
+            Self::Bool => "--ez",
+            Self::Int => "--ei",
+            Self::String => "--es",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct IntentExtra {
+    pub kind: IntentExtraKind,
+    pub key: String,
+    pub value: String,
+}
+
+/// the parameters of `am start`, and the empty ones are omitted.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct IntentSpec {
+    pub action: String,
+    pub component: String,
+    pub data_uri: String,
+    pub extras: Vec<IntentExtra>,
+}
+
+impl IntentSpec {
+    /// keeps the latest intents only.
+    pub const HISTORY_CAPACITY: usize = 20;
+
+    pub fn new(action: &str, data_uri: &str, component: &str, extras: &str) -> Fallible<Self> {
+        let spec = Self {
+            action: action.trim().into(),
+            component: component.trim().into(),
+            data_uri: data_uri.trim().into(),
+            extras: parse_extras(extras)?,
+        };
+        if spec.action.is_empty() && spec.data_uri.is_empty() && spec.component.is_empty() {
+            anyhow::bail!("specify an action, a data uri or a component");
+        }
+        Ok(spec)
+    }
+
+    /// the arguments after `am start`.
+    pub fn args(&self) -> Vec<String> {
+        let mut args = vec![];
+        if !self.action.is_empty() {
+            args.extend(["-a".into(), self.action.clone()]);
+        }
+        if !self.data_uri.is_empty() {
+            args.extend(["-d".into(), self.data_uri.clone()]);
+        }
+        if !self.component.is_empty() {
+            args.extend(["-n".into(), self.component.clone()]);
+        }
+        for extra in &self.extras {
+            args.extend([
+                extra.kind.flag().into(),
+                extra.key.clone(),
+                extra.value.clone(),
+            ]);
+        }
+        args
+    }
+
+    /// formats the extras as [parse_extras] accepts.
+    pub fn extras_text(&self) -> String {
+        self.extras
+            .iter()
+            .map(|data| match data.kind {
+                IntentExtraKind::Bool => format!("{}:bool={}", data.key, data.value),
+                IntentExtraKind::Int => format!("{}:int={}", data.key, data.value),
+                IntentExtraKind::String => format!("{}={}", data.key, data.value),
+            })
+            .collect::<Vec<_>>()
+            .join("; ")
+    }
+}
+
+impl Display for IntentSpec {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let summary = [&self.action, &self.data_uri, &self.component]
+            .into_iter()
+            .filter(|data| !data.is_empty())
+            .map(|data| data.as_str())
+            .collect::<Vec<_>>()
+            .join(" ");
+        match self.extras.len() {
+            0 => write!(f, "{}", summary),
+            len => write!(f, "{} (+{} extras)", summary, len),
+        }
+    }
+}
+
+/// parses the extras like `url=https://example.com; count:int=3; debug:bool=true`.
+pub fn parse_extras(value: &str) -> Fallible<Vec<IntentExtra>> {
+    let mut extras = vec![];
+    for entry in value
+        .split(';')
+        .map(str::trim)
+        .filter(|data| !data.is_empty())
+    {
+        let (key, value) = entry
+            .split_once('=')
+            .with_context(|| format!("extra must be key=value: {}", entry))?;
+        let (key, kind) = match key.trim().rsplit_once(':') {
+            Some((key, "bool")) => {
+                value
+                    .trim()
+                    .parse::<bool>()
+                    .with_context(|| format!("invalid bool: {}", entry))?;
+                (key, IntentExtraKind::Bool)
+            }
+            Some((key, "int")) => {
+                value
+                    .trim()
+                    .parse::<i32>()
+                    .with_context(|| format!("invalid int: {}", entry))?;
+                (key, IntentExtraKind::Int)
+            }
+            Some((_, kind)) => anyhow::bail!("unknown extra type: {}", kind),
+            None => (key.trim(), IntentExtraKind::String),
+        };
+        if key.is_empty() {
+            anyhow::bail!("extra key is empty: {}", entry);
+        }
+        extras.push(IntentExtra {
+            kind,
+            key: key.into(),
+            value: value.trim().into(),
+        });
+    }
+    Ok(extras)
+}
+
+/// moves the intent to the front of the history.
+pub fn push_intent_history(history: &mut Vec<IntentSpec>, spec: IntentSpec) {
+    history.retain(|data| data != &spec);
+    history.insert(0, spec);
+    history.truncate(IntentSpec::HISTORY_CAPACITY);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn intent_spec_args() {
+        let spec = IntentSpec::new(
+            "android.intent.action.VIEW",
+            "myapp://video/1",
+            "",
+            "title=a b; count:int=3; debug:bool=true",
+        )
+        .unwrap();
+        assert_eq!(
+            vec![
+                "-a",
+                "android.intent.action.VIEW",
+                "-d",
+                "myapp://video/1",
+                "--es",
+                "title",
+                "a b",
+                "--ei",
+                "count",
+                "3",
+                "--ez",
+                "debug",
+                "true",
+            ],
+            spec.args()
+        );
+        assert_eq!(
+            "title=a b; count:int=3; debug:bool=true",
+            spec.extras_text()
+        );
+        assert_eq!(spec.extras, parse_extras(&spec.extras_text()).unwrap());
+    }
+
+    #[test]
+    fn intent_spec_invalid() {
+        assert!(IntentSpec::new("", "", "", "").is_err());
+        assert!(parse_extras("title").is_err());
+        assert!(parse_extras("count:int=three").is_err());
+        assert!(parse_extras("count:float=1.0").is_err());
+        assert!(parse_extras("=value").is_err());
+    }
+
+    #[test]
+    fn intent_history() {
+        let mut history = vec![];
+        for num in 0..IntentSpec::HISTORY_CAPACITY + 1 {
+            let spec = IntentSpec::new("", &format!("myapp://{}", num), "", "").unwrap();
+            push_intent_history(&mut history, spec);
+        }
+        let first = IntentSpec::new("", "myapp://1", "", "").unwrap();
+        push_intent_history(&mut history, first.clone());
+
+        assert_eq!(IntentSpec::HISTORY_CAPACITY, history.len());
+        assert_eq!(first, history[0]);
+        assert_eq!(1, history.iter().filter(|data| *data == &first).count());
+    }
+}
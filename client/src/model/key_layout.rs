@@ -0,0 +1,210 @@
+/*
+ * Copyright 2022 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::model::keycode::is_valid_keycode;
+use crate::model::send_event_key::SendEventKey;
+use crate::model::KeyMap;
+
+/// the directory of the key layout files (`.kl`) that map the scan codes of the remotes.
+pub const KEY_LAYOUT_DIR: &str = "/system/usr/keylayout";
+
+/// a `key` line of the key layout file.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct KeyLayoutEntry {
+    pub scan_code: u32,
+    /// the name with the `KEYCODE_` prefix.
+    pub keycode: String,
+}
+
+/// the keycode of the key layout that the key of [KeyMap] is proposed to send.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct KeyMapProposal {
+    pub key: SendEventKey,
+    pub keycode: String,
+    pub scan_code: u32,
+}
+
+/// reads the `key <scan code> <keycode> [flags]` lines, and skips the others like `axis` and
+/// `key usage`.
+pub fn parse_key_layout(text: &str) -> Vec<KeyLayoutEntry> {
+    text.lines()
+        .filter_map(|line| {
+            let line = line.split('#').next().unwrap_or_default();
+            let mut segments = line.split_whitespace();
+            if segments.next() != Some("key") {
+                return None;
+            }
+
+            let scan_code = segments.next()?;
+            let scan_code = match scan_code.strip_prefix("0x") {
+                Some(data) => u32::from_str_radix(data, 16).ok()?,
+                None => scan_code.parse().ok()?,
+            };
+            let keycode = format!("KEYCODE_{}", segments.next()?);
+            if !is_valid_keycode(&keycode) {
+                return None;
+            }
+            Some(KeyLayoutEntry { scan_code, keycode })
+        })
+        .collect()
+}
+
+/// proposes the first keycode of the candidates that the key layout has for each key of
+/// [SendEventKey::MAPPED], so that the buttons send what the remote of the device sends.
+pub fn propose_key_map(entries: &[KeyLayoutEntry]) -> Vec<KeyMapProposal> {
+    SendEventKey::MAPPED
+        .iter()
+        .filter_map(|key| {
+            candidate_keycodes(key).iter().find_map(|keycode| {
+                entries
+                    .iter()
+                    .find(|data| data.keycode == *keycode)
+                    .map(|data| KeyMapProposal {
+                        key: key.clone(),
+                        keycode: data.keycode.clone(),
+                        scan_code: data.scan_code,
+                    })
+            })
+        })
+        .collect()
+}
+
+/// assigns the proposals and keeps the keys that the key layout doesn't have.
+pub fn apply_key_map_proposals(key_map: &mut KeyMap, proposals: &[KeyMapProposal]) {
+    for proposal in proposals {
+        if let Some(data) = key_map.keycode_mut(&proposal.key) {
+            *data = proposal.keycode.clone();
+        }
+    }
+}
+
+/// the keycodes that the key sends, from the preferred one.
+fn candidate_keycodes(key: &SendEventKey) -> &'static [&'static str] {
+    match key {
+        SendEventKey::Assist => &["KEYCODE_ASSIST", "KEYCODE_VOICE_ASSIST", "KEYCODE_SEARCH"],
+        SendEventKey::Back => &["KEYCODE_BACK"],
+        SendEventKey::ChannelDown => &["KEYCODE_CHANNEL_DOWN", "KEYCODE_PAGE_DOWN"],
+        SendEventKey::ChannelUp => &["KEYCODE_CHANNEL_UP", "KEYCODE_PAGE_UP"],
+        SendEventKey::ColorRed => &["KEYCODE_PROG_RED"],
+        SendEventKey::ColorGreen => &["KEYCODE_PROG_GREEN"],
+        SendEventKey::ColorBlue => &["KEYCODE_PROG_BLUE"],
+        SendEventKey::ColorYellow => &["KEYCODE_PROG_YELLOW"],
+        SendEventKey::Custom(_) => &[],
+        SendEventKey::DpadUp => &["KEYCODE_DPAD_UP"],
+        SendEventKey::DpadDown => &["KEYCODE_DPAD_DOWN"],
+        SendEventKey::DpadLeft => &["KEYCODE_DPAD_LEFT"],
+        SendEventKey::DpadRight => &["KEYCODE_DPAD_RIGHT"],
+        SendEventKey::DpadOk => &["KEYCODE_DPAD_CENTER", "KEYCODE_ENTER"],
+        SendEventKey::Guide => &["KEYCODE_GUIDE", "KEYCODE_TV"],
+        SendEventKey::Home => &["KEYCODE_HOME"],
+        SendEventKey::Info => &["KEYCODE_INFO"],
+        SendEventKey::MediaFastForward => &["KEYCODE_MEDIA_FAST_FORWARD"],
+        SendEventKey::MediaNext => &["KEYCODE_MEDIA_NEXT"],
+        SendEventKey::MediaPlayPause => &["KEYCODE_MEDIA_PLAY_PAUSE", "KEYCODE_MEDIA_PLAY"],
+        SendEventKey::MediaPrevious => &["KEYCODE_MEDIA_PREVIOUS"],
+        SendEventKey::MediaRewind => &["KEYCODE_MEDIA_REWIND"],
+        SendEventKey::MediaStop => &["KEYCODE_MEDIA_STOP"],
+        SendEventKey::Mute => &["KEYCODE_VOLUME_MUTE", "KEYCODE_MUTE"],
+        SendEventKey::Num0 => &["KEYCODE_0"],
+        SendEventKey::Num1 => &["KEYCODE_1"],
+        SendEventKey::Num2 => &["KEYCODE_2"],
+        SendEventKey::Num3 => &["KEYCODE_3"],
+        SendEventKey::Num4 => &["KEYCODE_4"],
+        SendEventKey::Num5 => &["KEYCODE_5"],
+        SendEventKey::Num6 => &["KEYCODE_6"],
+        SendEventKey::Num7 => &["KEYCODE_7"],
+        SendEventKey::Num8 => &["KEYCODE_8"],
+        SendEventKey::Num9 => &["KEYCODE_9"],
+        SendEventKey::Power => &["KEYCODE_POWER", "KEYCODE_TV_POWER"],
+        SendEventKey::Sleep => &["KEYCODE_SLEEP"],
+        SendEventKey::TvInput => &["KEYCODE_TV_INPUT"],
+        SendEventKey::VolumeUp => &["KEYCODE_VOLUME_UP"],
+        SendEventKey::VolumeDown => &["KEYCODE_VOLUME_DOWN"],
+        SendEventKey::Wakeup => &["KEYCODE_WAKEUP"],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY_LAYOUT: &str = r#"
+# the remote of a TV.
+key 116   POWER             WAKE
+key 158   BACK
+key 172   HOME
+key 0x160 DPAD_CENTER
+key 28    ENTER
+key 217   SEARCH
+key 398   PROG_RED
+key 113   MUTE
+key 402   CHANNEL_UP        # CH+
+key 999   UNKNOWN_NAME
+key usage 0x0c0067 WINDOW
+axis 0x00 X
+led 0x00 NUM_LOCK
+"#;
+
+    #[test]
+    fn parse_key_layout_lines() {
+        let entries = parse_key_layout(KEY_LAYOUT);
+        assert_eq!(
+            vec![
+                (116, "KEYCODE_POWER"),
+                (158, "KEYCODE_BACK"),
+                (172, "KEYCODE_HOME"),
+                (0x160, "KEYCODE_DPAD_CENTER"),
+                (28, "KEYCODE_ENTER"),
+                (217, "KEYCODE_SEARCH"),
+                (398, "KEYCODE_PROG_RED"),
+                (113, "KEYCODE_MUTE"),
+                (402, "KEYCODE_CHANNEL_UP"),
+            ],
+            entries
+                .iter()
+                .map(|data| (data.scan_code, data.keycode.as_str()))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn propose_key_map_by_candidates() {
+        let proposals = propose_key_map(&parse_key_layout(KEY_LAYOUT));
+        let proposal = |key: SendEventKey| {
+            proposals
+                .iter()
+                .find(|data| data.key == key)
+                .map(|data| (data.scan_code, data.keycode.as_str()))
+        };
+
+        assert_eq!(
+            Some((217, "KEYCODE_SEARCH")),
+            proposal(SendEventKey::Assist)
+        );
+        assert_eq!(
+            Some((0x160, "KEYCODE_DPAD_CENTER")),
+            proposal(SendEventKey::DpadOk)
+        );
+        assert_eq!(Some((113, "KEYCODE_MUTE")), proposal(SendEventKey::Mute));
+        assert_eq!(None, proposal(SendEventKey::ColorBlue));
+
+        let mut key_map = KeyMap::default();
+        apply_key_map_proposals(&mut key_map, &proposals);
+        assert_eq!("KEYCODE_SEARCH", key_map.assist);
+        assert_eq!("KEYCODE_MUTE", key_map.mute);
+        assert_eq!(KeyMap::default().color_blue, key_map.color_blue);
+    }
+}
@@ -0,0 +1,186 @@
+/*
+ * Copyright 2022 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::model::keycode::Keycode;
+use crate::prelude::*;
+use std::time::{Duration, Instant};
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum MacroStep {
+    Key(Keycode),
+    Wait(Duration),
+}
+
+/// a recorded macro that is saved in the preferences.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SavedMacro {
+    pub name: String,
+    pub steps: Vec<MacroStep>,
+}
+
+/// records the sent keys and the intervals between them.
+#[derive(Debug)]
+pub struct MacroRecorder {
+    /// the interval that the playback waits after each key, which is excluded from the waits.
+    key_interval: Duration,
+    last_key_at: Option<Instant>,
+    steps: Vec<MacroStep>,
+}
+
+impl MacroRecorder {
+    pub fn new(key_interval: Duration) -> Self {
+        Self {
+            key_interval,
+            last_key_at: None,
+            steps: vec![],
+        }
+    }
+
+    pub fn record(&mut self, keycode: Keycode, now: Instant) {
+        if let Some(last_key_at) = self.last_key_at {
+            // rounds down to 10ms for readability.
+            let millis = now
+                .saturating_duration_since(last_key_at)
+                .saturating_sub(self.key_interval)
+                .as_millis() as u64
+                / 10
+                * 10;
+            if millis != 0 {
+                self.steps
+                    .push(MacroStep::Wait(Duration::from_millis(millis)));
+            }
+        }
+        self.last_key_at = Some(now);
+        self.steps.push(MacroStep::Key(keycode));
+    }
+
+    pub fn key_count(&self) -> usize {
+        self.steps
+            .iter()
+            .filter(|data| matches!(data, MacroStep::Key(_)))
+            .count()
+    }
+
+    pub fn finish(self) -> Vec<MacroStep> {
+        self.steps
+    }
+}
+
+/// formats the steps as [parse_macro] accepts.
+pub fn format_macro(steps: &[MacroStep]) -> String {
+    steps
+        .iter()
+        .map(|data| match data {
+            MacroStep::Key(keycode) => format!("{}\n", keycode),
+            MacroStep::Wait(duration) => format!("wait {}\n", duration.as_millis()),
+        })
+        .collect()
+}
+
+/// parses a newline-separated list of keycodes.
+///
+/// `wait <milliseconds>` inserts a delay, and empty lines and lines starting with `#` are
+/// ignored.
+pub fn parse_macro(source: &str) -> Fallible<Vec<MacroStep>> {
+    let mut steps = vec![];
+    for (index, line) in source.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let step = match line.split_once(char::is_whitespace) {
+            Some(("wait", millis)) => {
+                MacroStep::Wait(Duration::from_millis(millis.trim().parse().with_context(
+                    || format!("line {}: invalid wait: {}", index + 1, line),
+                )?))
+            }
+            _ => MacroStep::Key(
+                line.parse()
+                    .with_context(|| format!("line {}: invalid keycode: {}", index + 1, line))?,
+            ),
+        };
+        steps.push(step);
+    }
+
+    if steps.is_empty() {
+        anyhow::bail!("no steps");
+    }
+
+    Ok(steps)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_macro_steps() {
+        let source = r#"
+# open the menu
+KEYCODE_MENU
+wait 500
+  KEYCODE_DPAD_DOWN
+23
+"#;
+
+        assert_eq!(
+            vec![
+                MacroStep::Key("KEYCODE_MENU".parse().unwrap()),
+                MacroStep::Wait(Duration::from_millis(500)),
+                MacroStep::Key("KEYCODE_DPAD_DOWN".parse().unwrap()),
+                MacroStep::Key("23".parse().unwrap()),
+            ],
+            parse_macro(source).unwrap()
+        );
+    }
+
+    #[test]
+    fn macro_recorder_waits() {
+        let started_at = Instant::now();
+        let mut recorder = MacroRecorder::new(Duration::from_millis(200));
+        recorder.record("KEYCODE_HOME".parse().unwrap(), started_at);
+        recorder.record(
+            "KEYCODE_DPAD_DOWN".parse().unwrap(),
+            started_at + Duration::from_millis(1234),
+        );
+        recorder.record(
+            "KEYCODE_DPAD_DOWN".parse().unwrap(),
+            started_at + Duration::from_millis(1334),
+        );
+        assert_eq!(3, recorder.key_count());
+
+        let steps = recorder.finish();
+        assert_eq!(
+            "KEYCODE_HOME\nwait 1030\nKEYCODE_DPAD_DOWN\nKEYCODE_DPAD_DOWN\n",
+            format_macro(&steps)
+        );
+        assert_eq!(steps, parse_macro(&format_macro(&steps)).unwrap());
+    }
+
+    #[test]
+    fn parse_macro_invalid() {
+        assert!(parse_macro("").is_err());
+        assert!(parse_macro("# comment only").is_err());
+        assert!(parse_macro("KEYCODE_MENU\nwait soon").is_err());
+        assert_eq!(
+            "line 2: invalid keycode: open the menu",
+            parse_macro("KEYCODE_MENU\nopen the menu")
+                .unwrap_err()
+                .to_string()
+        );
+    }
+}
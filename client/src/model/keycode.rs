@@ -0,0 +1,172 @@
+/*
+ * Copyright 2022 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+include!(concat!(env!("OUT_DIR"), "/keycode_table.rs"));
+
+/// accepts a known `KEYCODE_*` name or a numeric keycode.
+pub fn is_valid_keycode(value: &str) -> bool {
+    if !value.is_empty() && value.chars().all(|data| data.is_ascii_digit()) {
+        return value.parse::<i32>().is_ok();
+    }
+
+    keycode_value(value).is_some()
+}
+
+/// returns the numeric value of the `KEYCODE_*` name.
+pub fn keycode_value(name: &str) -> Option<i32> {
+    KEYCODE_TABLE
+        .iter()
+        .find(|(data, _)| *data == name)
+        .map(|(_, value)| *value)
+}
+
+/// returns the known name closest to the typo like `KEYCODE_PROG_REDD`.
+pub fn suggest_keycode(value: &str) -> Option<&'static str> {
+    const MAX_DISTANCE: usize = 2;
+
+    let value = value.trim().to_ascii_uppercase();
+    let value = if value.starts_with("KEYCODE_") {
+        value
+    } else {
+        format!("KEYCODE_{}", value)
+    };
+
+    KEYCODE_NAMES
+        .iter()
+        .map(|name| (edit_distance(&value, name), *name))
+        .filter(|(distance, _)| *distance <= MAX_DISTANCE)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, name)| name)
+}
+
+/// the Levenshtein distance of the ASCII strings.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.as_bytes();
+    let mut prev = (0..=b.len()).collect::<Vec<_>>();
+    for (i, a_char) in a.bytes().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, b_char) in b.iter().enumerate() {
+            let cost = if a_char == *b_char { 0 } else { 1 };
+            current[j + 1] = (prev[j] + cost).min(prev[j + 1] + 1).min(current[j] + 1);
+        }
+        prev = current;
+    }
+    prev[b.len()]
+}
+
+/// returns the `KEYCODE_*` name of the numeric value.
+pub fn keycode_name(value: i32) -> Option<&'static str> {
+    KEYCODE_TABLE
+        .binary_search_by_key(&value, |(_, data)| *data)
+        .ok()
+        .map(|index| KEYCODE_TABLE[index].0)
+}
+
+/// a keycode accepted by [is_valid_keycode].
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Keycode(String);
+
+impl Keycode {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// the numeric value for `input keyevent` that doesn't accept some names on old devices.
+    pub fn value(&self) -> i32 {
+        match keycode_value(&self.0) {
+            Some(data) => data,
+            // validated by from_str.
+            None => self.0.parse().unwrap(),
+        }
+    }
+}
+
+impl FromStr for Keycode {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if !is_valid_keycode(s) {
+            anyhow::bail!("invalid keycode: {:?}", s);
+        }
+
+        Ok(Self(s.into()))
+    }
+}
+
+impl Display for Keycode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn is_valid_keycode_name() {
+        assert!(is_valid_keycode("KEYCODE_PROG_RED"));
+        assert!(is_valid_keycode("KEYCODE_DPAD_CENTER"));
+        assert!(!is_valid_keycode("KEYCODE_PROG_REDD"));
+        assert!(!is_valid_keycode("keycode_back"));
+        assert!(!is_valid_keycode(""));
+    }
+
+    #[test]
+    fn is_valid_keycode_number() {
+        assert!(is_valid_keycode("4"));
+        assert!(is_valid_keycode("288"));
+        assert!(!is_valid_keycode("-1"));
+        assert!(!is_valid_keycode("4 "));
+        assert!(!is_valid_keycode("99999999999"));
+    }
+
+    #[test]
+    fn keycode_names_order() {
+        assert_eq!("KEYCODE_UNKNOWN", KEYCODE_NAMES[0]);
+        assert_eq!("KEYCODE_BACK", KEYCODE_NAMES[4]);
+        assert_eq!("KEYCODE_DPAD_CENTER", KEYCODE_NAMES[23]);
+        assert_eq!("KEYCODE_PROG_RED", KEYCODE_NAMES[183]);
+    }
+
+    #[test]
+    fn suggest_keycode_typo() {
+        assert_eq!(
+            Some("KEYCODE_PROG_RED"),
+            suggest_keycode("KEYCODE_PROG_REDD")
+        );
+        assert_eq!(Some("KEYCODE_BACK"), suggest_keycode("keycode_back"));
+        assert_eq!(Some("KEYCODE_HOME"), suggest_keycode("HOME"));
+        assert_eq!(Some("KEYCODE_DPAD_UP"), suggest_keycode("KEYCODE_DAPD_UP"));
+        assert_eq!(None, suggest_keycode("KEYCODE_SOMETHING_ELSE"));
+    }
+
+    #[test]
+    fn convert_keycode() {
+        assert_eq!(Some(4), keycode_value("KEYCODE_BACK"));
+        assert_eq!(Some("KEYCODE_BACK"), keycode_name(4));
+        assert_eq!(None, keycode_value("KEYCODE_BACKK"));
+        assert_eq!(None, keycode_name(-1));
+        assert_eq!(
+            23,
+            "KEYCODE_DPAD_CENTER".parse::<Keycode>().unwrap().value()
+        );
+        assert_eq!(23, "23".parse::<Keycode>().unwrap().value());
+    }
+}
@@ -0,0 +1,798 @@
+/*
+ * Copyright 2022 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::model::send_event_key::SendEventKey;
+use crate::model::CustomKey;
+
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum SectionId {
+    ColorKeys,
+    Dpad,
+    Navigation,
+    Numpad,
+    Media,
+    Power,
+    Tv,
+    CustomKeys,
+}
+
+impl SectionId {
+    pub const ALL: [SectionId; 8] = [
+        SectionId::ColorKeys,
+        SectionId::Dpad,
+        SectionId::Navigation,
+        SectionId::Numpad,
+        SectionId::Media,
+        SectionId::Power,
+        SectionId::Tv,
+        SectionId::CustomKeys,
+    ];
+
+    /// keeps the D-pad and the navigation that are always needed.
+    pub fn is_collapsible(&self) -> bool {
+        matches!(
+            self,
+            SectionId::ColorKeys
+                | SectionId::Numpad
+                | SectionId::Media
+                | SectionId::Power
+                | SectionId::Tv
+                | SectionId::CustomKeys
+        )
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Section {
+    pub id: SectionId,
+    pub rows: Vec<Vec<ButtonSpec>>,
+}
+
+impl Section {
+    /// disables the buttons that the device doesn't support instead of hiding them.
+    pub fn disable_keys(&mut self, predicate: impl Fn(&SendEventKey) -> bool) {
+        disable_keys(&mut self.rows, predicate);
+    }
+
+    /// highlights the buttons whose keys are sent but not acked by the device yet.
+    pub fn mark_pending_keys(&mut self, predicate: impl Fn(&SendEventKey) -> bool) {
+        mark_pending_keys(&mut self.rows, predicate);
+    }
+}
+
+/// disables the buttons of the rows like [Section::disable_keys].
+pub fn disable_keys(rows: &mut [Vec<ButtonSpec>], predicate: impl Fn(&SendEventKey) -> bool) {
+    for spec in rows.iter_mut().flatten() {
+        if let ButtonSpec::Key { key, enabled, .. } = spec {
+            if predicate(key) {
+                *enabled = false;
+            }
+        }
+    }
+}
+
+/// marks the buttons of the rows like [Section::mark_pending_keys].
+pub fn mark_pending_keys(rows: &mut [Vec<ButtonSpec>], predicate: impl Fn(&SendEventKey) -> bool) {
+    for spec in rows.iter_mut().flatten() {
+        if let ButtonSpec::Key { key, style, .. } = spec {
+            if predicate(key) {
+                *style = ButtonStyle::Pending;
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ButtonStyle {
+    /// the key is sent but the device hasn't acked it yet.
+    Pending,
+    Secondary,
+    ColorRed,
+    ColorGreen,
+    ColorBlue,
+    ColorYellow,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ButtonSpec {
+    Key {
+        label: String,
+        key: SendEventKey,
+        style: ButtonStyle,
+        width: u16,
+        enabled: bool,
+    },
+    Space {
+        width: u16,
+    },
+}
+
+impl ButtonSpec {
+    pub const DEFAULT_WIDTH: u16 = 90;
+    pub const HEIGHT: u16 = 30;
+
+    fn key(label: &str, key: SendEventKey, width: u16) -> Self {
+        Self::Key {
+            label: label.into(),
+            key,
+            style: ButtonStyle::Secondary,
+            width,
+            enabled: true,
+        }
+    }
+
+    fn color(key: SendEventKey, style: ButtonStyle, width: u16) -> Self {
+        Self::Key {
+            label: "".into(),
+            key,
+            style,
+            width,
+            enabled: true,
+        }
+    }
+
+    fn custom(custom_key: &CustomKey, width: u16) -> Self {
+        Self::Key {
+            label: if custom_key.is_valid() {
+                custom_key.label.clone()
+            } else {
+                format!("{} (!)", custom_key.label)
+            },
+            key: SendEventKey::Custom(custom_key.keycode.clone()),
+            style: ButtonStyle::Secondary,
+            width,
+            enabled: custom_key.is_valid(),
+        }
+    }
+
+    fn padding() -> Self {
+        Self::Space { width: 4 }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum ButtonSize {
+    Small,
+    #[default]
+    Medium,
+    Large,
+}
+
+impl ButtonSize {
+    pub const ALL: [ButtonSize; 3] = [ButtonSize::Small, ButtonSize::Medium, ButtonSize::Large];
+
+    pub fn width(&self, base: u16) -> u16 {
+        match self {
+            ButtonSize::Small => base - 20,
+            ButtonSize::Medium => base,
+            ButtonSize::Large => base + 20,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SectionConfig {
+    pub id: SectionId,
+    pub visible: bool,
+    pub button_size: ButtonSize,
+}
+
+/// an app or a deeplink that is pinned to the bottom of the main view.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LaunchShortcut {
+    pub label: String,
+    /// a package name or a deeplink uri.
+    pub target: String,
+}
+
+impl LaunchShortcut {
+    pub fn is_deeplink(&self) -> bool {
+        self.target.contains("://")
+    }
+}
+
+/// a button of [LayoutProfile::grid].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GridButton {
+    pub key: SendEventKey,
+    pub size: ButtonSize,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct GridPosition {
+    pub row: usize,
+    pub column: usize,
+}
+
+/// a named arrangement of the sections that can be chosen per device.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LayoutProfile {
+    pub name: String,
+    pub sections: Vec<SectionConfig>,
+    pub shortcuts: Vec<LaunchShortcut>,
+    /// the rows of the buttons built by the user, shown instead of the sections if not empty.
+    pub grid: Vec<Vec<GridButton>>,
+}
+
+impl LayoutProfile {
+    pub const MAX_SHORTCUTS: usize = 4;
+
+    pub fn full() -> Self {
+        Self::with_visible_sections("Full", &SectionId::ALL)
+    }
+
+    pub fn dpad_only() -> Self {
+        Self::with_visible_sections("D-pad only", &[SectionId::Dpad, SectionId::Navigation])
+    }
+
+    pub fn default_profiles() -> Vec<Self> {
+        vec![Self::full(), Self::dpad_only()]
+    }
+
+    fn with_visible_sections(name: &str, visible_sections: &[SectionId]) -> Self {
+        Self {
+            name: name.into(),
+            sections: SectionId::ALL
+                .iter()
+                .map(|id| SectionConfig {
+                    id: *id,
+                    visible: visible_sections.contains(id),
+                    button_size: ButtonSize::Medium,
+                })
+                .collect(),
+            shortcuts: vec![],
+            grid: vec![],
+        }
+    }
+
+    /// returns `false` if the profile already has [LayoutProfile::MAX_SHORTCUTS].
+    pub fn pin_shortcut(&mut self, shortcut: LaunchShortcut) -> bool {
+        if Self::MAX_SHORTCUTS <= self.shortcuts.len() {
+            return false;
+        }
+        self.shortcuts.push(shortcut);
+        true
+    }
+
+    /// returns the sections in display order, appending the ones missing in the preferences file.
+    pub fn sections_with_defaults(&self) -> Vec<SectionConfig> {
+        let mut sections = self.sections.clone();
+        for id in SectionId::ALL {
+            if !sections.iter().any(|data| data.id == id) {
+                sections.push(SectionConfig {
+                    id,
+                    visible: true,
+                    button_size: ButtonSize::Medium,
+                });
+            }
+        }
+        sections
+    }
+
+    pub fn section_mut(&mut self, id: SectionId) -> &mut SectionConfig {
+        self.sections = self.sections_with_defaults();
+        self.sections
+            .iter_mut()
+            .find(|data| data.id == id)
+            .expect("all sections are filled")
+    }
+
+    /// inserts the button before the position, or appends a row if the row is out of the grid.
+    pub fn insert_grid_button(&mut self, button: GridButton, to: GridPosition) {
+        match self.grid.get_mut(to.row) {
+            Some(row) => row.insert(to.column.min(row.len()), button),
+            None => self.grid.push(vec![button]),
+        }
+    }
+
+    /// removes the row together if it becomes empty.
+    pub fn remove_grid_button(&mut self, at: GridPosition) -> Option<GridButton> {
+        let row = self.grid.get_mut(at.row)?;
+        if row.len() <= at.column {
+            return None;
+        }
+
+        let button = row.remove(at.column);
+        if row.is_empty() {
+            self.grid.remove(at.row);
+        }
+        Some(button)
+    }
+
+    /// `to` is the position before removing the button like [LayoutProfile::insert_grid_button].
+    pub fn move_grid_button(&mut self, from: GridPosition, to: GridPosition) {
+        let row_removed = match self.grid.get(from.row) {
+            Some(row) => row.len() == 1,
+            None => return,
+        };
+        if row_removed && from.row == to.row {
+            // the only button of the row stays as is.
+            return;
+        }
+
+        let button = match self.remove_grid_button(from) {
+            Some(data) => data,
+            None => return,
+        };
+
+        let mut to = to;
+        if from.row == to.row && from.column < to.column {
+            to.column -= 1;
+        }
+        if row_removed && from.row < to.row {
+            to.row -= 1;
+        }
+        self.insert_grid_button(button, to);
+    }
+
+    pub fn grid_button_mut(&mut self, at: GridPosition) -> Option<&mut GridButton> {
+        self.grid.get_mut(at.row)?.get_mut(at.column)
+    }
+
+    pub fn move_section(&mut self, id: SectionId, offset: isize) {
+        self.sections = self.sections_with_defaults();
+        let from = match self.sections.iter().position(|data| data.id == id) {
+            Some(data) => data,
+            None => return,
+        };
+        let to = (from as isize + offset).clamp(0, self.sections.len() as isize - 1) as usize;
+        let section = self.sections.remove(from);
+        self.sections.insert(to, section);
+    }
+}
+
+/// builds the visible sections of the remote from top to bottom.
+pub fn create_layout(profile: &LayoutProfile, custom_keys: &[&CustomKey]) -> Vec<Section> {
+    profile
+        .sections_with_defaults()
+        .into_iter()
+        .filter(|data| data.visible)
+        .filter(|data| data.id != SectionId::CustomKeys || !custom_keys.is_empty())
+        .map(|data| create_section(data.id, data.button_size, custom_keys))
+        .collect()
+}
+
+/// builds the rows of [LayoutProfile::grid] with the same buttons as the sections.
+pub fn create_grid(grid: &[Vec<GridButton>], custom_keys: &[&CustomKey]) -> Vec<Vec<ButtonSpec>> {
+    grid.iter()
+        .map(|row| {
+            std::iter::once(ButtonSpec::padding())
+                .chain(row.iter().map(|data| grid_button_spec(data, custom_keys)))
+                .collect()
+        })
+        .collect()
+}
+
+fn grid_button_spec(button: &GridButton, custom_keys: &[&CustomKey]) -> ButtonSpec {
+    let width = button.size.width(ButtonSpec::DEFAULT_WIDTH);
+    match &button.key {
+        SendEventKey::Custom(keycode) => {
+            match custom_keys.iter().find(|data| &data.keycode == keycode) {
+                Some(custom_key) => ButtonSpec::custom(custom_key, width),
+                // the custom key that is hidden by the package or removed.
+                None => ButtonSpec::custom(
+                    &CustomKey {
+                        label: keycode.trim_start_matches("KEYCODE_").into(),
+                        keycode: keycode.clone(),
+                        package: None,
+                        long_keycode: None,
+                    },
+                    width,
+                ),
+            }
+        }
+        SendEventKey::ColorRed => {
+            ButtonSpec::color(button.key.clone(), ButtonStyle::ColorRed, width)
+        }
+        SendEventKey::ColorGreen => {
+            ButtonSpec::color(button.key.clone(), ButtonStyle::ColorGreen, width)
+        }
+        SendEventKey::ColorBlue => {
+            ButtonSpec::color(button.key.clone(), ButtonStyle::ColorBlue, width)
+        }
+        SendEventKey::ColorYellow => {
+            ButtonSpec::color(button.key.clone(), ButtonStyle::ColorYellow, width)
+        }
+        key => ButtonSpec::key(&key_label(key), key.clone(), width),
+    }
+}
+
+/// the label of the button, which shows the shortcut of the keyboard if exists.
+pub fn key_label(key: &SendEventKey) -> String {
+    match key {
+        SendEventKey::DpadUp => "Up (k)".into(),
+        SendEventKey::DpadDown => "Down (j)".into(),
+        SendEventKey::DpadLeft => "Left (h)".into(),
+        SendEventKey::DpadRight => "Right (l)".into(),
+        SendEventKey::Assist => "Assist (/)".into(),
+        SendEventKey::MediaPlayPause => "Play (space)".into(),
+        SendEventKey::MediaRewind => "Rew (,)".into(),
+        SendEventKey::MediaFastForward => "FF (.)".into(),
+        key => key.to_string(),
+    }
+}
+
+fn create_section(id: SectionId, size: ButtonSize, custom_keys: &[&CustomKey]) -> Section {
+    use ButtonSpec as B;
+    use SendEventKey as K;
+
+    let width = size.width(ButtonSpec::DEFAULT_WIDTH);
+    let key = |key: SendEventKey| B::key(&key_label(&key), key, width);
+    // aligns the next button to the center column.
+    let center = || B::Space { width: width + 8 };
+
+    let rows = match id {
+        SectionId::ColorKeys => {
+            let width = size.width(70);
+            vec![vec![
+                B::color(K::ColorRed, ButtonStyle::ColorRed, width),
+                B::color(K::ColorGreen, ButtonStyle::ColorGreen, width),
+                B::color(K::ColorBlue, ButtonStyle::ColorBlue, width),
+                B::color(K::ColorYellow, ButtonStyle::ColorYellow, width),
+            ]]
+        }
+        SectionId::Dpad => vec![
+            vec![center(), key(K::DpadUp)],
+            vec![
+                B::padding(),
+                key(K::DpadLeft),
+                key(K::DpadOk),
+                key(K::DpadRight),
+            ],
+            vec![center(), key(K::DpadDown)],
+        ],
+        SectionId::Navigation => vec![
+            vec![B::padding(), key(K::Back), key(K::Home), key(K::Assist)],
+            vec![
+                B::padding(),
+                key(K::VolumeDown),
+                key(K::Mute),
+                key(K::VolumeUp),
+            ],
+        ],
+        SectionId::Numpad => vec![
+            vec![B::padding(), key(K::Num1), key(K::Num2), key(K::Num3)],
+            vec![B::padding(), key(K::Num4), key(K::Num5), key(K::Num6)],
+            vec![B::padding(), key(K::Num7), key(K::Num8), key(K::Num9)],
+            vec![center(), key(K::Num0)],
+        ],
+        SectionId::Media => vec![
+            vec![
+                B::padding(),
+                key(K::MediaRewind),
+                key(K::MediaPlayPause),
+                key(K::MediaFastForward),
+            ],
+            vec![
+                B::padding(),
+                key(K::MediaPrevious),
+                key(K::MediaStop),
+                key(K::MediaNext),
+            ],
+        ],
+        SectionId::Power => vec![vec![
+            B::padding(),
+            key(K::Power),
+            key(K::Sleep),
+            key(K::Wakeup),
+        ]],
+        SectionId::Tv => vec![
+            vec![B::padding(), key(K::TvInput), key(K::Guide), key(K::Info)],
+            vec![B::padding(), key(K::ChannelDown), key(K::ChannelUp)],
+        ],
+        SectionId::CustomKeys => custom_keys
+            .chunks(3)
+            .map(|chunk| {
+                std::iter::once(B::padding())
+                    .chain(chunk.iter().map(|custom_key| B::custom(custom_key, width)))
+                    .collect()
+            })
+            .collect(),
+    };
+
+    Section { id, rows }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn create_layout_without_custom_keys() {
+        let sections = create_layout(&LayoutProfile::full(), &[]);
+        assert_eq!(
+            vec![
+                SectionId::ColorKeys,
+                SectionId::Dpad,
+                SectionId::Navigation,
+                SectionId::Numpad,
+                SectionId::Media,
+                SectionId::Power,
+                SectionId::Tv,
+            ],
+            sections.iter().map(|data| data.id).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn section_disable_color_keys() {
+        let mut sections = create_layout(&LayoutProfile::full(), &[]);
+        for section in &mut sections {
+            section.disable_keys(SendEventKey::is_color_key);
+        }
+
+        for spec in sections.iter().flat_map(|data| data.rows.iter().flatten()) {
+            if let ButtonSpec::Key { key, enabled, .. } = spec {
+                assert_eq!(!key.is_color_key(), *enabled, "{:?}", key);
+            }
+        }
+    }
+
+    #[test]
+    fn section_mark_pending_keys() {
+        let mut sections = create_layout(&LayoutProfile::full(), &[]);
+        for section in &mut sections {
+            section.mark_pending_keys(|key| key == &SendEventKey::Back);
+        }
+
+        for spec in sections.iter().flat_map(|data| data.rows.iter().flatten()) {
+            if let ButtonSpec::Key { key, style, .. } = spec {
+                assert_eq!(
+                    key == &SendEventKey::Back,
+                    style == &ButtonStyle::Pending,
+                    "{:?}",
+                    key
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn create_layout_custom_keys() {
+        let custom_keys = (0..4)
+            .map(|num| CustomKey {
+                label: format!("key {}", num),
+                keycode: if num == 3 {
+                    "KEYCODE_INVALID".into()
+                } else {
+                    "KEYCODE_MENU".into()
+                },
+                package: None,
+                long_keycode: None,
+            })
+            .collect::<Vec<_>>();
+        let sections = create_layout(
+            &LayoutProfile::full(),
+            &custom_keys.iter().collect::<Vec<_>>(),
+        );
+        let custom_keys_section = sections.last().unwrap();
+
+        assert_eq!(SectionId::CustomKeys, custom_keys_section.id);
+        assert_eq!(2, custom_keys_section.rows.len());
+        assert_eq!(4, custom_keys_section.rows[0].len());
+        assert_eq!(
+            ButtonSpec::Key {
+                label: "key 3 (!)".into(),
+                key: SendEventKey::Custom("KEYCODE_INVALID".into()),
+                style: ButtonStyle::Secondary,
+                width: ButtonSpec::DEFAULT_WIDTH,
+                enabled: false,
+            },
+            custom_keys_section.rows[1][1]
+        );
+    }
+
+    fn grid_keys(profile: &LayoutProfile) -> Vec<Vec<&str>> {
+        profile
+            .grid
+            .iter()
+            .map(|row| row.iter().map(|data| data.key.id()).collect())
+            .collect()
+    }
+
+    fn grid_button(key: SendEventKey) -> GridButton {
+        GridButton {
+            key,
+            size: ButtonSize::Medium,
+        }
+    }
+
+    #[test]
+    fn layout_profile_edit_grid() {
+        let at = |row, column| GridPosition { row, column };
+        let mut profile = LayoutProfile::full();
+        profile.insert_grid_button(grid_button(SendEventKey::DpadUp), at(0, 0));
+        profile.insert_grid_button(grid_button(SendEventKey::Back), at(1, 0));
+        profile.insert_grid_button(grid_button(SendEventKey::Home), at(1, 9));
+        profile.insert_grid_button(grid_button(SendEventKey::DpadOk), at(1, 0));
+        assert_eq!(
+            vec![vec!["dpad_up"], vec!["dpad_ok", "back", "home"]],
+            grid_keys(&profile)
+        );
+
+        profile.move_grid_button(at(1, 0), at(1, 3));
+        assert_eq!(
+            vec![vec!["dpad_up"], vec!["back", "home", "dpad_ok"]],
+            grid_keys(&profile)
+        );
+
+        profile.move_grid_button(at(0, 0), at(1, 1));
+        assert_eq!(
+            vec![vec!["back", "dpad_up", "home", "dpad_ok"]],
+            grid_keys(&profile)
+        );
+
+        profile.move_grid_button(at(0, 3), at(1, 0));
+        assert_eq!(
+            vec![vec!["back", "dpad_up", "home"], vec!["dpad_ok"]],
+            grid_keys(&profile)
+        );
+
+        // the only button of the row.
+        profile.move_grid_button(at(1, 0), at(1, 1));
+        assert_eq!(
+            vec![vec!["back", "dpad_up", "home"], vec!["dpad_ok"]],
+            grid_keys(&profile)
+        );
+
+        assert_eq!(
+            Some(grid_button(SendEventKey::DpadOk)),
+            profile.remove_grid_button(at(1, 0))
+        );
+        assert_eq!(None, profile.remove_grid_button(at(0, 3)));
+        assert_eq!(vec![vec!["back", "dpad_up", "home"]], grid_keys(&profile));
+    }
+
+    #[test]
+    fn create_grid_custom_keys() {
+        let custom_key = CustomKey {
+            label: "Menu".into(),
+            keycode: "KEYCODE_MENU".into(),
+            package: None,
+            long_keycode: None,
+        };
+        let grid = vec![vec![
+            grid_button(SendEventKey::DpadLeft),
+            GridButton {
+                key: SendEventKey::Custom("KEYCODE_MENU".into()),
+                size: ButtonSize::Large,
+            },
+            grid_button(SendEventKey::Custom("KEYCODE_GUIDE".into())),
+        ]];
+
+        let rows = create_grid(&grid, &[&custom_key]);
+        assert_eq!(1, rows.len());
+        assert_eq!(
+            vec![
+                ButtonSpec::padding(),
+                ButtonSpec::key("Left (h)", SendEventKey::DpadLeft, 90),
+                ButtonSpec::custom(&custom_key, 110),
+                ButtonSpec::key("GUIDE", SendEventKey::Custom("KEYCODE_GUIDE".into()), 90),
+            ],
+            rows[0]
+        );
+    }
+
+    #[test]
+    fn section_id_is_collapsible() {
+        let collapsible = SectionId::ALL
+            .into_iter()
+            .filter(SectionId::is_collapsible)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                SectionId::ColorKeys,
+                SectionId::Numpad,
+                SectionId::Media,
+                SectionId::Power,
+                SectionId::Tv,
+                SectionId::CustomKeys
+            ],
+            collapsible
+        );
+    }
+
+    #[test]
+    fn create_layout_dpad_only() {
+        let sections = create_layout(&LayoutProfile::dpad_only(), &[]);
+        assert_eq!(
+            vec![SectionId::Dpad, SectionId::Navigation],
+            sections.iter().map(|data| data.id).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn layout_profile_missing_sections() {
+        let profile = LayoutProfile {
+            name: "test".into(),
+            sections: vec![SectionConfig {
+                id: SectionId::Numpad,
+                visible: true,
+                button_size: ButtonSize::Small,
+            }],
+            shortcuts: vec![],
+            grid: vec![],
+        };
+
+        let sections = create_layout(&profile, &[]);
+        assert_eq!(
+            vec![
+                SectionId::Numpad,
+                SectionId::ColorKeys,
+                SectionId::Dpad,
+                SectionId::Navigation,
+                SectionId::Media,
+                SectionId::Power,
+                SectionId::Tv,
+            ],
+            sections.iter().map(|data| data.id).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            ButtonSpec::Key {
+                label: "1".into(),
+                key: SendEventKey::Num1,
+                style: ButtonStyle::Secondary,
+                width: 70,
+                enabled: true,
+            },
+            sections[0].rows[0][1]
+        );
+    }
+
+    #[test]
+    fn layout_profile_pin_shortcut() {
+        let mut profile = LayoutProfile::full();
+        for num in 0..LayoutProfile::MAX_SHORTCUTS {
+            assert!(profile.pin_shortcut(LaunchShortcut {
+                label: num.to_string(),
+                target: "com.example".into(),
+            }));
+        }
+
+        assert!(!profile.pin_shortcut(LaunchShortcut {
+            label: "overflow".into(),
+            target: "https://example.com/".into(),
+        }));
+        assert_eq!(LayoutProfile::MAX_SHORTCUTS, profile.shortcuts.len());
+    }
+
+    #[test]
+    fn layout_profile_move_section() {
+        let mut profile = LayoutProfile::full();
+
+        profile.move_section(SectionId::Numpad, -1);
+        profile.move_section(SectionId::ColorKeys, -1);
+        profile.move_section(SectionId::CustomKeys, 1);
+
+        assert_eq!(
+            vec![
+                SectionId::ColorKeys,
+                SectionId::Dpad,
+                SectionId::Numpad,
+                SectionId::Navigation,
+                SectionId::Media,
+                SectionId::Power,
+                SectionId::Tv,
+                SectionId::CustomKeys,
+            ],
+            profile
+                .sections
+                .iter()
+                .map(|data| data.id)
+                .collect::<Vec<_>>()
+        );
+    }
+}
@@ -0,0 +1,82 @@
+/*
+ * Copyright 2022 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::fmt::{Display, Formatter};
+
+/// the locales for the localization test including the pseudo-locales and a RTL one.
+pub const LOCALE_PRESETS: &[&str] = &[
+    "en-US", "ja-JP", "de-DE", "fr-FR", "zh-CN", "ko-KR", "ar-EG", "en-XA", "ar-XB",
+];
+
+/// the font scale in percent to avoid comparing floats.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct FontScale(pub u16);
+
+impl FontScale {
+    pub const PRESETS: &'static [FontScale] = &[
+        FontScale(85),
+        FontScale(100),
+        FontScale(115),
+        FontScale(130),
+        FontScale(150),
+        FontScale(200),
+    ];
+
+    /// `settings get` prints `null` if the scale hasn't been changed.
+    pub fn parse(value: &str) -> Option<Self> {
+        let value = value.trim();
+        if value == "null" {
+            return Some(Self(100));
+        }
+
+        let scale = value.parse::<f32>().ok()?;
+        if !scale.is_finite() || scale <= 0.0 {
+            return None;
+        }
+        Some(Self((scale * 100.0).round() as u16))
+    }
+
+    /// the value for `settings put system font_scale`.
+    pub fn to_setting(self) -> String {
+        format!("{}", self.0 as f32 / 100.0)
+    }
+}
+
+impl Display for FontScale {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}%", self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn font_scale_parse() {
+        assert_eq!(Some(FontScale(115)), FontScale::parse("1.15\n"));
+        assert_eq!(Some(FontScale(100)), FontScale::parse("null\n"));
+        assert_eq!(None, FontScale::parse("-1"));
+        assert_eq!(None, FontScale::parse(""));
+    }
+
+    #[test]
+    fn font_scale_to_setting() {
+        assert_eq!("1.15", FontScale(115).to_setting());
+        assert_eq!("2", FontScale(200).to_setting());
+        assert_eq!("130%", FontScale(130).to_string());
+    }
+}
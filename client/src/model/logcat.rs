@@ -0,0 +1,302 @@
+/*
+ * Copyright 2022 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::model::history::HistoryEntry;
+use crate::prelude::*;
+use chrono::{Datelike, Local, NaiveDateTime};
+use regex::Regex;
+use std::fmt::{Display, Formatter};
+
+#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
+pub enum LogPriority {
+    Verbose,
+    Debug,
+    Info,
+    Warn,
+    Error,
+    Fatal,
+}
+
+impl LogPriority {
+    pub const ALL: [LogPriority; 6] = [
+        LogPriority::Verbose,
+        LogPriority::Debug,
+        LogPriority::Info,
+        LogPriority::Warn,
+        LogPriority::Error,
+        LogPriority::Fatal,
+    ];
+
+    fn from_letter(value: &str) -> Option<Self> {
+        match value {
+            "V" => Some(LogPriority::Verbose),
+            "D" => Some(LogPriority::Debug),
+            "I" => Some(LogPriority::Info),
+            "W" => Some(LogPriority::Warn),
+            "E" => Some(LogPriority::Error),
+            "F" | "A" => Some(LogPriority::Fatal),
+            _ => None,
+        }
+    }
+}
+
+impl Display for LogPriority {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LogPriority::Verbose => f.write_str("verbose"),
+            LogPriority::Debug => f.write_str("debug"),
+            LogPriority::Info => f.write_str("info"),
+            LogPriority::Warn => f.write_str("warn"),
+            LogPriority::Error => f.write_str("error"),
+            LogPriority::Fatal => f.write_str("fatal"),
+        }
+    }
+}
+
+/// a line of `logcat -v threadtime`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LogcatLine {
+    pub raw: String,
+    /// `None` for the lines such as "--------- beginning of main".
+    pub priority: Option<LogPriority>,
+    pub tag: Option<String>,
+    /// the local time of the device, which has no year.
+    pub time: Option<NaiveDateTime>,
+}
+
+impl LogcatLine {
+    pub fn parse(raw: String) -> Self {
+        Self::parse_in_year(raw, Local::now().year())
+    }
+
+    fn parse_in_year(raw: String, year: i32) -> Self {
+        let time = parse_time(&raw, year);
+        match parse_header(&raw) {
+            Some((priority, tag)) => Self {
+                priority: Some(priority),
+                tag: Some(tag),
+                time,
+                raw,
+            },
+            None => Self {
+                raw,
+                priority: None,
+                tag: None,
+                time: None,
+            },
+        }
+    }
+}
+
+/// parses `<month>-<day> <hour>:<minute>:<second>.<millis>` at the head of the line.
+fn parse_time(raw: &str, year: i32) -> Option<NaiveDateTime> {
+    let mut segments = raw.split_whitespace();
+    let date_time = format!("{} {} {}", year, segments.next()?, segments.next()?);
+    NaiveDateTime::parse_from_str(&date_time, "%Y %m-%d %H:%M:%S%.3f").ok()
+}
+
+/// a row of the timeline that correlates the log lines and the sent keys.
+#[derive(Debug, Eq, PartialEq)]
+pub enum TimelineRow<'a> {
+    Key(&'a HistoryEntry),
+    Line(&'a LogcatLine),
+}
+
+/// puts each entry above the newest line that is older than it.
+///
+/// both `lines` and `entries` are from the newest. the device clock is assumed to be in sync
+/// with this machine. the entries older than the last line are dropped since the lines around
+/// them aren't shown.
+pub fn merge_timeline<'a>(
+    lines: impl Iterator<Item = &'a LogcatLine>,
+    entries: impl Iterator<Item = &'a HistoryEntry>,
+) -> Vec<TimelineRow<'a>> {
+    let mut entries = entries.peekable();
+    let mut rows = vec![];
+    for line in lines {
+        if let Some(time) = line.time {
+            while let Some(entry) = entries.next_if(|data| time < data.sent_at.naive_local()) {
+                rows.push(TimelineRow::Key(entry));
+            }
+        }
+        rows.push(TimelineRow::Line(line));
+    }
+    rows
+}
+
+/// parses `<date> <time> <pid> <tid> <priority> <tag>: <message>`.
+///
+/// the tag is padded with spaces, so it ends at the first ':'.
+fn parse_header(raw: &str) -> Option<(LogPriority, String)> {
+    let mut rest = raw.trim_start();
+    // skips the date, the time, the pid and the tid.
+    for _ in 0..4 {
+        let (_, data) = rest.split_once(char::is_whitespace)?;
+        rest = data.trim_start();
+    }
+    let (priority, rest) = rest.split_once(' ')?;
+    let priority = LogPriority::from_letter(priority)?;
+    let (tag, _) = rest.split_once(':')?;
+    Some((priority, tag.trim().into()))
+}
+
+/// the lines without the priority, e.g. the buffer dividers, are shown only without the filters.
+#[derive(Clone, Debug)]
+pub struct LogcatFilter {
+    tag: String,
+    min_priority: LogPriority,
+    pattern: Option<Regex>,
+}
+
+impl LogcatFilter {
+    /// an empty `tag` or `pattern` matches everything.
+    pub fn new(tag: &str, min_priority: LogPriority, pattern: &str) -> Fallible<Self> {
+        Ok(Self {
+            tag: tag.trim().into(),
+            min_priority,
+            pattern: match pattern {
+                "" => None,
+                data => Some(Regex::new(data).with_context(|| format!("invalid regex: {}", data))?),
+            },
+        })
+    }
+
+    pub fn matches(&self, line: &LogcatLine) -> bool {
+        let filtered = !self.tag.is_empty() || self.min_priority != LogPriority::Verbose;
+        if filtered {
+            match (&line.priority, &line.tag) {
+                (Some(priority), Some(tag)) => {
+                    if *priority < self.min_priority {
+                        return false;
+                    }
+                    if !self.tag.is_empty() && !tag.contains(&self.tag) {
+                        return false;
+                    }
+                }
+                _ => return false,
+            }
+        }
+
+        match &self.pattern {
+            Some(pattern) => pattern.is_match(&line.raw),
+            None => true,
+        }
+    }
+}
+
+impl Default for LogcatFilter {
+    fn default() -> Self {
+        Self {
+            tag: "".into(),
+            min_priority: LogPriority::Verbose,
+            pattern: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LINE: &str =
+        "11-20 12:34:56.789  1234  1250 W ActivityManager: Slow operation: 63ms so far";
+
+    #[test]
+    fn parse_line() {
+        let line = LogcatLine::parse(LINE.into());
+        assert_eq!(Some(LogPriority::Warn), line.priority);
+        assert_eq!(Some("ActivityManager".into()), line.tag);
+        assert_eq!(
+            NaiveDateTime::parse_from_str("2022-11-20 12:34:56.789", "%Y-%m-%d %H:%M:%S%.3f").ok(),
+            LogcatLine::parse_in_year(LINE.into(), 2022).time
+        );
+
+        let line = LogcatLine::parse("11-20 12:34:56.789  1234  1250 I chatty  : uid=1000".into());
+        assert_eq!(Some(LogPriority::Info), line.priority);
+        assert_eq!(Some("chatty".into()), line.tag);
+
+        let line = LogcatLine::parse("--------- beginning of main".into());
+        assert_eq!(None, line.priority);
+        assert_eq!(None, line.tag);
+        assert_eq!(None, line.time);
+    }
+
+    #[test]
+    fn merge_timeline_markers() {
+        use crate::model::history::HistoryEntryKind;
+        use chrono::TimeZone;
+
+        let line =
+            |time: &str| LogcatLine::parse_in_year(format!("11-20 {}  1 2 I Tag: m", time), 2022);
+        let entry = |time: &str| HistoryEntry {
+            kind: HistoryEntryKind::Marker(time.into()),
+            sent_at: Local
+                .datetime_from_str(&format!("2022-11-20 {}", time), "%Y-%m-%d %H:%M:%S%.3f")
+                .unwrap(),
+            serial: "emulator-5554".into(),
+            device_received_at: None,
+        };
+
+        let lines = [
+            line("12:00:03.000"),
+            line("12:00:02.000"),
+            LogcatLine::parse("--------- beginning of main".into()),
+            line("12:00:01.000"),
+        ];
+        let entries = [
+            entry("12:00:04.000"),
+            entry("12:00:02.500"),
+            entry("12:00:01.500"),
+            entry("12:00:00.500"),
+        ];
+
+        assert_eq!(
+            vec![
+                TimelineRow::Key(&entries[0]),
+                TimelineRow::Line(&lines[0]),
+                TimelineRow::Key(&entries[1]),
+                TimelineRow::Line(&lines[1]),
+                TimelineRow::Line(&lines[2]),
+                TimelineRow::Key(&entries[2]),
+                TimelineRow::Line(&lines[3]),
+            ],
+            merge_timeline(lines.iter(), entries.iter())
+        );
+    }
+
+    #[test]
+    fn filter_matches() {
+        let line = LogcatLine::parse(LINE.into());
+        let divider = LogcatLine::parse("--------- beginning of main".into());
+
+        let filter = LogcatFilter::default();
+        assert!(filter.matches(&line));
+        assert!(filter.matches(&divider));
+
+        let filter = LogcatFilter::new("Activity", LogPriority::Warn, "").unwrap();
+        assert!(filter.matches(&line));
+        assert!(!filter.matches(&divider));
+
+        let filter = LogcatFilter::new("", LogPriority::Error, "").unwrap();
+        assert!(!filter.matches(&line));
+
+        let filter = LogcatFilter::new("", LogPriority::Verbose, r"\d+ms").unwrap();
+        assert!(filter.matches(&line));
+        assert!(!filter.matches(&divider));
+
+        assert!(LogcatFilter::new("", LogPriority::Verbose, "(").is_err());
+    }
+}
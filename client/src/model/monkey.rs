@@ -0,0 +1,126 @@
+/*
+ * Copyright 2022 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::prelude::*;
+
+/// the arguments of the platform `monkey` tool.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MonkeyConfig {
+    pub package: String,
+    pub event_count: u32,
+    pub throttle_millis: u32,
+    pub seed: Option<u64>,
+}
+
+impl MonkeyConfig {
+    /// creates the config from the text fields. an empty seed means a random seed.
+    pub fn parse(
+        package: &str,
+        event_count: &str,
+        throttle_millis: &str,
+        seed: &str,
+    ) -> Fallible<Self> {
+        let package = package.trim();
+        if package.is_empty() {
+            anyhow::bail!("package is empty");
+        }
+
+        Ok(Self {
+            package: package.into(),
+            event_count: event_count
+                .trim()
+                .parse()
+                .with_context(|| format!("invalid event count: {}", event_count))?,
+            throttle_millis: throttle_millis
+                .trim()
+                .parse()
+                .with_context(|| format!("invalid throttle: {}", throttle_millis))?,
+            seed: match seed.trim() {
+                "" => None,
+                data => Some(
+                    data.parse()
+                        .with_context(|| format!("invalid seed: {}", seed))?,
+                ),
+            },
+        })
+    }
+
+    pub fn args(&self) -> Vec<String> {
+        let mut args = vec!["monkey".into(), "-p".into(), self.package.clone()];
+        if let Some(data) = self.seed {
+            args.push("-s".into());
+            args.push(data.to_string());
+        }
+        args.push("--throttle".into());
+        args.push(self.throttle_millis.to_string());
+        args.push("-v".into());
+        args.push(self.event_count.to_string());
+        args
+    }
+}
+
+/// `monkey` prints it only if all events are injected.
+pub fn is_monkey_finished(output: &[String]) -> bool {
+    output
+        .iter()
+        .any(|data| data.starts_with("// Monkey finished"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_config() {
+        let config = MonkeyConfig::parse(" com.example ", "500", "100", "42").unwrap();
+        assert_eq!(
+            vec![
+                "monkey",
+                "-p",
+                "com.example",
+                "-s",
+                "42",
+                "--throttle",
+                "100",
+                "-v",
+                "500"
+            ],
+            config.args()
+        );
+
+        let config = MonkeyConfig::parse("com.example", "500", "0", "").unwrap();
+        assert_eq!(None, config.seed);
+    }
+
+    #[test]
+    fn parse_config_invalid() {
+        assert!(MonkeyConfig::parse("", "500", "100", "").is_err());
+        assert!(MonkeyConfig::parse("com.example", "-1", "100", "").is_err());
+        assert!(MonkeyConfig::parse("com.example", "500", "fast", "").is_err());
+        assert!(MonkeyConfig::parse("com.example", "500", "100", "seed").is_err());
+    }
+
+    #[test]
+    fn monkey_finished() {
+        assert!(is_monkey_finished(&[
+            "Events injected: 500".into(),
+            "// Monkey finished".into()
+        ]));
+        assert!(!is_monkey_finished(&[
+            "** Monkey aborted due to error.".into()
+        ]));
+    }
+}
@@ -0,0 +1,61 @@
+/*
+ * Copyright 2022 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/// the MQTT broker that sends the keys to the device, e.g. from Home Assistant.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct MqttConfig {
+    pub host: String,
+    pub port: u16,
+    /// subscribes `{topic}/key` and publishes `{topic}/state`.
+    pub topic: String,
+    /// the device that receives the keys.
+    pub serial: String,
+    pub username: Option<String>,
+    pub password: Option<String>,
+}
+
+impl MqttConfig {
+    pub const DEFAULT_PORT: u16 = 1883;
+
+    /// receives the keycode like `KEYCODE_HOME` as the payload.
+    pub fn key_topic(&self) -> String {
+        format!("{}/key", self.topic.trim_end_matches('/'))
+    }
+
+    /// the retained connectivity of the device, or `offline` if the client is gone.
+    pub fn state_topic(&self) -> String {
+        format!("{}/state", self.topic.trim_end_matches('/'))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mqtt_config_topics() {
+        let config = MqttConfig {
+            host: "localhost".into(),
+            port: MqttConfig::DEFAULT_PORT,
+            topic: "home/living-tv/".into(),
+            serial: "emulator-5554".into(),
+            username: None,
+            password: None,
+        };
+        assert_eq!("home/living-tv/key", config.key_topic());
+        assert_eq!("home/living-tv/state", config.state_topic());
+    }
+}
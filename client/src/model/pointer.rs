@@ -0,0 +1,85 @@
+/*
+ * Copyright 2022 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::time::Duration;
+
+/// a drag on the pointer pad in the coordinates normalized to `[0.0, 1.0]`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PointerGesture {
+    pub from: (f32, f32),
+    pub to: (f32, f32),
+    pub duration: Duration,
+}
+
+impl PointerGesture {
+    /// moves shorter than this ratio of the screen are a tap.
+    const TAP_SLOP: f32 = 0.02;
+
+    /// the arguments of `input` for the screen of `width` x `height` pixels.
+    pub fn input_args(&self, width: u32, height: u32) -> Vec<String> {
+        let to_pixels = |(x, y): (f32, f32)| {
+            (
+                (x.clamp(0.0, 1.0) * width.saturating_sub(1) as f32).round() as u32,
+                (y.clamp(0.0, 1.0) * height.saturating_sub(1) as f32).round() as u32,
+            )
+        };
+        let (from_x, from_y) = to_pixels(self.from);
+        let (to_x, to_y) = to_pixels(self.to);
+
+        let distance =
+            ((self.to.0 - self.from.0).powi(2) + (self.to.1 - self.from.1).powi(2)).sqrt();
+        if distance < Self::TAP_SLOP {
+            return vec!["tap".into(), from_x.to_string(), from_y.to_string()];
+        }
+
+        vec![
+            "swipe".into(),
+            from_x.to_string(),
+            from_y.to_string(),
+            to_x.to_string(),
+            to_y.to_string(),
+            self.duration.as_millis().to_string(),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pointer_gesture_tap() {
+        let gesture = PointerGesture {
+            from: (0.5, 0.5),
+            to: (0.505, 0.5),
+            duration: Duration::from_millis(80),
+        };
+        assert_eq!(vec!["tap", "540", "960"], gesture.input_args(1081, 1921));
+    }
+
+    #[test]
+    fn pointer_gesture_swipe() {
+        let gesture = PointerGesture {
+            from: (0.5, 0.8),
+            to: (0.5, 1.2),
+            duration: Duration::from_millis(300),
+        };
+        assert_eq!(
+            vec!["swipe", "540", "1536", "540", "1920", "300"],
+            gesture.input_args(1081, 1921)
+        );
+    }
+}
@@ -14,12 +14,291 @@
  * limitations under the License.
  */
 
-use crate::model::AppTheme;
+use crate::model::device_profile::DeviceProfile;
+use crate::model::gesture::ButtonGesture;
+use crate::model::intent::IntentSpec;
+use crate::model::key_macro::SavedMacro;
+use crate::model::keycode::{is_valid_keycode, suggest_keycode};
+use crate::model::language::Language;
+use crate::model::layout::{LayoutProfile, SectionId};
+use crate::model::mqtt::MqttConfig;
+use crate::model::schedule::ScheduledMacro;
+use crate::model::screen_record::ScreenRecordConfig;
+use crate::model::send_event_key::SendEventKey;
+use crate::model::shortcut::KeyShortcut;
+use crate::model::target::{CecTarget, NetworkTarget};
+use crate::model::webhook::Webhook;
+use crate::model::{AppTheme, CustomKey};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
+use std::time::Duration;
 
-#[derive(Debug, Default, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Preferences {
+    /// the accessibility services to toggle in addition to TalkBack.
+    pub accessibility_services: Vec<String>,
+    /// the adb binary to use instead of the one found by [find_adb_path].
+    ///
+    /// [find_adb_path]: crate::function::command_functions::find_adb_path
+    pub adb_path: Option<PathBuf>,
+    /// serves the local REST API on 127.0.0.1 if specified.
+    pub api_port: Option<u16>,
+    /// the device serials that were approved to connect.
+    pub approved_serials: BTreeSet<String>,
+    /// the device serials that are never connected.
+    pub blocked_serials: BTreeSet<String>,
+    /// listed only if built with the `cec` feature.
+    pub cec_targets: Vec<CecTarget>,
+    /// checks the latest release on GitHub on startup.
+    pub check_updates: bool,
+    /// the sections of the main view that show only the header.
+    pub collapsed_sections: BTreeSet<SectionId>,
+    /// asks before sending [SendEventKey::Power] by the button.
+    pub confirm_power_key: bool,
+    /// asks before connecting to the adb device that isn't approved.
+    pub confirm_unknown_devices: bool,
+    pub custom_keys: Vec<CustomKey>,
+    /// the display names of the device serials.
+    pub device_labels: BTreeMap<String, String>,
+    /// a name of [LayoutProfile] for each device serial.
+    pub device_layouts: BTreeMap<String, String>,
+    pub gestures: Vec<ButtonGesture>,
+    /// the device serials that vibrate on each sent click.
+    pub haptic_devices: BTreeSet<String>,
+    /// sends the down on press and the up on release instead of the click gestures.
+    pub hold_buttons: bool,
+    /// the intents sent from the intent view from the newest.
+    pub intent_history: Vec<IntentSpec>,
+    /// the key map in use, which is a copy of the selected [KeyMapProfile] if any.
     pub key_map: KeyMap,
+    /// a name of the selected [KeyMapProfile].
+    pub key_map_profile: Option<String>,
+    pub key_map_profiles: Vec<KeyMapProfile>,
+    /// repeats the down while holding the button if specified.
+    pub key_repeat_interval: Option<Duration>,
+    /// the keyboard shortcuts preferred to the built-in ones.
+    pub key_shortcuts: Vec<KeyShortcut>,
+    /// the delay until the held key of the keyboard starts repeating the down.
+    pub keyboard_repeat_delay: Duration,
+    /// repeats the down while holding the key of the keyboard if specified.
+    pub keyboard_repeat_interval: Option<Duration>,
+    pub language: Language,
+    pub layout_profiles: Vec<LayoutProfile>,
+    /// connects to the MQTT broker if specified.
+    pub mqtt: Option<MqttConfig>,
+    /// the devices that are added to the device list without adb.
+    pub network_targets: Vec<NetworkTarget>,
+    pub notification: bool,
+    /// the bit rate of the screen recording, or the device default.
+    pub recording_bit_rate_mbps: Option<u32>,
+    /// the directory that the screen recordings are pulled into, or the temporary directory.
+    pub recording_dir: Option<PathBuf>,
+    pub recording_time_limit: Duration,
+    /// the macros recorded on the main view.
+    pub saved_macros: Vec<SavedMacro>,
+    pub scheduled_macros: Vec<ScheduledMacro>,
+    pub server_auto_build: bool,
+    pub server_source_dir: Option<PathBuf>,
     pub theme: AppTheme,
+    pub webhooks: Vec<Webhook>,
+}
+
+impl Default for Preferences {
+    fn default() -> Self {
+        Self {
+            accessibility_services: vec![],
+            adb_path: None,
+            api_port: None,
+            approved_serials: Default::default(),
+            blocked_serials: Default::default(),
+            cec_targets: vec![],
+            check_updates: true,
+            collapsed_sections: Default::default(),
+            confirm_power_key: true,
+            confirm_unknown_devices: true,
+            custom_keys: vec![],
+            device_labels: Default::default(),
+            device_layouts: Default::default(),
+            gestures: vec![],
+            haptic_devices: Default::default(),
+            hold_buttons: false,
+            intent_history: vec![],
+            key_map: Default::default(),
+            key_map_profile: None,
+            key_map_profiles: vec![],
+            key_repeat_interval: None,
+            key_shortcuts: vec![],
+            keyboard_repeat_delay: Duration::from_millis(400),
+            keyboard_repeat_interval: Some(Duration::from_millis(50)),
+            language: Default::default(),
+            layout_profiles: LayoutProfile::default_profiles(),
+            mqtt: None,
+            network_targets: vec![],
+            notification: true,
+            recording_bit_rate_mbps: None,
+            recording_dir: None,
+            recording_time_limit: ScreenRecordConfig::MAX_TIME_LIMIT,
+            saved_macros: vec![],
+            scheduled_macros: vec![],
+            server_auto_build: false,
+            server_source_dir: None,
+            theme: Default::default(),
+            webhooks: vec![],
+        }
+    }
+}
+
+impl Preferences {
+    /// returns the profile assigned to the device, or the first profile.
+    pub fn layout_profile(&self, serial: Option<&str>) -> LayoutProfile {
+        serial
+            .and_then(|serial| self.device_layouts.get(serial))
+            .and_then(|name| self.layout_profiles.iter().find(|data| &data.name == name))
+            .or_else(|| self.layout_profiles.first())
+            .cloned()
+            .unwrap_or_else(LayoutProfile::full)
+    }
+
+    /// lists the keycodes of the key maps and the custom keys that are sent to nowhere.
+    pub fn invalid_keycodes(&self) -> Vec<InvalidKeycode> {
+        let key_maps = std::iter::once((None, &self.key_map)).chain(
+            self.key_map_profiles
+                .iter()
+                // the selected profile is the same as the key map.
+                .filter(|data| self.key_map_profile.as_ref() != Some(&data.name))
+                .map(|data| (Some(&data.name), &data.key_map)),
+        );
+
+        let mut ret = vec![];
+        for (profile, key_map) in key_maps {
+            let location = |key: &dyn std::fmt::Display| match profile {
+                Some(name) => format!("{} / {}", name, key),
+                None => key.to_string(),
+            };
+            for key in &SendEventKey::MAPPED {
+                let keycode = key_map.keycode(key);
+                if !is_valid_keycode(keycode) {
+                    ret.push(InvalidKeycode {
+                        location: location(key),
+                        keycode: keycode.into(),
+                    });
+                }
+            }
+            for (id, keycode) in &key_map.long_keycodes {
+                if !is_valid_keycode(keycode) {
+                    ret.push(InvalidKeycode {
+                        location: location(&format!("{} (long)", SendEventKey::from_id(id))),
+                        keycode: keycode.clone(),
+                    });
+                }
+            }
+        }
+
+        for custom_key in &self.custom_keys {
+            if !custom_key.is_valid() {
+                ret.push(InvalidKeycode {
+                    location: custom_key.label.clone(),
+                    keycode: custom_key.keycode.clone(),
+                });
+            }
+            if let Some(keycode) = &custom_key.long_keycode {
+                if !is_valid_keycode(keycode) {
+                    ret.push(InvalidKeycode {
+                        location: format!("{} (long)", custom_key.label),
+                        keycode: keycode.clone(),
+                    });
+                }
+            }
+        }
+        ret
+    }
+
+    /// the keycode sent instead of the key held longer than the long-press timeout.
+    pub fn long_keycode(&self, key: &SendEventKey) -> Option<&str> {
+        let keycode = match key {
+            SendEventKey::Custom(keycode) => self
+                .custom_keys
+                .iter()
+                .find(|data| &data.keycode == keycode)?
+                .long_keycode
+                .as_ref(),
+            key => self.key_map.long_keycodes.get(key.id()),
+        };
+        keycode.map(String::as_str)
+    }
+
+    pub fn move_custom_key(&mut self, index: usize, offset: isize) {
+        if index >= self.custom_keys.len() {
+            return;
+        }
+        let to = (index as isize + offset).clamp(0, self.custom_keys.len() as isize - 1) as usize;
+        let custom_key = self.custom_keys.remove(index);
+        self.custom_keys.insert(to, custom_key);
+    }
+
+    /// switches [Preferences::key_map] to the profile, or does nothing if not found.
+    pub fn select_key_map_profile(&mut self, name: &str) {
+        if let Some(data) = self.key_map_profiles.iter().find(|data| data.name == name) {
+            self.key_map = data.key_map.clone();
+            self.key_map_profile = Some(data.name.clone());
+        }
+    }
+
+    /// saves [Preferences::key_map] as the profile of the name and selects it.
+    pub fn save_key_map_profile(&mut self, name: String) {
+        let key_map = self.key_map.clone();
+        match self
+            .key_map_profiles
+            .iter_mut()
+            .find(|data| data.name == name)
+        {
+            Some(data) => data.key_map = key_map,
+            None => self.key_map_profiles.push(KeyMapProfile {
+                name: name.clone(),
+                key_map,
+            }),
+        }
+        self.key_map_profile = Some(name);
+    }
+
+    /// removes the profile and keeps [Preferences::key_map] as is.
+    pub fn remove_key_map_profile(&mut self, name: &str) {
+        self.key_map_profiles.retain(|data| data.name != name);
+        if self.key_map_profile.as_deref() == Some(name) {
+            self.key_map_profile = None;
+        }
+    }
+
+    pub fn screen_record_config(&self) -> ScreenRecordConfig {
+        ScreenRecordConfig {
+            time_limit: self.recording_time_limit,
+            bit_rate_mbps: self.recording_bit_rate_mbps,
+        }
+    }
+
+    /// replaces the layout profile of the same name and assigns it to the device.
+    pub fn import_device_profile(&mut self, profile: DeviceProfile) {
+        let name = profile.layout.name.clone();
+        match self
+            .layout_profiles
+            .iter_mut()
+            .find(|data| data.name == name)
+        {
+            Some(data) => *data = profile.layout,
+            None => self.layout_profiles.push(profile.layout),
+        }
+        self.device_layouts.insert(profile.serial, name);
+
+        if let Some(target) = profile.network_target {
+            if !self
+                .network_targets
+                .iter()
+                .any(|data| data.name == target.name)
+            {
+                self.network_targets.push(target);
+            }
+        }
+    }
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -45,6 +324,142 @@ pub struct KeyMap {
     pub num_8: String,
     pub num_9: String,
     pub home: String,
+    pub volume_up: String,
+    pub volume_down: String,
+    pub mute: String,
+    pub media_play_pause: String,
+    pub media_stop: String,
+    pub media_rewind: String,
+    pub media_fast_forward: String,
+    pub media_next: String,
+    pub media_previous: String,
+    pub power: String,
+    pub sleep: String,
+    pub wakeup: String,
+    pub tv_input: String,
+    pub guide: String,
+    pub info: String,
+    pub channel_up: String,
+    pub channel_down: String,
+    pub assist: String,
+    /// the keycodes sent instead of the keys held longer than the long-press timeout, by the id
+    /// of [SendEventKey].
+    pub long_keycodes: BTreeMap<String, String>,
+}
+
+impl KeyMap {
+    /// returns the keycode assigned to the key, or the keycode of [SendEventKey::Custom].
+    pub fn keycode<'a>(&'a self, key: &'a SendEventKey) -> &'a str {
+        match key {
+            SendEventKey::Back => &self.back,
+            SendEventKey::ColorRed => &self.color_red,
+            SendEventKey::ColorGreen => &self.color_green,
+            SendEventKey::ColorBlue => &self.color_blue,
+            SendEventKey::ColorYellow => &self.color_yellow,
+            SendEventKey::Custom(keycode) => keycode,
+            SendEventKey::DpadUp => &self.dpad_up,
+            SendEventKey::DpadDown => &self.dpad_down,
+            SendEventKey::DpadLeft => &self.dpad_left,
+            SendEventKey::DpadRight => &self.dpad_right,
+            SendEventKey::DpadOk => &self.dpad_ok,
+            SendEventKey::Num0 => &self.num_0,
+            SendEventKey::Num1 => &self.num_1,
+            SendEventKey::Num2 => &self.num_2,
+            SendEventKey::Num3 => &self.num_3,
+            SendEventKey::Num4 => &self.num_4,
+            SendEventKey::Num5 => &self.num_5,
+            SendEventKey::Num6 => &self.num_6,
+            SendEventKey::Num7 => &self.num_7,
+            SendEventKey::Num8 => &self.num_8,
+            SendEventKey::Num9 => &self.num_9,
+            SendEventKey::Home => &self.home,
+            SendEventKey::VolumeUp => &self.volume_up,
+            SendEventKey::VolumeDown => &self.volume_down,
+            SendEventKey::Mute => &self.mute,
+            SendEventKey::MediaPlayPause => &self.media_play_pause,
+            SendEventKey::MediaStop => &self.media_stop,
+            SendEventKey::MediaRewind => &self.media_rewind,
+            SendEventKey::MediaFastForward => &self.media_fast_forward,
+            SendEventKey::MediaNext => &self.media_next,
+            SendEventKey::MediaPrevious => &self.media_previous,
+            SendEventKey::Power => &self.power,
+            SendEventKey::Sleep => &self.sleep,
+            SendEventKey::Wakeup => &self.wakeup,
+            SendEventKey::TvInput => &self.tv_input,
+            SendEventKey::Guide => &self.guide,
+            SendEventKey::Info => &self.info,
+            SendEventKey::ChannelUp => &self.channel_up,
+            SendEventKey::ChannelDown => &self.channel_down,
+            SendEventKey::Assist => &self.assist,
+        }
+    }
+
+    /// returns `None` for [SendEventKey::Custom] that isn't in the key map.
+    pub fn keycode_mut(&mut self, key: &SendEventKey) -> Option<&mut String> {
+        let keycode = match key {
+            SendEventKey::Back => &mut self.back,
+            SendEventKey::ColorRed => &mut self.color_red,
+            SendEventKey::ColorGreen => &mut self.color_green,
+            SendEventKey::ColorBlue => &mut self.color_blue,
+            SendEventKey::ColorYellow => &mut self.color_yellow,
+            SendEventKey::Custom(_) => return None,
+            SendEventKey::DpadUp => &mut self.dpad_up,
+            SendEventKey::DpadDown => &mut self.dpad_down,
+            SendEventKey::DpadLeft => &mut self.dpad_left,
+            SendEventKey::DpadRight => &mut self.dpad_right,
+            SendEventKey::DpadOk => &mut self.dpad_ok,
+            SendEventKey::Num0 => &mut self.num_0,
+            SendEventKey::Num1 => &mut self.num_1,
+            SendEventKey::Num2 => &mut self.num_2,
+            SendEventKey::Num3 => &mut self.num_3,
+            SendEventKey::Num4 => &mut self.num_4,
+            SendEventKey::Num5 => &mut self.num_5,
+            SendEventKey::Num6 => &mut self.num_6,
+            SendEventKey::Num7 => &mut self.num_7,
+            SendEventKey::Num8 => &mut self.num_8,
+            SendEventKey::Num9 => &mut self.num_9,
+            SendEventKey::Home => &mut self.home,
+            SendEventKey::VolumeUp => &mut self.volume_up,
+            SendEventKey::VolumeDown => &mut self.volume_down,
+            SendEventKey::Mute => &mut self.mute,
+            SendEventKey::MediaPlayPause => &mut self.media_play_pause,
+            SendEventKey::MediaStop => &mut self.media_stop,
+            SendEventKey::MediaRewind => &mut self.media_rewind,
+            SendEventKey::MediaFastForward => &mut self.media_fast_forward,
+            SendEventKey::MediaNext => &mut self.media_next,
+            SendEventKey::MediaPrevious => &mut self.media_previous,
+            SendEventKey::Power => &mut self.power,
+            SendEventKey::Sleep => &mut self.sleep,
+            SendEventKey::Wakeup => &mut self.wakeup,
+            SendEventKey::TvInput => &mut self.tv_input,
+            SendEventKey::Guide => &mut self.guide,
+            SendEventKey::Info => &mut self.info,
+            SendEventKey::ChannelUp => &mut self.channel_up,
+            SendEventKey::ChannelDown => &mut self.channel_down,
+            SendEventKey::Assist => &mut self.assist,
+        };
+        Some(keycode)
+    }
+}
+
+/// a keycode in the preferences that isn't accepted by [is_valid_keycode].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct InvalidKeycode {
+    /// the label of the key in the settings.
+    pub location: String,
+    pub keycode: String,
+}
+
+impl InvalidKeycode {
+    pub fn suggestion(&self) -> Option<&'static str> {
+        suggest_keycode(&self.keycode)
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct KeyMapProfile {
+    pub name: String,
+    pub key_map: KeyMap,
 }
 
 impl Default for KeyMap {
@@ -71,6 +486,198 @@ impl Default for KeyMap {
             num_9: "KEYCODE_9".into(),
             back: "KEYCODE_BACK".into(),
             home: "KEYCODE_HOME".into(),
+            volume_up: "KEYCODE_VOLUME_UP".into(),
+            volume_down: "KEYCODE_VOLUME_DOWN".into(),
+            mute: "KEYCODE_VOLUME_MUTE".into(),
+            media_play_pause: "KEYCODE_MEDIA_PLAY_PAUSE".into(),
+            media_stop: "KEYCODE_MEDIA_STOP".into(),
+            media_rewind: "KEYCODE_MEDIA_REWIND".into(),
+            media_fast_forward: "KEYCODE_MEDIA_FAST_FORWARD".into(),
+            media_next: "KEYCODE_MEDIA_NEXT".into(),
+            media_previous: "KEYCODE_MEDIA_PREVIOUS".into(),
+            power: "KEYCODE_POWER".into(),
+            sleep: "KEYCODE_SLEEP".into(),
+            wakeup: "KEYCODE_WAKEUP".into(),
+            tv_input: "KEYCODE_TV_INPUT".into(),
+            guide: "KEYCODE_GUIDE".into(),
+            info: "KEYCODE_INFO".into(),
+            channel_up: "KEYCODE_CHANNEL_UP".into(),
+            channel_down: "KEYCODE_CHANNEL_DOWN".into(),
+            assist: "KEYCODE_ASSIST".into(),
+            long_keycodes: Default::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::layout::LaunchShortcut;
+    use crate::model::target::NetworkTargetKind;
+
+    #[test]
+    fn key_map_keycode_mut() {
+        let mut key_map = KeyMap::default();
+        for key in &SendEventKey::MAPPED {
+            *key_map.keycode_mut(key).unwrap() = format!("{}", key);
+        }
+
+        for key in &SendEventKey::MAPPED {
+            assert_eq!(key.to_string(), key_map.keycode(key));
         }
+        assert!(key_map
+            .keycode_mut(&SendEventKey::Custom("KEYCODE_MENU".into()))
+            .is_none());
+    }
+
+    #[test]
+    fn preferences_invalid_keycodes() {
+        let mut prefs = Preferences::default();
+        assert!(prefs.invalid_keycodes().is_empty());
+
+        prefs.key_map.color_red = "KEYCODE_PROG_REDD".into();
+        prefs.save_key_map_profile("Android TV".into());
+        prefs.key_map.color_red = "KEYCODE_PROG_RED".into();
+        prefs.key_map.back = "4".into();
+        prefs.save_key_map_profile("Fire TV".into());
+        prefs.custom_keys.push(CustomKey {
+            label: "Menu".into(),
+            keycode: "KEYCODE_MENUU".into(),
+            package: None,
+            long_keycode: None,
+        });
+
+        let invalid_keycodes = prefs.invalid_keycodes();
+        assert_eq!(
+            vec![
+                InvalidKeycode {
+                    location: "Android TV / Red".into(),
+                    keycode: "KEYCODE_PROG_REDD".into(),
+                },
+                InvalidKeycode {
+                    location: "Menu".into(),
+                    keycode: "KEYCODE_MENUU".into(),
+                },
+            ],
+            invalid_keycodes
+        );
+        assert_eq!(Some("KEYCODE_PROG_RED"), invalid_keycodes[0].suggestion());
+    }
+
+    #[test]
+    fn preferences_long_keycode() {
+        let mut prefs = Preferences::default();
+        prefs
+            .key_map
+            .long_keycodes
+            .insert("dpad_ok".into(), "KEYCODE_MENU".into());
+        prefs.custom_keys.push(CustomKey {
+            label: "Guide".into(),
+            keycode: "KEYCODE_GUIDE".into(),
+            package: None,
+            long_keycode: Some("KEYCODE_INFOO".into()),
+        });
+
+        assert_eq!(
+            Some("KEYCODE_MENU"),
+            prefs.long_keycode(&SendEventKey::DpadOk)
+        );
+        assert_eq!(None, prefs.long_keycode(&SendEventKey::Back));
+        assert_eq!(
+            Some("KEYCODE_INFOO"),
+            prefs.long_keycode(&SendEventKey::Custom("KEYCODE_GUIDE".into()))
+        );
+        assert_eq!(
+            vec![InvalidKeycode {
+                location: "Guide (long)".into(),
+                keycode: "KEYCODE_INFOO".into(),
+            }],
+            prefs.invalid_keycodes()
+        );
+    }
+
+    #[test]
+    fn preferences_move_custom_key() {
+        let mut prefs = Preferences {
+            custom_keys: ["a", "b", "c"]
+                .iter()
+                .map(|label| CustomKey {
+                    label: label.to_string(),
+                    keycode: "KEYCODE_MENU".into(),
+                    package: None,
+                    long_keycode: None,
+                })
+                .collect(),
+            ..Default::default()
+        };
+        let labels = |prefs: &Preferences| {
+            prefs
+                .custom_keys
+                .iter()
+                .map(|data| data.label.clone())
+                .collect::<Vec<_>>()
+        };
+
+        prefs.move_custom_key(0, 1);
+        assert_eq!(vec!["b", "a", "c"], labels(&prefs));
+        prefs.move_custom_key(1, -5);
+        assert_eq!(vec!["a", "b", "c"], labels(&prefs));
+        prefs.move_custom_key(2, 1);
+        assert_eq!(vec!["a", "b", "c"], labels(&prefs));
+        prefs.move_custom_key(3, -1);
+        assert_eq!(vec!["a", "b", "c"], labels(&prefs));
+    }
+
+    #[test]
+    fn preferences_key_map_profile() {
+        let mut prefs = Preferences::default();
+        prefs.save_key_map_profile("Android TV".into());
+        prefs.key_map.back = "KEYCODE_ESCAPE".into();
+        prefs.save_key_map_profile("Fire TV".into());
+        assert_eq!(Some("Fire TV"), prefs.key_map_profile.as_deref());
+
+        prefs.select_key_map_profile("Android TV");
+        assert_eq!(KeyMap::default(), prefs.key_map);
+        assert_eq!(Some("Android TV"), prefs.key_map_profile.as_deref());
+
+        prefs.select_key_map_profile("debug");
+        assert_eq!(Some("Android TV"), prefs.key_map_profile.as_deref());
+
+        prefs.select_key_map_profile("Fire TV");
+        assert_eq!("KEYCODE_ESCAPE", prefs.key_map.back);
+
+        prefs.remove_key_map_profile("Fire TV");
+        assert_eq!(None, prefs.key_map_profile);
+        assert_eq!("KEYCODE_ESCAPE", prefs.key_map.back);
+        assert_eq!(1, prefs.key_map_profiles.len());
+    }
+
+    #[test]
+    fn preferences_import_device_profile() {
+        let mut prefs = Preferences::default();
+        let mut layout = LayoutProfile::dpad_only();
+        layout.shortcuts.push(LaunchShortcut {
+            label: "Settings".into(),
+            target: "com.android.settings".into(),
+        });
+        let target = NetworkTarget {
+            kind: NetworkTargetKind::Roku,
+            name: "Roku".into(),
+            host: "192.168.0.20".into(),
+        };
+        let profile = DeviceProfile {
+            serial: "Roku".into(),
+            layout: layout.clone(),
+            network_target: Some(target.clone()),
+        };
+        prefs.import_device_profile(profile.clone());
+        prefs.import_device_profile(profile);
+
+        assert_eq!(
+            LayoutProfile::default_profiles().len(),
+            prefs.layout_profiles.len()
+        );
+        assert_eq!(layout, prefs.layout_profile(Some("Roku")));
+        assert_eq!(vec![target], prefs.network_targets);
     }
 }
@@ -0,0 +1,240 @@
+/*
+ * Copyright 2022 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::model::keycode::Keycode;
+use crate::prelude::*;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// the flag of [ServerCommand::KeyLongPress] in the frame.
+const FLAG_LONG_PRESS: u8 = 0x01;
+
+/// the size of the command id, the keycode, the flags and the sequence number.
+const FRAME_HEADER_LEN: usize = 1 + 4 + 1 + 4;
+
+/// a command of the server.
+///
+/// the server reads the commands encoded by [encode_frame].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ServerCommand {
+    KeyDown(Keycode),
+    /// the repeated down with `KeyEvent.FLAG_LONG_PRESS`.
+    KeyLongPress(Keycode),
+    KeyUp(Keycode),
+    Ping,
+    Quit,
+    Text(String),
+}
+
+impl ServerCommand {
+    /// text must be a single line since the commands are shown line by line, e.g. to the
+    /// WebSocket clients.
+    pub fn text(value: &str) -> Fallible<Self> {
+        if value.is_empty() || value.contains(['\n', '\r']) {
+            anyhow::bail!("text must be a non-empty single line: {:?}", value);
+        }
+
+        Ok(ServerCommand::Text(value.into()))
+    }
+
+    /// the key that the command injects.
+    pub fn keycode(&self) -> Option<&Keycode> {
+        match self {
+            ServerCommand::KeyDown(keycode)
+            | ServerCommand::KeyLongPress(keycode)
+            | ServerCommand::KeyUp(keycode) => Some(keycode),
+            ServerCommand::Ping | ServerCommand::Quit | ServerCommand::Text(_) => None,
+        }
+    }
+
+    pub fn click(keycode: Keycode) -> [Self; 2] {
+        [
+            ServerCommand::KeyDown(keycode.clone()),
+            ServerCommand::KeyUp(keycode),
+        ]
+    }
+
+    /// the command id in the frame.
+    fn id(&self) -> u8 {
+        match self {
+            ServerCommand::KeyDown(_) | ServerCommand::KeyLongPress(_) => 1,
+            ServerCommand::KeyUp(_) => 2,
+            ServerCommand::Ping => 3,
+            ServerCommand::Quit => 4,
+            ServerCommand::Text(_) => 5,
+        }
+    }
+}
+
+impl Display for ServerCommand {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ServerCommand::KeyDown(keycode) => write!(f, "down {}", keycode),
+            ServerCommand::KeyLongPress(keycode) => write!(f, "longpress {}", keycode),
+            ServerCommand::KeyUp(keycode) => write!(f, "up {}", keycode),
+            ServerCommand::Ping => f.write_str("ping"),
+            ServerCommand::Quit => f.write_str("quit"),
+            ServerCommand::Text(value) => write!(f, "text {}", value),
+        }
+    }
+}
+
+impl FromStr for ServerCommand {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "ping" => return Ok(ServerCommand::Ping),
+            "quit" => return Ok(ServerCommand::Quit),
+            _ => (),
+        }
+
+        match s.split_once(' ') {
+            Some(("down", keycode)) => Ok(ServerCommand::KeyDown(keycode.parse()?)),
+            Some(("longpress", keycode)) => Ok(ServerCommand::KeyLongPress(keycode.parse()?)),
+            Some(("up", keycode)) => Ok(ServerCommand::KeyUp(keycode.parse()?)),
+            Some(("text", value)) => ServerCommand::text(value),
+            _ => anyhow::bail!("unexpected command: {:?}", s),
+        }
+    }
+}
+
+/// a line that the server prints to stdout.
+///
+/// the stdout stays text since it also has the output of `app_process`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ServerReply {
+    /// the command of the sequence number has been injected into the device.
+    Ack(u32),
+    Pong,
+}
+
+impl FromStr for ServerReply {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once(' ') {
+            Some(("ack", sequence)) => Ok(ServerReply::Ack(sequence.parse()?)),
+            _ if s == "pong" => Ok(ServerReply::Pong),
+            _ => anyhow::bail!("unexpected reply: {:?}", s),
+        }
+    }
+}
+
+/// joins the commands into the lines to show, e.g. to the WebSocket clients.
+pub fn encode_commands(commands: &[ServerCommand]) -> String {
+    commands
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// encodes the command into the frame that the server reads from stdin.
+///
+/// the frame is big-endian for `DataInputStream`: `length: u32`, `id: u8`, `keycode: i32`,
+/// `flags: u8`, `sequence: u32` and the UTF-8 text. the length excludes itself.
+pub fn encode_frame(sequence: u32, command: &ServerCommand) -> Vec<u8> {
+    let payload = match command {
+        ServerCommand::Text(value) => value.as_bytes(),
+        _ => &[],
+    };
+    let keycode = command.keycode().map(Keycode::value).unwrap_or(0);
+    let flags = match command {
+        ServerCommand::KeyLongPress(_) => FLAG_LONG_PRESS,
+        _ => 0,
+    };
+
+    let mut buf = Vec::with_capacity(4 + FRAME_HEADER_LEN + payload.len());
+    buf.extend_from_slice(&((FRAME_HEADER_LEN + payload.len()) as u32).to_be_bytes());
+    buf.push(command.id());
+    buf.extend_from_slice(&keycode.to_be_bytes());
+    buf.push(flags);
+    buf.extend_from_slice(&sequence.to_be_bytes());
+    buf.extend_from_slice(payload);
+    buf
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encode_click() {
+        let keycode = "KEYCODE_BACK".parse().unwrap();
+        assert_eq!(
+            "down KEYCODE_BACK\nup KEYCODE_BACK",
+            encode_commands(&ServerCommand::click(keycode))
+        );
+    }
+
+    #[test]
+    fn parse_round_trip() {
+        for line in [
+            "down KEYCODE_DPAD_UP",
+            "longpress KEYCODE_DPAD_CENTER",
+            "up 23",
+            "ping",
+            "quit",
+            "text hello world",
+        ] {
+            assert_eq!(line, line.parse::<ServerCommand>().unwrap().to_string());
+        }
+    }
+
+    #[test]
+    fn parse_invalid() {
+        for line in [
+            "",
+            "down",
+            "down KEYCODE_BACK ",
+            "left KEYCODE_BACK",
+            "text ",
+        ] {
+            assert!(line.parse::<ServerCommand>().is_err(), "line: {:?}", line);
+        }
+        assert!(ServerCommand::text("a\nup KEYCODE_HOME").is_err());
+    }
+
+    #[test]
+    fn parse_reply() {
+        assert_eq!(ServerReply::Pong, "pong".parse().unwrap());
+        assert_eq!(ServerReply::Ack(42), "ack 42".parse().unwrap());
+        for line in [
+            "",
+            "ack",
+            "ack -1",
+            "ack up KEYCODE_BACK",
+            "Hello",
+            "pong 1",
+        ] {
+            assert!(line.parse::<ServerReply>().is_err(), "line: {:?}", line);
+        }
+    }
+
+    #[test]
+    fn encode_frame_key() {
+        let keycode = "KEYCODE_DPAD_CENTER".parse().unwrap();
+        assert_eq!(
+            vec![0, 0, 0, 10, 1, 0, 0, 0, 23, 1, 0, 0, 1, 0],
+            encode_frame(256, &ServerCommand::KeyLongPress(keycode))
+        );
+        assert_eq!(
+            vec![0, 0, 0, 12, 5, 0, 0, 0, 0, 0, 0, 0, 0, 7, b'h', b'i'],
+            encode_frame(7, &ServerCommand::Text("hi".into()))
+        );
+    }
+}
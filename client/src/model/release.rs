@@ -0,0 +1,80 @@
+/*
+ * Copyright 2022 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::model::FileVersion;
+use crate::prelude::*;
+
+/// a release of the client on GitHub.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Release {
+    pub version: FileVersion,
+    /// the release page.
+    pub url: String,
+}
+
+impl Release {
+    /// parses the response of `GET /repos/{owner}/{repo}/releases/latest`.
+    pub fn from_github_json(json: &str) -> Fallible<Self> {
+        let value = serde_json::from_str::<serde_json::Value>(json)
+            .context("failed to parse the release")?;
+        let tag = value["tag_name"].as_str().context("tag_name not found")?;
+        let url = value["html_url"].as_str().context("html_url not found")?;
+
+        // the tags are like `v0.1.3`.
+        let version = tag.trim_start_matches('v');
+        if version.split('.').count() != 3 {
+            anyhow::bail!("unexpected tag: {}", tag);
+        }
+
+        Ok(Self {
+            version: version
+                .parse()
+                .with_context(|| format!("unexpected tag: {}", tag))?,
+            url: url.into(),
+        })
+    }
+
+    pub fn is_newer_than_current(&self) -> bool {
+        let current = env!("CARGO_PKG_VERSION")
+            .parse::<FileVersion>()
+            .expect("invalid package version");
+        current < self.version
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn release_from_github_json() {
+        let json = r#"{
+            "tag_name": "v0.2.0",
+            "html_url": "https://github.com/sukawasatoru/android-commander/releases/tag/v0.2.0",
+            "draft": false
+        }"#;
+        assert_eq!(
+            Release {
+                version: FileVersion::from([0, 2, 0]),
+                url: "https://github.com/sukawasatoru/android-commander/releases/tag/v0.2.0".into(),
+            },
+            Release::from_github_json(json).unwrap()
+        );
+
+        assert!(Release::from_github_json(r#"{"tag_name": "v1.0", "html_url": ""}"#).is_err());
+        assert!(Release::from_github_json(r#"{"message": "Not Found"}"#).is_err());
+    }
+}
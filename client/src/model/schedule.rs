@@ -0,0 +1,157 @@
+/*
+ * Copyright 2022 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use chrono::{DateTime, Duration, Local, NaiveDateTime, NaiveTime};
+use std::path::PathBuf;
+
+/// a macro file that runs every day at the `time` for the unattended checks.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ScheduledMacro {
+    pub name: String,
+    /// `HH:MM` in the local time.
+    pub time: String,
+    pub macro_path: PathBuf,
+    /// runs only if the connected device is one of them.
+    pub serials: Vec<String>,
+    pub report_dir: PathBuf,
+    /// receives the summary of the report as JSON.
+    pub webhook_url: Option<String>,
+}
+
+impl ScheduledMacro {
+    pub fn parsed_time(&self) -> Option<NaiveTime> {
+        NaiveTime::parse_from_str(self.time.trim(), "%H:%M").ok()
+    }
+
+    /// whether the time of any day is in `(since, now]`.
+    pub fn is_due(&self, since: NaiveDateTime, now: NaiveDateTime) -> bool {
+        let time = match self.parsed_time() {
+            Some(data) => data,
+            None => return false,
+        };
+
+        let mut scheduled_at = now.date().and_time(time);
+        if now < scheduled_at {
+            scheduled_at -= Duration::days(1);
+        }
+        since < scheduled_at
+    }
+}
+
+/// the result of the scheduled macro that is exported to the report directory.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MacroReport {
+    pub name: String,
+    pub serial: String,
+    pub started_at: DateTime<Local>,
+    pub finished_at: DateTime<Local>,
+    pub steps: usize,
+    /// the reason if failed or skipped.
+    pub failure: Option<String>,
+    pub screenshot: Option<PathBuf>,
+}
+
+impl MacroReport {
+    pub fn file_name(&self) -> String {
+        format!(
+            "{}-{}.txt",
+            self.name,
+            self.started_at.format("%Y%m%d-%H%M%S")
+        )
+    }
+
+    /// the summary followed by the history of the run.
+    pub fn to_text(&self, history_tsv: &str) -> String {
+        let mut text = format!(
+            "name: {}\nserial: {}\nstarted_at: {}\nfinished_at: {}\nsteps: {}\nresult: {}\n",
+            self.name,
+            self.serial,
+            self.started_at.to_rfc3339(),
+            self.finished_at.to_rfc3339(),
+            self.steps,
+            match &self.failure {
+                Some(data) => format!("failed: {}", data),
+                None => "passed".into(),
+            },
+        );
+        if let Some(data) = &self.screenshot {
+            text.push_str(&format!("screenshot: {}\n", data.display()));
+        }
+        text.push('\n');
+        text.push_str(history_tsv);
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn scheduled_macro(time: &str) -> ScheduledMacro {
+        ScheduledMacro {
+            name: "nightly".into(),
+            time: time.into(),
+            macro_path: "nightly.txt".into(),
+            serials: vec!["emulator-5554".into()],
+            report_dir: "reports".into(),
+            webhook_url: None,
+        }
+    }
+
+    #[test]
+    fn scheduled_macro_is_due() {
+        let at = |day, hour, min| NaiveDate::from_ymd(2022, 11, day).and_hms(hour, min, 0);
+        let data = scheduled_macro("02:30");
+
+        assert!(data.is_due(at(1, 2, 29), at(1, 2, 30)));
+        assert!(!data.is_due(at(1, 2, 30), at(1, 2, 31)));
+        assert!(!data.is_due(at(1, 10, 0), at(1, 10, 1)));
+        // across the midnight.
+        assert!(data.is_due(at(1, 23, 0), at(2, 3, 0)));
+        assert!(!scheduled_macro("25:00").is_due(at(1, 0, 0), at(2, 0, 0)));
+    }
+
+    #[test]
+    fn macro_report_to_text() {
+        let started_at = Local::now();
+        let report = MacroReport {
+            name: "nightly".into(),
+            serial: "emulator-5554".into(),
+            started_at,
+            finished_at: started_at,
+            steps: 3,
+            failure: Some("disconnected at 2/3".into()),
+            screenshot: None,
+        };
+
+        assert_eq!(
+            format!(
+                "name: nightly
+serial: emulator-5554
+started_at: {0}
+finished_at: {0}
+steps: 3
+result: failed: disconnected at 2/3
+
+sent_at\tserial\tdevice_received_at\tentry
+",
+                started_at.to_rfc3339()
+            ),
+            report.to_text("sent_at\tserial\tdevice_received_at\tentry\n")
+        );
+    }
+}
@@ -0,0 +1,103 @@
+/*
+ * Copyright 2022 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::prelude::*;
+use std::time::Duration;
+
+/// the arguments of `screenrecord`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ScreenRecordConfig {
+    pub time_limit: Duration,
+    /// the device default if `None`.
+    pub bit_rate_mbps: Option<u32>,
+}
+
+impl ScreenRecordConfig {
+    /// `screenrecord` doesn't record longer than this.
+    pub const MAX_TIME_LIMIT: Duration = Duration::from_secs(180);
+
+    pub fn args(&self, device_path: &str) -> Vec<String> {
+        let mut args = vec![
+            "screenrecord".into(),
+            "--time-limit".into(),
+            self.time_limit.as_secs().to_string(),
+        ];
+        if let Some(data) = self.bit_rate_mbps {
+            args.push("--bit-rate".into());
+            args.push((u64::from(data) * 1_000_000).to_string());
+        }
+        args.push(device_path.into());
+        args
+    }
+}
+
+/// parses the seconds within [ScreenRecordConfig::MAX_TIME_LIMIT].
+pub fn parse_time_limit(value: &str) -> Fallible<Duration> {
+    let secs = value
+        .trim()
+        .parse::<u64>()
+        .with_context(|| format!("invalid time limit: {}", value))?;
+    let time_limit = Duration::from_secs(secs);
+    if secs == 0 || ScreenRecordConfig::MAX_TIME_LIMIT < time_limit {
+        anyhow::bail!(
+            "time limit must be 1 to {} seconds",
+            ScreenRecordConfig::MAX_TIME_LIMIT.as_secs()
+        );
+    }
+    Ok(time_limit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn screen_record_args() {
+        let config = ScreenRecordConfig {
+            time_limit: Duration::from_secs(30),
+            bit_rate_mbps: Some(4),
+        };
+        assert_eq!(
+            vec![
+                "screenrecord",
+                "--time-limit",
+                "30",
+                "--bit-rate",
+                "4000000",
+                "/sdcard/a.mp4"
+            ],
+            config.args("/sdcard/a.mp4")
+        );
+
+        let config = ScreenRecordConfig {
+            bit_rate_mbps: None,
+            ..config
+        };
+        assert_eq!(
+            vec!["screenrecord", "--time-limit", "30", "/sdcard/a.mp4"],
+            config.args("/sdcard/a.mp4")
+        );
+    }
+
+    #[test]
+    fn parse_time_limit_range() {
+        assert_eq!(Duration::from_secs(1), parse_time_limit(" 1 ").unwrap());
+        assert_eq!(Duration::from_secs(180), parse_time_limit("180").unwrap());
+        assert!(parse_time_limit("0").is_err());
+        assert!(parse_time_limit("181").is_err());
+        assert!(parse_time_limit("").is_err());
+    }
+}
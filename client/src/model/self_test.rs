@@ -0,0 +1,168 @@
+/*
+ * Copyright 2022 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::fmt::{Display, Formatter};
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// the stages of the self-test, run in this order.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SelfTestStage {
+    PushServer,
+    Handshake,
+    SendKey,
+    /// the server answers the ping after the key, so the key was handled.
+    Ack,
+    Screenshot,
+}
+
+impl SelfTestStage {
+    pub const ALL: [SelfTestStage; 5] = [
+        SelfTestStage::PushServer,
+        SelfTestStage::Handshake,
+        SelfTestStage::SendKey,
+        SelfTestStage::Ack,
+        SelfTestStage::Screenshot,
+    ];
+}
+
+impl Display for SelfTestStage {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SelfTestStage::PushServer => f.write_str("push server"),
+            SelfTestStage::Handshake => f.write_str("handshake"),
+            SelfTestStage::SendKey => f.write_str("send key"),
+            SelfTestStage::Ack => f.write_str("ack"),
+            SelfTestStage::Screenshot => f.write_str("screenshot"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SelfTestStageResult {
+    pub stage: SelfTestStage,
+    pub elapsed: Duration,
+    /// `None` if passed.
+    pub error: Option<String>,
+}
+
+/// the stages after the failed one aren't run.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SelfTestReport {
+    pub serial: String,
+    pub results: Vec<SelfTestStageResult>,
+    pub screenshot: Option<PathBuf>,
+}
+
+impl SelfTestReport {
+    pub fn new(serial: String) -> Self {
+        Self {
+            serial,
+            results: vec![],
+            screenshot: None,
+        }
+    }
+
+    pub fn is_passed(&self) -> bool {
+        self.results.len() == SelfTestStage::ALL.len()
+            && self.results.iter().all(|data| data.error.is_none())
+    }
+
+    /// the plain text to paste into the support requests.
+    pub fn to_text(&self) -> String {
+        let mut lines = vec![format!(
+            "self-test of {}: {}",
+            self.serial,
+            if self.is_passed() { "passed" } else { "failed" }
+        )];
+        for stage in SelfTestStage::ALL {
+            lines.push(match self.results.iter().find(|data| data.stage == stage) {
+                Some(SelfTestStageResult {
+                    elapsed,
+                    error: None,
+                    ..
+                }) => format!("[ok] {} {}ms", stage, elapsed.as_millis()),
+                Some(SelfTestStageResult {
+                    elapsed,
+                    error: Some(error),
+                    ..
+                }) => format!("[ng] {} {}ms: {}", stage, elapsed.as_millis(), error),
+                None => format!("[--] {} skipped", stage),
+            });
+        }
+        if let Some(path) = &self.screenshot {
+            lines.push(format!("screenshot: {}", path.display()));
+        }
+        lines.join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn self_test_report_failed() {
+        let report = SelfTestReport {
+            serial: "emulator-5554".into(),
+            results: vec![
+                SelfTestStageResult {
+                    stage: SelfTestStage::PushServer,
+                    elapsed: Duration::from_millis(120),
+                    error: None,
+                },
+                SelfTestStageResult {
+                    stage: SelfTestStage::Handshake,
+                    elapsed: Duration::from_millis(10_000),
+                    error: Some("handshake timed out".into()),
+                },
+            ],
+            screenshot: None,
+        };
+
+        assert!(!report.is_passed());
+        assert_eq!(
+            "self-test of emulator-5554: failed
+[ok] push server 120ms
+[ng] handshake 10000ms: handshake timed out
+[--] send key skipped
+[--] ack skipped
+[--] screenshot skipped",
+            report.to_text()
+        );
+    }
+
+    #[test]
+    fn self_test_report_passed() {
+        let mut report = SelfTestReport::new("emulator-5554".into());
+        for stage in SelfTestStage::ALL {
+            report.results.push(SelfTestStageResult {
+                stage,
+                elapsed: Duration::from_millis(1),
+                error: None,
+            });
+        }
+        report.screenshot = Some("/tmp/self-test.png".into());
+
+        assert!(report.is_passed());
+        assert!(report
+            .to_text()
+            .starts_with("self-test of emulator-5554: passed\n"));
+        assert!(report
+            .to_text()
+            .ends_with("\nscreenshot: /tmp/self-test.png"));
+    }
+}
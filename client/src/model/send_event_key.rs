@@ -14,19 +14,36 @@
  * limitations under the License.
  */
 
+use std::fmt::{Display, Formatter};
+
 #[derive(Clone, Debug, Eq, Hash, PartialEq)]
 pub enum SendEventKey {
+    /// the voice assistant or the search, which is chosen by the keycode in the key map.
+    Assist,
     Back,
+    ChannelDown,
+    ChannelUp,
     ColorRed,
     ColorGreen,
     ColorBlue,
     ColorYellow,
+    /// a keycode of [crate::model::CustomKey].
+    Custom(String),
     DpadUp,
     DpadDown,
     DpadLeft,
     DpadRight,
     DpadOk,
+    Guide,
     Home,
+    Info,
+    MediaFastForward,
+    MediaNext,
+    MediaPlayPause,
+    MediaPrevious,
+    MediaRewind,
+    MediaStop,
+    Mute,
     Num0,
     Num1,
     Num2,
@@ -37,4 +54,184 @@ pub enum SendEventKey {
     Num7,
     Num8,
     Num9,
+    Power,
+    Sleep,
+    TvInput,
+    VolumeUp,
+    VolumeDown,
+    Wakeup,
+}
+
+impl SendEventKey {
+    /// the keys assigned in [crate::model::KeyMap] in the display order.
+    pub const MAPPED: [SendEventKey; 39] = [
+        SendEventKey::Back,
+        SendEventKey::Home,
+        SendEventKey::Assist,
+        SendEventKey::DpadUp,
+        SendEventKey::DpadDown,
+        SendEventKey::DpadLeft,
+        SendEventKey::DpadRight,
+        SendEventKey::DpadOk,
+        SendEventKey::ColorRed,
+        SendEventKey::ColorGreen,
+        SendEventKey::ColorBlue,
+        SendEventKey::ColorYellow,
+        SendEventKey::Num0,
+        SendEventKey::Num1,
+        SendEventKey::Num2,
+        SendEventKey::Num3,
+        SendEventKey::Num4,
+        SendEventKey::Num5,
+        SendEventKey::Num6,
+        SendEventKey::Num7,
+        SendEventKey::Num8,
+        SendEventKey::Num9,
+        SendEventKey::VolumeUp,
+        SendEventKey::VolumeDown,
+        SendEventKey::Mute,
+        SendEventKey::MediaPlayPause,
+        SendEventKey::MediaStop,
+        SendEventKey::MediaRewind,
+        SendEventKey::MediaFastForward,
+        SendEventKey::MediaPrevious,
+        SendEventKey::MediaNext,
+        SendEventKey::Power,
+        SendEventKey::Sleep,
+        SendEventKey::Wakeup,
+        SendEventKey::TvInput,
+        SendEventKey::Guide,
+        SendEventKey::Info,
+        SendEventKey::ChannelUp,
+        SendEventKey::ChannelDown,
+    ];
+
+    /// the name in the preferences, which is the field of [crate::model::KeyMap] or the keycode.
+    pub fn id(&self) -> &str {
+        match self {
+            SendEventKey::Assist => "assist",
+            SendEventKey::Back => "back",
+            SendEventKey::ChannelDown => "channel_down",
+            SendEventKey::ChannelUp => "channel_up",
+            SendEventKey::ColorRed => "color_red",
+            SendEventKey::ColorGreen => "color_green",
+            SendEventKey::ColorBlue => "color_blue",
+            SendEventKey::ColorYellow => "color_yellow",
+            SendEventKey::Custom(keycode) => keycode,
+            SendEventKey::DpadUp => "dpad_up",
+            SendEventKey::DpadDown => "dpad_down",
+            SendEventKey::DpadLeft => "dpad_left",
+            SendEventKey::DpadRight => "dpad_right",
+            SendEventKey::DpadOk => "dpad_ok",
+            SendEventKey::Guide => "guide",
+            SendEventKey::Home => "home",
+            SendEventKey::Info => "info",
+            SendEventKey::MediaFastForward => "media_fast_forward",
+            SendEventKey::MediaNext => "media_next",
+            SendEventKey::MediaPlayPause => "media_play_pause",
+            SendEventKey::MediaPrevious => "media_previous",
+            SendEventKey::MediaRewind => "media_rewind",
+            SendEventKey::MediaStop => "media_stop",
+            SendEventKey::Mute => "mute",
+            SendEventKey::Num0 => "num_0",
+            SendEventKey::Num1 => "num_1",
+            SendEventKey::Num2 => "num_2",
+            SendEventKey::Num3 => "num_3",
+            SendEventKey::Num4 => "num_4",
+            SendEventKey::Num5 => "num_5",
+            SendEventKey::Num6 => "num_6",
+            SendEventKey::Num7 => "num_7",
+            SendEventKey::Num8 => "num_8",
+            SendEventKey::Num9 => "num_9",
+            SendEventKey::Power => "power",
+            SendEventKey::Sleep => "sleep",
+            SendEventKey::TvInput => "tv_input",
+            SendEventKey::VolumeUp => "volume_up",
+            SendEventKey::VolumeDown => "volume_down",
+            SendEventKey::Wakeup => "wakeup",
+        }
+    }
+
+    /// treats the unknown id as the keycode of [SendEventKey::Custom].
+    pub fn from_id(id: &str) -> Self {
+        Self::MAPPED
+            .iter()
+            .find(|data| data.id() == id)
+            .cloned()
+            .unwrap_or_else(|| SendEventKey::Custom(id.into()))
+    }
+
+    pub fn is_color_key(&self) -> bool {
+        matches!(
+            self,
+            SendEventKey::ColorRed
+                | SendEventKey::ColorGreen
+                | SendEventKey::ColorBlue
+                | SendEventKey::ColorYellow
+        )
+    }
+}
+
+impl Display for SendEventKey {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SendEventKey::Assist => write!(f, "Assist"),
+            SendEventKey::Back => write!(f, "Back"),
+            SendEventKey::ChannelDown => write!(f, "CH-"),
+            SendEventKey::ChannelUp => write!(f, "CH+"),
+            SendEventKey::ColorRed => write!(f, "Red"),
+            SendEventKey::ColorGreen => write!(f, "Green"),
+            SendEventKey::ColorBlue => write!(f, "Blue"),
+            SendEventKey::ColorYellow => write!(f, "Yellow"),
+            SendEventKey::Custom(keycode) => write!(f, "{}", keycode),
+            SendEventKey::DpadUp => write!(f, "Up"),
+            SendEventKey::DpadDown => write!(f, "Down"),
+            SendEventKey::DpadLeft => write!(f, "Left"),
+            SendEventKey::DpadRight => write!(f, "Right"),
+            SendEventKey::DpadOk => write!(f, "OK"),
+            SendEventKey::Guide => write!(f, "Guide"),
+            SendEventKey::Home => write!(f, "Home"),
+            SendEventKey::Info => write!(f, "Info"),
+            SendEventKey::MediaFastForward => write!(f, "FF"),
+            SendEventKey::MediaNext => write!(f, "Next"),
+            SendEventKey::MediaPlayPause => write!(f, "Play/Pause"),
+            SendEventKey::MediaPrevious => write!(f, "Prev"),
+            SendEventKey::MediaRewind => write!(f, "Rew"),
+            SendEventKey::MediaStop => write!(f, "Stop"),
+            SendEventKey::Mute => write!(f, "Mute"),
+            SendEventKey::Num0 => write!(f, "0"),
+            SendEventKey::Num1 => write!(f, "1"),
+            SendEventKey::Num2 => write!(f, "2"),
+            SendEventKey::Num3 => write!(f, "3"),
+            SendEventKey::Num4 => write!(f, "4"),
+            SendEventKey::Num5 => write!(f, "5"),
+            SendEventKey::Num6 => write!(f, "6"),
+            SendEventKey::Num7 => write!(f, "7"),
+            SendEventKey::Num8 => write!(f, "8"),
+            SendEventKey::Num9 => write!(f, "9"),
+            SendEventKey::Power => write!(f, "Power"),
+            SendEventKey::Sleep => write!(f, "Sleep"),
+            SendEventKey::TvInput => write!(f, "Input"),
+            SendEventKey::VolumeUp => write!(f, "Vol+"),
+            SendEventKey::VolumeDown => write!(f, "Vol-"),
+            SendEventKey::Wakeup => write!(f, "Wake"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn send_event_key_id() {
+        for key in &SendEventKey::MAPPED {
+            assert_eq!(key, &SendEventKey::from_id(key.id()));
+        }
+        assert_eq!(
+            SendEventKey::Custom("KEYCODE_MENU".into()),
+            SendEventKey::from_id("KEYCODE_MENU")
+        );
+        assert_eq!("KEYCODE_MENU", SendEventKey::from_id("KEYCODE_MENU").id());
+    }
 }
@@ -0,0 +1,186 @@
+/*
+ * Copyright 2022 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::model::send_event_key::SendEventKey;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// the keys other than a letter, a digit or `f1`-`f12`, named after the key codes of iced.
+const NAMED_KEYS: [&str; 21] = [
+    "backspace",
+    "comma",
+    "delete",
+    "down",
+    "end",
+    "enter",
+    "equals",
+    "escape",
+    "home",
+    "insert",
+    "left",
+    "minus",
+    "pagedown",
+    "pageup",
+    "period",
+    "right",
+    "semicolon",
+    "slash",
+    "space",
+    "tab",
+    "up",
+];
+
+/// a combination of the keyboard like `ctrl+r` or `shift+1`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Shortcut {
+    pub ctrl: bool,
+    pub alt: bool,
+    pub shift: bool,
+    /// the lowercase name of the key like `r`, `1` or `f5`.
+    pub key: String,
+}
+
+impl Shortcut {
+    pub fn matches(&self, key: &str, ctrl: bool, alt: bool, shift: bool) -> bool {
+        self.key == key && self.ctrl == ctrl && self.alt == alt && self.shift == shift
+    }
+}
+
+fn is_key_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => c.is_ascii_lowercase() || c.is_ascii_digit(),
+        (Some('f'), Some(_)) => matches!(name[1..].parse::<u8>(), Ok(1..=12)),
+        _ => NAMED_KEYS.contains(&name),
+    }
+}
+
+impl FromStr for Shortcut {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim().to_lowercase();
+        let mut shortcut = Self {
+            ctrl: false,
+            alt: false,
+            shift: false,
+            key: "".into(),
+        };
+
+        let mut parts = s.split('+').map(str::trim).collect::<Vec<_>>();
+        let key = parts.pop().unwrap_or_default();
+        for modifier in parts {
+            match modifier {
+                "ctrl" | "control" => shortcut.ctrl = true,
+                "alt" => shortcut.alt = true,
+                "shift" => shortcut.shift = true,
+                _ => anyhow::bail!("unknown modifier: {:?}", modifier),
+            }
+        }
+
+        if !is_key_name(key) {
+            anyhow::bail!("unknown key: {:?}", key);
+        }
+        shortcut.key = key.into();
+
+        Ok(shortcut)
+    }
+}
+
+impl Display for Shortcut {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.ctrl {
+            f.write_str("ctrl+")?;
+        }
+        if self.alt {
+            f.write_str("alt+")?;
+        }
+        if self.shift {
+            f.write_str("shift+")?;
+        }
+        f.write_str(&self.key)
+    }
+}
+
+/// sends the key by the shortcut in addition to the built-in ones.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct KeyShortcut {
+    pub shortcut: Shortcut,
+    /// a key of [crate::model::KeyMap] or [SendEventKey::Custom].
+    pub key: SendEventKey,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_shortcut() {
+        assert_eq!(
+            Shortcut {
+                ctrl: true,
+                alt: false,
+                shift: false,
+                key: "r".into(),
+            },
+            "ctrl+r".parse().unwrap()
+        );
+        assert_eq!(
+            Shortcut {
+                ctrl: true,
+                alt: true,
+                shift: true,
+                key: "f5".into(),
+            },
+            " Shift + Alt + Control + F5 ".parse().unwrap()
+        );
+        assert_eq!(
+            Shortcut {
+                ctrl: false,
+                alt: false,
+                shift: false,
+                key: "pagedown".into(),
+            },
+            "pagedown".parse().unwrap()
+        );
+    }
+
+    #[test]
+    fn parse_shortcut_invalid() {
+        assert!("".parse::<Shortcut>().is_err());
+        assert!("ctrl+".parse::<Shortcut>().is_err());
+        assert!("ctrl".parse::<Shortcut>().is_err());
+        assert!("meta+r".parse::<Shortcut>().is_err());
+        assert!("ctrl+rr".parse::<Shortcut>().is_err());
+        assert!("f13".parse::<Shortcut>().is_err());
+    }
+
+    #[test]
+    fn shortcut_display() {
+        for data in ["ctrl+alt+shift+1", "shift+space", "r"] {
+            assert_eq!(data, data.parse::<Shortcut>().unwrap().to_string());
+        }
+    }
+
+    #[test]
+    fn shortcut_matches() {
+        let shortcut = "shift+1".parse::<Shortcut>().unwrap();
+        assert!(shortcut.matches("1", false, false, true));
+        assert!(!shortcut.matches("1", false, false, false));
+        assert!(!shortcut.matches("1", true, false, true));
+        assert!(!shortcut.matches("2", false, false, true));
+    }
+}
@@ -0,0 +1,165 @@
+/*
+ * Copyright 2022 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::model::AndroidDevice;
+
+/// how to send the keys to the device.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum DeviceKind {
+    Adb,
+    /// the Android TV Remote Service on the consumer TVs without adb.
+    AndroidTvRemote {
+        host: String,
+    },
+    /// the HDMI-CEC user control keys through the Pulse-Eight USB-CEC adapter.
+    Cec {
+        port: String,
+        logical_address: u8,
+    },
+    /// the External Control Protocol of Roku devices.
+    Roku {
+        host: String,
+    },
+}
+
+impl DeviceKind {
+    /// the capabilities assumed until the device is connected.
+    pub fn capabilities(&self) -> DeviceCapabilities {
+        match self {
+            DeviceKind::Adb => DeviceCapabilities::ADB,
+            DeviceKind::AndroidTvRemote { .. } => DeviceCapabilities::ANDROID_TV_REMOTE,
+            DeviceKind::Cec { .. } => DeviceCapabilities::CEC,
+            DeviceKind::Roku { .. } => DeviceCapabilities::ROKU,
+        }
+    }
+}
+
+/// what the device supports other than the basic keys.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct DeviceCapabilities {
+    /// needs to pair with the code shown on the device before connecting.
+    pub needs_pairing: bool,
+    /// echoes an ack per command so that the lost keys can be told from the delivered ones.
+    pub supports_ack: bool,
+    pub supports_color_keys: bool,
+    /// sends the down and the up separately so that the key can be held.
+    pub supports_hold: bool,
+    pub supports_screenshot: bool,
+    /// runs the shell commands such as the shortcuts, the log marker and the monkey.
+    pub supports_shell: bool,
+    pub supports_text_injection: bool,
+}
+
+impl DeviceCapabilities {
+    pub const ADB: Self = Self {
+        needs_pairing: false,
+        supports_ack: true,
+        supports_color_keys: true,
+        supports_hold: true,
+        supports_screenshot: true,
+        supports_shell: true,
+        supports_text_injection: true,
+    };
+
+    /// the adb device driven by `input` since the server can't run.
+    pub const ADB_INPUT: Self = Self {
+        supports_ack: false,
+        supports_hold: false,
+        ..Self::ADB
+    };
+
+    pub const ANDROID_TV_REMOTE: Self = Self {
+        needs_pairing: true,
+        supports_ack: false,
+        supports_screenshot: false,
+        supports_shell: false,
+        supports_text_injection: false,
+        ..Self::ADB
+    };
+
+    /// the TV may ignore the color keys but the adapter can't tell it.
+    pub const CEC: Self = Self {
+        supports_ack: false,
+        supports_screenshot: false,
+        supports_shell: false,
+        supports_text_injection: false,
+        ..Self::ADB
+    };
+
+    pub const ROKU: Self = Self {
+        supports_ack: false,
+        supports_color_keys: false,
+        supports_screenshot: false,
+        supports_shell: false,
+        ..Self::ADB
+    };
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum NetworkTargetKind {
+    AndroidTvRemote,
+    Roku,
+}
+
+/// a device that is reached over the network instead of adb.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct NetworkTarget {
+    pub kind: NetworkTargetKind,
+    /// shown in the device picker, and used as the serial.
+    pub name: String,
+    pub host: String,
+}
+
+/// a TV or receiver that is controlled through the USB-CEC adapter on the `port`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CecTarget {
+    pub name: String,
+    pub port: String,
+    /// the CEC logical address of the device, 0 for the TV and 5 for the audio system.
+    pub logical_address: u8,
+}
+
+impl CecTarget {
+    pub fn to_device(&self) -> AndroidDevice {
+        AndroidDevice {
+            serial: self.name.clone(),
+            kind: DeviceKind::Cec {
+                port: self.port.clone(),
+                logical_address: self.logical_address,
+            },
+            label: None,
+            details: None,
+        }
+    }
+}
+
+impl NetworkTarget {
+    pub fn to_device(&self) -> AndroidDevice {
+        AndroidDevice {
+            serial: self.name.clone(),
+            kind: match self.kind {
+                NetworkTargetKind::AndroidTvRemote => DeviceKind::AndroidTvRemote {
+                    host: self.host.clone(),
+                },
+                NetworkTargetKind::Roku => DeviceKind::Roku {
+                    host: self.host.clone(),
+                },
+            },
+            label: None,
+            details: None,
+        }
+    }
+}
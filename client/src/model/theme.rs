@@ -72,6 +72,41 @@ impl Default for ColorKeyButtonStyle {
     }
 }
 
+/// a colored square to show the connection state.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum StatusIndicatorStyle {
+    Green,
+    Yellow,
+    Red,
+    Gray,
+}
+
+impl iced::widget::button::StyleSheet for StatusIndicatorStyle {
+    type Style = Theme;
+
+    fn active(&self, _style: &Self::Style) -> iced::widget::button::Appearance {
+        let color = match self {
+            StatusIndicatorStyle::Green => Color::from_rgb8(0x22, 0xC5, 0x5E),
+            StatusIndicatorStyle::Yellow => Color::from_rgb8(0xEA, 0xB3, 0x08),
+            StatusIndicatorStyle::Red => Color::from_rgb8(0xEF, 0x44, 0x44),
+            StatusIndicatorStyle::Gray => Color::from_rgb8(0x94, 0xA3, 0xB8),
+        };
+        iced::widget::button::Appearance {
+            shadow_offset: Default::default(),
+            background: Some(color.into()),
+            border_radius: 6.0,
+            border_width: 0.0,
+            border_color: Color::TRANSPARENT,
+            text_color: Color::BLACK,
+        }
+    }
+
+    fn disabled(&self, style: &Self::Style) -> iced::widget::button::Appearance {
+        // the indicator is never pressable.
+        self.active(style)
+    }
+}
+
 impl iced::widget::button::StyleSheet for ColorKeyButtonStyle {
     type Style = Theme;
 
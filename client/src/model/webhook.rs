@@ -0,0 +1,108 @@
+/*
+ * Copyright 2022 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/// an outgoing webhook that receives the events as JSON.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Webhook {
+    pub url: String,
+    /// signs the body with HMAC-SHA256 if specified.
+    pub secret: Option<String>,
+    /// receives all events if empty.
+    pub events: Vec<WebhookEventKind>,
+}
+
+impl Webhook {
+    pub fn accepts(&self, kind: WebhookEventKind) -> bool {
+        self.events.is_empty() || self.events.contains(&kind)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum WebhookEventKind {
+    /// the connection was lost without the user disconnecting it.
+    Disconnected,
+    MacroFailed,
+    MacroFinished,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum WebhookEvent {
+    Disconnected { serial: String, reason: String },
+    MacroFailed { serial: String, reason: String },
+    MacroFinished { serial: String, steps: usize },
+}
+
+impl WebhookEvent {
+    pub fn kind(&self) -> WebhookEventKind {
+        match self {
+            WebhookEvent::Disconnected { .. } => WebhookEventKind::Disconnected,
+            WebhookEvent::MacroFailed { .. } => WebhookEventKind::MacroFailed,
+            WebhookEvent::MacroFinished { .. } => WebhookEventKind::MacroFinished,
+        }
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        match self {
+            WebhookEvent::Disconnected { serial, reason } => serde_json::json!({
+                "event": "disconnected",
+                "serial": serial,
+                "reason": reason,
+            }),
+            WebhookEvent::MacroFailed { serial, reason } => serde_json::json!({
+                "event": "macro_failed",
+                "serial": serial,
+                "reason": reason,
+            }),
+            WebhookEvent::MacroFinished { serial, steps } => serde_json::json!({
+                "event": "macro_finished",
+                "serial": serial,
+                "steps": steps,
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn webhook_accepts() {
+        let mut webhook = Webhook {
+            url: "https://example.com/hook".into(),
+            secret: None,
+            events: vec![],
+        };
+        assert!(webhook.accepts(WebhookEventKind::Disconnected));
+
+        webhook.events = vec![WebhookEventKind::MacroFailed];
+        assert!(!webhook.accepts(WebhookEventKind::Disconnected));
+        assert!(webhook.accepts(WebhookEventKind::MacroFailed));
+    }
+
+    #[test]
+    fn webhook_event_to_json() {
+        let event = WebhookEvent::MacroFinished {
+            serial: "emulator-5554".into(),
+            steps: 3,
+        };
+        assert_eq!(WebhookEventKind::MacroFinished, event.kind());
+        assert_eq!(
+            r#"{"event":"macro_finished","serial":"emulator-5554","steps":3}"#,
+            event.to_json().to_string()
+        );
+    }
+}
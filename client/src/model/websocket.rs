@@ -0,0 +1,165 @@
+/*
+ * Copyright 2022 sukawasatoru
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::prelude::*;
+use sha1::{Digest, Sha1};
+
+/// the payload limit of the client frames since the messages are small JSON.
+pub const MAX_PAYLOAD_LEN: u64 = 4096;
+
+/// the GUID appended to `Sec-WebSocket-Key` of RFC 6455.
+const ACCEPT_GUID: &str = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum WsOpcode {
+    Text,
+    Binary,
+    Close,
+    Ping,
+    Pong,
+}
+
+impl WsOpcode {
+    fn from_bits(value: u8) -> Option<Self> {
+        match value {
+            0x1 => Some(Self::Text),
+            0x2 => Some(Self::Binary),
+            0x8 => Some(Self::Close),
+            0x9 => Some(Self::Ping),
+            0xa => Some(Self::Pong),
+            _ => None,
+        }
+    }
+
+    fn bits(&self) -> u8 {
+        match self {
+            Self::Text => 0x1,
+            Self::Binary => 0x2,
+            Self::Close => 0x8,
+            Self::Ping => 0x9,
+            Self::Pong => 0xa,
+        }
+    }
+}
+
+/// the first 2 bytes of the frame.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct WsFrameHead {
+    pub opcode: WsOpcode,
+    pub masked: bool,
+    /// the 7-bit length, or 126 and 127 for the extended length that follows.
+    pub len: u8,
+}
+
+impl WsFrameHead {
+    /// the fragmented frames are rejected since the messages fit in a frame.
+    pub fn parse(head: [u8; 2]) -> Fallible<Self> {
+        if head[0] & 0x80 == 0 {
+            anyhow::bail!("fragmented frames are not supported");
+        }
+        if head[0] & 0x70 != 0 {
+            anyhow::bail!("unexpected reserved bits");
+        }
+
+        Ok(Self {
+            opcode: WsOpcode::from_bits(head[0] & 0x0f)
+                .with_context(|| format!("unknown opcode: {:#x}", head[0] & 0x0f))?,
+            masked: head[1] & 0x80 != 0,
+            len: head[1] & 0x7f,
+        })
+    }
+}
+
+/// the value of `Sec-WebSocket-Accept` for the `Sec-WebSocket-Key`.
+pub fn accept_key(key: &str) -> String {
+    let mut hasher = Sha1::new();
+    hasher.update(key.trim().as_bytes());
+    hasher.update(ACCEPT_GUID.as_bytes());
+    base64::encode(hasher.finalize())
+}
+
+/// unmasks the payload of the client frame in place.
+pub fn apply_mask(payload: &mut [u8], mask: [u8; 4]) {
+    for (index, data) in payload.iter_mut().enumerate() {
+        *data ^= mask[index % 4];
+    }
+}
+
+/// encodes the unmasked frame that the server sends.
+pub fn encode_frame(opcode: WsOpcode, payload: &[u8]) -> Vec<u8> {
+    let mut frame = vec![0x80 | opcode.bits()];
+    match payload.len() {
+        len if len < 126 => frame.push(len as u8),
+        len if len <= u16::MAX as usize => {
+            frame.push(126);
+            frame.extend_from_slice(&(len as u16).to_be_bytes());
+        }
+        len => {
+            frame.push(127);
+            frame.extend_from_slice(&(len as u64).to_be_bytes());
+        }
+    }
+    frame.extend_from_slice(payload);
+    frame
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accept_key_rfc() {
+        assert_eq!(
+            "s3pPLMBiTxaQ9kYGzzhZRbK+xOo=",
+            accept_key("dGhlIHNhbXBsZSBub25jZQ==")
+        );
+    }
+
+    #[test]
+    fn frame_masked_hello() {
+        let frame = [
+            0x81, 0x85, 0x37, 0xfa, 0x21, 0x3d, 0x7f, 0x9f, 0x4d, 0x51, 0x58,
+        ];
+        let head = WsFrameHead::parse([frame[0], frame[1]]).unwrap();
+        assert_eq!(
+            WsFrameHead {
+                opcode: WsOpcode::Text,
+                masked: true,
+                len: 5,
+            },
+            head
+        );
+
+        let mut payload = frame[6..].to_vec();
+        apply_mask(&mut payload, [frame[2], frame[3], frame[4], frame[5]]);
+        assert_eq!(b"Hello", payload.as_slice());
+
+        assert!(WsFrameHead::parse([0x01, 0x85]).is_err());
+        assert!(WsFrameHead::parse([0x83, 0x85]).is_err());
+    }
+
+    #[test]
+    fn encode_frame_len() {
+        assert_eq!(
+            vec![0x81, 0x05, b'H', b'e', b'l', b'l', b'o'],
+            encode_frame(WsOpcode::Text, b"Hello")
+        );
+
+        let frame = encode_frame(WsOpcode::Binary, &[0; 256]);
+        assert_eq!([0x82, 126, 0x01, 0x00], frame[..4]);
+        assert_eq!(260, frame.len());
+    }
+}
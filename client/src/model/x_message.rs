@@ -14,11 +14,15 @@
  * limitations under the License.
  */
 
-use crate::model::Preferences;
+use crate::model::{AndroidDevice, Preferences};
 use std::sync::Arc;
 
 #[derive(Clone, Debug)]
 pub enum XMessage {
+    OnDeviceSelected(Arc<AndroidDevice>),
+    /// sends the click of the keycode to the connected device to try the key map.
+    OnKeycodeTestRequested(String),
     OnNewPreferences(Arc<Preferences>),
     OnPrefsFileUpdated,
+    OnWindowFocusChanged(bool),
 }
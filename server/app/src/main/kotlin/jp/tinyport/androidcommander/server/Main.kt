@@ -17,96 +17,167 @@
 package jp.tinyport.androidcommander.server
 
 import android.os.SystemClock
-import android.text.TextUtils
+import android.view.KeyCharacterMap
 import android.view.KeyEvent
-import java.util.Scanner
+import java.io.BufferedInputStream
+import java.io.DataInputStream
+import java.io.EOFException
+
+/** the flag of [KeyAction.LongPress] in the frame. */
+private const val FLAG_LONG_PRESS = 0x01
+
+/** the size of the command id, the keycode, the flags and the sequence number. */
+private const val FRAME_HEADER_LENGTH = 1 + 4 + 1 + 4
 
 fun main() {
     println("Hello")
 
     val serviceManager = ServiceManagerWrapper()
     val inputManager = serviceManager.inputManager
-    val scanner = Scanner(System.`in`)
+    val input = DataInputStream(BufferedInputStream(System.`in`))
 
-    var lastCommand: ClientCommand? = null
+    var lastCommand: ClientCommand.Key? = null
     var repeatCount = 0
+    var downTime = 0L
 
-    while (true) {
-        val command = try {
-            parseLine(scanner.nextLine()) ?: continue
-        } catch (e: NoSuchElementException) {
+    loop@ while (true) {
+        val frame = try {
+            readFrame(input)
+        } catch (e: EOFException) {
             break
         }
-
-        if (command.action == KeyAction.Down && command == lastCommand) {
-            repeatCount += 1
-        } else {
-            repeatCount = 0
+        val command = frame.command ?: continue
+        val ack = "ack ${frame.sequence.toLong() and 0xffffffffL}"
+
+        when (command) {
+            is ClientCommand.Key -> {
+                val eventTime = SystemClock.uptimeMillis()
+                val holding = lastCommand?.action != KeyAction.Up &&
+                    lastCommand?.code == command.code
+
+                if (command.action != KeyAction.Up && holding) {
+                    repeatCount += 1
+                } else {
+                    repeatCount = 0
+                }
+
+                // the repeats and the up need the time of the first down.
+                if (!holding) {
+                    downTime = eventTime
+                }
+
+                inputManager.injectInputEvent(
+                    KeyEvent(
+                        downTime,
+                        eventTime,
+                        command.action.code,
+                        command.code,
+                        repeatCount,
+                        0,
+                        KeyCharacterMap.VIRTUAL_KEYBOARD,
+                        0,
+                        command.action.flags,
+                    ),
+                    InputManagerWrapper.INJECT_INPUT_EVENT_MODE_ASYNC,
+                )
+
+                lastCommand = command
+                println(ack)
+            }
+            is ClientCommand.Text -> {
+                val events = KeyCharacterMap.load(KeyCharacterMap.VIRTUAL_KEYBOARD)
+                    .getEvents(command.value.toCharArray())
+                if (events == null) {
+                    println("unsupported text: ${command.value}")
+                    continue@loop
+                }
+
+                for (event in events) {
+                    inputManager.injectInputEvent(
+                        event,
+                        InputManagerWrapper.INJECT_INPUT_EVENT_MODE_ASYNC,
+                    )
+                }
+                println(ack)
+            }
+            ClientCommand.Ping -> println("pong")
+            ClientCommand.Quit -> break@loop
         }
-
-        val eventTime = SystemClock.uptimeMillis()
-        inputManager.injectInputEvent(
-            KeyEvent(
-                eventTime,
-                eventTime,
-                command.action.code,
-                command.code.code,
-                repeatCount
-            ),
-            InputManagerWrapper.INJECT_INPUT_EVENT_MODE_ASYNC,
-        )
-
-        lastCommand = command
     }
 
     println("Bye")
 }
 
-private fun parseLine(line: String): ClientCommand? {
-    val lineSegments = TextUtils.split(line, " ")
-    if (lineSegments.size != 2) {
-        println("unexpected format: $line")
-        return null
+/**
+ * reads a big-endian frame: `length: u32`, `id: u8`, `keycode: i32`, `flags: u8`,
+ * `sequence: u32` and the UTF-8 text. the length excludes itself.
+ */
+private fun readFrame(input: DataInputStream): Frame {
+    val length = input.readInt()
+    if (length < FRAME_HEADER_LENGTH) {
+        // the rest of the stream can't be split into the frames.
+        println("unexpected length: $length")
+        throw EOFException()
+    }
+
+    val id = input.readUnsignedByte()
+    val code = input.readInt()
+    val flags = input.readUnsignedByte()
+    val sequence = input.readInt()
+    val payload = ByteArray(length - FRAME_HEADER_LENGTH)
+    input.readFully(payload)
+
+    val command = when (id) {
+        1 -> ClientCommand.Key(
+            action = if (flags and FLAG_LONG_PRESS != 0) KeyAction.LongPress else KeyAction.Down,
+            code = code,
+        )
+        2 -> ClientCommand.Key(action = KeyAction.Up, code = code)
+        3 -> ClientCommand.Ping
+        4 -> ClientCommand.Quit
+        5 -> ClientCommand.Text(String(payload, Charsets.UTF_8))
+        else -> {
+            println("unexpected command: $id")
+            null
+        }
     }
 
-    val (actionString, codeString) = lineSegments
-
-    return ClientCommand(
-        action = KeyAction.from(actionString) ?: run {
-            println("unexpected action: $actionString")
-            return null
-        },
-        code = KeyCode.from(codeString) ?: run {
-            println("unexpected code: $codeString")
-            return null
-        },
-    )
+    return Frame(sequence, command)
 }
 
 enum class KeyAction {
     Down,
+    LongPress,
     Up;
 
-    companion object {
-        fun from(value: String): KeyAction? {
-            return values().find { it.id == value }
-        }
-    }
-
-    val id: String
+    val code: Int
         get() = when (this) {
-            Down -> "down"
-            Up -> "up"
+            Down, LongPress -> KeyEvent.ACTION_DOWN
+            Up -> KeyEvent.ACTION_UP
         }
 
-    val code: Int
+    val flags: Int
         get() = when (this) {
-            Down -> KeyEvent.ACTION_DOWN
-            Up -> KeyEvent.ACTION_UP
+            LongPress -> KeyEvent.FLAG_LONG_PRESS
+            Down, Up -> 0
         }
 }
 
-data class ClientCommand(
-    val action: KeyAction,
-    val code: KeyCode,
+/** a command with the sequence number that the ack echoes back. */
+class Frame(
+    val sequence: Int,
+    val command: ClientCommand?,
 )
+
+sealed class ClientCommand {
+    data class Key(
+        val action: KeyAction,
+        val code: Int,
+    ) : ClientCommand()
+
+    data class Text(val value: String) : ClientCommand()
+
+    object Ping : ClientCommand()
+
+    object Quit : ClientCommand()
+}